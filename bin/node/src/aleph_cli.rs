@@ -35,6 +35,20 @@ pub struct AlephCli {
     #[arg(long, value_name = "PATH", group = "backup")]
     backup_path: Option<PathBuf>,
 
+    /// The number of past sessions whose backups are kept, besides the backup of the current one.
+    #[arg(long, default_value_t = 0)]
+    backup_retention_sessions: u32,
+
+    /// The maximum total size of the backups in MiB. When exceeded, backups of the oldest sessions
+    /// are removed first. The backup of the current session is never removed, as it is needed for
+    /// crash recovery.
+    #[arg(long, value_name = "MiB")]
+    backup_max_size: Option<u64>,
+
+    /// Compress the backups, trading some CPU time for disk space.
+    #[arg(long, default_value_t = false)]
+    backup_compression: bool,
+
     /// The maximum number of nonfinalized blocks, after which block production should be locally
     /// stopped. DO NOT CHANGE THIS, PRODUCING MORE OR FEWER BLOCKS MIGHT BE CONSIDERED MALICIOUS
     /// BEHAVIOUR AND PUNISHED ACCORDINGLY!
@@ -55,10 +69,30 @@ pub struct AlephCli {
     #[arg(long, default_value_t = 5*1024*1024)]
     substrate_network_bit_rate: u64,
 
+    /// Maximum bit-rate in bits per second of the data received from a single peer of the
+    /// alephbft validator network. Unlimited by default.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    validator_network_peer_bit_rate: Option<u64>,
+
+    /// Maximum bit-rate in bits per second of the block sync messages received from a single peer.
+    /// Unlimited by default.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    sync_peer_bit_rate: Option<u64>,
+
+    /// Maximum share, in percent, of the combined bit-rate of the alephbft and substrate networks
+    /// that block sync can use. The rest is reserved for AlephBFT, so that a flood of sync
+    /// messages cannot starve it. Block sync is not held back by default.
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=100))]
+    sync_bandwidth_share: Option<u8>,
+
     /// Don't spend some extra time to collect more debugging data (e.g. validator network details).
     /// By default collecting is enabled, as the impact on performance is negligible, if any.
     #[arg(long, default_value_t = true)]
     collect_validator_network_data: bool,
+
+    /// Set from the `--sync` option, see `SyncConfigValidator`.
+    #[arg(skip)]
+    warp_sync: bool,
 }
 
 impl AlephCli {
@@ -86,6 +120,20 @@ impl AlephCli {
         self.no_backup
     }
 
+    pub fn backup_retention_sessions(&self) -> u32 {
+        self.backup_retention_sessions
+    }
+
+    /// The maximum total size of the backups in bytes.
+    pub fn backup_max_size(&self) -> Option<u64> {
+        self.backup_max_size
+            .map(|mebibytes| mebibytes.saturating_mul(1024 * 1024))
+    }
+
+    pub fn backup_compression(&self) -> bool {
+        self.backup_compression
+    }
+
     pub fn max_nonfinalized_blocks(&self) -> u32 {
         if self.max_nonfinalized_blocks != DEFAULT_MAX_NON_FINALIZED_BLOCKS {
             warn!("Running block production with a value of max-nonfinalized-blocks {}, which is not the default of 20. THIS MIGHT BE CONSIDERED MALICIOUS BEHAVIOUR AND RESULT IN PENALTIES!", self.max_nonfinalized_blocks);
@@ -97,6 +145,14 @@ impl AlephCli {
         self.enable_pruning
     }
 
+    pub fn warp_sync(&self) -> bool {
+        self.warp_sync
+    }
+
+    pub fn set_warp_sync(&mut self) {
+        self.warp_sync = true;
+    }
+
     pub fn alephbft_network_bit_rate(&self) -> u64 {
         self.alephbft_network_bit_rate
     }
@@ -105,6 +161,18 @@ impl AlephCli {
         self.substrate_network_bit_rate
     }
 
+    pub fn validator_network_peer_bit_rate(&self) -> Option<u64> {
+        self.validator_network_peer_bit_rate
+    }
+
+    pub fn sync_peer_bit_rate(&self) -> Option<u64> {
+        self.sync_peer_bit_rate
+    }
+
+    pub fn sync_bandwidth_share(&self) -> Option<u8> {
+        self.sync_bandwidth_share
+    }
+
     pub fn collect_validator_network_data(&self) -> bool {
         self.collect_validator_network_data
     }
@@ -8,7 +8,8 @@ pub type SelendraNodeChainSpec = sc_service::GenericChainSpec<Option<()>>;
 use crate::{
     aleph_cli::AlephCli,
     resources::{mainnet_chainspec, testnet_chainspec},
-    eth::EthConfiguration
+    eth::EthConfiguration,
+    snapshot::{ExportSnapshotCmd, ImportSnapshotCmd},
 };
 
 #[derive(Debug, Parser)]
@@ -94,6 +95,13 @@ pub enum Subcommand {
     /// Import blocks.
     ImportBlocks(sc_cli::ImportBlocksCmd),
 
+    /// Export the state at the last finalized session end, with the justifications proving it
+    /// final, for bootstrapping other nodes of the same chain.
+    ExportSnapshot(ExportSnapshotCmd),
+
+    /// Verify a snapshot and initialize an empty database with its state.
+    ImportSnapshot(ImportSnapshotCmd),
+
     /// Remove the whole chain.
     PurgeChain(PurgeChainCmd),
 
@@ -1,31 +1,40 @@
-use log::warn;
+use log::{info, warn};
 use sc_cli::arg_enums::SyncMode;
 
 use crate::Cli;
 
-/// Modifies the sync config to ensure only full sync is used.
+/// Translates the requested sync mode into the Aleph warp sync setting.
+///
+/// Substrate only ever runs full sync, as skipping the genesis state would leave us without
+/// the initial authorities. Fast and warp sync instead make the node download AlephBFT
+/// justifications session by session, and the state at the last one, before starting.
+/// Fast sync without state proofs is not supported.
 pub struct SyncConfigValidator {
-    overwritten: Option<SyncMode>,
+    requested: SyncMode,
 }
 
 impl SyncConfigValidator {
     /// Modifies the settings.
     pub fn process(cli: &mut Cli) -> Self {
-        let overwritten = match cli.run.network_params.sync {
-            SyncMode::Full => None,
-            mode => Some(mode),
-        };
+        let requested = cli.run.network_params.sync;
+        if requested != SyncMode::Full {
+            cli.aleph.set_warp_sync();
+        }
         cli.run.network_params.sync = SyncMode::Full;
-        SyncConfigValidator { overwritten }
+        SyncConfigValidator { requested }
     }
 
-    /// Warns the user if they attempted to use a sync setting other than full.
+    /// Informs the user how the requested sync mode is handled.
     pub fn report(self) {
-        if let Some(mode) = self.overwritten {
-            warn!(
-                "Only full sync mode is supported, ignoring request for {:?} mode.",
+        match self.requested {
+            SyncMode::Full => (),
+            SyncMode::FastUnsafe => warn!(
+                "Fast sync without state proofs is not supported, using warp sync with proofs instead."
+            ),
+            mode => info!(
+                "Using warp sync for {:?} sync mode, the state will be downloaded at the last finished session.",
                 mode
-            );
+            ),
         }
     }
 }
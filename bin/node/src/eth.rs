@@ -88,6 +88,22 @@ pub struct EthConfiguration {
 	/// Default value is 200MB.
 	#[arg(long, default_value = "209715200")]
 	pub frontier_sql_backend_cache_size: u64,
+
+	/// Enables the `debug_traceTransaction`, `debug_traceBlockBy*` and `trace_filter` RPCs.
+	///
+	/// Traced blocks are replayed on top of their parent state, so this needs an archive node
+	/// and a runtime built with the `evm-tracing` feature passed through
+	/// `--wasm-runtime-overrides`.
+	#[arg(long)]
+	pub enable_evm_tracing: bool,
+
+	/// Maximum number of blocks replayed by a single `trace_filter` request.
+	#[arg(long, default_value = "100")]
+	pub trace_filter_max_block_range: u32,
+
+	/// Maximum number of traces returned by a single `trace_filter` request.
+	#[arg(long, default_value = "500")]
+	pub trace_filter_max_count: u32,
 }
 
 pub struct FrontierPartialComponents {
@@ -111,6 +127,8 @@ pub trait EthCompatRuntimeApiCollection:
 	sp_api::ApiExt<Block>
 	+ fp_rpc::ConvertTransactionRuntimeApi<Block>
 	+ fp_rpc::EthereumRuntimeRPCApi<Block>
+	+ fp_evm_proof::EvmProofApi<Block>
+	+ fp_evm_tracing::EvmTracingApi<Block>
 {
 }
 
@@ -118,6 +136,8 @@ impl<Api> EthCompatRuntimeApiCollection for Api where
 	Api: sp_api::ApiExt<Block>
 		+ fp_rpc::ConvertTransactionRuntimeApi<Block>
 		+ fp_rpc::EthereumRuntimeRPCApi<Block>
+		+ fp_evm_proof::EvmProofApi<Block>
+		+ fp_evm_tracing::EvmTracingApi<Block>
 {
 }
 
@@ -5,6 +5,7 @@ mod executor;
 mod resources;
 mod rpc;
 pub mod service;
+mod snapshot;
 pub mod eth;
 
 pub use cli::{Cli, Subcommand};
@@ -67,6 +67,28 @@ fn main() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		}
+		Some(Subcommand::ExportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|mut config| {
+				let ServiceComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = new_partial(&mut config, &cli.eth)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		}
+		Some(Subcommand::ImportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|mut config| {
+				let ServiceComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = new_partial(&mut config, &cli.eth)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		}
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| {
@@ -5,7 +5,7 @@ use jsonrpsee::RpcModule;
 use sc_client_api::{
 	backend::{Backend, StorageProvider},
 	client::BlockchainEvents,
-	AuxStore, UsageProvider,
+	AuxStore, BlockBackend, ProofProvider, UsageProvider,
 };
 use sc_network::service::traits::NetworkService;
 use sc_network_sync::SyncingService;
@@ -19,9 +19,11 @@ use sp_core::H256;
 use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::traits::Block as BlockT;
 // Frontier
-pub use fc_rpc::{EthBlockDataCacheTask, EthConfig};
+pub use fc_rpc::{EthBlockDataCacheTask, EthConfig, TraceFilterLimits};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 use fc_storage::StorageOverride;
+use fp_evm_proof::EvmProofApi;
+use fp_evm_tracing::EvmTracingApi;
 use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
 
 /// Extra dependencies for Ethereum compatibility.
@@ -63,6 +65,8 @@ pub struct EthDeps<B: BlockT, C, P, CT, CIDP> {
 	pub forced_parent_hashes: Option<BTreeMap<H256, H256>>,
 	/// Something that can create the inherent data providers for pending state
 	pub pending_create_inherent_data_providers: CIDP,
+	/// Enables the `debug_trace*` and `trace_filter` RPCs with the given limits.
+	pub tracing: Option<TraceFilterLimits>,
 }
 
 /// Instantiate Ethereum-compatible RPC extensions.
@@ -82,8 +86,11 @@ where
 	C::Api: AuraApi<B, AuraId>
 		+ BlockBuilderApi<B>
 		+ ConvertTransactionRuntimeApi<B>
-		+ EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + HeaderMetadata<B, Error = BlockChainError>,
+		+ EthereumRuntimeRPCApi<B>
+		+ EvmProofApi<B>
+		+ EvmTracingApi<B>,
+	C: HeaderBackend<B> + HeaderMetadata<B, Error = BlockChainError> + BlockBackend<B>,
+	C: ProofProvider<B>,
 	C: BlockchainEvents<B> + AuxStore + UsageProvider<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B, Hash = B::Hash> + 'static,
@@ -92,8 +99,9 @@ where
 	EC: EthConfig<B, C>,
 {
 	use fc_rpc::{
-		pending, Debug, DebugApiServer, Eth, EthApiServer, EthDevSigner,
+		pending, Debug, DebugApiServer, DebugTraceApiServer, Eth, EthApiServer, EthDevSigner,
 		EthFilter, EthFilterApiServer, EthPubSub, EthPubSubApiServer, EthSigner, Net, NetApiServer,
+		Trace, TraceApiServer,
 	};
 	#[cfg(feature = "txpool")]
 	use fc_rpc::{TxPool, TxPoolApiServer};
@@ -117,6 +125,7 @@ where
 		execute_gas_limit_multiplier,
 		forced_parent_hashes,
 		pending_create_inherent_data_providers,
+		tracing,
 	} = deps;
 
 	let mut signers = Vec::new();
@@ -186,13 +195,19 @@ where
 	io.merge(
 		Debug::new(
 			client.clone(),
-			frontier_backend,
+			frontier_backend.clone(),
 			storage_override,
-			block_data_cache,
+			block_data_cache.clone(),
 		)
 	.into_rpc(),
 	)?;
 
+	if let Some(limits) = tracing {
+		let trace = Trace::new(client.clone(), frontier_backend, block_data_cache, limits);
+		io.merge(DebugTraceApiServer::into_rpc(trace.clone()))?;
+		io.merge(TraceApiServer::into_rpc(trace))?;
+	}
+
 	#[cfg(feature = "txpool")]
 	io.merge(TxPool::new(client, graph).into_rpc())?;
 
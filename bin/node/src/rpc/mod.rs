@@ -13,7 +13,9 @@ pub use selendra_node_rpc::{SelendraNode, SelendraNodeApiServer};
 
 use std::sync::Arc;
 
-use finality_aleph::{Justification, JustificationTranslator, ValidatorAddressCache};
+use finality_aleph::{
+	AddressRegistrationSigner, Justification, JustificationTranslator, ValidatorAddressCache,
+};
 use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
 use primitives::{AccountId, Balance, Block, Nonce};
@@ -21,7 +23,7 @@ use sp_core::H256;
 use sc_client_api::{
 	backend::{Backend, StorageProvider},
 	client::BlockchainEvents,
-	AuxStore, UsageProvider,
+	AuxStore, BlockBackend, ProofProvider, UsageProvider,
 };
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::{CallApiAt, ProvideRuntimeApi};
@@ -48,6 +50,8 @@ pub struct FullDeps<C, P, CT, CIDP, SO> {
 	pub sync_oracle: SO,
 	/// validator address cache
 	pub validator_address_cache: Option<ValidatorAddressCache>,
+	/// signer of the registrations of the validator network address
+	pub address_registration_signer: AddressRegistrationSigner,
 	/// Ethereum-compatibility specific dependencies.
 	pub eth: EthDeps<Block, C, P, CT, CIDP>,
 }
@@ -79,6 +83,8 @@ where
 		+ ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ BlockBackend<Block>
+		+ ProofProvider<Block>
 		+ BlockchainEvents<Block>
 		+ UsageProvider<Block>
 		+ StorageProvider<Block, BE>
@@ -92,6 +98,10 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
+	C::Api: fp_evm_proof::EvmProofApi<Block>,
+	C::Api: fp_evm_tracing::EvmTracingApi<Block>,
+	C::Api: pallet_aleph_runtime_api::ValidatorPerformanceApi<Block>,
+	C::Api: pallet_aleph_runtime_api::AbftScoresApi<Block>,
 	BE: Backend<Block> + 'static,
 	P: TransactionPool<Block = Block, Hash = H256> + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + Send + 'static,
@@ -109,6 +119,7 @@ where
 		justification_translator,
 		sync_oracle,
 		validator_address_cache,
+		address_registration_signer,
 		eth,
 	} = deps;
 
@@ -123,6 +134,7 @@ where
 			client,
 			sync_oracle,
 			validator_address_cache,
+			address_registration_signer,
 		)
 		.into_rpc(),
 	)?;
@@ -1,18 +1,25 @@
 use std::{collections::HashMap, sync::Arc};
 
 use finality_aleph::{
-    AlephJustification, BlockId, Justification, JustificationTranslator, ValidatorAddressCache,
-    ValidatorAddressingInfo,
+    AddressRegistration, AddressRegistrationSigner, AlephJustification, BlockId, Justification,
+    JustificationTranslator, ValidatorAddressCache, ValidatorAddressingInfo,
 };
 use futures::channel::mpsc;
 use jsonrpsee::{
     core::RpcResult,
     proc_macros::rpc,
     types::error::{ErrorObject, ErrorObjectOwned},
+    Extensions,
 };
+use pallet_aleph_runtime_api::{AbftScoresApi, ValidatorPerformanceApi};
 use parity_scale_codec::Decode;
-use primitives::{AccountId, Block, BlockHash, BlockNumber, Signature};
+use primitives::{
+    AccountId, Block, BlockHash, BlockNumber, SessionIndex, SessionValidatorError, Signature,
+    ValidatorPerformance, ValidatorScores,
+};
 use sc_client_api::StorageProvider;
+use sc_rpc_api::check_if_safe;
+use sp_api::ProvideRuntimeApi;
 use sp_arithmetic::traits::Zero;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
@@ -61,6 +68,12 @@ pub enum Error {
     /// Network info caching is not enabled.
     #[error("Unable to get any data, because network info caching is not enabled.")]
     NetworkInfoCachingNotEnabled,
+    /// Runtime API call failed.
+    #[error("Runtime API call failed at the block {0}: {1}.")]
+    RuntimeApiCallFailed(String, String),
+    /// Requested sessions are not covered by the data available on chain.
+    #[error("Sessions have to start between {0} and {1}.")]
+    SessionNotWithinRange(SessionIndex, SessionIndex),
 }
 
 // Base code for all system errors.
@@ -85,6 +98,10 @@ const FAILED_HEADER_DECODING_ERROR: i32 = BASE_ERROR + 8;
 const UNKNOWN_HASH_ERROR: i32 = BASE_ERROR + 9;
 /// Network info caching is not enabled.
 const NETWORK_INFO_CACHING_NOT_ENABLED_ERROR: i32 = BASE_ERROR + 10;
+/// Runtime API call failed.
+const RUNTIME_API_CALL_FAILED_ERROR: i32 = BASE_ERROR + 11;
+/// Requested sessions are not covered by the data available on chain.
+const SESSION_NOT_WITHIN_RANGE_ERROR: i32 = BASE_ERROR + 12;
 
 impl From<Error> for ErrorObjectOwned {
     fn from(e: Error) -> Self {
@@ -145,6 +162,16 @@ impl From<Error> for ErrorObjectOwned {
                 "Unable to get any data, because network info caching is not enabled.",
                 None::<()>,
             ),
+            Error::RuntimeApiCallFailed(hash, err) => ErrorObject::owned(
+                RUNTIME_API_CALL_FAILED_ERROR,
+                format!("Runtime API call failed at the block {hash}: {err}."),
+                None::<()>,
+            ),
+            Error::SessionNotWithinRange(lower_limit, upper_limit) => ErrorObject::owned(
+                SESSION_NOT_WITHIN_RANGE_ERROR,
+                format!("Sessions have to start between {lower_limit} and {upper_limit}."),
+                None::<()>,
+            ),
         }
     }
 }
@@ -171,6 +198,34 @@ pub trait SelendraNodeApi<BE> {
 
     #[method(name = "unstable_validatorNetworkInfo")]
     fn validator_network_info(&self) -> RpcResult<HashMap<AccountId, ValidatorAddressingInfo>>;
+
+    /// Get the performance of the validator in the given range of sessions, inclusive, as seen
+    /// at the best block.
+    #[method(name = "validatorPerformance")]
+    fn validator_performance(
+        &self,
+        validator: AccountId,
+        first_session: SessionIndex,
+        last_session: SessionIndex,
+    ) -> RpcResult<ValidatorPerformance<AccountId>>;
+
+    /// Get the AlephBFT scores of the finality committee members in the given range of sessions,
+    /// inclusive, grouped by validator, as seen at the best block. Sessions older than the
+    /// retention period of the scores are not reported.
+    #[method(name = "abftScoreHistory")]
+    fn abft_score_history(
+        &self,
+        first_session: SessionIndex,
+        last_session: SessionIndex,
+    ) -> RpcResult<Vec<ValidatorScores<AccountId>>>;
+
+    /// Sign the registration of the validator network address of this node in the latest
+    /// session it is a validator in, to be submitted by `owner` with
+    /// `ValidatorAddresses::register_address`. Returns `null` if the node has not been a
+    /// validator yet. Unsafe, as it signs with the authority key of the node.
+    #[method(name = "signAddressRegistration", with_extensions)]
+    fn sign_address_registration(&self, owner: AccountId)
+        -> RpcResult<Option<AddressRegistration>>;
 }
 
 /// Aleph Node API implementation
@@ -180,6 +235,7 @@ pub struct SelendraNode<Client, SO> {
     client: Arc<Client>,
     sync_oracle: SO,
     validator_address_cache: Option<ValidatorAddressCache>,
+    address_registration_signer: AddressRegistrationSigner,
 }
 
 impl<Client, SO> SelendraNode<Client, SO>
@@ -192,6 +248,7 @@ where
         client: Arc<Client>,
         sync_oracle: SO,
         validator_address_cache: Option<ValidatorAddressCache>,
+        address_registration_signer: AddressRegistrationSigner,
     ) -> Self {
         SelendraNode {
             import_justification_tx,
@@ -199,6 +256,7 @@ where
             client,
             sync_oracle,
             validator_address_cache,
+            address_registration_signer,
         }
     }
 }
@@ -206,7 +264,13 @@ where
 impl<Client, BE, SO> SelendraNodeApiServer<BE> for SelendraNode<Client, SO>
 where
     BE: sc_client_api::Backend<Block> + 'static,
-    Client: HeaderBackend<Block> + StorageProvider<Block, BE> + 'static,
+    Client: HeaderBackend<Block>
+        + StorageProvider<Block, BE>
+        + ProvideRuntimeApi<Block>
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: ValidatorPerformanceApi<Block> + AbftScoresApi<Block>,
     SO: SyncOracle + Send + Sync + 'static,
 {
     fn emergency_finalize(
@@ -273,6 +337,52 @@ where
             .map(|c| c.snapshot())
             .ok_or(Error::NetworkInfoCachingNotEnabled.into())
     }
+
+    fn validator_performance(
+        &self,
+        validator: AccountId,
+        first_session: SessionIndex,
+        last_session: SessionIndex,
+    ) -> RpcResult<ValidatorPerformance<AccountId>> {
+        let best_hash = self.client.info().best_hash;
+        self.client
+            .runtime_api()
+            .validator_performance(best_hash, validator, first_session, last_session)
+            .map_err(|e| Error::RuntimeApiCallFailed(best_hash.to_string(), e.to_string()))?
+            .map_err(|e| match e {
+                SessionValidatorError::SessionNotWithinRange {
+                    lower_limit,
+                    upper_limit,
+                } => Error::SessionNotWithinRange(lower_limit, upper_limit).into(),
+                SessionValidatorError::Other(e) => Error::RuntimeApiCallFailed(
+                    best_hash.to_string(),
+                    String::from_utf8_lossy(&e).into_owned(),
+                )
+                .into(),
+            })
+    }
+
+    fn abft_score_history(
+        &self,
+        first_session: SessionIndex,
+        last_session: SessionIndex,
+    ) -> RpcResult<Vec<ValidatorScores<AccountId>>> {
+        let best_hash = self.client.info().best_hash;
+        self.client
+            .runtime_api()
+            .abft_score_history(best_hash, first_session, last_session)
+            .map_err(|e| Error::RuntimeApiCallFailed(best_hash.to_string(), e.to_string()).into())
+    }
+
+    fn sign_address_registration(
+        &self,
+        ext: &Extensions,
+        owner: AccountId,
+    ) -> RpcResult<Option<AddressRegistration>> {
+        check_if_safe(ext)?;
+        let genesis_hash = self.client.info().genesis_hash;
+        Ok(self.address_registration_signer.sign(&genesis_hash, &owner))
+    }
 }
 
 fn read_storage<
@@ -7,10 +7,10 @@ use std::{
 
 use client_runtime_api::fake_runtime::RuntimeApi;
 use finality_aleph::{
-    build_network, get_selendra_block_import, run_validator_node, AlephConfig, BlockImporter,
-    BuildNetworkOutput, ChannelProvider, FavouriteSelectChainProvider, Justification,
-    JustificationTranslator, MillisecsPerBlock, RateLimiterConfig, RedirectingBlockImport,
-    SessionPeriod, SubstrateChainStatus, SyncOracle, ValidatorAddressCache,
+    build_network, get_selendra_block_import, run_validator_node, AddressRegistrationSigner,
+    AlephConfig, BackupConfig, BlockImporter, BuildNetworkOutput, ChannelProvider, FavouriteSelectChainProvider,
+    Justification, JustificationTranslator, MillisecsPerBlock, RateLimiterConfig,
+    RedirectingBlockImport, SessionPeriod, SubstrateChainStatus, SyncOracle, ValidatorAddressCache,
 };
 use log::warn;
 use pallet_aleph_runtime_api::AlephSessionApi;
@@ -101,6 +101,15 @@ fn backup_path(aleph_config: &AlephCli, base_path: &Path) -> Option<PathBuf> {
 	}
 }
 
+fn backup_config(aleph_config: &AlephCli, base_path: &Path) -> BackupConfig {
+	BackupConfig {
+		path: backup_path(aleph_config, base_path),
+		retention_sessions: aleph_config.backup_retention_sessions(),
+		max_size: aleph_config.backup_max_size(),
+		compression: aleph_config.backup_compression(),
+	}
+}
+
 pub fn new_partial(
 	config: &Configuration,
 	eth_config: &EthConfiguration,
@@ -291,6 +300,9 @@ fn get_rate_limit_config(aleph_config: &AlephCli) -> RateLimiterConfig {
     RateLimiterConfig {
         alephbft_network_bit_rate: aleph_config.alephbft_network_bit_rate(),
         substrate_network_bit_rate: aleph_config.substrate_network_bit_rate(),
+        validator_network_peer_bit_rate: aleph_config.validator_network_peer_bit_rate(),
+        sync_peer_bit_rate: aleph_config.sync_peer_bit_rate(),
+        sync_bandwidth_share: aleph_config.sync_bandwidth_share(),
     }
 }
 
@@ -310,7 +322,7 @@ pub async fn new_authority(
 
 	let mut service_components = new_partial(&config, &eth_config)?;
 
-	let backup_path = backup_path(&aleph_config, config.base_path.path());
+	let backup_config = backup_config(&aleph_config, config.base_path.path());
 
 	let backoff_authoring_blocks = Some(LimitNonfinalized(aleph_config.max_nonfinalized_blocks()));
 	let prometheus_registry = config.prometheus_registry().cloned();
@@ -351,9 +363,15 @@ pub async fn new_authority(
 
 	let import_queue_handle = BlockImporter::new(service_components.import_queue.service());
     let rate_limiter_config = get_rate_limit_config(&aleph_config);
+    let SelendraRuntimeVars {
+        millisecs_per_block,
+        session_period,
+        score_submission_period,
+    } = get_selendra_runtime_vars(&service_components.client);
     let network_config = finality_aleph::SubstrateNetworkConfig {
         substrate_network_bit_rate: rate_limiter_config.substrate_network_bit_rate,
         network_config: config.network.clone(),
+        session_period,
     };
 
 	let BuildNetworkOutput {
@@ -363,6 +381,7 @@ pub async fn new_authority(
 		sync_service,
 		tx_handler_controller,
 		system_rpc_tx,
+		warp_sync_protocols,
 	} = build_network(
         network_config,
 		config.protocol_id(),
@@ -391,6 +410,7 @@ pub async fn new_authority(
 	let chain_status = SubstrateChainStatus::new(service_components.backend.clone())
 		.map_err(|e| ServiceError::Other(format!("failed to set up chain status: {e}")))?;
 	let validator_address_cache = get_validator_address_cache(&aleph_config);
+	let address_registration_signer = AddressRegistrationSigner::new();
 	let role = config.role.clone();
 
 	let rpc_builder = {
@@ -398,6 +418,7 @@ pub async fn new_authority(
 		let pool = service_components.transaction_pool.clone();
 		let sync_oracle = sync_oracle.clone();
 		let validator_address_cache = validator_address_cache.clone();
+		let address_registration_signer = address_registration_signer.clone();
 		let import_justification_tx =
 			service_components.justification_channel_provider.get_sender();
 		let chain_status = chain_status.clone();
@@ -420,6 +441,10 @@ pub async fn new_authority(
 		let is_authority = role.is_authority();
 		let frontier_backend = service_components.frontier_backend.clone();
 		let target_gas_price = eth_config.target_gas_price;
+		let tracing = eth_config.enable_evm_tracing.then_some(fc_rpc::TraceFilterLimits {
+			max_block_range: eth_config.trace_filter_max_block_range,
+			max_count: eth_config.trace_filter_max_count,
+		});
 		let pending_create_inherent_data_providers = move |_, ()| async move {
 			let current = sp_timestamp::InherentDataProvider::from_system_time();
 			let next_slot = current.timestamp().as_millis() + slot_duration.as_millis();
@@ -455,6 +480,7 @@ pub async fn new_authority(
 				execute_gas_limit_multiplier,
 				forced_parent_hashes: None,
 				pending_create_inherent_data_providers,
+				tracing,
 			};
 			let deps = RpcFullDeps {
 				client: client.clone(),
@@ -463,6 +489,7 @@ pub async fn new_authority(
 				justification_translator: JustificationTranslator::new(chain_status.clone()),
 				sync_oracle: sync_oracle.clone(),
 				validator_address_cache: validator_address_cache.clone(),
+				address_registration_signer: address_registration_signer.clone(),
 				eth: eth_deps,
 			};
 
@@ -494,6 +521,16 @@ pub async fn new_authority(
 		service_components.import_queue.run(&NoopLink),
 	);
 
+	let warp_sync = aleph_config.warp_sync().then(|| {
+		finality_aleph::warp_sync(
+			service_components.client.clone(),
+			network.clone(),
+			sync_service.clone(),
+			warp_sync_protocols,
+			sync_oracle.clone(),
+		)
+	});
+
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		network,
 		sync_service,
@@ -514,12 +551,6 @@ pub async fn new_authority(
 		.spawn_essential_handle()
 		.spawn_blocking("aura", None, aura);
 
-    let SelendraRuntimeVars {
-        millisecs_per_block,
-        session_period,
-        score_submission_period,
-    } = get_selendra_runtime_vars(&service_components.client);
-
 	let aleph_config = AlephConfig {
 		authentication_network,
 		block_sync_network,
@@ -536,19 +567,27 @@ pub async fn new_authority(
 		block_rx,
 		registry: prometheus_registry,
 		unit_creation_delay: aleph_config.unit_creation_delay(),
-		backup_saving_path: backup_path,
+		backup_config,
 		external_addresses: aleph_config.external_addresses(),
 		validator_port: aleph_config.validator_port(),
 		rate_limiter_config,
 		sync_oracle,
 		validator_address_cache,
+		address_registration_signer,
 		transaction_pool: service_components.transaction_pool,
 	};
 
 	service_components
         .task_manager
         .spawn_essential_handle()
-        .spawn_blocking("aleph", None, run_validator_node(aleph_config));
+        .spawn_blocking("aleph", None, async move {
+            if let Some(warp_sync) = warp_sync {
+                if let Err(e) = warp_sync.await {
+                    warn!("Warp sync failed, continuing with full sync: {e}");
+                }
+            }
+            run_validator_node(aleph_config).await
+        });
 
 	Ok(service_components.task_manager)
 }
@@ -0,0 +1,115 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use finality_aleph::{export_snapshot, import_snapshot, SnapshotError};
+use log::info;
+use sc_cli::{
+    clap::{self, Parser},
+    CliConfiguration, DatabaseParams, PruningParams, SharedParams,
+};
+
+use crate::service::FullClient;
+
+fn snapshot_error(e: SnapshotError) -> sc_cli::Error {
+    sc_cli::Error::Application(e.to_string().into())
+}
+
+/// The `export-snapshot` command, writing the state at the last finalized session end together
+/// with the justifications proving it final, starting from the genesis authorities.
+#[derive(Debug, Clone, Parser)]
+pub struct ExportSnapshotCmd {
+    /// Output file, stdout if unspecified.
+    #[arg()]
+    pub output: Option<PathBuf>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl ExportSnapshotCmd {
+    pub async fn run(&self, client: Arc<FullClient>) -> sc_cli::Result<()> {
+        let output: Box<dyn Write + Send> = match &self.output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        };
+        let number = export_snapshot(client, BufWriter::new(output)).map_err(snapshot_error)?;
+        info!("Exported a snapshot of block #{number}.");
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ExportSnapshotCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+/// The `import-snapshot` command, verifying a snapshot against the genesis authorities of the
+/// chain spec and initializing an empty database with its state.
+#[derive(Debug, Clone, Parser)]
+pub struct ImportSnapshotCmd {
+    /// Input file, stdin if unspecified.
+    #[arg()]
+    pub input: Option<PathBuf>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl ImportSnapshotCmd {
+    pub async fn run(&self, client: Arc<FullClient>) -> sc_cli::Result<()> {
+        let input: Box<dyn Read + Send> = match &self.input {
+            Some(path) => Box::new(File::open(path)?),
+            None => Box::new(io::stdin()),
+        };
+        let number = import_snapshot(client, BufReader::new(input))
+            .await
+            .map_err(snapshot_error)?;
+        info!("Imported a snapshot of block #{number}, the node will continue syncing from there.");
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ImportSnapshotCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
@@ -4,7 +4,7 @@ mod precompiles;
 
 use crate::{
 	Aura, Balances, DynamicEvmBaseFee, Runtime, RuntimeEvent, Timestamp,
-	NORMAL_DISPATCH_RATIO
+	DAYS, NORMAL_DISPATCH_RATIO
 };
 
 use parity_scale_codec::Encode;
@@ -25,7 +25,7 @@ use pallet_ethereum::PostLogContent;
 
 use precompiles::FrontierPrecompiles;
 use primitives::{
-	TOKEN, AccountId, Balance, BlakeTwo256,
+	TOKEN, AccountId, Balance, BlakeTwo256, BlockNumber,
 	evm::HashedDefaultMappings,
 };
 
@@ -119,14 +119,17 @@ impl pallet_ethereum::Config for Runtime {
 
 parameter_types! {
 	pub const AccountMappingStorageFee: Balance = TOKEN / 100; // 0.01 SEL storage fee
+	pub const MappingChangeCooldown: BlockNumber = DAYS;
 }
 
 impl pallet_unified_accounts::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type Currency = Balances;
 	type DefaultMappings = HashedDefaultMappings<BlakeTwo256>;
 	type ChainId = ChainId;
 	type AccountMappingStorageFee = AccountMappingStorageFee;
+	type MappingChangeCooldown = MappingChangeCooldown;
 	type WeightInfo = pallet_unified_accounts::weights::SubstrateWeight<Runtime>;
 }
 
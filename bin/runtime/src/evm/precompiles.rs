@@ -4,9 +4,22 @@ use sp_std::marker::PhantomData;
 use pallet_evm::{
 	IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
 };
+use pallet_evm_precompile_blake2::Blake2F;
+use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_kzg_point_evaluation::KzgPointEvaluation;
 use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
+use pallet_evm_precompile_staking::StakingPrecompile;
+use pallet_evm_precompile_unified_accounts::UnifiedAccountsPrecompile;
+use pallet_evm_precompile_xvm::XvmPrecompile;
+
+/// Address of the native staking and nomination pools precompile.
+pub const STAKING_PRECOMPILE_ADDRESS: u64 = 20481;
+/// Address of the cross-VM call precompile.
+pub const XVM_PRECOMPILE_ADDRESS: u64 = 20485;
+/// Address of the unified accounts precompile.
+pub const UNIFIED_ACCOUNTS_PRECOMPILE_ADDRESS: u64 = 20486;
 
 pub struct FrontierPrecompiles<R>(PhantomData<R>);
 
@@ -17,13 +30,32 @@ where
 	pub fn new() -> Self {
 		Self(Default::default())
 	}
-	pub fn used_addresses() -> [H160; 7] {
-		[hash(1), hash(2), hash(3), hash(4), hash(5), hash(1024), hash(1025)]
+	pub fn used_addresses() -> [H160; 15] {
+		[
+			hash(1),
+			hash(2),
+			hash(3),
+			hash(4),
+			hash(5),
+			hash(6),
+			hash(7),
+			hash(8),
+			hash(9),
+			hash(10),
+			hash(1024),
+			hash(1025),
+			hash(STAKING_PRECOMPILE_ADDRESS),
+			hash(XVM_PRECOMPILE_ADDRESS),
+			hash(UNIFIED_ACCOUNTS_PRECOMPILE_ADDRESS),
+		]
 	}
 }
 impl<R> PrecompileSet for FrontierPrecompiles<R>
 where
 	R: pallet_evm::Config,
+	StakingPrecompile<R>: Precompile,
+	XvmPrecompile<R, pallet_xvm::Pallet<R>>: Precompile,
+	UnifiedAccountsPrecompile<R>: Precompile,
 {
 	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
 		match handle.code_address() {
@@ -33,9 +65,24 @@ where
 			a if a == hash(3) => Some(Ripemd160::execute(handle)),
 			a if a == hash(4) => Some(Identity::execute(handle)),
 			a if a == hash(5) => Some(Modexp::execute(handle)),
+			a if a == hash(6) => Some(Bn128Add::execute(handle)),
+			a if a == hash(7) => Some(Bn128Mul::execute(handle)),
+			a if a == hash(8) => Some(Bn128Pairing::execute(handle)),
+			a if a == hash(9) => Some(Blake2F::execute(handle)),
+			a if a == hash(10) => Some(KzgPointEvaluation::execute(handle)),
 			// Non-Frontier specific nor Ethereum precompiles :
 			a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
 			a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
+			// Selendra specific precompiles :
+			a if a == hash(STAKING_PRECOMPILE_ADDRESS) => {
+				Some(StakingPrecompile::<R>::execute(handle))
+			}
+			a if a == hash(XVM_PRECOMPILE_ADDRESS) => {
+				Some(XvmPrecompile::<R, pallet_xvm::Pallet<R>>::execute(handle))
+			}
+			a if a == hash(UNIFIED_ACCOUNTS_PRECOMPILE_ADDRESS) => {
+				Some(UnifiedAccountsPrecompile::<R>::execute(handle))
+			}
 			_ => None,
 		}
 	}
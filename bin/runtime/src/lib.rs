@@ -54,14 +54,19 @@ use pallet_ethereum::{
 };
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{
-    crypto::SignatureSet, staking::MAX_NOMINATORS_REWARDED_PER_VALIDATOR, Address,
-    SelendraNodeSessionKeys as SessionKeys, ApiError as SelendraApiError, AuraId, AuthorityId as SelendraId,
-    AuthoritySignature, BlockNumber as SelendraBlockNumber, Header as SelendraHeader, Score,
+    crypto::SignatureSet,
+    staking::{MAX_NOMINATORS_REWARDED_PER_VALIDATOR, MIN_VALIDATOR_BOND},
+    Address,
+    SelendraNodeSessionKeys as SessionKeys, ApiError as SelendraApiError, AuraId,
+    AuthorityId as SelendraId,
+    AuthoritySignature, BlockNumber as SelendraBlockNumber, ElectionCandidates,
+    Header as SelendraHeader, Score,
     SessionAuthorityData, SessionCommittee, SessionIndex, SessionInfoProvider,
-    SessionValidatorError, TotalIssuanceProvider as TotalIssuanceProviderT,
-    Version as FinalityVersion, ADDRESSES_ENCODING, DEFAULT_BAN_REASON_LENGTH, DEFAULT_MAX_WINNERS,
+    SessionValidatorError, TotalIssuanceProvider as TotalIssuanceProviderT, ValidatorPerformance,
+    ValidatorScores, Version as FinalityVersion, ADDRESSES_ENCODING, DEFAULT_BAN_REASON_LENGTH, DEFAULT_MAX_WINNERS,
     DEFAULT_SESSIONS_PER_ERA, DEFAULT_SESSION_PERIOD, MAX_BLOCK_SIZE, MILLISECS_PER_BLOCK,
-    SCORE_SUBMISSION_PERIOD, TOKEN, SLOT_DURATION,
+    SCORE_SUBMISSION_PERIOD, TOKEN, SLOT_DURATION, MAX_VALIDATOR_ADDRESS_LENGTH,
+    xvm::{CallResult, Context as XvmContext, VmId, XvmCall},
 };
 pub use primitives::{AccountId, AccountIndex, Balance, Hash, Nonce, Signature};
 
@@ -93,7 +98,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: Cow::Borrowed("selendra"),
 	impl_name: Cow::Borrowed("selendra"),
 	authoring_version: 1,
-	spec_version: 20016,
+	spec_version: 20017,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -417,6 +422,9 @@ parameter_types! {
     pub const SessionPeriod: u32 = DEFAULT_SESSION_PERIOD;
     pub const MaximumBanReasonLength: u32 = DEFAULT_BAN_REASON_LENGTH;
     pub const MaxWinners: u32 = DEFAULT_MAX_WINNERS;
+    pub const ValidatorIntentDeposit: Balance = 100 * TOKEN;
+    pub const MaxValidatorCommissionCap: Perbill = Perbill::from_percent(50);
+    pub const MinValidatorSelfBond: Balance = MIN_VALIDATOR_BOND;
 }
 
 impl pallet_elections::Config for Runtime {
@@ -431,6 +439,13 @@ impl pallet_elections::Config for Runtime {
         EnsureRoot<AccountId>,
         EnsureThreeFifthsCouncil,
     >;
+    type SessionKeysProvider = Session;
+    type StakingInfoProvider = Staking;
+    type Currency = Balances;
+    type ValidatorIntentDeposit = ValidatorIntentDeposit;
+    type MaxCommissionCap = MaxValidatorCommissionCap;
+    type MinSelfBond = MinValidatorSelfBond;
+    type WeightInfo = pallet_elections::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_operations::Config for Runtime {
@@ -442,6 +457,22 @@ impl pallet_operations::Config for Runtime {
     type ContractInfoProvider = Contracts;
 }
 
+parameter_types! {
+    // Covers the storage of an address of the maximal length.
+    pub const ValidatorAddressDeposit: Balance =
+        (MAX_VALIDATOR_ADDRESS_LENGTH as Balance + 128) * LEGACY_DEPOSIT_PER_BYTE;
+}
+
+impl pallet_validator_addresses::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type AuthorityId = SelendraId;
+    type Currency = Balances;
+    type AddressDeposit = ValidatorAddressDeposit;
+    type MaxAddressLength = ConstU32<MAX_VALIDATOR_ADDRESS_LENGTH>;
+    type SessionInfoProvider = SessionInfoImpl;
+    type WeightInfo = pallet_validator_addresses::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_committee_management::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     // Allow either Root or a 3/5 Council majority to manage committee settings
@@ -454,9 +485,11 @@ impl pallet_committee_management::Config for Runtime {
     type ValidatorProvider = Elections;
     type ValidatorRewardsHandler = Staking;
     type ValidatorExtractor = Staking;
+    type ValidatorSlasher = Staking;
     type FinalityCommitteeManager = Aleph;
     type SessionPeriod = SessionPeriod;
     type AbftScoresProvider = Aleph;
+    type Randomness = pallet_committee_management::RecentBlocksRandomness<Runtime>;
     type MaxValidators = ConstU32<1000>;
     type MaxValidatorRewards = ConstU32<1000>;
 }
@@ -948,7 +981,7 @@ impl pallet_contracts::Config for Runtime {
     type CallFilter = ();
     type WeightPrice = pallet_transaction_payment::Pallet<Self>;
     type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-    type ChainExtension = ();
+    type ChainExtension = pallet_chain_extension_xvm::XvmExtension<Self, Xvm>;
     type Schedule = Schedule;
     type CallStack = [pallet_contracts::Frame<Self>; 16];
     type DepositPerByte = DepositPerByte;
@@ -1206,6 +1239,7 @@ construct_runtime!(
 		CommitteeManagement: pallet_committee_management = 15,
         Treasury: pallet_treasury = 16,
         NominationPools: pallet_nomination_pools = 18,
+        ValidatorAddresses: pallet_validator_addresses = 19,
 
         // Governance
         Council: pallet_collective::<Instance1> = 30,
@@ -1340,7 +1374,10 @@ pub type Executive = frame_executive::Executive<
 /// NOTE: If you encounter `invalid_version` panic during runtime upgrade,
 /// it means the on-chain storage version doesn't match what the migration expects.
 /// Either remove the migration (if pallet is unused) or provide the correct version.
-pub type Migrations = ();
+pub type Migrations = (
+    pallet_aleph::migration::v3::Migration<Runtime>,
+    pallet_elections::migration::v6::Migration<Runtime>,
+);
 
 #[derive(Clone)]
 pub struct TransactionConverter;
@@ -1427,7 +1464,11 @@ impl fp_self_contained::SelfContainedCall for RuntimeCall {
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benches {
-    frame_benchmarking::define_benchmarks!();
+    frame_benchmarking::define_benchmarks!(
+        [pallet_elections, Elections]
+        [pallet_unified_accounts, UnifiedAccounts]
+        [pallet_validator_addresses, ValidatorAddresses]
+    );
 }
 
 type EventRecord = frame_system::EventRecord<RuntimeEvent, Hash>;
@@ -1631,6 +1672,59 @@ impl_runtime_apis! {
         }
 	}
 
+	impl pallet_aleph_runtime_api::ValidatorPerformanceApi<Block> for Runtime {
+		fn validator_performance(
+			validator: AccountId,
+			first_session: SessionIndex,
+			last_session: SessionIndex,
+		) -> Result<ValidatorPerformance<AccountId>, SessionValidatorError> {
+			CommitteeManagement::validator_performance(
+				validator,
+				first_session,
+				last_session,
+				Session::current_index(),
+			)
+		}
+	}
+
+	impl pallet_aleph_runtime_api::AbftScoresApi<Block> for Runtime {
+		fn abft_score_history(
+			first_session: SessionIndex,
+			last_session: SessionIndex,
+		) -> Vec<ValidatorScores<AccountId>> {
+			Aleph::abft_score_history(first_session, last_session)
+		}
+	}
+
+	impl pallet_aleph_runtime_api::ElectionsApi<Block> for Runtime {
+		fn election_candidates() -> ElectionCandidates<AccountId> {
+			Elections::election_candidates()
+		}
+	}
+
+	impl pallet_aleph_runtime_api::XvmApi<Block> for Runtime {
+		fn xvm_call(
+			source: AccountId,
+			vm_id: VmId,
+			target: Vec<u8>,
+			input: Vec<u8>,
+			value: Balance,
+			weight_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+		) -> CallResult {
+			let source_vm_id = match vm_id {
+				VmId::Evm => VmId::Wasm,
+				VmId::Wasm => VmId::Evm,
+			};
+			let max_block = BlockWeights::get().max_block;
+			let context = XvmContext {
+				source_vm_id,
+				weight_limit: weight_limit.map_or(max_block, |limit| limit.min(max_block)),
+			};
+			Xvm::call(context, vm_id, source, target, input, value, storage_deposit_limit)
+		}
+	}
+
     impl pallet_nomination_pools_runtime_api::NominationPoolsApi<Block, AccountId, Balance> for Runtime {
         fn pending_rewards(member: AccountId) -> Balance {
             NominationPools::api_pending_rewards(member).unwrap_or_default()
@@ -1885,6 +1979,83 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl fp_evm_proof::EvmProofApi<Block> for Runtime {
+		fn system_account_key(address: H160) -> Vec<u8> {
+			use pallet_evm::AddressMapping;
+			let account_id = <Runtime as pallet_evm::Config>::AddressMapping::into_account_id(address);
+			frame_system::Account::<Runtime>::hashed_key_for(account_id)
+		}
+	}
+
+	impl fp_evm_tracing::EvmTracingApi<Block> for Runtime {
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &EthereumTransaction,
+			config: fp_evm_tracing::TracerConfig,
+		) -> Result<fp_evm_tracing::TransactionTrace, sp_runtime::DispatchError> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				for ext in extrinsics.into_iter() {
+					match &ext.0.function {
+						RuntimeCall::Ethereum(transact { transaction: tx }) if tx == transaction => {
+							let (_, trace) = pallet_evm::runner::tracing::trace(config, || {
+								Executive::apply_extrinsic(ext)
+							});
+							return Ok(trace);
+						}
+						_ => {
+							let _ = Executive::apply_extrinsic(ext);
+						}
+					}
+				}
+				Err(sp_runtime::DispatchError::Other(
+					"Failed to find Ethereum transaction among the extrinsics.",
+				))
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			{
+				let _ = (extrinsics, transaction, config);
+				Err(sp_runtime::DispatchError::Other(
+					"Runtime built without the `evm-tracing` feature.",
+				))
+			}
+		}
+
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			known_transactions: Vec<H256>,
+			config: fp_evm_tracing::TracerConfig,
+		) -> Result<Vec<fp_evm_tracing::TransactionTrace>, sp_runtime::DispatchError> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				let mut traces = Vec::new();
+				for ext in extrinsics.into_iter() {
+					match &ext.0.function {
+						RuntimeCall::Ethereum(transact { transaction })
+							if known_transactions.contains(&transaction.hash()) =>
+						{
+							let (_, trace) = pallet_evm::runner::tracing::trace(config, || {
+								Executive::apply_extrinsic(ext)
+							});
+							traces.push(trace);
+						}
+						_ => {
+							let _ = Executive::apply_extrinsic(ext);
+						}
+					}
+				}
+				Ok(traces)
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			{
+				let _ = (extrinsics, known_transactions, config);
+				Err(sp_runtime::DispatchError::Other(
+					"Runtime built without the `evm-tracing` feature.",
+				))
+			}
+		}
+	}
+
     impl pallet_contracts::ContractsApi<Block, AccountId, Balance, SelendraBlockNumber, Hash, EventRecord>
         for Runtime
     {
@@ -0,0 +1,106 @@
+//! Types of the XVM chain extension.
+//!
+//! Kept free of runtime dependencies so ink! contracts can depend on this crate to build calls
+//! and decode results.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Id of the XVM chain extension, the upper two bytes of the id passed by the contract.
+pub const XVM_EXTENSION_ID: u16 = 1;
+
+/// Function ids of the XVM chain extension.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XvmFuncId {
+    /// Call a contract in another VM.
+    Call = 1,
+}
+
+impl TryFrom<u16> for XvmFuncId {
+    type Error = ();
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(XvmFuncId::Call),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Arguments of `XvmFuncId::Call`.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct XvmCallArgs {
+    /// Id of the target VM, `0x0F` for EVM.
+    pub vm_id: u8,
+    /// Address of the target contract, 20 bytes for EVM.
+    pub to: Vec<u8>,
+    /// Input of the call, ABI encoded for EVM.
+    pub input: Vec<u8>,
+    /// Value transferred to the target contract.
+    pub value: u128,
+}
+
+/// Status code returned by the XVM chain extension.
+///
+/// On `Ok` the output buffer holds the output of the call, on `VmRevert` it holds the revert
+/// data of the target contract.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum XvmExecutionResult {
+    /// The call succeeded.
+    Ok = 0,
+    /// The target contract reverted.
+    VmRevert = 1,
+    /// The target address is not valid for the VM.
+    InvalidTarget = 2,
+    /// The input is too large.
+    InputTooLarge = 3,
+    /// The VM id is not known.
+    InvalidVmId = 4,
+    /// Calls into the same VM are not allowed.
+    SameVmCallDenied = 5,
+    /// Reentrant XVM calls are not allowed.
+    ReentranceDenied = 6,
+    /// The target VM failed to execute the call.
+    VmError = 7,
+    /// The call ran out of weight.
+    OutOfGas = 8,
+    /// The call is not allowed in a read-only context.
+    ReadOnly = 9,
+}
+
+impl From<XvmExecutionResult> for u32 {
+    fn from(result: XvmExecutionResult) -> u32 {
+        result as u32
+    }
+}
+
+impl TryFrom<u32> for XvmExecutionResult {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        use XvmExecutionResult::*;
+        [
+            Ok,
+            VmRevert,
+            InvalidTarget,
+            InputTooLarge,
+            InvalidVmId,
+            SameVmCallDenied,
+            ReentranceDenied,
+            VmError,
+            OutOfGas,
+            ReadOnly,
+        ]
+        .into_iter()
+        .find(|result| *result as u32 == value)
+        .ok_or(())
+    }
+}
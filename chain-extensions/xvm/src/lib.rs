@@ -0,0 +1,133 @@
+//! Chain extension letting ink! contracts make cross-VM calls through `pallet_xvm`.
+//!
+//! The calling contract acts as the source of the XVM call. The worst case weight is charged up
+//! front and adjusted down to the weight actually used by the call. The status code of the call
+//! is returned as [`XvmExecutionResult`], while the output or revert data of the target contract
+//! is written to the output buffer.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{
+    ChainExtension, Environment, Ext, InitState, RegisteredChainExtension,
+    Result as DispatchResult, RetVal,
+};
+use primitives::xvm::{Context, FailureError, FailureReason, FailureRevert, VmId, XvmCall};
+use sp_runtime::DispatchError;
+use sp_std::marker::PhantomData;
+pub use xvm_chain_extension_types::{XvmCallArgs, XvmExecutionResult, XvmFuncId, XVM_EXTENSION_ID};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Maps an XVM failure to the status code returned to the contract.
+pub fn execution_result(reason: &FailureReason) -> XvmExecutionResult {
+    match reason {
+        FailureReason::Revert(FailureRevert::VmRevert(_)) => XvmExecutionResult::VmRevert,
+        FailureReason::Revert(FailureRevert::InvalidTarget) => XvmExecutionResult::InvalidTarget,
+        FailureReason::Revert(FailureRevert::InputTooLarge) => XvmExecutionResult::InputTooLarge,
+        FailureReason::Error(FailureError::InvalidVmId) => XvmExecutionResult::InvalidVmId,
+        FailureReason::Error(FailureError::SameVmCallDenied) => {
+            XvmExecutionResult::SameVmCallDenied
+        }
+        FailureReason::Error(FailureError::ReentranceDenied) => {
+            XvmExecutionResult::ReentranceDenied
+        }
+        FailureReason::Error(FailureError::VmError(_)) => XvmExecutionResult::VmError,
+        FailureReason::Error(FailureError::OutOfGas) => XvmExecutionResult::OutOfGas,
+    }
+}
+
+/// XVM chain extension.
+pub struct XvmExtension<T, XC>(PhantomData<(T, XC)>);
+
+impl<T, XC> Default for XvmExtension<T, XC> {
+    fn default() -> Self {
+        XvmExtension(PhantomData)
+    }
+}
+
+impl<T, XC> ChainExtension<T> for XvmExtension<T, XC>
+where
+    T: pallet_contracts::Config,
+    XC: XvmCall<T::AccountId>,
+{
+    fn call<E: Ext<T = T>>(&mut self, env: Environment<E, InitState>) -> DispatchResult<RetVal> {
+        let func_id = XvmFuncId::try_from(env.func_id())
+            .map_err(|_| DispatchError::Other("Unsupported XVM chain extension function"))?;
+
+        match func_id {
+            XvmFuncId::Call => {
+                let mut env = env.buf_in_buf_out();
+
+                if env.ext().is_read_only() {
+                    return Ok(RetVal::Converging(XvmExecutionResult::ReadOnly.into()));
+                }
+
+                // Charge the worst case up front, it's adjusted once the actual weight is known.
+                let weight_limit = env.ext().gas_meter().gas_left();
+                let charged_weight = env.charge_weight(weight_limit)?;
+
+                let len = env.in_len();
+                let XvmCallArgs {
+                    vm_id,
+                    to,
+                    input,
+                    value,
+                } = env.read_as_unbounded(len)?;
+
+                let vm_id = match VmId::try_from(vm_id) {
+                    Ok(vm_id) => vm_id,
+                    Err(_) => {
+                        env.adjust_weight(charged_weight, Weight::zero());
+                        return Ok(RetVal::Converging(XvmExecutionResult::InvalidVmId.into()));
+                    }
+                };
+                let source = env.ext().address().clone();
+                let context = Context {
+                    source_vm_id: VmId::Wasm,
+                    weight_limit,
+                };
+
+                log::trace!(
+                    target: "xvm-extension::xvm_call",
+                    "vm_id: {:?}, source: {:?}, value: {:?}",
+                    vm_id, source, value,
+                );
+
+                let call_result = XC::call(context, vm_id, source, to, input, value, None);
+
+                let used_weight = match &call_result {
+                    Ok(success) => success.used_weight,
+                    Err(failure) => failure.used_weight,
+                };
+                env.adjust_weight(charged_weight, used_weight);
+
+                match call_result {
+                    Ok(success) => {
+                        env.write(&success.output, false, None)?;
+                        Ok(RetVal::Converging(XvmExecutionResult::Ok.into()))
+                    }
+                    Err(failure) => {
+                        let result = execution_result(&failure.reason);
+                        if let FailureReason::Revert(FailureRevert::VmRevert(data)) = failure.reason
+                        {
+                            env.write(&data, false, None)?;
+                        }
+                        Ok(RetVal::Converging(result.into()))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T, XC> RegisteredChainExtension<T> for XvmExtension<T, XC>
+where
+    T: pallet_contracts::Config,
+    XC: XvmCall<T::AccountId>,
+{
+    const ID: u16 = XVM_EXTENSION_ID;
+}
@@ -0,0 +1,361 @@
+use std::cell::RefCell;
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstBool, ConstU128, ConstU32, ConstU64, Everything, Randomness},
+    weights::Weight,
+};
+use frame_system::EnsureSigned;
+use pallet_contracts::{Code, CollectEvents, DebugInfo, Determinism};
+use parity_scale_codec::Encode;
+use primitives::{
+    xvm::{CallFailure, CallOutput, CallResult},
+    Balance,
+};
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+    traits::{AccountIdLookup, BlakeTwo256},
+    AccountId32, BuildStorage, Perbill,
+};
+
+use super::*;
+
+pub type AccountId = AccountId32;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub struct Runtime {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        Balances: pallet_balances,
+        Contracts: pallet_contracts,
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Nonce = u64;
+    type RuntimeCall = RuntimeCall;
+    type Block = Block;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = AccountIdLookup<AccountId, ()>;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+    type RuntimeTask = RuntimeTask;
+    type SingleBlockMigrations = ();
+    type MultiBlockMigrator = ();
+    type PreInherents = ();
+    type PostInherents = ();
+    type PostTransactions = ();
+    type ExtensionsWeightInfo = ();
+}
+
+impl pallet_balances::Config for Runtime {
+    type MaxLocks = ConstU32<4>;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type FreezeIdentifier = ();
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type MaxFreezes = ConstU32<0>;
+    type DoneSlashHandler = ();
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<3>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+    pub CodeHashLockupDepositPercent: Perbill = Perbill::from_percent(10);
+}
+
+pub struct DummyDeprecatedRandomness;
+impl Randomness<H256, u64> for DummyDeprecatedRandomness {
+    fn random(_: &[u8]) -> (H256, u64) {
+        (Default::default(), 0)
+    }
+}
+
+impl pallet_contracts::Config for Runtime {
+    type Time = Timestamp;
+    type Randomness = DummyDeprecatedRandomness;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type CallFilter = ();
+    type WeightPrice = ();
+    type WeightInfo = ();
+    type ChainExtension = XvmExtension<Self, MockXvm>;
+    type Schedule = Schedule;
+    type CallStack = [pallet_contracts::Frame<Self>; 5];
+    type DepositPerByte = ConstU128<1>;
+    type DefaultDepositLimit = ConstU128<{ u128::MAX }>;
+    type DepositPerItem = ConstU128<1>;
+    type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+    type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+    type MaxStorageKeyLen = ConstU32<128>;
+    type UploadOrigin = EnsureSigned<AccountId>;
+    type InstantiateOrigin = EnsureSigned<AccountId>;
+    type UnsafeUnstableInterface = ConstBool<false>;
+    type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type Migrations = ();
+    type MaxDelegateDependencies = ConstU32<32>;
+    type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+    type MaxTransientStorageSize = ConstU32<{ 1024 * 1024 }>;
+    type Debug = ();
+    type Environment = ();
+    type ApiVersion = ();
+    type Xcm = ();
+}
+
+/// Arguments `MockXvm` was called with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCall {
+    pub context: Context,
+    pub vm_id: VmId,
+    pub source: AccountId,
+    pub target: Vec<u8>,
+    pub input: Vec<u8>,
+    pub value: Balance,
+    pub storage_deposit_limit: Option<Balance>,
+}
+
+thread_local! {
+    static CALL_RESULT: RefCell<CallResult> =
+        RefCell::new(Ok(CallOutput::new(vec![], Weight::zero())));
+    static RECORDED_CALL: RefCell<Option<RecordedCall>> = RefCell::new(None);
+}
+
+/// `XvmCall` returning a preset result and recording its arguments.
+pub struct MockXvm;
+
+impl MockXvm {
+    pub fn set_result(result: CallResult) {
+        CALL_RESULT.with(|r| *r.borrow_mut() = result);
+    }
+
+    pub fn recorded_call() -> Option<RecordedCall> {
+        RECORDED_CALL.with(|c| c.borrow().clone())
+    }
+}
+
+impl XvmCall<AccountId> for MockXvm {
+    fn call(
+        context: Context,
+        vm_id: VmId,
+        source: AccountId,
+        target: Vec<u8>,
+        input: Vec<u8>,
+        value: Balance,
+        storage_deposit_limit: Option<Balance>,
+    ) -> CallResult {
+        RECORDED_CALL.with(|c| {
+            *c.borrow_mut() = Some(RecordedCall {
+                context,
+                vm_id,
+                source,
+                target,
+                input,
+                value,
+                storage_deposit_limit,
+            })
+        });
+        CALL_RESULT.with(|r| r.borrow().clone())
+    }
+}
+
+/// Calls the chain extension with the id and input passed to `call`, and returns the status
+/// code followed by the output buffer.
+const EXTENSION_CALLER: &str = r#"
+(module
+    (import "seal0" "input" (func $input (param i32 i32)))
+    (import "seal0" "call_chain_extension"
+        (func $call_chain_extension (param i32 i32 i32 i32 i32) (result i32)))
+    (import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+    (import "env" "memory" (memory 1 1))
+
+    ;; [0, 4) input length
+    ;; [4, 8) output length
+    ;; [8, 12) status code, followed by the output buffer
+    ;; [2048, ..) input: extension id followed by the extension input
+
+    (func (export "deploy"))
+
+    (func (export "call")
+        (i32.store (i32.const 0) (i32.const 1024))
+        (call $input (i32.const 2048) (i32.const 0))
+        (i32.store (i32.const 4) (i32.const 1024))
+        (i32.store (i32.const 8)
+            (call $call_chain_extension
+                (i32.load (i32.const 2048))
+                (i32.const 2052)
+                (i32.sub (i32.load (i32.const 0)) (i32.const 4))
+                (i32.const 12)
+                (i32.const 4)))
+        (call $seal_return
+            (i32.const 0)
+            (i32.const 8)
+            (i32.add (i32.load (i32.const 4)) (i32.const 4)))
+    )
+)
+"#;
+
+/// Tail calls the contract whose address starts the input with the rest of the input, in
+/// read-only mode.
+const READ_ONLY_CALLER: &str = r#"
+(module
+    (import "seal0" "input" (func $input (param i32 i32)))
+    (import "seal1" "call"
+        (func $call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+    (import "env" "memory" (memory 1 1))
+
+    ;; [0, 4) input length
+    ;; [16, 32) zero value
+    ;; [2048, 2080) callee, followed by its input
+
+    (func (export "deploy"))
+
+    (func (export "call")
+        (i32.store (i32.const 0) (i32.const 1024))
+        (call $input (i32.const 2048) (i32.const 0))
+        (drop
+            (call $call
+                (i32.const 20) ;; READ_ONLY | TAIL_CALL
+                (i32.const 2048)
+                (i64.const 0)
+                (i32.const 16)
+                (i32.const 2080)
+                (i32.sub (i32.load (i32.const 0)) (i32.const 32))
+                (i32.const -1) ;; skip the output
+                (i32.const 0)))
+    )
+)
+"#;
+
+pub const ALICE: AccountId = AccountId32::new([1u8; 32]);
+pub const GAS_LIMIT: Weight = Weight::from_parts(100_000_000_000, 3 * 1024 * 1024);
+
+/// Id passed to `call_chain_extension` for `func_id` of the XVM extension.
+pub fn extension_id(func_id: u16) -> u32 {
+    ((XVM_EXTENSION_ID as u32) << 16) | func_id as u32
+}
+
+fn deploy(wat: &str) -> AccountId {
+    let code = wat::parse_str(wat).expect("valid fixture");
+    Contracts::bare_instantiate(
+        ALICE,
+        0,
+        GAS_LIMIT,
+        None,
+        Code::Upload(code),
+        vec![],
+        vec![],
+        DebugInfo::Skip,
+        CollectEvents::Skip,
+    )
+    .result
+    .expect("fixture instantiates")
+    .account_id
+}
+
+pub fn deploy_extension_caller() -> AccountId {
+    deploy(EXTENSION_CALLER)
+}
+
+pub fn deploy_read_only_caller() -> AccountId {
+    deploy(READ_ONLY_CALLER)
+}
+
+/// Result of calling the chain extension through a fixture contract.
+pub struct ExtensionOutcome {
+    pub status: u32,
+    pub output: Vec<u8>,
+    pub gas_consumed: Weight,
+}
+
+pub fn call_contract(contract: AccountId, data: Vec<u8>) -> ExtensionOutcome {
+    let result = Contracts::bare_call(
+        ALICE,
+        contract,
+        0,
+        GAS_LIMIT,
+        None,
+        data,
+        DebugInfo::Skip,
+        CollectEvents::Skip,
+        Determinism::Enforced,
+    );
+    let returned = result.result.expect("contract call succeeds").data;
+    let (status, output) = returned.split_at(4);
+    ExtensionOutcome {
+        status: u32::from_le_bytes(status.try_into().expect("status code")),
+        output: output.to_vec(),
+        gas_consumed: result.gas_consumed,
+    }
+}
+
+/// Calls `XvmFuncId::Call` through the `EXTENSION_CALLER` fixture.
+pub fn call_xvm(contract: &AccountId, args: &XvmCallArgs) -> ExtensionOutcome {
+    let mut data = extension_id(XvmFuncId::Call as u16).to_le_bytes().to_vec();
+    data.extend(args.encode());
+    call_contract(contract.clone(), data)
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+    pub fn build(self) -> TestExternalities {
+        MockXvm::set_result(Ok(CallOutput::new(vec![], Weight::zero())));
+        RECORDED_CALL.with(|c| *c.borrow_mut() = None);
+
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(ALICE, 1_000_000_000_000)],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        let mut ext = TestExternalities::from(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
+
+/// Failure returned by `MockXvm` with the given reason and used weight.
+pub fn failure(reason: FailureReason, used_weight: Weight) -> CallFailure {
+    CallFailure {
+        reason,
+        used_weight,
+    }
+}
@@ -0,0 +1,251 @@
+use parity_scale_codec::{Decode, Encode};
+
+use super::*;
+
+#[test]
+fn failures_map_to_distinct_status_codes() {
+    let cases = [
+        (
+            FailureReason::Revert(FailureRevert::VmRevert(vec![1, 2])),
+            XvmExecutionResult::VmRevert,
+        ),
+        (
+            FailureReason::Revert(FailureRevert::InvalidTarget),
+            XvmExecutionResult::InvalidTarget,
+        ),
+        (
+            FailureReason::Revert(FailureRevert::InputTooLarge),
+            XvmExecutionResult::InputTooLarge,
+        ),
+        (
+            FailureReason::Error(FailureError::InvalidVmId),
+            XvmExecutionResult::InvalidVmId,
+        ),
+        (
+            FailureReason::Error(FailureError::SameVmCallDenied),
+            XvmExecutionResult::SameVmCallDenied,
+        ),
+        (
+            FailureReason::Error(FailureError::ReentranceDenied),
+            XvmExecutionResult::ReentranceDenied,
+        ),
+        (
+            FailureReason::Error(FailureError::VmError(vec![])),
+            XvmExecutionResult::VmError,
+        ),
+        (
+            FailureReason::Error(FailureError::OutOfGas),
+            XvmExecutionResult::OutOfGas,
+        ),
+    ];
+
+    for (reason, expected) in cases {
+        let result = execution_result(&reason);
+        assert_eq!(result, expected);
+        assert_ne!(u32::from(result), u32::from(XvmExecutionResult::Ok));
+    }
+}
+
+#[test]
+fn status_codes_round_trip() {
+    for code in 0..10u32 {
+        let result = XvmExecutionResult::try_from(code).expect("known status code");
+        assert_eq!(u32::from(result), code);
+    }
+    assert!(XvmExecutionResult::try_from(10).is_err());
+}
+
+#[test]
+fn call_args_are_scale_encoded() {
+    let args = XvmCallArgs {
+        vm_id: VmId::Evm as u8,
+        to: vec![0xAA; 20],
+        input: vec![0x12, 0x34],
+        value: 7,
+    };
+
+    assert_eq!(XvmCallArgs::decode(&mut &args.encode()[..]), Ok(args));
+}
+
+mod dispatch {
+    use frame_support::weights::Weight;
+    use pallet_contracts::{CollectEvents, DebugInfo, Determinism};
+    use primitives::xvm::CallOutput;
+
+    use super::*;
+    use crate::mock::*;
+
+    fn evm_call_args() -> XvmCallArgs {
+        XvmCallArgs {
+            vm_id: VmId::Evm as u8,
+            to: vec![0xAA; 20],
+            input: vec![0x12, 0x34],
+            value: 0,
+        }
+    }
+
+    #[test]
+    fn call_is_forwarded_with_the_contract_as_source() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+            MockXvm::set_result(Ok(CallOutput::new(vec![1, 2, 3], Weight::zero())));
+
+            let outcome = call_xvm(&contract, &evm_call_args());
+
+            assert_eq!(outcome.status, u32::from(XvmExecutionResult::Ok));
+            assert_eq!(outcome.output, vec![1, 2, 3]);
+
+            let call = MockXvm::recorded_call().expect("XVM was called");
+            assert_eq!(call.context.source_vm_id, VmId::Wasm);
+            assert!(call.context.weight_limit.ref_time() > 0);
+            assert!(call.context.weight_limit.ref_time() < GAS_LIMIT.ref_time());
+            assert_eq!(call.vm_id, VmId::Evm);
+            assert_eq!(call.source, contract);
+            assert_eq!(call.target, vec![0xAA; 20]);
+            assert_eq!(call.input, vec![0x12, 0x34]);
+            assert_eq!(call.value, 0);
+            assert_eq!(call.storage_deposit_limit, None);
+        });
+    }
+
+    #[test]
+    fn weight_used_by_the_call_is_charged() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+            let used_weight = Weight::from_parts(1_000_000_000, 0);
+
+            MockXvm::set_result(Ok(CallOutput::new(vec![], Weight::zero())));
+            let free = call_xvm(&contract, &evm_call_args());
+            MockXvm::set_result(Ok(CallOutput::new(vec![], used_weight)));
+            let charged = call_xvm(&contract, &evm_call_args());
+
+            assert_eq!(
+                charged.gas_consumed.ref_time() - free.gas_consumed.ref_time(),
+                used_weight.ref_time()
+            );
+        });
+    }
+
+    #[test]
+    fn weight_used_by_a_failed_call_is_charged() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+            let used_weight = Weight::from_parts(1_000_000_000, 0);
+
+            MockXvm::set_result(Err(failure(
+                FailureReason::Error(FailureError::VmError(vec![])),
+                Weight::zero(),
+            )));
+            let free = call_xvm(&contract, &evm_call_args());
+            MockXvm::set_result(Err(failure(
+                FailureReason::Error(FailureError::VmError(vec![])),
+                used_weight,
+            )));
+            let charged = call_xvm(&contract, &evm_call_args());
+
+            assert_eq!(charged.status, u32::from(XvmExecutionResult::VmError));
+            assert_eq!(
+                charged.gas_consumed.ref_time() - free.gas_consumed.ref_time(),
+                used_weight.ref_time()
+            );
+        });
+    }
+
+    #[test]
+    fn revert_data_is_written_to_the_output() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+            MockXvm::set_result(Err(failure(
+                FailureReason::Revert(FailureRevert::VmRevert(vec![0xde, 0xad])),
+                Weight::zero(),
+            )));
+
+            let outcome = call_xvm(&contract, &evm_call_args());
+
+            assert_eq!(outcome.status, u32::from(XvmExecutionResult::VmRevert));
+            assert_eq!(outcome.output, vec![0xde, 0xad]);
+        });
+    }
+
+    #[test]
+    fn call_failures_are_returned_as_status_codes() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+
+            for (reason, expected) in [
+                (
+                    FailureReason::Revert(FailureRevert::InvalidTarget),
+                    XvmExecutionResult::InvalidTarget,
+                ),
+                (
+                    FailureReason::Error(FailureError::ReentranceDenied),
+                    XvmExecutionResult::ReentranceDenied,
+                ),
+                (
+                    FailureReason::Error(FailureError::OutOfGas),
+                    XvmExecutionResult::OutOfGas,
+                ),
+            ] {
+                MockXvm::set_result(Err(failure(reason, Weight::zero())));
+                let outcome = call_xvm(&contract, &evm_call_args());
+                assert_eq!(outcome.status, u32::from(expected));
+            }
+        });
+    }
+
+    #[test]
+    fn invalid_vm_id_is_rejected_before_calling_xvm() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+            let args = XvmCallArgs {
+                vm_id: 0x42,
+                ..evm_call_args()
+            };
+
+            let outcome = call_xvm(&contract, &args);
+
+            assert_eq!(outcome.status, u32::from(XvmExecutionResult::InvalidVmId));
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+
+    #[test]
+    fn read_only_calls_are_rejected() {
+        ExtBuilder::default().build().execute_with(|| {
+            let callee = deploy_extension_caller();
+            let caller = deploy_read_only_caller();
+
+            let mut data = callee.encode();
+            data.extend(extension_id(XvmFuncId::Call as u16).to_le_bytes());
+            data.extend(evm_call_args().encode());
+            let outcome = call_contract(caller, data);
+
+            assert_eq!(outcome.status, u32::from(XvmExecutionResult::ReadOnly));
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+
+    #[test]
+    fn unknown_function_traps() {
+        ExtBuilder::default().build().execute_with(|| {
+            let contract = deploy_extension_caller();
+
+            let mut data = extension_id(2).to_le_bytes().to_vec();
+            data.extend(evm_call_args().encode());
+            let result = Contracts::bare_call(
+                ALICE,
+                contract,
+                0,
+                GAS_LIMIT,
+                None,
+                data,
+                DebugInfo::Skip,
+                CollectEvents::Skip,
+                Determinism::Enforced,
+            );
+
+            assert!(result.result.is_err());
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::{Display, Error as FmtError, Formatter};
 
 use futures::channel::{mpsc, oneshot};
 use log::{debug, info};
+use rate_limiter::PerPeerRateLimiter;
 
 use crate::{
     metrics::Metrics,
@@ -43,6 +44,7 @@ async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
     data_for_user: mpsc::UnboundedSender<D>,
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) -> Result<(), IncomingError<SK::PublicKey>> {
     debug!(
         target: LOG_TARGET,
@@ -58,6 +60,7 @@ async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
             data_for_user,
             authorization_requests_sender,
             metrics,
+            peer_rate_limiter,
         )
         .await?)
 }
@@ -74,6 +77,7 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     data_for_user: mpsc::UnboundedSender<D>,
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) {
     let addr = stream.peer_address_info();
     if let Err(e) = manage_incoming(
@@ -83,6 +87,7 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
         data_for_user,
         authorization_requests_sender,
         metrics,
+        peer_rate_limiter,
     )
     .await
     {
@@ -16,6 +16,7 @@ pub const MAX_DATA_SIZE: u32 = 16 * 1024 * 1024;
 pub enum Error {
     ConnectionClosed(IoError),
     DataTooLong(u32),
+    NonceExhausted,
 }
 
 impl Display for Error {
@@ -27,6 +28,7 @@ impl Display for Error {
                 f,
                 "encoded data too long - {length} bytes, the limit is {MAX_DATA_SIZE}"
             ),
+            NonceExhausted => write!(f, "ran out of nonces for the encrypted connection"),
         }
     }
 }
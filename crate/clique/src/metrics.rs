@@ -1,4 +1,8 @@
-use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, U64};
+use std::fmt::Display;
+
+use substrate_prometheus_endpoint::{
+    register, CounterVec, Gauge, Opts, PrometheusError, Registry, U64,
+};
 
 #[derive(Clone)]
 pub enum Metrics {
@@ -7,6 +11,8 @@ pub enum Metrics {
         missing_incoming_connections: Gauge<U64>,
         outgoing_connections: Gauge<U64>,
         missing_outgoing_connections: Gauge<U64>,
+        peer_received_bytes: CounterVec<U64>,
+        peer_sent_bytes: CounterVec<U64>,
     },
     Noop,
 }
@@ -54,6 +60,26 @@ impl Metrics {
                     )?,
                     &registry,
                 )?,
+                peer_received_bytes: register(
+                    CounterVec::new(
+                        Opts::new(
+                            "clique_network_peer_received_bytes",
+                            "size of encoded data received from a peer",
+                        ),
+                        &["peer"],
+                    )?,
+                    &registry,
+                )?,
+                peer_sent_bytes: register(
+                    CounterVec::new(
+                        Opts::new(
+                            "clique_network_peer_sent_bytes",
+                            "size of encoded data sent to a peer",
+                        ),
+                        &["peer"],
+                    )?,
+                    &registry,
+                )?,
             }),
             None => Ok(Metrics::Noop),
         }
@@ -70,6 +96,7 @@ impl Metrics {
             outgoing_connections,
             missing_incoming_connections,
             missing_outgoing_connections,
+            ..
         } = self
         {
             match event {
@@ -96,4 +123,27 @@ impl Metrics {
             }
         }
     }
+
+    pub fn report_received<PK: Display>(&self, peer: &PK, bytes: usize) {
+        if let Metrics::Prometheus {
+            peer_received_bytes,
+            ..
+        } = self
+        {
+            peer_received_bytes
+                .with_label_values(&[&peer.to_string()])
+                .inc_by(bytes as u64);
+        }
+    }
+
+    pub fn report_sent<PK: Display>(&self, peer: &PK, bytes: usize) {
+        if let Metrics::Prometheus {
+            peer_sent_bytes, ..
+        } = self
+        {
+            peer_sent_bytes
+                .with_label_values(&[&peer.to_string()])
+                .inc_by(bytes as u64);
+        }
+    }
 }
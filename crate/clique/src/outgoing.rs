@@ -2,6 +2,7 @@ use std::fmt::{Debug, Display, Error as FmtError, Formatter};
 
 use futures::channel::mpsc;
 use log::{debug, info};
+use rate_limiter::PerPeerRateLimiter;
 use tokio::time::{sleep, timeout, Duration};
 
 use crate::{
@@ -35,6 +36,7 @@ impl<PK: PublicKey, A: Data, ND: Dialer<A>> Display for OutgoingError<PK, A, ND>
 /// Arbitrarily chosen timeout, should be more than enough.
 const DIAL_TIMEOUT: Duration = Duration::from_secs(60);
 
+#[allow(clippy::too_many_arguments)]
 async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
     secret_key: SK,
     public_key: SK::PublicKey,
@@ -43,6 +45,7 @@ async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) -> Result<(), OutgoingError<SK::PublicKey, A, ND>> {
     debug!(target: LOG_TARGET, "Trying to connect to {}.", public_key);
     let stream = timeout(DIAL_TIMEOUT, dialer.connect(address))
@@ -66,6 +69,7 @@ async fn manage_outgoing<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>>(
             result_for_parent,
             data_for_user,
             metrics,
+            peer_rate_limiter,
         )
         .await
         .map_err(|e| OutgoingError::Protocol(peer_address_info.clone(), e))
@@ -76,6 +80,7 @@ const RETRY_DELAY: Duration = Duration::from_secs(10);
 /// Establish an outgoing connection to the provided peer using the dialer and then manage it.
 /// While this works it will send any data from the user to the peer. Any failures will be reported
 /// to the parent, so that connections can be reestablished if necessary.
+#[allow(clippy::too_many_arguments)]
 pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
     secret_key: SK,
     public_key: SK::PublicKey,
@@ -84,6 +89,7 @@ pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) {
     if let Err(e) = manage_outgoing(
         secret_key,
@@ -93,6 +99,7 @@ pub async fn outgoing<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>>(
         result_for_parent.clone(),
         data_for_user,
         metrics,
+        peer_rate_limiter,
     )
     .await
     {
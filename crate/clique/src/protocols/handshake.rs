@@ -22,6 +22,8 @@ pub enum HandshakeError<PK: PublicKey> {
     SignatureError,
     /// Challenge contains invalid peer id.
     ChallengeError(PK, PK),
+    /// The peer sent a key share that does not contribute to the shared secret.
+    WeakKey,
     /// Timeout.
     TimedOut,
 }
@@ -37,6 +39,7 @@ impl<PK: PublicKey> Display for HandshakeError<PK> {
                 f,
                 "challenge error, expected peer {expected}, received from {got}"
             ),
+            WeakKey => write!(f, "weak key exchange share"),
             TimedOut => write!(f, "timed out"),
         }
     }
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Error as FmtError, Formatter};
 
 use futures::channel::{mpsc, oneshot};
+use rate_limiter::PerPeerRateLimiter;
 
 use crate::{
     io::{ReceiveError, SendError},
@@ -11,6 +12,7 @@ use crate::{
 mod handshake;
 mod negotiation;
 mod v1;
+mod v2;
 
 use handshake::HandshakeError;
 pub use negotiation::{protocol, ProtocolNegotiationError};
@@ -22,12 +24,15 @@ pub type Version = u32;
 /// connection was unsuccessful and should be reestablished.
 pub type ResultForService<PK, D> = (PK, Option<mpsc::Sender<D>>);
 
-/// Defines the protocol for communication. Currently single variant, but left in case of protocol change.
+/// Defines the protocol for communication.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Protocol {
-    /// The current version of the protocol, with pseudorandom connection direction and
-    /// multiplexing.
+    /// The first version of the protocol, with pseudorandom connection direction and
+    /// multiplexing. Only authenticates the peers, kept for nodes that did not upgrade yet.
     V1,
+    /// The same as `V1`, but the handshake also establishes ephemeral keys bound to the
+    /// identities of both peers, and every frame is encrypted and authenticated.
+    V2,
 }
 
 /// Protocol error.
@@ -90,9 +95,10 @@ impl Protocol {
     const MIN_VERSION: Version = 1;
 
     /// Maximal supported protocol version.
-    const MAX_VERSION: Version = 1;
+    const MAX_VERSION: Version = 2;
 
     /// Launches the proper variant of the protocol (receiver half).
+    #[allow(clippy::too_many_arguments)]
     pub async fn manage_incoming<SK: SecretKey, D: Data, S: Splittable>(
         &self,
         stream: S,
@@ -104,6 +110,7 @@ impl Protocol {
             oneshot::Sender<bool>,
         )>,
         metrics: Metrics,
+        peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
     ) -> Result<(), ProtocolError<SK::PublicKey>> {
         use Protocol::*;
         match self {
@@ -115,6 +122,19 @@ impl Protocol {
                     result_for_parent,
                     data_for_user,
                     metrics,
+                    peer_rate_limiter,
+                )
+                .await
+            }
+            V2 => {
+                v2::incoming(
+                    stream,
+                    secret_key,
+                    authorization_requests_sender,
+                    result_for_parent,
+                    data_for_user,
+                    metrics,
+                    peer_rate_limiter,
                 )
                 .await
             }
@@ -122,6 +142,7 @@ impl Protocol {
     }
 
     /// Launches the proper variant of the protocol (sender half).
+    #[allow(clippy::too_many_arguments)]
     pub async fn manage_outgoing<SK: SecretKey, D: Data, S: Splittable>(
         &self,
         stream: S,
@@ -130,6 +151,7 @@ impl Protocol {
         result_for_service: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
         data_for_user: mpsc::UnboundedSender<D>,
         metrics: Metrics,
+        peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
     ) -> Result<(), ProtocolError<SK::PublicKey>> {
         use Protocol::*;
         match self {
@@ -141,6 +163,19 @@ impl Protocol {
                     result_for_service,
                     data_for_user,
                     metrics,
+                    peer_rate_limiter,
+                )
+                .await
+            }
+            V2 => {
+                v2::outgoing(
+                    stream,
+                    secret_key,
+                    public_key,
+                    result_for_service,
+                    data_for_user,
+                    metrics,
+                    peer_rate_limiter,
                 )
                 .await
             }
@@ -154,6 +189,7 @@ impl TryFrom<Version> for Protocol {
     fn try_from(version: Version) -> Result<Self, Self::Error> {
         match version {
             1 => Ok(Protocol::V1),
+            2 => Ok(Protocol::V2),
             unknown_version => Err(unknown_version),
         }
     }
@@ -138,12 +138,22 @@ mod tests {
     use futures::{pin_mut, FutureExt};
     use tokio::io::duplex;
 
-    use super::{negotiate_protocol_version, supported_protocol_range, ProtocolNegotiationError};
+    use super::{
+        negotiate_protocol_version, supported_protocol_range, ProtocolNegotiationError,
+        ProtocolsRange,
+    };
     use crate::protocols::Protocol;
 
     fn correct_negotiation<S>(result: Result<(S, Protocol), ProtocolNegotiationError>) {
+        negotiated(result, Protocol::V2)
+    }
+
+    fn negotiated<S>(
+        result: Result<(S, Protocol), ProtocolNegotiationError>,
+        expected_protocol: Protocol,
+    ) {
         match result {
-            Ok((_stream, protocol)) => assert_eq!(Protocol::V1, protocol),
+            Ok((_stream, protocol)) => assert_eq!(expected_protocol, protocol),
             Err(e) => panic!("Unexpected error: {e:?}"),
         }
     }
@@ -192,6 +202,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn negotiates_v1_with_legacy_peer() {
+        let (stream1, stream2) = duplex(4096);
+        let legacy_protocol_range = ProtocolsRange(1, 1);
+        let negotiation1 = negotiate_protocol_version(stream1, supported_protocol_range()).fuse();
+        pin_mut!(negotiation1);
+        let negotiation2 = negotiate_protocol_version(stream2, legacy_protocol_range).fuse();
+        pin_mut!(negotiation2);
+        for _ in 0..2 {
+            tokio::select! {
+                result = &mut negotiation1 => negotiated(result, Protocol::V1),
+                result = &mut negotiation2 => negotiated(result, Protocol::V1),
+            }
+        }
+    }
+
     #[tokio::test]
     async fn fails_when_no_intersection() {
         let (stream1, stream2) = duplex(4096);
@@ -4,9 +4,10 @@ use futures::{
 };
 use log::{debug, info, trace};
 use parity_scale_codec::{Decode, Encode};
+use rate_limiter::PerPeerRateLimiter;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
-    time::{timeout, Duration},
+    time::{sleep_until, timeout, Duration},
 };
 
 use crate::{
@@ -19,16 +20,16 @@ use crate::{
     Data, PublicKey, SecretKey, Splittable, LOG_TARGET, SEND_DATA_BUFFER,
 };
 
-const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
-const MAX_MISSED_HEARTBEATS: u32 = 4;
+pub(super) const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
+pub(super) const MAX_MISSED_HEARTBEATS: u32 = 4;
 
 #[derive(Debug, Clone, Encode, Decode)]
-enum Message<D: Data> {
+pub(super) enum Message<D: Data> {
     Data(D),
     Heartbeat,
 }
 
-async fn check_authorization<SK: SecretKey>(
+pub(super) async fn check_authorization<SK: SecretKey>(
     authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
     public_key: SK::PublicKey,
 ) -> Result<bool, ProtocolError<SK::PublicKey>> {
@@ -44,6 +45,8 @@ async fn check_authorization<SK: SecretKey>(
 async fn sending<PK: PublicKey, D: Data, S: AsyncWrite + Unpin + Send>(
     mut sender: S,
     mut data_from_user: mpsc::Receiver<D>,
+    public_key: &PK,
+    metrics: &Metrics,
 ) -> Result<(), ProtocolError<PK>> {
     use Message::*;
     loop {
@@ -55,6 +58,7 @@ async fn sending<PK: PublicKey, D: Data, S: AsyncWrite + Unpin + Send>(
             },
             _ => Heartbeat,
         };
+        metrics.report_sent(public_key, to_send.encoded_size());
         sender = timeout(
             MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
             send_data(sender, to_send),
@@ -67,22 +71,32 @@ async fn sending<PK: PublicKey, D: Data, S: AsyncWrite + Unpin + Send>(
 async fn receiving<PK: PublicKey, D: Data, S: AsyncRead + Unpin + Send>(
     mut stream: S,
     data_for_user: mpsc::UnboundedSender<D>,
+    public_key: &PK,
+    metrics: &Metrics,
+    peer_rate_limiter: &PerPeerRateLimiter<PK>,
 ) -> Result<(), ProtocolError<PK>> {
     use Message::*;
     loop {
-        let (old_stream, message) = timeout(
+        let (old_stream, message): (_, Message<D>) = timeout(
             MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
             receive_data(stream),
         )
         .await
         .map_err(|_| ProtocolError::CardiacArrest)??;
         stream = old_stream;
+        let size = message.encoded_size();
+        metrics.report_received(public_key, size);
         match message {
             Data(data) => data_for_user
                 .unbounded_send(data)
                 .map_err(|_| ProtocolError::NoUserConnection)?,
             Heartbeat => (),
         }
+        // Stop reading until the peer is back within its cap, so that it slows down without
+        // affecting the connections to other peers.
+        if let Some(deadline) = peer_rate_limiter.rate_limit(public_key, 8 * size as u64) {
+            sleep_until(deadline.into()).await;
+        }
     }
 }
 
@@ -96,9 +110,18 @@ async fn manage_connection<
     receiver: R,
     data_from_user: mpsc::Receiver<D>,
     data_for_user: mpsc::UnboundedSender<D>,
+    public_key: &PK,
+    metrics: &Metrics,
+    peer_rate_limiter: &PerPeerRateLimiter<PK>,
 ) -> Result<(), ProtocolError<PK>> {
-    let sending = sending(sender, data_from_user);
-    let receiving = receiving(receiver, data_for_user);
+    let sending = sending(sender, data_from_user, public_key, metrics);
+    let receiving = receiving(
+        receiver,
+        data_for_user,
+        public_key,
+        metrics,
+        peer_rate_limiter,
+    );
     tokio::select! {
         result = receiving => result,
         result = sending => result,
@@ -114,6 +137,7 @@ pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) -> Result<(), ProtocolError<SK::PublicKey>> {
     use Event::*;
     trace!(target: LOG_TARGET, "Extending hand to {}.", public_key);
@@ -132,7 +156,16 @@ pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
         target: LOG_TARGET,
         "Starting worker for communicating with {}.", public_key
     );
-    let result = manage_connection(sender, receiver, data_from_user, data_for_user).await;
+    let result = manage_connection(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        &public_key,
+        &metrics,
+        &peer_rate_limiter,
+    )
+    .await;
     metrics.report_event(DisconnectedOutgoing);
     result
 }
@@ -147,6 +180,7 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
     result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
     data_for_user: mpsc::UnboundedSender<D>,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 ) -> Result<(), ProtocolError<SK::PublicKey>> {
     use Event::*;
     trace!(target: LOG_TARGET, "Waiting for extended hand...");
@@ -169,7 +203,16 @@ pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
         target: LOG_TARGET,
         "Starting worker for communicating with {}.", public_key
     );
-    let result = manage_connection(sender, receiver, data_from_user, data_for_user).await;
+    let result = manage_connection(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        &public_key,
+        &metrics,
+        &peer_rate_limiter,
+    )
+    .await;
     metrics.report_event(DisconnectedIncoming);
     result
 }
@@ -180,6 +223,7 @@ mod tests {
         channel::{mpsc, oneshot},
         pin_mut, Future, FutureExt, StreamExt,
     };
+    use rate_limiter::PerPeerRateLimiter;
 
     use crate::{
         metrics::Metrics,
@@ -208,6 +252,7 @@ mod tests {
             incoming_result_for_service,
             incoming_data_for_user,
             Metrics::noop(),
+            PerPeerRateLimiter::unlimited(),
         ));
         let outgoing_handle = Box::pin(outgoing(
             stream_outgoing,
@@ -216,6 +261,7 @@ mod tests {
             outgoing_result_for_service,
             outgoing_data_for_user,
             Metrics::noop(),
+            PerPeerRateLimiter::unlimited(),
         ));
         MockPrelims {
             id_incoming,
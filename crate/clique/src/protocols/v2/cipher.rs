@@ -0,0 +1,150 @@
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+use parity_scale_codec::DecodeAll;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::{
+    io::{receive_data, send_data, Error, ReceiveError, SendError},
+    Data,
+};
+
+/// Key used to encrypt a single direction of a connection.
+pub type SymmetricKey = [u8; 32];
+
+/// Authenticated encryption of consecutive frames going in one direction. Every frame uses the
+/// next nonce, so frames that are dropped, reordered or replayed fail to decrypt.
+struct Cipher {
+    aead: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl Cipher {
+    fn new(key: &SymmetricKey) -> Self {
+        Cipher {
+            aead: ChaCha20Poly1305::new(Key::from_slice(key)),
+            counter: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> Result<Nonce, Error> {
+        let counter = self.counter;
+        self.counter = counter.checked_add(1).ok_or(Error::NonceExhausted)?;
+        let mut nonce = [0; 12];
+        nonce[4..].copy_from_slice(&counter.to_le_bytes());
+        Ok(nonce.into())
+    }
+}
+
+/// The sending half of an encrypted connection.
+pub struct EncryptedSender<S> {
+    stream: S,
+    cipher: Cipher,
+}
+
+impl<S: AsyncWriteExt + Unpin> EncryptedSender<S> {
+    pub fn new(stream: S, key: &SymmetricKey) -> Self {
+        EncryptedSender {
+            stream,
+            cipher: Cipher::new(key),
+        }
+    }
+
+    /// Encrypts the data and sends it as a single frame.
+    pub async fn send<D: Data>(mut self, data: D) -> Result<Self, SendError> {
+        let nonce = self.cipher.next_nonce()?;
+        let encoded = data.encode();
+        let ciphertext = self
+            .cipher
+            .aead
+            .encrypt(&nonce, encoded.as_slice())
+            .map_err(|_| Error::DataTooLong(u32::try_from(encoded.len()).unwrap_or(u32::MAX)))?;
+        self.stream = send_data(self.stream, ciphertext).await?;
+        Ok(self)
+    }
+}
+
+/// The receiving half of an encrypted connection.
+pub struct EncryptedReceiver<R> {
+    stream: R,
+    cipher: Cipher,
+}
+
+impl<R: AsyncReadExt + Unpin> EncryptedReceiver<R> {
+    pub fn new(stream: R, key: &SymmetricKey) -> Self {
+        EncryptedReceiver {
+            stream,
+            cipher: Cipher::new(key),
+        }
+    }
+
+    /// Receives a single frame and decrypts it. Frames that fail authentication are reported as
+    /// corrupted data.
+    pub async fn receive<D: Data>(mut self) -> Result<(Self, D), ReceiveError> {
+        let (stream, ciphertext) = receive_data::<_, Vec<u8>>(self.stream).await?;
+        self.stream = stream;
+        let nonce = self.cipher.next_nonce()?;
+        let plaintext = self
+            .cipher
+            .aead
+            .decrypt(&nonce, ciphertext.as_slice())
+            .map_err(|_| ReceiveError::DataCorrupted)?;
+        let data = D::decode_all(&mut &plaintext[..]).map_err(|_| ReceiveError::DataCorrupted)?;
+        Ok((self, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::duplex;
+
+    use super::{EncryptedReceiver, EncryptedSender};
+    use crate::io::{send_data, ReceiveError};
+
+    #[tokio::test]
+    async fn sends_and_receives_encrypted_data() {
+        let (sender, receiver) = duplex(4096);
+        let sender = EncryptedSender::new(sender, &[7; 32]);
+        let receiver = EncryptedReceiver::new(receiver, &[7; 32]);
+        let sender = sender.send(vec![4, 3, 43]).await.expect("data should send");
+        let _sender = sender
+            .send(vec![2, 1, 3, 7])
+            .await
+            .expect("data should send");
+        let (receiver, data) = receiver
+            .receive::<Vec<i32>>()
+            .await
+            .expect("should receive");
+        assert_eq!(data, vec![4, 3, 43]);
+        let (_, data) = receiver
+            .receive::<Vec<i32>>()
+            .await
+            .expect("should receive");
+        assert_eq!(data, vec![2, 1, 3, 7]);
+    }
+
+    #[tokio::test]
+    async fn rejects_data_encrypted_with_other_key() {
+        let (sender, receiver) = duplex(4096);
+        let sender = EncryptedSender::new(sender, &[7; 32]);
+        let receiver = EncryptedReceiver::new(receiver, &[8; 32]);
+        let _sender = sender.send(vec![4, 3, 43]).await.expect("data should send");
+        match receiver.receive::<Vec<i32>>().await {
+            Err(ReceiveError::DataCorrupted) => (),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("received data encrypted with another key"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unencrypted_data() {
+        let (sender, receiver) = duplex(4096);
+        let receiver = EncryptedReceiver::new(receiver, &[7; 32]);
+        let _sender = send_data(sender, vec![0u8; 32])
+            .await
+            .expect("data should send");
+        match receiver.receive::<Vec<i32>>().await {
+            Err(ReceiveError::DataCorrupted) => (),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("received unencrypted data"),
+        }
+    }
+}
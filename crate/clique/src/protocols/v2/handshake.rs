@@ -0,0 +1,302 @@
+use hkdf::Hkdf;
+use parity_scale_codec::{Decode, Encode};
+use sha2::{Digest, Sha256};
+use tokio::time::timeout;
+use x25519_dalek::{EphemeralSecret, PublicKey as KeySharePublic, SharedSecret};
+
+use crate::{
+    io::{receive_data, send_data},
+    protocols::{
+        handshake::{HandshakeError, HANDSHAKE_TIMEOUT},
+        v2::cipher::{EncryptedReceiver, EncryptedSender, SymmetricKey},
+    },
+    PublicKey, SecretKey, Splittable,
+};
+
+/// Mixed into the transcript, so that signatures cannot be reused in other protocols.
+const PROTOCOL_LABEL: &[u8] = b"clique-v2-handshake";
+
+/// Ephemeral x25519 public key sent by both sides.
+#[derive(Debug, Clone, Encode, Decode)]
+struct KeyShare([u8; 32]);
+
+impl KeyShare {
+    fn generate() -> (EphemeralSecret, Self) {
+        let secret = EphemeralSecret::random_from_rng(rand::thread_rng());
+        let share = KeyShare(KeySharePublic::from(&secret).to_bytes());
+        (secret, share)
+    }
+}
+
+/// Sent encrypted after the key exchange. Proves that the sender owns the public key and took
+/// part in this particular exchange.
+#[derive(Debug, Clone, Encode, Decode)]
+struct Identity<PK: PublicKey> {
+    public_key: PK,
+    signature: PK::Signature,
+}
+
+impl<PK: PublicKey> Identity<PK> {
+    /// Create a new identity by signing the transcript.
+    fn new<SK: SecretKey<PublicKey = PK, Signature = PK::Signature>>(
+        secret_key: &SK,
+        transcript: &[u8; 32],
+    ) -> Self {
+        Self {
+            public_key: secret_key.public_key(),
+            signature: secret_key.sign(&signed_message(transcript)),
+        }
+    }
+
+    /// Verify the identity sent by the peer.
+    fn verify(&self, transcript: &[u8; 32]) -> bool {
+        self.public_key
+            .verify(&signed_message(transcript), &self.signature)
+    }
+}
+
+fn signed_message(transcript: &[u8; 32]) -> Vec<u8> {
+    [PROTOCOL_LABEL, transcript].concat()
+}
+
+/// Result of the key exchange, the same on both sides.
+struct SessionKeys {
+    transcript: [u8; 32],
+    initiator_key: SymmetricKey,
+    responder_key: SymmetricKey,
+}
+
+impl SessionKeys {
+    fn derive(shared: &SharedSecret, initiator: &KeyShare, responder: &KeyShare) -> Self {
+        let transcript: [u8; 32] = Sha256::new()
+            .chain_update(PROTOCOL_LABEL)
+            .chain_update(initiator.0)
+            .chain_update(responder.0)
+            .finalize()
+            .into();
+        let hkdf = Hkdf::<Sha256>::new(Some(&transcript), shared.as_bytes());
+        let mut initiator_key = [0; 32];
+        let mut responder_key = [0; 32];
+        hkdf.expand(b"initiator", &mut initiator_key)
+            .expect("32 bytes is a valid hkdf output length");
+        hkdf.expand(b"responder", &mut responder_key)
+            .expect("32 bytes is a valid hkdf output length");
+        SessionKeys {
+            transcript,
+            initiator_key,
+            responder_key,
+        }
+    }
+}
+
+fn agree<PK: PublicKey>(
+    secret: EphemeralSecret,
+    their_share: &KeyShare,
+) -> Result<SharedSecret, HandshakeError<PK>> {
+    let shared = secret.diffie_hellman(&KeySharePublic::from(their_share.0));
+    match shared.was_contributory() {
+        true => Ok(shared),
+        false => Err(HandshakeError::WeakKey),
+    }
+}
+
+/// Performs the handshake with a peer that called us.
+/// Both sides exchange ephemeral keys and derive one symmetric key per direction, then the
+/// peer proves their identity by signing the transcript of the exchange. Contrary to the v0
+/// handshake, the returned halves encrypt and authenticate everything sent through them.
+pub async fn execute_v2_handshake_incoming<SK: SecretKey, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+) -> Result<
+    (
+        EncryptedSender<S::Sender>,
+        EncryptedReceiver<S::Receiver>,
+        SK::PublicKey,
+    ),
+    HandshakeError<SK::PublicKey>,
+> {
+    // exchange key shares
+    let (stream, their_share) = receive_data::<_, KeyShare>(stream).await?;
+    let (our_secret, our_share) = KeyShare::generate();
+    let stream = send_data(stream, our_share.clone()).await?;
+    let shared = agree(our_secret, &their_share)?;
+    let keys = SessionKeys::derive(&shared, &their_share, &our_share);
+    let (sender, receiver) = stream.split();
+    let sender = EncryptedSender::new(sender, &keys.responder_key);
+    let receiver = EncryptedReceiver::new(receiver, &keys.initiator_key);
+    // prove our identity
+    let sender = sender
+        .send(Identity::new(&secret_key, &keys.transcript))
+        .await?;
+    // receive and validate the identity of the peer
+    let (receiver, peer_identity) = receiver.receive::<Identity<SK::PublicKey>>().await?;
+    if !peer_identity.verify(&keys.transcript) {
+        return Err(HandshakeError::SignatureError);
+    }
+    Ok((sender, receiver, peer_identity.public_key))
+}
+
+/// Performs the handshake with a peer that we called. We assume that their
+/// public key is known to us, and reject the connection if they prove a different one.
+pub async fn execute_v2_handshake_outgoing<SK: SecretKey, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    public_key: SK::PublicKey,
+) -> Result<
+    (EncryptedSender<S::Sender>, EncryptedReceiver<S::Receiver>),
+    HandshakeError<SK::PublicKey>,
+> {
+    // exchange key shares
+    let (our_secret, our_share) = KeyShare::generate();
+    let stream = send_data(stream, our_share.clone()).await?;
+    let (stream, their_share) = receive_data::<_, KeyShare>(stream).await?;
+    let shared = agree(our_secret, &their_share)?;
+    let keys = SessionKeys::derive(&shared, &our_share, &their_share);
+    let (sender, receiver) = stream.split();
+    let sender = EncryptedSender::new(sender, &keys.initiator_key);
+    let receiver = EncryptedReceiver::new(receiver, &keys.responder_key);
+    // receive and validate the identity of the peer
+    let (receiver, peer_identity) = receiver.receive::<Identity<SK::PublicKey>>().await?;
+    if public_key != peer_identity.public_key {
+        return Err(HandshakeError::ChallengeError(
+            public_key,
+            peer_identity.public_key,
+        ));
+    }
+    if !peer_identity.verify(&keys.transcript) {
+        return Err(HandshakeError::SignatureError);
+    }
+    // prove our identity
+    let sender = sender
+        .send(Identity::new(&secret_key, &keys.transcript))
+        .await?;
+    Ok((sender, receiver))
+}
+
+/// Wrapper that adds timeout to the function performing handshake.
+pub async fn v2_handshake_incoming<SK: SecretKey, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+) -> Result<
+    (
+        EncryptedSender<S::Sender>,
+        EncryptedReceiver<S::Receiver>,
+        SK::PublicKey,
+    ),
+    HandshakeError<SK::PublicKey>,
+> {
+    timeout(
+        HANDSHAKE_TIMEOUT,
+        execute_v2_handshake_incoming(stream, secret_key),
+    )
+    .await
+    .map_err(|_| HandshakeError::TimedOut)?
+}
+
+/// Wrapper that adds timeout to the function performing handshake.
+pub async fn v2_handshake_outgoing<SK: SecretKey, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    public_key: SK::PublicKey,
+) -> Result<
+    (EncryptedSender<S::Sender>, EncryptedReceiver<S::Receiver>),
+    HandshakeError<SK::PublicKey>,
+> {
+    timeout(
+        HANDSHAKE_TIMEOUT,
+        execute_v2_handshake_outgoing(stream, secret_key, public_key),
+    )
+    .await
+    .map_err(|_| HandshakeError::TimedOut)?
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::try_join;
+
+    use super::{
+        execute_v2_handshake_incoming, execute_v2_handshake_outgoing, HandshakeError, KeyShare,
+    };
+    use crate::{
+        io::{receive_data, send_data},
+        mock::{key, MockPublicKey, MockSplittable},
+        Splittable,
+    };
+
+    #[tokio::test]
+    async fn handshake() {
+        let (stream_a, stream_b) = MockSplittable::new(4096);
+        let (id_a, pen_a) = key();
+        let (id_b, pen_b) = key();
+        assert_ne!(id_a, id_b);
+        let ((sender_a, receiver_a, received_id_b), (sender_b, receiver_b)) = try_join!(
+            execute_v2_handshake_incoming(stream_a, pen_a),
+            execute_v2_handshake_outgoing(stream_b, pen_b, id_a),
+        )
+        .expect("handshake should work");
+        assert_eq!(id_b, received_id_b);
+        let _sender_a = sender_a.send(vec![1, 2, 3]).await.expect("should send");
+        let _sender_b = sender_b.send(vec![4, 5]).await.expect("should send");
+        let (_, data) = receiver_b
+            .receive::<Vec<i32>>()
+            .await
+            .expect("should receive");
+        assert_eq!(data, vec![1, 2, 3]);
+        let (_, data) = receiver_a
+            .receive::<Vec<i32>>()
+            .await
+            .expect("should receive");
+        assert_eq!(data, vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn handshake_with_wrong_peer() {
+        let (stream_a, stream_b) = MockSplittable::new(4096);
+        let (_, pen_a) = key();
+        let (_, pen_b) = key();
+        let (expected_id, _) = key();
+        tokio::select! {
+            _ = execute_v2_handshake_incoming(stream_a, pen_a) => panic!("should wait"),
+            result = execute_v2_handshake_outgoing(stream_b, pen_b, expected_id) => match result {
+                Err(HandshakeError::ChallengeError(_, _)) => (),
+                Err(e) => panic!("should end with HandshakeError::ChallengeError, but we got {e:?}"),
+                Ok(_) => panic!("handshake with a wrong peer should fail"),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn handshake_with_weak_key_share() {
+        async fn execute_malicious_v2_handshake_outgoing<S: Splittable>(stream: S) {
+            // the identity point makes the shared secret known to everyone
+            let stream = send_data(stream, KeyShare([0; 32]))
+                .await
+                .expect("should send");
+            let _ = receive_data::<_, KeyShare>(stream).await;
+            futures::future::pending::<()>().await;
+        }
+
+        let (stream_a, stream_b) = MockSplittable::new(4096);
+        let (_, pen_a) = key();
+        tokio::select! {
+            result = execute_v2_handshake_incoming(stream_a, pen_a) => match result {
+                Err(HandshakeError::<MockPublicKey>::WeakKey) => (),
+                Err(e) => panic!("should end with HandshakeError::WeakKey, but we got {e:?}"),
+                Ok(_) => panic!("handshake with a weak key share should fail"),
+            },
+            _ = execute_malicious_v2_handshake_outgoing(stream_b) => panic!("should wait"),
+        }
+    }
+
+    #[tokio::test]
+    async fn broken_outgoing_connection() {
+        let (stream_a, _) = MockSplittable::new(4096);
+        let (_, pen_a) = key();
+        let (id_b, _) = key();
+        match execute_v2_handshake_outgoing(stream_a, pen_a, id_b).await {
+            Err(HandshakeError::SendError(_)) | Err(HandshakeError::ReceiveError(_)) => (),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("handshake over a dropped connection should fail"),
+        }
+    }
+}
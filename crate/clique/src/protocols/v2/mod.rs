@@ -0,0 +1,381 @@
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+use log::{debug, info, trace};
+use parity_scale_codec::Encode;
+use rate_limiter::PerPeerRateLimiter;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    time::{sleep_until, timeout},
+};
+
+use crate::{
+    metrics::{Event, Metrics},
+    protocols::{
+        v1::{check_authorization, Message, HEARTBEAT_TIMEOUT, MAX_MISSED_HEARTBEATS},
+        ProtocolError, ResultForService,
+    },
+    Data, PublicKey, SecretKey, Splittable, LOG_TARGET, SEND_DATA_BUFFER,
+};
+
+mod cipher;
+mod handshake;
+
+use cipher::{EncryptedReceiver, EncryptedSender};
+use handshake::{v2_handshake_incoming, v2_handshake_outgoing};
+
+async fn sending<PK: PublicKey, D: Data, S: AsyncWrite + Unpin + Send>(
+    mut sender: EncryptedSender<S>,
+    mut data_from_user: mpsc::Receiver<D>,
+    public_key: &PK,
+    metrics: &Metrics,
+) -> Result<(), ProtocolError<PK>> {
+    use Message::*;
+    loop {
+        let to_send = match timeout(HEARTBEAT_TIMEOUT, data_from_user.next()).await {
+            Ok(maybe_data) => match maybe_data {
+                Some(data) => Data(data),
+                // We have been closed by the parent service, all good.
+                None => return Ok(()),
+            },
+            _ => Heartbeat,
+        };
+        metrics.report_sent(public_key, to_send.encoded_size());
+        sender = timeout(
+            MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
+            sender.send(to_send),
+        )
+        .await
+        .map_err(|_| ProtocolError::SendTimeout)??;
+    }
+}
+
+async fn receiving<PK: PublicKey, D: Data, R: AsyncRead + Unpin + Send>(
+    mut receiver: EncryptedReceiver<R>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    public_key: &PK,
+    metrics: &Metrics,
+    peer_rate_limiter: &PerPeerRateLimiter<PK>,
+) -> Result<(), ProtocolError<PK>> {
+    use Message::*;
+    loop {
+        let (old_receiver, message): (_, Message<D>) = timeout(
+            MAX_MISSED_HEARTBEATS * HEARTBEAT_TIMEOUT,
+            receiver.receive(),
+        )
+        .await
+        .map_err(|_| ProtocolError::CardiacArrest)??;
+        receiver = old_receiver;
+        let size = message.encoded_size();
+        metrics.report_received(public_key, size);
+        match message {
+            Data(data) => data_for_user
+                .unbounded_send(data)
+                .map_err(|_| ProtocolError::NoUserConnection)?,
+            Heartbeat => (),
+        }
+        // Stop reading until the peer is back within its cap, so that it slows down without
+        // affecting the connections to other peers.
+        if let Some(deadline) = peer_rate_limiter.rate_limit(public_key, 8 * size as u64) {
+            sleep_until(deadline.into()).await;
+        }
+    }
+}
+
+async fn manage_connection<
+    PK: PublicKey,
+    D: Data,
+    S: AsyncWrite + Unpin + Send,
+    R: AsyncRead + Unpin + Send,
+>(
+    sender: EncryptedSender<S>,
+    receiver: EncryptedReceiver<R>,
+    data_from_user: mpsc::Receiver<D>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    public_key: &PK,
+    metrics: &Metrics,
+    peer_rate_limiter: &PerPeerRateLimiter<PK>,
+) -> Result<(), ProtocolError<PK>> {
+    let sending = sending(sender, data_from_user, public_key, metrics);
+    let receiving = receiving(
+        receiver,
+        data_for_user,
+        public_key,
+        metrics,
+        peer_rate_limiter,
+    );
+    tokio::select! {
+        result = receiving => result,
+        result = sending => result,
+    }
+}
+
+/// Performs the outgoing encrypted handshake, and then manages a connection sending and
+/// receiving data. Exits on parent request, or in case of broken or dead network connection.
+pub async fn outgoing<SK: SecretKey, D: Data, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    public_key: SK::PublicKey,
+    result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
+) -> Result<(), ProtocolError<SK::PublicKey>> {
+    use Event::*;
+    trace!(target: LOG_TARGET, "Extending encrypted hand to {}.", public_key);
+    let (sender, receiver) = v2_handshake_outgoing(stream, secret_key, public_key.clone()).await?;
+    info!(
+        target: LOG_TARGET,
+        "Outgoing encrypted handshake with {} finished successfully.", public_key
+    );
+    let (data_for_network, data_from_user) = mpsc::channel(SEND_DATA_BUFFER);
+    result_for_parent
+        .unbounded_send((public_key.clone(), Some(data_for_network)))
+        .map_err(|_| ProtocolError::NoParentConnection)?;
+    metrics.report_event(ConnectedOutgoing);
+
+    debug!(
+        target: LOG_TARGET,
+        "Starting worker for communicating with {}.", public_key
+    );
+    let result = manage_connection(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        &public_key,
+        &metrics,
+        &peer_rate_limiter,
+    )
+    .await;
+    metrics.report_event(DisconnectedOutgoing);
+    result
+}
+
+/// Performs the incoming encrypted handshake, and then manages a connection sending and
+/// receiving data. Exits on parent request (when the data source is dropped), or in case of
+/// broken or dead network connection.
+pub async fn incoming<SK: SecretKey, D: Data, S: Splittable>(
+    stream: S,
+    secret_key: SK,
+    authorization_requests_sender: mpsc::UnboundedSender<(SK::PublicKey, oneshot::Sender<bool>)>,
+    result_for_parent: mpsc::UnboundedSender<ResultForService<SK::PublicKey, D>>,
+    data_for_user: mpsc::UnboundedSender<D>,
+    metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
+) -> Result<(), ProtocolError<SK::PublicKey>> {
+    use Event::*;
+    trace!(target: LOG_TARGET, "Waiting for extended encrypted hand...");
+    let (sender, receiver, public_key) = v2_handshake_incoming(stream, secret_key).await?;
+    info!(
+        target: LOG_TARGET,
+        "Incoming encrypted handshake with {} finished successfully.", public_key
+    );
+
+    if !check_authorization::<SK>(authorization_requests_sender, public_key.clone()).await? {
+        return Err(ProtocolError::NotAuthorized);
+    }
+
+    let (data_for_network, data_from_user) = mpsc::channel(SEND_DATA_BUFFER);
+    result_for_parent
+        .unbounded_send((public_key.clone(), Some(data_for_network)))
+        .map_err(|_| ProtocolError::NoParentConnection)?;
+    metrics.report_event(ConnectedIncoming);
+    debug!(
+        target: LOG_TARGET,
+        "Starting worker for communicating with {}.", public_key
+    );
+    let result = manage_connection(
+        sender,
+        receiver,
+        data_from_user,
+        data_for_user,
+        &public_key,
+        &metrics,
+        &peer_rate_limiter,
+    )
+    .await;
+    metrics.report_event(DisconnectedIncoming);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{
+        channel::{mpsc, oneshot},
+        pin_mut, Future, FutureExt, StreamExt,
+    };
+    use rate_limiter::PerPeerRateLimiter;
+
+    use crate::{
+        metrics::Metrics,
+        mock::{key, MockPrelims, MockSplittable},
+        protocols::{
+            v2::{incoming, outgoing},
+            ProtocolError,
+        },
+        Data,
+    };
+
+    fn prepare<D: Data>() -> MockPrelims<D> {
+        let (stream_incoming, stream_outgoing) = MockSplittable::new(4096);
+        let (id_incoming, pen_incoming) = key();
+        let (id_outgoing, pen_outgoing) = key();
+        assert_ne!(id_incoming, id_outgoing);
+        let (incoming_result_for_service, result_from_incoming) = mpsc::unbounded();
+        let (outgoing_result_for_service, result_from_outgoing) = mpsc::unbounded();
+        let (incoming_data_for_user, data_from_incoming) = mpsc::unbounded::<D>();
+        let (outgoing_data_for_user, data_from_outgoing) = mpsc::unbounded::<D>();
+        let (authorization_requests_sender, authorization_requests) = mpsc::unbounded();
+        let incoming_handle = Box::pin(incoming(
+            stream_incoming,
+            pen_incoming.clone(),
+            authorization_requests_sender,
+            incoming_result_for_service,
+            incoming_data_for_user,
+            Metrics::noop(),
+            PerPeerRateLimiter::unlimited(),
+        ));
+        let outgoing_handle = Box::pin(outgoing(
+            stream_outgoing,
+            pen_outgoing.clone(),
+            id_incoming.clone(),
+            outgoing_result_for_service,
+            outgoing_data_for_user,
+            Metrics::noop(),
+            PerPeerRateLimiter::unlimited(),
+        ));
+        MockPrelims {
+            id_incoming,
+            pen_incoming,
+            id_outgoing,
+            pen_outgoing,
+            incoming_handle,
+            outgoing_handle,
+            data_from_incoming,
+            data_from_outgoing: Some(data_from_outgoing),
+            result_from_incoming,
+            result_from_outgoing,
+            authorization_requests,
+        }
+    }
+
+    fn authorization_handler<PK: Send + 'static>(
+        mut authorization_requests: mpsc::UnboundedReceiver<(PK, oneshot::Sender<bool>)>,
+        result: bool,
+    ) -> impl Future<Output = Result<(), ()>> {
+        tokio::spawn(async move {
+            let (_, response_sender) = authorization_requests
+                .next()
+                .await
+                .expect("We should recieve at least one authorization request.");
+            response_sender
+                .send(result)
+                .expect("We should be able to send back an authorization response.");
+        })
+        .map(|result| result.map_err(|_| ()))
+    }
+
+    #[tokio::test]
+    async fn send_data() {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            mut data_from_incoming,
+            data_from_outgoing,
+            mut result_from_incoming,
+            mut result_from_outgoing,
+            authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>();
+        let mut data_from_outgoing = data_from_outgoing.expect("No data from outgoing!");
+        let incoming_handle = incoming_handle.fuse();
+        let outgoing_handle = outgoing_handle.fuse();
+        pin_mut!(incoming_handle);
+        pin_mut!(outgoing_handle);
+        let _authorization_handle = authorization_handler(authorization_requests, true);
+        let _data_for_outgoing = tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            result = result_from_outgoing.next() => {
+                let (_, maybe_data_for_outgoing) = result.expect("the channel shouldn't be dropped");
+                let mut data_for_outgoing = maybe_data_for_outgoing.expect("successfully connected");
+                data_for_outgoing
+                    .try_send(vec![4, 3, 43])
+                    .expect("should send");
+                data_for_outgoing
+            },
+        };
+        let _data_for_incoming = tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            result = result_from_incoming.next() => {
+                let (_, maybe_data_for_incoming) = result.expect("the channel shouldn't be dropped");
+                let mut data_for_incoming = maybe_data_for_incoming.expect("successfully connected");
+                data_for_incoming
+                    .try_send(vec![5, 4, 44])
+                    .expect("should send");
+                data_for_incoming
+            },
+        };
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            v = data_from_incoming.next() => {
+                assert_eq!(v, Some(vec![4, 3, 43]));
+            },
+        };
+        tokio::select! {
+            _ = &mut incoming_handle => panic!("incoming process unexpectedly finished"),
+            _ = &mut outgoing_handle => panic!("outgoing process unexpectedly finished"),
+            v = data_from_outgoing.next() => {
+                assert_eq!(v, Some(vec![5, 4, 44]));
+            },
+        };
+    }
+
+    #[tokio::test]
+    async fn sender_dead_before_handshake() {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>();
+        let _authorization_handle = authorization_handler(authorization_requests, true);
+        std::mem::drop(outgoing_handle);
+        match incoming_handle.await {
+            Err(ProtocolError::HandshakeError(_)) => (),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("successfully finished when connection dead"),
+        };
+    }
+
+    #[tokio::test]
+    async fn do_not_call_sender_and_receiver_until_authorized() {
+        let MockPrelims {
+            incoming_handle,
+            outgoing_handle,
+            mut data_from_incoming,
+            mut result_from_incoming,
+            authorization_requests,
+            ..
+        } = prepare::<Vec<i32>>();
+
+        let authorization_handle = authorization_handler(authorization_requests, false);
+
+        let (incoming_result, outgoing_result, authorization_result) =
+            tokio::join!(incoming_handle, outgoing_handle, authorization_handle);
+
+        assert!(incoming_result.is_err());
+        assert!(outgoing_result.is_err());
+        assert!(authorization_result.is_ok());
+
+        let data_from_incoming = data_from_incoming.try_next();
+        assert!(data_from_incoming.ok().flatten().is_none());
+
+        let result_from_incoming = result_from_incoming.try_next();
+        assert!(result_from_incoming.ok().flatten().is_none());
+    }
+}
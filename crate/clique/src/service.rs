@@ -9,6 +9,7 @@ use futures::{
     Future, StreamExt, TryFutureExt,
 };
 use log::{info, trace, warn};
+use rate_limiter::PerPeerRateLimiter;
 use substrate_prometheus_endpoint::Registry;
 use tokio::time;
 
@@ -145,6 +146,7 @@ where
     spawn_handle: SH,
     secret_key: SK,
     metrics: Metrics,
+    peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
 }
 
 impl<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>, NL: Listener, SH: SpawnHandleT>
@@ -152,13 +154,15 @@ impl<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>, NL: Listener, SH: S
 where
     SK::PublicKey: PeerId,
 {
-    /// Create a new clique network service plus an interface for interacting with it.
+    /// Create a new clique network service plus an interface for interacting with it. Data
+    /// received from every peer is limited by the `peer_rate_limiter`.
     pub fn new(
         dialer: ND,
         listener: NL,
         secret_key: SK,
         spawn_handle: SH,
         metrics_registry: Option<Registry>,
+        peer_rate_limiter: PerPeerRateLimiter<SK::PublicKey>,
     ) -> (Self, impl Network<SK::PublicKey, A, D>) {
         // Channel for sending commands between the service and interface
         let (commands_for_service, commands_from_interface) = mpsc::unbounded();
@@ -181,6 +185,7 @@ where
                 spawn_handle,
                 secret_key,
                 metrics,
+                peer_rate_limiter,
             },
             ServiceInterface {
                 commands_for_service,
@@ -199,6 +204,7 @@ where
         let dialer = self.dialer.clone();
         let next_to_interface = self.next_to_interface.clone();
         let metrics = self.metrics.clone();
+        let peer_rate_limiter = self.peer_rate_limiter.clone();
         self.spawn_handle
             .spawn("aleph/clique_network_outgoing", async move {
                 outgoing(
@@ -209,6 +215,7 @@ where
                     result_for_parent,
                     next_to_interface,
                     metrics,
+                    peer_rate_limiter,
                 )
                 .await;
             });
@@ -226,6 +233,7 @@ where
         let secret_key = self.secret_key.clone();
         let next_to_interface = self.next_to_interface.clone();
         let metrics = self.metrics.clone();
+        let peer_rate_limiter = self.peer_rate_limiter.clone();
         self.spawn_handle
             .spawn("aleph/clique_network_incoming", async move {
                 incoming(
@@ -235,6 +243,7 @@ where
                     next_to_interface,
                     authorization_requests_sender,
                     metrics,
+                    peer_rate_limiter,
                 )
                 .await;
             });
@@ -269,6 +278,7 @@ where
             // remove the peer from the manager all workers will be killed automatically, due to closed channels
             DelConnection(public_key) => {
                 self.manager.remove_peer(&public_key);
+                self.peer_rate_limiter.remove(&public_key);
             }
             // pass the data to the manager
             SendData(data, public_key) => match self.manager.send_to(&public_key, data) {
@@ -0,0 +1,333 @@
+//! Aggregation of BLS signatures of a committee. A committee signature becomes a single curve
+//! point together with a bitmap of the authorities that signed, so its size barely depends on the
+//! size of the committee. The BLS keys are not part of the session keys yet, so until they are
+//! nothing produces aggregates and justifications carrying one are rejected.
+//!
+//! Every authority signs the message prefixed with its own public key, so no two signers sign the
+//! same bytes and the aggregate has to be checked with one pairing per signer. Summing the keys
+//! of signers of a common message instead would let anyone register a key derived from the keys
+//! of others and forge aggregates alone, as the session pallet does not check proofs of
+//! possession of the keys.
+
+use parity_scale_codec::{Decode, Encode};
+use sp_core::Pair;
+use w3f_bls::{
+    DoublePublicKey, DoubleSignature, EngineBLS, Message, PublicKey, SerializableToBytes,
+    Signature, TinyBLS381,
+};
+
+use crate::{
+    abft::{NodeCount, NodeIndex},
+    selendra_primitives::{AuthorityBlsId, AuthorityBlsPair, AuthorityBlsSignature},
+};
+
+type Engine = TinyBLS381;
+
+/// Length of a serialized point of the signature group.
+const SIGNATURE_LENGTH: usize = 48;
+
+/// Which authorities contributed to an aggregate signature, one bit per authority.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SignerBitmap {
+    #[codec(compact)]
+    size: u32,
+    bits: Vec<u8>,
+}
+
+impl SignerBitmap {
+    pub fn with_size(size: NodeCount) -> Self {
+        SignerBitmap {
+            size: size.0 as u32,
+            bits: vec![0; size.0.div_ceil(8)],
+        }
+    }
+
+    pub fn size(&self) -> NodeCount {
+        NodeCount(self.size as usize)
+    }
+
+    /// Marks the node as a signer, returns whether it was not marked before.
+    fn insert(&mut self, index: NodeIndex) -> bool {
+        if index.0 >= self.size().0 {
+            return false;
+        }
+        let newly_inserted = !self.contains(index);
+        self.bits[index.0 / 8] |= 1 << (index.0 % 8);
+        newly_inserted
+    }
+
+    pub fn contains(&self, index: NodeIndex) -> bool {
+        index.0 < self.size().0 && self.bits[index.0 / 8] & (1 << (index.0 % 8)) != 0
+    }
+
+    /// Indices of all the signers, in increasing order.
+    pub fn signers(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        (0..self.size().0)
+            .map(NodeIndex)
+            .filter(|index| self.contains(*index))
+    }
+
+    /// Decoded bitmaps might have the wrong number of bytes or bits set past the size.
+    fn is_well_formed(&self) -> bool {
+        let size = self.size().0;
+        if self.bits.len() != size.div_ceil(8) {
+            return false;
+        }
+        match size % 8 {
+            0 => true,
+            used => self.bits.last().copied().unwrap_or(0) >> used == 0,
+        }
+    }
+}
+
+/// A single BLS signature standing in for the signatures of all the marked authorities.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct AggregateSignature {
+    signers: SignerBitmap,
+    signature: [u8; SIGNATURE_LENGTH],
+}
+
+impl AggregateSignature {
+    pub fn signers(&self) -> &SignerBitmap {
+        &self.signers
+    }
+}
+
+/// The bytes actually signed by the authority with the given key.
+fn augmented_message(key: &AuthorityBlsId, msg: &[u8]) -> Vec<u8> {
+    [key.as_ref(), msg].concat()
+}
+
+/// Signs the message in a way that allows aggregating the signature with ones of other
+/// authorities.
+pub fn sign_for_aggregation(pair: &AuthorityBlsPair, msg: &[u8]) -> AuthorityBlsSignature {
+    pair.sign(&augmented_message(&pair.public(), msg))
+}
+
+fn signature_point(signature: &AuthorityBlsSignature) -> Option<Signature<Engine>> {
+    DoubleSignature::<Engine>::from_bytes(signature.as_ref())
+        .ok()
+        .map(|signature| Signature(signature.0))
+}
+
+/// Aggregates signatures of a single message, made with `sign_for_aggregation` by the authorities with the given indices.
+/// Returns `None` if there are no signatures, any of them is malformed, or an index lies outside
+/// of the committee. Repeated indices are only counted once.
+pub fn aggregate_signatures<I: IntoIterator<Item = (NodeIndex, AuthorityBlsSignature)>>(
+    node_count: NodeCount,
+    signatures: I,
+) -> Option<AggregateSignature> {
+    let mut signers = SignerBitmap::with_size(node_count);
+    let mut aggregate: Option<Signature<Engine>> = None;
+    for (index, signature) in signatures {
+        if index.0 >= node_count.0 {
+            return None;
+        }
+        let point = signature_point(&signature)?;
+        if !signers.insert(index) {
+            continue;
+        }
+        aggregate = Some(match aggregate {
+            Some(mut aggregate) => {
+                aggregate.0 += point.0;
+                aggregate
+            }
+            None => point,
+        });
+    }
+    let mut signature = [0; SIGNATURE_LENGTH];
+    signature.copy_from_slice(&aggregate?.to_bytes());
+    Some(AggregateSignature { signers, signature })
+}
+
+/// Holds the BLS keys of a session committee, in the order of the committee.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BlsVerifier {
+    keys: Vec<AuthorityBlsId>,
+}
+
+impl BlsVerifier {
+    pub fn new(keys: Vec<AuthorityBlsId>) -> Self {
+        BlsVerifier { keys }
+    }
+
+    pub fn node_count(&self) -> NodeCount {
+        NodeCount(self.keys.len())
+    }
+
+    fn threshold(&self) -> usize {
+        2 * self.keys.len() / 3 + 1
+    }
+
+    fn public_key_point(key: &AuthorityBlsId) -> Option<PublicKey<Engine>> {
+        DoublePublicKey::<Engine>::from_bytes(key.as_ref())
+            .ok()
+            .map(|key| PublicKey(key.1))
+    }
+
+    /// Verifies whether the aggregate combines correct signatures of the message by more than 2/3
+    /// of all authorities.
+    pub fn is_complete(&self, msg: &[u8], aggregate: &AggregateSignature) -> bool {
+        let signers = aggregate.signers();
+        if signers.size() != self.node_count() || !signers.is_well_formed() {
+            return false;
+        }
+        if signers.signers().count() < self.threshold() {
+            return false;
+        }
+        let signature = match Signature::<Engine>::from_bytes(&aggregate.signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        let mut inputs = Vec::new();
+        for index in signers.signers() {
+            let key = &self.keys[index.0];
+            let point = match Self::public_key_point(key) {
+                Some(point) => point,
+                None => return false,
+            };
+            let message = Message::new(b"", &augmented_message(key, msg));
+            inputs.push((
+                Engine::prepare_public_key(point.0),
+                Engine::prepare_signature(message.hash_to_signature_curve::<Engine>()),
+            ));
+        }
+        Engine::verify_prepared(Engine::prepare_signature(signature.0), &inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::{Decode, Encode};
+    use sp_core::{crypto::ByteArray, Pair};
+    use w3f_bls::{DoublePublicKey, Message, PublicKey, SerializableToBytes};
+
+    use super::{
+        aggregate_signatures, sign_for_aggregation, AggregateSignature, BlsVerifier, Engine,
+        SignerBitmap,
+    };
+    use crate::{
+        abft::{NodeCount, NodeIndex},
+        selendra_primitives::{AuthorityBlsId, AuthorityBlsPair, AuthorityBlsSignature},
+    };
+
+    const MESSAGE: &[u8] = b"finalize me";
+
+    fn committee(size: usize) -> (Vec<AuthorityBlsPair>, BlsVerifier) {
+        let pairs: Vec<_> = (0..size).map(|_| AuthorityBlsPair::generate().0).collect();
+        let verifier = BlsVerifier::new(pairs.iter().map(|pair| pair.public()).collect());
+        (pairs, verifier)
+    }
+
+    fn signatures<'a>(
+        pairs: &'a [AuthorityBlsPair],
+        signers: &'a [usize],
+        msg: &'a [u8],
+    ) -> impl Iterator<Item = (NodeIndex, AuthorityBlsSignature)> + 'a {
+        signers
+            .iter()
+            .map(move |index| (NodeIndex(*index), sign_for_aggregation(&pairs[*index], msg)))
+    }
+
+    #[test]
+    fn verifies_aggregate_of_enough_signers() {
+        let (pairs, verifier) = committee(7);
+        let aggregate =
+            aggregate_signatures(NodeCount(7), signatures(&pairs, &[0, 2, 3, 5, 6], MESSAGE))
+                .expect("signatures should aggregate");
+        assert!(verifier.is_complete(MESSAGE, &aggregate));
+        let decoded = AggregateSignature::decode(&mut aggregate.encode().as_slice());
+        assert_eq!(decoded, Ok(aggregate));
+    }
+
+    #[test]
+    fn rejects_aggregate_of_too_few_signers() {
+        let (pairs, verifier) = committee(7);
+        let aggregate =
+            aggregate_signatures(NodeCount(7), signatures(&pairs, &[0, 2, 3, 5], MESSAGE))
+                .expect("signatures should aggregate");
+        assert!(!verifier.is_complete(MESSAGE, &aggregate));
+    }
+
+    #[test]
+    fn rejects_aggregate_of_other_message() {
+        let (pairs, verifier) = committee(4);
+        let aggregate =
+            aggregate_signatures(NodeCount(4), signatures(&pairs, &[0, 1, 2], b"other"))
+                .expect("signatures should aggregate");
+        assert!(!verifier.is_complete(MESSAGE, &aggregate));
+    }
+
+    #[test]
+    fn rejects_aggregate_with_tampered_signers() {
+        let (pairs, verifier) = committee(4);
+        let mut aggregate =
+            aggregate_signatures(NodeCount(4), signatures(&pairs, &[0, 1, 2], MESSAGE))
+                .expect("signatures should aggregate");
+        let mut signers = SignerBitmap::with_size(NodeCount(4));
+        for index in [0, 1, 3] {
+            signers.insert(NodeIndex(index));
+        }
+        aggregate.signers = signers;
+        assert!(!verifier.is_complete(MESSAGE, &aggregate));
+    }
+
+    #[test]
+    fn counts_repeated_signer_once() {
+        let (pairs, verifier) = committee(4);
+        let aggregate =
+            aggregate_signatures(NodeCount(4), signatures(&pairs, &[0, 1, 1, 1, 2], MESSAGE))
+                .expect("signatures should aggregate");
+        assert_eq!(aggregate.signers().signers().count(), 3);
+        assert!(verifier.is_complete(MESSAGE, &aggregate));
+    }
+
+    #[test]
+    fn rejects_signer_outside_committee() {
+        let (pairs, _) = committee(5);
+        assert!(
+            aggregate_signatures(NodeCount(4), signatures(&pairs, &[0, 1, 4], MESSAGE)).is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_plain_signatures() {
+        let (pairs, verifier) = committee(4);
+        let plain = [0, 1, 2]
+            .into_iter()
+            .map(|index| (NodeIndex(index), pairs[index].sign(MESSAGE)));
+        let aggregate =
+            aggregate_signatures(NodeCount(4), plain).expect("signatures should aggregate");
+        assert!(!verifier.is_complete(MESSAGE, &aggregate));
+    }
+
+    #[test]
+    fn rejects_aggregate_with_rogue_key() {
+        let honest = AuthorityBlsPair::generate().0.public();
+        let attacker = AuthorityBlsPair::generate().0;
+        let double_key = |key: &AuthorityBlsId| {
+            DoublePublicKey::<Engine>::from_bytes(key.as_ref()).expect("keys are well formed")
+        };
+        let (honest_key, attacker_key) = (double_key(&honest), double_key(&attacker.public()));
+        // Together with the honest key this sums up to the key of the attacker.
+        let rogue_key =
+            DoublePublicKey::<Engine>(attacker_key.0 - honest_key.0, attacker_key.1 - honest_key.1);
+        let rogue =
+            AuthorityBlsId::from_slice(&rogue_key.to_bytes()).expect("key has the right length");
+        let verifier = BlsVerifier::new(vec![honest, rogue]);
+
+        let mut aggregate =
+            aggregate_signatures(NodeCount(2), [(NodeIndex(1), attacker.sign(MESSAGE))])
+                .expect("signature should aggregate");
+        let mut signers = SignerBitmap::with_size(NodeCount(2));
+        signers.insert(NodeIndex(0));
+        signers.insert(NodeIndex(1));
+        aggregate.signers = signers;
+
+        let signature = w3f_bls::Signature::<Engine>::from_bytes(&aggregate.signature)
+            .expect("signature is well formed");
+        let summed_key = PublicKey::<Engine>(honest_key.1 + rogue_key.1);
+        assert!(signature.verify(&Message::new(b"", MESSAGE), &summed_key));
+        assert!(!verifier.is_complete(MESSAGE, &aggregate));
+    }
+}
@@ -8,15 +8,21 @@ use parity_scale_codec::{Decode, Encode};
 
 use crate::{
     abft::SignatureSet,
-    selendra_primitives::Hash,
     crypto::Signature,
     network::{
         data::{Network, SendError},
         Data,
     },
+    selendra_primitives::Hash,
     Keychain,
 };
 
+mod bls;
+
+pub use bls::{
+    aggregate_signatures, sign_for_aggregation, AggregateSignature, BlsVerifier, SignerBitmap,
+};
+
 /// Either a block hash or a performance report hash. They should never overlap. We assume that
 /// BlockHash and Hash are the same, it has always been this way, but if it ever changes this place
 /// will cause trouble.
@@ -20,7 +20,7 @@ pub use justification::{
     InnerJustification, Justification, JustificationTranslator, TranslateError,
 };
 pub use status_notifier::SubstrateChainStatusNotifier;
-pub use verification::{SubstrateFinalizationInfo, VerifierCache};
+pub use verification::{SessionVerifier, SubstrateFinalizationInfo, VerifierCache};
 
 use crate::{
     block::{BestBlockSelector, BlockchainEvents},
@@ -29,6 +29,7 @@ pub enum SessionVerificationError {
     BadMultisignature,
     BadEmergencySignature,
     NoEmergencySigner,
+    NoBlsAuthorities,
 }
 
 impl Display for SessionVerificationError {
@@ -38,6 +39,7 @@ impl Display for SessionVerificationError {
             BadMultisignature => write!(f, "bad multisignature"),
             BadEmergencySignature => write!(f, "bad emergency signature"),
             NoEmergencySigner => write!(f, "no emergency signer defined"),
+            NoBlsAuthorities => write!(f, "no bls keys of authorities known"),
         }
     }
 }
@@ -67,6 +69,8 @@ impl SessionVerifier {
                 true => Ok(()),
                 false => Err(BadEmergencySignature),
             },
+            // The BLS keys of the authorities are not kept on chain yet.
+            CommitteeAggregateSignature(_) => Err(NoBlsAuthorities),
         }
     }
 }
@@ -8,6 +8,7 @@ use parity_scale_codec::{Decode, DecodeAll, Encode, Error as CodecError, Input a
 
 use crate::{
     abft::SignatureSet,
+    aggregation::AggregateSignature,
     crypto::{Signature, SignatureV1},
     justification::{AlephJustification, LOG_TARGET},
     Version,
@@ -55,6 +56,8 @@ enum VersionedAlephJustification {
     V1(AlephJustificationV1),
     V2(AlephJustificationV2),
     V3(AlephJustification),
+    // Only for aggregated signatures, which nodes understanding just V3 cannot decode.
+    V4(AggregateSignature),
 }
 
 fn encode_with_version(version: Version, payload: &[u8]) -> Vec<u8> {
@@ -97,6 +100,7 @@ impl Encode for VersionedAlephJustification {
                 V1(justification) => justification.size_hint(),
                 V2(justification) => justification.size_hint(),
                 V3(justification) => justification.size_hint(),
+                V4(signature) => signature.size_hint(),
             }
     }
 
@@ -107,6 +111,7 @@ impl Encode for VersionedAlephJustification {
             V1(justification) => encode_with_version(Version(1), &justification.encode()),
             V2(justification) => encode_with_version(Version(2), &justification.encode()),
             V3(justification) => encode_with_version(Version(3), &justification.encode()),
+            V4(signature) => encode_with_version(Version(4), &signature.encode()),
         }
     }
 }
@@ -120,6 +125,7 @@ impl Decode for VersionedAlephJustification {
             Version(1) => Ok(V1(AlephJustificationV1::decode(input)?)),
             Version(2) => Ok(V2(AlephJustificationV2::decode(input)?)),
             Version(3) => Ok(V3(AlephJustification::decode(input)?)),
+            Version(4) => Ok(V4(AggregateSignature::decode(input)?)),
             _ => {
                 let mut payload = vec![0; num_bytes.into()];
                 input.read(payload.as_mut_slice())?;
@@ -133,6 +139,7 @@ impl Decode for VersionedAlephJustification {
 pub enum Error {
     BadFormat,
     UnknownVersion(Version),
+    AggregateNotProduced,
 }
 
 impl Display for Error {
@@ -147,6 +154,9 @@ impl Display for Error {
                     version.0
                 )
             }
+            AggregateNotProduced => {
+                write!(f, "aggregated justifications are not produced yet")
+            }
         }
     }
 }
@@ -181,6 +191,10 @@ pub fn backwards_compatible_decode(
             match justification {
                 V1(justification) => Ok(justification.into()),
                 V2(justification) => Ok(justification.into()),
+                // No node aggregates signatures yet, so an aggregate cannot be an honest one.
+                V3(AlephJustification::CommitteeAggregateSignature(_)) | V4(_) => {
+                    Err(AggregateNotProduced)
+                }
                 V3(justification) => Ok(justification),
                 Other(version, _) => {
                     // it is a coincidence that sometimes pre-compatibility legacy justification second word,
@@ -197,8 +211,15 @@ pub fn backwards_compatible_decode(
 }
 
 /// Encodes the justification in a way that is forwards compatible with future versions.
+/// Aggregated signatures get their own version, everything else is still encoded as V3, so that
+/// nodes unaware of aggregation can decode it.
 pub fn versioned_encode(justification: AlephJustification) -> Vec<u8> {
-    VersionedAlephJustification::V3(justification).encode()
+    use AlephJustification::*;
+    match justification {
+        CommitteeAggregateSignature(signature) => VersionedAlephJustification::V4(signature),
+        justification => VersionedAlephJustification::V3(justification),
+    }
+    .encode()
 }
 
 #[cfg(test)]
@@ -208,12 +229,15 @@ mod test {
 
     use super::{
         backwards_compatible_decode, versioned_encode, AlephJustificationV1, AlephJustificationV2,
-        VersionedAlephJustification,
+        Error, VersionedAlephJustification,
     };
     use crate::{
-        selendra_primitives::{AuthorityPair, AuthoritySignature},
+        aggregation::{aggregate_signatures, sign_for_aggregation},
         crypto::{Signature, SignatureV1},
         justification::AlephJustification,
+        selendra_primitives::{
+            AuthorityBlsPair, AuthorityBlsSignature, AuthorityPair, AuthoritySignature,
+        },
         NodeCount, SignatureSet, Version,
     };
 
@@ -276,6 +300,31 @@ mod test {
         assert_eq!(decoded, Ok(just_v3));
     }
 
+    #[test]
+    fn rejects_v4_until_produced() {
+        let signatures = (0..7).map(|i| {
+            let signature: AuthorityBlsSignature = sign_for_aggregation(
+                &AuthorityBlsPair::generate().0,
+                vec![0u8, 0u8, 0u8, 0u8].as_slice(),
+            );
+            (i.into(), signature)
+        });
+        let aggregate =
+            aggregate_signatures(7.into(), signatures).expect("signatures should aggregate");
+
+        let just_v4 = AlephJustification::CommitteeAggregateSignature(aggregate.clone());
+        let encoded_just = versioned_encode(just_v4.clone());
+        assert_eq!(
+            VersionedAlephJustification::decode(&mut encoded_just.as_slice()),
+            Ok(VersionedAlephJustification::V4(aggregate))
+        );
+        let decoded = backwards_compatible_decode(encoded_just);
+        assert_eq!(decoded, Err(Error::AggregateNotProduced));
+        let encoded_v3 = VersionedAlephJustification::V3(just_v4).encode();
+        let decoded = backwards_compatible_decode(encoded_v3);
+        assert_eq!(decoded, Err(Error::AggregateNotProduced));
+    }
+
     #[test]
     fn correctly_decodes_other() {
         let other = VersionedAlephJustification::Other(Version(43), vec![21, 37]);
@@ -295,6 +344,9 @@ mod test {
             Ok(AlephJustification::EmergencySignature(_)) => {
                 panic!("decoded V1 as emergency signature")
             }
+            Ok(AlephJustification::CommitteeAggregateSignature(_)) => {
+                panic!("decoded V1 as aggregate signature")
+            }
             Err(e) => panic!("decoding V1 failed: {e}"),
         }
     }
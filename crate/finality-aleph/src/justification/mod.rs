@@ -3,6 +3,7 @@ use sp_runtime::Justification;
 
 use crate::{
     abft::SignatureSet,
+    aggregation::AggregateSignature,
     selendra_primitives::{AuthoritySignature, ALEPH_ENGINE_ID},
     crypto::Signature,
 };
@@ -13,12 +14,15 @@ pub use compatibility::{backwards_compatible_decode, versioned_encode, Error as
 
 const LOG_TARGET: &str = "aleph-justification";
 
-/// A proof of block finality, currently in the form of a sufficiently long list of signatures or a
-/// sudo signature of a block for emergency finalization.
+/// A proof of block finality, in the form of a sufficiently long list of signatures, a single
+/// aggregated BLS signature of sufficiently many authorities, or a sudo signature of a block for
+/// emergency finalization. Aggregated signatures cannot be verified until the BLS keys of the
+/// authorities are part of the session keys.
 #[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
 pub enum AlephJustification {
     CommitteeMultisignature(SignatureSet<Signature>),
     EmergencySignature(AuthoritySignature),
+    CommitteeAggregateSignature(AggregateSignature),
 }
 
 impl From<AlephJustification> for Justification {
@@ -57,8 +57,12 @@ mod sync;
 mod sync_oracle;
 #[cfg(test)]
 pub mod testing;
+mod warp_sync;
 
 pub use crate::{
+    aggregation::{
+        aggregate_signatures, sign_for_aggregation, AggregateSignature, BlsVerifier, SignerBitmap,
+    },
     block::{
         substrate::{BlockImporter, Justification, JustificationTranslator, SubstrateChainStatus},
         BlockId,
@@ -67,6 +71,7 @@ pub use crate::{
     justification::AlephJustification,
     network::{
         address_cache::{ValidatorAddressCache, ValidatorAddressingInfo},
+        address_registration::{AddressRegistration, AddressRegistrationSigner},
         build_network, BuildNetworkOutput, ProtocolNetwork, SubstrateNetworkConfig,
         SubstratePeerId,
     },
@@ -74,6 +79,10 @@ pub use crate::{
     session::SessionPeriod,
     sync::FavouriteSelectChainProvider,
     sync_oracle::SyncOracle,
+    warp_sync::{
+        export_snapshot, import_snapshot, warp_sync, Error as WarpSyncError, SnapshotError,
+        WarpSyncProtocols,
+    },
 };
 
 /// Constant defining how often components of finality-aleph should report their state
@@ -255,6 +264,29 @@ pub struct RateLimiterConfig {
     pub alephbft_network_bit_rate: u64,
     /// Maximum bit-rate in bits per second of the substrate network (shared by sync, gossip, etc.).
     pub substrate_network_bit_rate: u64,
+    /// Maximum bit-rate in bits per second of the data received from a single peer of the alephbft
+    /// validator network, unlimited if not set.
+    pub validator_network_peer_bit_rate: Option<u64>,
+    /// Maximum bit-rate in bits per second of the block sync messages received from a single peer,
+    /// unlimited if not set.
+    pub sync_peer_bit_rate: Option<u64>,
+    /// Maximum percentage of the combined bit-rate of both networks that block sync can use, the
+    /// rest is reserved for AlephBFT. Block sync is not held back if not set.
+    pub sync_bandwidth_share: Option<u8>,
+}
+
+/// How AlephBFT backups are stored and cleaned up.
+#[derive(Clone, Debug, Default)]
+pub struct BackupConfig {
+    /// Directory to save backups to, backups are turned off if not set.
+    pub path: Option<PathBuf>,
+    /// Number of sessions before the current one whose backups are kept.
+    pub retention_sessions: u32,
+    /// Maximum total size of the backups in bytes. Backups of the oldest sessions are removed
+    /// first, the backup of the current session is never removed.
+    pub max_size: Option<u64>,
+    /// Whether to compress the backups.
+    pub compression: bool,
 }
 
 pub struct AlephConfig<C, T> {
@@ -273,11 +305,12 @@ pub struct AlephConfig<C, T> {
     pub millisecs_per_block: MillisecsPerBlock,
     pub score_submission_period: u32,
     pub unit_creation_delay: UnitCreationDelay,
-    pub backup_saving_path: Option<PathBuf>,
+    pub backup_config: BackupConfig,
     pub external_addresses: Vec<String>,
     pub validator_port: u16,
     pub rate_limiter_config: RateLimiterConfig,
     pub sync_oracle: SyncOracle,
     pub validator_address_cache: Option<ValidatorAddressCache>,
+    pub address_registration_signer: AddressRegistrationSigner,
     pub transaction_pool: Arc<T>,
 }
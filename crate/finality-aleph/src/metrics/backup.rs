@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use substrate_prometheus_endpoint::{
+    exponential_buckets, register, Gauge, Histogram, HistogramOpts, PrometheusError, Registry, U64,
+};
+
+#[derive(Clone)]
+pub enum BackupMetrics {
+    Prometheus {
+        size: Gauge<U64>,
+        write_latency: Histogram,
+    },
+    Noop,
+}
+
+impl BackupMetrics {
+    pub fn new(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+        let registry = match registry {
+            Some(registry) => registry,
+            None => return Ok(BackupMetrics::Noop),
+        };
+        Ok(BackupMetrics::Prometheus {
+            size: register(
+                Gauge::new(
+                    "aleph_backup_size_bytes",
+                    "Total size of the AlephBFT backups on disk",
+                )?,
+                registry,
+            )?,
+            write_latency: register(
+                Histogram::with_opts(
+                    HistogramOpts::new(
+                        "aleph_backup_write_latency_seconds",
+                        "Time of writing a single AlephBFT backup record to disk",
+                    )
+                    .buckets(exponential_buckets(0.0001, 4.0, 8)?),
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    pub fn noop() -> Self {
+        BackupMetrics::Noop
+    }
+
+    pub fn set_size(&self, bytes: u64) {
+        if let BackupMetrics::Prometheus { size, .. } = self {
+            size.set(bytes);
+        }
+    }
+
+    pub fn report_write(&self, bytes: u64, latency: Duration) {
+        if let BackupMetrics::Prometheus {
+            size,
+            write_latency,
+        } = self
+        {
+            size.add(bytes);
+            write_latency.observe(latency.as_secs_f64());
+        }
+    }
+}
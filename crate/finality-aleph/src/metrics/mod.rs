@@ -1,11 +1,15 @@
 mod abft_score;
+mod backup;
 mod best_block;
 mod finality_rate;
+mod network;
 mod slo;
 mod timing;
 pub mod transaction_pool;
 
 pub use abft_score::ScoreMetrics;
+pub use backup::BackupMetrics;
+pub use network::{NetworkComponent, NetworkUsageMetrics};
 pub use slo::{run_metrics_service, SloMetrics};
 pub use timing::{Checkpoint, DefaultClock};
 pub type TimingBlockMetrics = timing::TimingBlockMetrics<DefaultClock>;
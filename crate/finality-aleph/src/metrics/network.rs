@@ -0,0 +1,93 @@
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+use substrate_prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+
+/// Parts of the node sharing the network, whose traffic is accounted separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkComponent {
+    /// AlephBFT units.
+    Abft,
+    /// Signatures aggregated into justifications.
+    Aggregation,
+    /// Block sync requests and responses.
+    Sync,
+    /// Authentications of validators, used for discovery.
+    Authentication,
+}
+
+impl NetworkComponent {
+    fn label(&self) -> &'static str {
+        use NetworkComponent::*;
+        match self {
+            Abft => "abft",
+            Aggregation => "aggregation",
+            Sync => "sync",
+            Authentication => "authentication",
+        }
+    }
+}
+
+impl Display for NetworkComponent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(f, "{}", self.label())
+    }
+}
+
+#[derive(Clone)]
+pub enum NetworkUsageMetrics {
+    Prometheus {
+        received: CounterVec<U64>,
+        sent: CounterVec<U64>,
+    },
+    Noop,
+}
+
+impl NetworkUsageMetrics {
+    pub fn new(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+        let registry = match registry {
+            Some(registry) => registry,
+            None => return Ok(NetworkUsageMetrics::Noop),
+        };
+        Ok(NetworkUsageMetrics::Prometheus {
+            received: register(
+                CounterVec::new(
+                    Opts::new(
+                        "aleph_network_received_bytes",
+                        "Size of encoded messages received by a component",
+                    ),
+                    &["component"],
+                )?,
+                registry,
+            )?,
+            sent: register(
+                CounterVec::new(
+                    Opts::new(
+                        "aleph_network_sent_bytes",
+                        "Size of encoded messages sent by a component",
+                    ),
+                    &["component"],
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    pub fn noop() -> Self {
+        NetworkUsageMetrics::Noop
+    }
+
+    pub fn report_received(&self, component: NetworkComponent, bytes: usize) {
+        if let NetworkUsageMetrics::Prometheus { received, .. } = self {
+            received
+                .with_label_values(&[component.label()])
+                .inc_by(bytes as u64);
+        }
+    }
+
+    pub fn report_sent(&self, component: NetworkComponent, bytes: usize) {
+        if let NetworkUsageMetrics::Prometheus { sent, .. } = self {
+            sent.with_label_values(&[component.label()])
+                .inc_by(bytes as u64);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use parity_scale_codec::Encode;
+use parking_lot::Mutex;
+use primitives::{validator_address_message, AccountId, BlockHash, SessionIndex};
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+
+use crate::{crypto::AuthorityPen, selendra_primitives::AuthorityId, session::SessionId};
+
+/// Registration of the validator network address of this node, signed by its authority key and
+/// ready to be submitted with `ValidatorAddresses::register_address` by its owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressRegistration {
+    /// Authority the address is registered for.
+    pub authority: AuthorityId,
+    /// Encoded addressing information of the validator network endpoint.
+    pub address: Bytes,
+    /// Session the registration is signed for.
+    pub session: SessionIndex,
+    /// Encoded signature of the authority over the registration.
+    pub signature: Bytes,
+}
+
+struct ValidatorAddress {
+    session_id: SessionId,
+    address: Vec<u8>,
+    pen: AuthorityPen,
+}
+
+/// Signs registrations of the address this node uses in the latest session it is a validator
+/// in, so that operators can register it on chain.
+#[derive(Clone, Default)]
+pub struct AddressRegistrationSigner {
+    latest: Arc<Mutex<Option<ValidatorAddress>>>,
+}
+
+impl AddressRegistrationSigner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember the address of this node in a validator session, unless it is a validator in
+    /// a later session already.
+    pub(crate) fn update<A: Encode>(&self, session_id: SessionId, address: &A, pen: AuthorityPen) {
+        let mut latest = self.latest.lock();
+        if latest
+            .as_ref()
+            .map_or(true, |latest| latest.session_id <= session_id)
+        {
+            *latest = Some(ValidatorAddress {
+                session_id,
+                address: address.encode(),
+                pen,
+            });
+        }
+    }
+
+    /// Sign the registration of the current address of this node to be submitted by `owner` on
+    /// the chain with `genesis_hash`, `None` if the node has not been a validator yet.
+    pub fn sign(&self, genesis_hash: &BlockHash, owner: &AccountId) -> Option<AddressRegistration> {
+        let latest = self.latest.lock();
+        let ValidatorAddress {
+            session_id,
+            address,
+            pen,
+        } = latest.as_ref()?;
+        let message = validator_address_message(genesis_hash, owner, address, session_id.0);
+
+        Some(AddressRegistration {
+            authority: pen.authority_id(),
+            address: address.clone().into(),
+            session: session_id.0,
+            signature: pen.sign(&message).0.encode().into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::{Decode, Encode};
+    use primitives::{validator_address_message, AccountId, AuthoritySignature, BlockHash};
+    use sp_runtime::RuntimeAppPublic;
+
+    use super::AddressRegistrationSigner;
+    use crate::{network::mock::crypto_basics, SessionId};
+
+    #[test]
+    fn signs_nothing_before_validating() {
+        let signer = AddressRegistrationSigner::new();
+
+        assert_eq!(
+            signer.sign(&BlockHash::zero(), &AccountId::new([1; 32])),
+            None
+        );
+    }
+
+    #[test]
+    fn signs_registration_for_owner_and_chain() {
+        let (pens, _) = crypto_basics(1);
+        let pen = pens[0].1.clone();
+        let signer = AddressRegistrationSigner::new();
+        let genesis_hash = BlockHash::repeat_byte(7);
+        let owner = AccountId::new([1; 32]);
+        signer.update(SessionId(3), &b"address".to_vec(), pen.clone());
+
+        let registration = signer
+            .sign(&genesis_hash, &owner)
+            .expect("the node is a validator");
+
+        assert_eq!(registration.authority, pen.authority_id());
+        assert_eq!(registration.session, 3);
+        let signature = AuthoritySignature::decode(&mut &registration.signature[..])
+            .expect("the signature is encoded");
+        let message = |genesis_hash: &BlockHash, owner: &AccountId| {
+            validator_address_message(genesis_hash, owner, &registration.address, 3)
+        };
+        assert!(pen
+            .authority_id()
+            .verify(&message(&genesis_hash, &owner), &signature));
+        assert!(!pen.authority_id().verify(
+            &message(&genesis_hash, &AccountId::new([2; 32])),
+            &signature
+        ));
+        assert!(!pen
+            .authority_id()
+            .verify(&message(&BlockHash::zero(), &owner), &signature));
+    }
+
+    #[test]
+    fn signs_registration_for_latest_session() {
+        let (pens, _) = crypto_basics(1);
+        let pen = pens[0].1.clone();
+        let signer = AddressRegistrationSigner::new();
+        signer.update(SessionId(4), &b"new address".to_vec(), pen.clone());
+        signer.update(SessionId(3), &b"old address".to_vec(), pen);
+
+        let registration = signer
+            .sign(&BlockHash::zero(), &AccountId::new([1; 32]))
+            .expect("the node is a validator");
+
+        assert_eq!(registration.session, 4);
+        assert_eq!(registration.address.0, b"new address".to_vec().encode());
+    }
+}
@@ -7,7 +7,7 @@ use sc_network::{
         Params as NetworkParams, ProtocolId, Role,
     },
     error::Error as NetworkError,
-    NetworkService, NetworkWorker,
+    NetworkService, NetworkWorker, ProtocolName,
 };
 use sc_network_common::ExHashT;
 use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
@@ -21,7 +21,9 @@ use crate::{
     network::build::{
         own_protocols::Networks, transactions::build_transactions_prototype, SPAWN_CATEGORY,
     },
-    BlockHash, BlockNumber, ClientForAleph,
+    session::SessionBoundaryInfo,
+    warp_sync::{RequestHandler as WarpRequestHandler, WarpProofProvider, WarpSyncProtocols},
+    BlockHash, BlockNumber, ClientForAleph, SessionPeriod,
 };
 
 fn spawn_state_request_handler<B: Block, BE: Backend<B>, C: ClientForAleph<B, BE>>(
@@ -29,7 +31,8 @@ fn spawn_state_request_handler<B: Block, BE: Backend<B>, C: ClientForAleph<B, BE
     protocol_id: &ProtocolId,
     client: Arc<C>,
     spawn_handle: &SpawnTaskHandle,
-) where
+) -> ProtocolName
+where
     B::Hash: ExHashT,
 {
     let num_peer_hint = full_network_config
@@ -43,8 +46,10 @@ fn spawn_state_request_handler<B: Block, BE: Backend<B>, C: ClientForAleph<B, BE
     let (service, protocol_config) =
     // The None is the fork id, which we don't have.
         StateRequestHandler::new::<NetworkWorker<B, B::Hash>>(protocol_id, None, client, num_peer_hint);
+    let protocol_name = protocol_config.name.clone();
     spawn_handle.spawn("state-request-handler", SPAWN_CATEGORY, service.run());
     full_network_config.add_request_response_protocol(protocol_config);
+    protocol_name
 }
 
 fn spawn_light_client_request_handler<B: Block, BE: Backend<B>, C: ClientForAleph<B, BE>>(
@@ -66,10 +71,33 @@ fn spawn_light_client_request_handler<B: Block, BE: Backend<B>, C: ClientForAlep
     full_network_config.add_request_response_protocol(protocol_config);
 }
 
+fn spawn_warp_request_handler<B, BE, C>(
+    full_network_config: &mut FullNetworkConfiguration<B, B::Hash, NetworkWorker<B, B::Hash>>,
+    genesis_hash: &BlockHash,
+    session_period: SessionPeriod,
+    client: Arc<C>,
+    spawn_handle: &SpawnTaskHandle,
+) -> ProtocolName
+where
+    B: Block<Hash = BlockHash>,
+    B::Header: Header<Number = BlockNumber>,
+    BE: Backend<B>,
+    C: ClientForAleph<B, BE>,
+{
+    let provider = WarpProofProvider::new(client, SessionBoundaryInfo::new(session_period));
+    let (handler, protocol_config) =
+        WarpRequestHandler::new::<NetworkWorker<B, B::Hash>>(genesis_hash, provider);
+    let protocol_name = protocol_config.name.clone();
+    spawn_handle.spawn("warp-request-handler", SPAWN_CATEGORY, handler.run());
+    full_network_config.add_request_response_protocol(protocol_config);
+    protocol_name
+}
+
 type BaseNetworkOutput<B> = (
     Arc<NetworkService<B, <B as Block>::Hash>>,
     Networks,
     TransactionsHandlerPrototype,
+    WarpSyncProtocols,
 );
 
 /// Create a base network with all the protocols already included. Also spawn (almost) all the necessary services.
@@ -82,6 +110,7 @@ pub fn network<B, BE, C>(
         std::sync::Arc<sc_network::transport::BandwidthSinks>,
     ),
     protocol_id: ProtocolId,
+    session_period: SessionPeriod,
     client: Arc<C>,
     spawn_handle: &SpawnTaskHandle,
     base_protocol_config: NonDefaultSetConfig,
@@ -102,12 +131,19 @@ where
         .expect("Genesis block exists.");
     let networks = Networks::new(&mut full_network_config, &genesis_hash);
 
-    spawn_state_request_handler(
+    let state = spawn_state_request_handler(
         &mut full_network_config,
         &protocol_id,
         client.clone(),
         spawn_handle,
     );
+    let warp = spawn_warp_request_handler(
+        &mut full_network_config,
+        &genesis_hash,
+        session_period,
+        client.clone(),
+        spawn_handle,
+    );
     spawn_light_client_request_handler(
         &mut full_network_config,
         &protocol_id,
@@ -144,5 +180,10 @@ where
         NetworkWorker::new_with_custom_transport(network_params, transport_builder)?;
     let network = network_service.service().clone();
     spawn_handle.spawn_blocking("network-worker", SPAWN_CATEGORY, network_service.run());
-    Ok((network, networks, transactions_prototype))
+    Ok((
+        network,
+        networks,
+        transactions_prototype,
+        WarpSyncProtocols { warp, state },
+    ))
 }
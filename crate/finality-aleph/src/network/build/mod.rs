@@ -22,7 +22,8 @@ use crate::{
         base_protocol::{setup as setup_base_protocol, Service as BaseProtocolService},
         LOG_TARGET,
     },
-    BlockHash, BlockNumber, ClientForAleph, ProtocolNetwork,
+    warp_sync::WarpSyncProtocols,
+    BlockHash, BlockNumber, ClientForAleph, ProtocolNetwork, SessionPeriod,
 };
 
 mod base;
@@ -47,6 +48,7 @@ pub struct NetworkOutput<TP: TransactionPool + 'static> {
     pub sync_service: Arc<SyncingService<TP::Block>>,
     pub tx_handler_controller: TransactionsHandlerController<TP::Hash>,
     pub system_rpc_tx: TracingUnboundedSender<RpcRequest<TP::Block>>,
+    pub warp_sync_protocols: WarpSyncProtocols,
 }
 
 pub struct SubstrateNetworkConfig {
@@ -54,6 +56,8 @@ pub struct SubstrateNetworkConfig {
     pub substrate_network_bit_rate: u64,
     /// Configuration of the network service.
     pub network_config: NetworkConfiguration,
+    /// Session period of the chain, needed for serving warp proofs.
+    pub session_period: SessionPeriod,
 }
 
 /// Start everything necessary to run the inter-node network and return the interfaces for it.
@@ -93,10 +97,12 @@ where
             authentication_network,
         },
         transaction_prototype,
+        warp_sync_protocols,
     ) = base_network(
         &network_config.network_config,
         transport_builder,
         protocol_id,
+        network_config.session_period,
         client.clone(),
         spawn_handle,
         base_protocol_config,
@@ -138,5 +144,6 @@ where
         sync_service: syncing_service,
         tx_handler_controller: transaction_interface,
         system_rpc_tx: rpc_interface,
+        warp_sync_protocols,
     })
 }
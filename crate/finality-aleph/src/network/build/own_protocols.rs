@@ -4,6 +4,7 @@ use sc_network_common::ExHashT;
 use sp_runtime::traits::Block;
 
 use crate::{
+    metrics::NetworkComponent,
     network::{
         session::MAX_MESSAGE_SIZE as MAX_AUTHENTICATION_MESSAGE_SIZE, substrate::ProtocolNetwork,
     },
@@ -30,6 +31,7 @@ impl Networks {
     fn add_protocol<B: Block, N: NetworkBackend<B, B::Hash>>(
         genesis_hash: &BlockHash,
         protocol_name: &str,
+        component: NetworkComponent,
         max_message_size: u64,
         net_config: &mut FullNetworkConfiguration<B, B::Hash, N>,
     ) -> ProtocolNetwork
@@ -51,7 +53,7 @@ impl Networks {
             peer_store_handle,
         );
         net_config.add_notification_protocol(config);
-        ProtocolNetwork::new(notifications)
+        ProtocolNetwork::new(notifications, component)
     }
 
     /// Create the full configuration and networks per protocol.
@@ -62,12 +64,14 @@ impl Networks {
         let authentication_network = Self::add_protocol(
             genesis_hash,
             AUTHENTICATION_PROTOCOL_NAME,
+            NetworkComponent::Authentication,
             MAX_AUTHENTICATION_MESSAGE_SIZE,
             net_config,
         );
         let block_sync_network = Self::add_protocol(
             genesis_hash,
             BLOCK_SYNC_PROTOCOL_NAME,
+            NetworkComponent::Sync,
             MAX_BLOCK_SYNC_MESSAGE_SIZE,
             net_config,
         );
@@ -5,15 +5,16 @@ use std::{marker::PhantomData, sync::Arc};
 use futures::channel::mpsc;
 use log::{debug, trace};
 use parity_scale_codec::{Decode, Encode};
-use tokio::sync::Mutex;
+use tokio::{sync::Mutex, time::sleep_until};
 
 use crate::{
+    metrics::NetworkComponent,
     network::{
         data::{
             component::{Network, NetworkExt, Receiver, Sender, SimpleNetwork},
             SendError,
         },
-        Data,
+        Data, TrafficControl,
     },
     Recipient, Version, Versioned,
 };
@@ -72,6 +73,8 @@ struct SplitSender<
     Conv: Convert,
 > {
     sender: S,
+    component: NetworkComponent,
+    traffic: TrafficControl,
     phantom: PhantomData<(LeftData, RightData, Conv)>,
 }
 
@@ -86,6 +89,7 @@ where
     <Conv as Convert>::To: Data,
 {
     fn send(&self, data: Conv::From, recipient: Recipient) -> Result<(), SendError> {
+        self.traffic.sent(self.component, data.encoded_size());
         self.sender.send(Conv::convert(data), recipient)
     }
 }
@@ -106,7 +110,8 @@ struct SplitReceiver<
     translated_receiver: mpsc::UnboundedReceiver<TranslatedData>,
     left_sender: mpsc::UnboundedSender<LeftData>,
     right_sender: mpsc::UnboundedSender<RightData>,
-    name: &'static str,
+    components: (NetworkComponent, NetworkComponent),
+    traffic: TrafficControl,
 }
 
 #[async_trait::async_trait]
@@ -123,7 +128,7 @@ impl<
                 data = self.translated_receiver.next() => {
                     return data;
                 },
-                should_go_on = forward_or_wait(&self.receiver, &self.left_sender, &self.right_sender, self.name) => {
+                should_go_on = forward_or_wait(&self.receiver, &self.left_sender, &self.right_sender, self.components, &self.traffic) => {
                     if !should_go_on {
                         return None;
                     }
@@ -145,35 +150,46 @@ async fn forward_or_wait<
     receiver: &Mutex<R>,
     left_sender: &mpsc::UnboundedSender<LeftData>,
     right_sender: &mpsc::UnboundedSender<RightData>,
-    name: &str,
+    (left, right): (NetworkComponent, NetworkComponent),
+    traffic: &TrafficControl,
 ) -> bool {
     // It's totally fine if we are unable to send a message on left_sender or right_sender.
     // The other half of the channel can be dropped for any reason,
     // but it's not our responsibility to react for it here.
-    match receiver.lock().await.next().await {
+    let maybe_data = receiver.lock().await.next().await;
+    let deadline = match maybe_data {
         Some(Split::Left(data)) => {
+            let deadline = traffic.received(left, data.encoded_size());
             if left_sender.unbounded_send(data).is_err() {
-                debug!(target: "aleph-network", "Unable to send to LeftNetwork ({}) - already disabled", name);
+                debug!(target: "aleph-network", "Unable to send to LeftNetwork ({}) - already disabled", left);
             }
-            true
+            deadline
         }
         Some(Split::Right(data)) => {
+            let deadline = traffic.received(right, data.encoded_size());
             if right_sender.unbounded_send(data).is_err() {
-                debug!(target: "aleph-network", "Unable to send to RightNetwork ({}) - already disabled", name);
+                debug!(target: "aleph-network", "Unable to send to RightNetwork ({}) - already disabled", right);
             }
-            true
+            deadline
         }
         None => {
             trace!(target: "aleph-network", "Split data channel ended");
             left_sender.close_channel();
             right_sender.close_channel();
-            false
+            return false;
         }
+    };
+    // The data is already forwarded, so being cancelled while waiting loses nothing.
+    if let Some(deadline) = deadline {
+        sleep_until(deadline.into()).await;
     }
+    true
 }
 
 fn split_sender<LeftData: Data, RightData: Data, S: Sender<Split<LeftData, RightData>>>(
     sender: S,
+    (left, right): (NetworkComponent, NetworkComponent),
+    traffic: TrafficControl,
 ) -> (
     LeftSender<LeftData, RightData, S>,
     RightSender<LeftData, RightData, S>,
@@ -181,10 +197,14 @@ fn split_sender<LeftData: Data, RightData: Data, S: Sender<Split<LeftData, Right
     (
         LeftSender {
             sender: sender.clone(),
+            component: left,
+            traffic: traffic.clone(),
             phantom: PhantomData,
         },
         RightSender {
             sender,
+            component: right,
+            traffic,
             phantom: PhantomData,
         },
     )
@@ -192,8 +212,8 @@ fn split_sender<LeftData: Data, RightData: Data, S: Sender<Split<LeftData, Right
 
 fn split_receiver<LeftData: Data, RightData: Data, R: Receiver<Split<LeftData, RightData>>>(
     receiver: R,
-    left_name: &'static str,
-    right_name: &'static str,
+    components: (NetworkComponent, NetworkComponent),
+    traffic: TrafficControl,
 ) -> (
     LeftReceiver<LeftData, RightData, R>,
     RightReceiver<LeftData, RightData, R>,
@@ -207,14 +227,16 @@ fn split_receiver<LeftData: Data, RightData: Data, R: Receiver<Split<LeftData, R
             translated_receiver: left_receiver,
             left_sender: left_sender.clone(),
             right_sender: right_sender.clone(),
-            name: left_name,
+            components,
+            traffic: traffic.clone(),
         },
         RightReceiver {
             receiver,
             translated_receiver: right_receiver,
             left_sender,
             right_sender,
-            name: right_name,
+            components,
+            traffic,
         },
     )
 }
@@ -227,15 +249,17 @@ fn split_receiver<LeftData: Data, RightData: Data, R: Receiver<Split<LeftData, R
 /// the end of those channels which contains the type that it is supposed to return.
 ///
 /// The main example for now is creating an `aleph_bft::Network` and a separate one for accumulating
-/// signatures for justifications.
+/// signatures for justifications. The traffic of both is accounted as coming from the respective
+/// components.
 pub fn split<LeftData: Data, RightData: Data, CN: Network<Split<LeftData, RightData>>>(
     network: CN,
-    left_name: &'static str,
-    right_name: &'static str,
+    left: NetworkComponent,
+    right: NetworkComponent,
+    traffic: TrafficControl,
 ) -> (impl NetworkExt<LeftData>, impl NetworkExt<RightData>) {
     let (sender, receiver) = network.into();
-    let (left_sender, right_sender) = split_sender(sender);
-    let (left_receiver, right_receiver) = split_receiver(receiver, left_name, right_name);
+    let (left_sender, right_sender) = split_sender(sender, (left, right), traffic.clone());
+    let (left_receiver, right_receiver) = split_receiver(receiver, (left, right), traffic);
     (
         SimpleNetwork::new(left_receiver, left_sender),
         SimpleNetwork::new(right_receiver, right_sender),
@@ -10,8 +10,10 @@ pub use build::{
 use network_clique::{AddressingInformation, NetworkIdentity, PeerId};
 use parity_scale_codec::Codec;
 pub use substrate::{PeerId as SubstratePeerId, ProtocolNetwork};
+pub use traffic::TrafficControl;
 
 pub mod address_cache;
+pub mod address_registration;
 mod base_protocol;
 mod build;
 pub mod data;
@@ -20,6 +22,7 @@ pub mod mock;
 pub mod session;
 mod substrate;
 pub mod tcp;
+mod traffic;
 
 const LOG_TARGET: &str = "aleph-network";
 
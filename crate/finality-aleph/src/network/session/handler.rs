@@ -160,6 +160,25 @@ impl<A: AddressingInformation> Handler<A> {
         Some(address)
     }
 
+    /// Checks an address the node registered on chain and returns it as a hint we should connect
+    /// to, unless an authentication of the node is already known. The registration might be
+    /// stale, so the node stays missing until it authenticates.
+    pub fn handle_registered_address(&self, node_id: NodeIndex, address: A) -> Option<A> {
+        if Some(node_id) == self.index()
+            || node_id.0 >= self.node_count().0
+            || self.peers_by_node.contains_key(&node_id)
+        {
+            return None;
+        }
+        if !address.verify() {
+            return None;
+        }
+        if address.peer_id() == self.own_peer_id {
+            return None;
+        }
+        Some(address)
+    }
+
     /// Returns the PeerId of the node with the given NodeIndex, if known.
     pub fn peer_id(&self, node_id: &NodeIndex) -> Option<A::PeerId> {
         self.peers_by_node.get(node_id).cloned()
@@ -487,4 +506,74 @@ pub mod tests {
         assert_eq!(missing_nodes, expected_missing);
         assert!(handler0.peer_id(&NodeIndex(1)).is_none());
     }
+
+    #[test]
+    fn uses_registered_address_only_as_hint() {
+        let crypto_basics = crypto_basics(NUM_NODES);
+        let handler0 = Handler::new(
+            Some(crypto_basics.0[0].clone()),
+            crypto_basics.1,
+            SessionId(43),
+            random_address(),
+        );
+        let address = random_address();
+        assert_eq!(
+            handler0.handle_registered_address(NodeIndex(1), address.clone()),
+            Some(address.clone())
+        );
+        assert!(handler0.peer_id(&NodeIndex(1)).is_none());
+        let expected_missing: Vec<_> = (1..NUM_NODES).map(NodeIndex).collect();
+        assert_eq!(handler0.missing_nodes(), expected_missing);
+    }
+
+    #[test]
+    fn ignores_invalid_or_own_registered_address() {
+        let crypto_basics = crypto_basics(NUM_NODES);
+        let own_address = random_address();
+        let handler0 = Handler::new(
+            Some(crypto_basics.0[0].clone()),
+            crypto_basics.1,
+            SessionId(43),
+            own_address.clone(),
+        );
+        assert!(handler0
+            .handle_registered_address(NodeIndex(0), random_address())
+            .is_none());
+        assert!(handler0
+            .handle_registered_address(NodeIndex(1), random_invalid_address())
+            .is_none());
+        assert!(handler0
+            .handle_registered_address(NodeIndex(1), own_address)
+            .is_none());
+        assert!(handler0
+            .handle_registered_address(NodeIndex(NUM_NODES), random_address())
+            .is_none());
+        let expected_missing: Vec<_> = (1..NUM_NODES).map(NodeIndex).collect();
+        assert_eq!(handler0.missing_nodes(), expected_missing);
+    }
+
+    #[test]
+    fn prefers_authentication_to_registered_address() {
+        let crypto_basics = crypto_basics(NUM_NODES);
+        let mut handler0 = Handler::new(
+            Some(crypto_basics.0[0].clone()),
+            crypto_basics.1.clone(),
+            SessionId(43),
+            random_address(),
+        );
+        let address = random_address();
+        let handler1 = Handler::new(
+            Some(crypto_basics.0[1].clone()),
+            crypto_basics.1,
+            SessionId(43),
+            address.clone(),
+        );
+        assert!(handler0
+            .handle_authentication(authentication(&handler1))
+            .is_some());
+        assert!(handler0
+            .handle_registered_address(NodeIndex(1), random_address())
+            .is_none());
+        assert_eq!(handler0.peer_id(&NodeIndex(1)), Some(address.peer_id()));
+    }
 }
@@ -6,12 +6,14 @@ use std::{
 
 use futures::channel::mpsc;
 use log::{debug, info};
+use parity_scale_codec::DecodeAll;
 
 use crate::{
     abft::Recipient,
     crypto::{AuthorityPen, AuthorityVerifier},
     network::{
         address_cache::{ValidatorAddressCacheUpdater, ValidatorAddressingInfo},
+        address_registration::AddressRegistrationSigner,
         session::{
             data::DataInSession, Authentication, Connections, Discovery, DiscoveryMessage,
             SessionHandler, SessionHandlerError,
@@ -44,6 +46,8 @@ pub struct PreValidatorSession {
     pub verifier: AuthorityVerifier,
     pub node_id: NodeIndex,
     pub pen: AuthorityPen,
+    /// Encoded addressing information registered on chain, indexed by nodes.
+    pub registered_addresses: Vec<Option<Vec<u8>>>,
 }
 
 #[derive(Clone)]
@@ -53,18 +57,18 @@ pub struct PreNonvalidatorSession {
     pub verifier: AuthorityVerifier,
 }
 
-/// Actions that the manager wants to take as the result of some information. Might contain a
-/// command for connecting to or disconnecting from some peers or a message to broadcast for
+/// Actions that the manager wants to take as the result of some information. Might contain
+/// commands for connecting to or disconnecting from some peers or a message to broadcast for
 /// discovery  purposes.
 pub struct ManagerActions<A: AddressingInformation> {
-    pub maybe_command: Option<ConnectionCommand<A>>,
+    pub commands: Vec<ConnectionCommand<A>>,
     pub maybe_message: Option<Authentication<A>>,
 }
 
 impl<A: AddressingInformation> ManagerActions<A> {
     fn noop() -> Self {
         ManagerActions {
-            maybe_command: None,
+            commands: Vec::new(),
             maybe_message: None,
         }
     }
@@ -84,6 +88,7 @@ pub struct Manager<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdat
     connections: Connections<NI::PeerId>,
     sessions: HashMap<SessionId, Session<D, NI::AddressingInformation>>,
     validator_address_cache_updater: VCU,
+    address_registration_signer: AddressRegistrationSigner,
     discovery_cooldown: Duration,
 }
 
@@ -99,6 +104,7 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
     pub fn new(
         network_identity: NI,
         validator_address_cache_updater: VCU,
+        address_registration_signer: AddressRegistrationSigner,
         discovery_cooldown: Duration,
     ) -> Self {
         Manager {
@@ -106,6 +112,7 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             connections: Connections::new(),
             sessions: HashMap::new(),
             validator_address_cache_updater,
+            address_registration_signer,
             discovery_cooldown,
         }
     }
@@ -126,7 +133,9 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
     ) -> ManagerActions<NI::AddressingInformation> {
         self.sessions.remove(&session_id);
         ManagerActions {
-            maybe_command: Self::delete_reserved(self.connections.remove_session(session_id)),
+            commands: Self::delete_reserved(self.connections.remove_session(session_id))
+                .into_iter()
+                .collect(),
             maybe_message: None,
         }
     }
@@ -151,12 +160,48 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             .collect()
     }
 
+    /// Uses the addresses registered on chain as a bootstrap for the connections within the
+    /// session, returns the ones the handler accepted.
+    fn handle_registered_addresses(
+        handler: &SessionHandler<NI::AddressingInformation>,
+        registered_addresses: Vec<Option<Vec<u8>>>,
+    ) -> Vec<NI::AddressingInformation> {
+        registered_addresses
+            .into_iter()
+            .enumerate()
+            .filter_map(|(node_id, encoded)| {
+                let address =
+                    NI::AddressingInformation::decode_all(&mut encoded?.as_slice()).ok()?;
+                handler.handle_registered_address(NodeIndex(node_id), address)
+            })
+            .collect()
+    }
+
+    fn add_reserved(
+        &mut self,
+        session_id: SessionId,
+        addresses: Vec<NI::AddressingInformation>,
+    ) -> Option<ConnectionCommand<NI::AddressingInformation>> {
+        if addresses.is_empty() {
+            return None;
+        }
+        debug!(target: "aleph-network", "Adding registered addresses for session {:?} to reserved: {:?}", session_id, addresses);
+        self.connections.add_peers(
+            session_id,
+            addresses.iter().map(|address| address.peer_id()),
+        );
+        Some(ConnectionCommand::AddReserved(
+            addresses.into_iter().collect(),
+        ))
+    }
+
     fn start_validator_session(
         &mut self,
         pre_session: PreValidatorSession,
         address: NI::AddressingInformation,
     ) -> (
         Option<Authentication<NI::AddressingInformation>>,
+        Vec<NI::AddressingInformation>,
         mpsc::UnboundedReceiver<D>,
     ) {
         let PreValidatorSession {
@@ -164,8 +209,12 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             verifier,
             node_id,
             pen,
+            registered_addresses,
         } = pre_session;
+        self.address_registration_signer
+            .update(session_id, &address, pen.clone());
         let handler = SessionHandler::new(Some((node_id, pen)), verifier, session_id, address);
+        let registered = Self::handle_registered_addresses(&handler, registered_addresses);
         let discovery = Discovery::new(self.discovery_cooldown);
         let (data_for_user, data_from_network) = mpsc::unbounded();
         let data_for_user = Some(data_for_user);
@@ -177,7 +226,11 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
                 data_for_user,
             },
         );
-        (self.discover_authorities(&session_id), data_from_network)
+        (
+            self.discover_authorities(&session_id),
+            registered,
+            data_from_network,
+        )
     }
 
     /// Starts or updates a validator session.
@@ -192,14 +245,18 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
         SessionHandlerError,
     > {
         let address = self.network_identity.identity();
-        let session = match self.sessions.get_mut(&pre_session.session_id) {
+        let session_id = pre_session.session_id;
+        let session = match self.sessions.get_mut(&session_id) {
             Some(session) => session,
             None => {
-                let (maybe_message, data_from_network) =
+                let (maybe_message, registered, data_from_network) =
                     self.start_validator_session(pre_session, address);
                 return Ok((
                     ManagerActions {
-                        maybe_command: None,
+                        commands: self
+                            .add_reserved(session_id, registered)
+                            .into_iter()
+                            .collect(),
                         maybe_message,
                     },
                     data_from_network,
@@ -211,6 +268,7 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             verifier,
             node_id,
             pen,
+            registered_addresses,
         } = pre_session;
         self.validator_address_cache_updater.update(
             node_id,
@@ -221,25 +279,28 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             },
         );
 
-        let peers_to_stay = session
+        let mut peers_to_stay: HashSet<_> = session
             .handler
             .update(Some((node_id, pen)), verifier, address)?
             .iter()
             .map(|address| address.peer_id())
             .collect();
-        let maybe_command = Self::delete_reserved(
-            self.connections
-                .remove_session(session_id)
-                .difference(&peers_to_stay)
-                .cloned()
-                .collect(),
-        );
+        let registered = Self::handle_registered_addresses(&session.handler, registered_addresses);
         let (data_for_user, data_from_network) = mpsc::unbounded();
         session.data_for_user = Some(data_for_user);
+        peers_to_stay.extend(registered.iter().map(|address| address.peer_id()));
+        let previous_peers = self.connections.remove_session(session_id);
+        let new_registered = registered
+            .into_iter()
+            .filter(|address| !previous_peers.contains(&address.peer_id()))
+            .collect();
+        let maybe_delete =
+            Self::delete_reserved(previous_peers.difference(&peers_to_stay).cloned().collect());
         self.connections.add_peers(session_id, peers_to_stay);
+        let maybe_add = self.add_reserved(session_id, new_registered);
         Ok((
             ManagerActions {
-                maybe_command,
+                commands: maybe_delete.into_iter().chain(maybe_add).collect(),
                 maybe_message: self.discover_authorities(&session_id),
             },
             data_from_network,
@@ -331,7 +392,7 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
             }) => {
                 let (maybe_address, maybe_message) =
                     discovery.handle_authentication(message, handler);
-                let mut maybe_command = None;
+                let mut commands = Vec::new();
                 if let Some(address) = maybe_address {
                     self.validator_address_cache_updater.update(
                         creator,
@@ -344,11 +405,11 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
                     if handler.is_validator() {
                         debug!(target: "aleph-network", "Adding addresses for session {:?} to reserved: {:?}", session_id, address);
                         self.connections.add_peers(session_id, [address.peer_id()]);
-                        maybe_command = Some(ConnectionCommand::AddReserved([address].into()));
+                        commands.push(ConnectionCommand::AddReserved([address].into()));
                     }
                 }
                 ManagerActions {
-                    maybe_command,
+                    commands,
                     maybe_message,
                 }
             }
@@ -458,6 +519,7 @@ mod tests {
 
     use futures::StreamExt;
     use network_clique::mock::{random_address, MockAddressingInformation};
+    use parity_scale_codec::Encode;
 
     use super::{
         ConnectionCommand, Manager, ManagerActions, PreNonvalidatorSession, PreValidatorSession,
@@ -466,6 +528,7 @@ mod tests {
     use crate::{
         network::{
             address_cache::{test::noop_updater, ValidatorAddressCacheUpdater},
+            address_registration::AddressRegistrationSigner,
             mock::crypto_basics,
             session::data::DataInSession,
         },
@@ -476,7 +539,12 @@ mod tests {
     const DISCOVERY_PERIOD: Duration = Duration::from_secs(60);
 
     fn build() -> Manager<MockAddressingInformation, i32, impl ValidatorAddressCacheUpdater> {
-        Manager::new(random_address(), noop_updater(), DISCOVERY_PERIOD)
+        Manager::new(
+            random_address(),
+            noop_updater(),
+            AddressRegistrationSigner::new(),
+            DISCOVERY_PERIOD,
+        )
     }
 
     #[test]
@@ -485,7 +553,7 @@ mod tests {
         let (_, verifier) = crypto_basics(NUM_NODES);
         let session_id = SessionId(43);
         let ManagerActions {
-            maybe_command,
+            commands,
             maybe_message,
         } = manager
             .update_nonvalidator_session(PreNonvalidatorSession {
@@ -493,7 +561,7 @@ mod tests {
                 verifier,
             })
             .unwrap();
-        assert!(maybe_command.is_none());
+        assert!(commands.is_empty());
         assert!(maybe_message.is_none());
         assert_eq!(
             manager.send_session_data(&session_id, -43),
@@ -509,7 +577,7 @@ mod tests {
         let session_id = SessionId(43);
         let (
             ManagerActions {
-                maybe_command,
+                commands,
                 maybe_message,
             },
             _data_from_network,
@@ -519,9 +587,10 @@ mod tests {
                 verifier,
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
-        assert!(maybe_command.is_none());
+        assert!(commands.is_empty());
         assert!(maybe_message.is_some());
         assert_eq!(manager.send_session_data(&session_id, -43), Ok(()));
     }
@@ -534,7 +603,7 @@ mod tests {
         let session_id = SessionId(43);
         let (
             ManagerActions {
-                maybe_command,
+                commands,
                 maybe_message,
             },
             mut data_from_network,
@@ -544,17 +613,18 @@ mod tests {
                 verifier,
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
-        assert!(maybe_command.is_none());
+        assert!(commands.is_empty());
         assert!(maybe_message.is_some());
         assert_eq!(manager.send_session_data(&session_id, -43), Ok(()));
         assert_eq!(data_from_network.next().await, Some(-43));
         let ManagerActions {
-            maybe_command,
+            commands,
             maybe_message,
         } = manager.finish_session(session_id);
-        assert!(maybe_command.is_none());
+        assert!(commands.is_empty());
         assert!(maybe_message.is_none());
         assert_eq!(
             manager.send_session_data(&session_id, -43),
@@ -575,6 +645,7 @@ mod tests {
                 verifier: verifier.clone(),
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
         let mut other_manager = build();
@@ -585,16 +656,17 @@ mod tests {
                 verifier,
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
         let message = maybe_message.expect("there should be a discovery message");
         let (address, message) = (message.0.address(), message);
         let ManagerActions {
-            maybe_command,
+            commands,
             maybe_message,
         } = manager.on_discovery_message(message);
         assert_eq!(
-            maybe_command,
+            commands,
             Some(ConnectionCommand::AddReserved(
                 iter::once(address).collect()
             ))
@@ -614,6 +686,7 @@ mod tests {
                 verifier: verifier.clone(),
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
         let mut other_manager = build();
@@ -624,6 +697,7 @@ mod tests {
                 verifier,
                 node_id,
                 pen,
+                registered_addresses: Vec::new(),
             })
             .unwrap();
         let message = maybe_message.expect("there should be a discovery message");
@@ -639,4 +713,46 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn reserves_registered_addresses() {
+        let mut manager = build();
+        let (validator_data, verifier) = crypto_basics(NUM_NODES);
+        let (node_id, pen) = validator_data[0].clone();
+        let session_id = SessionId(43);
+        let address = random_address();
+        let mut registered_addresses = vec![None; NUM_NODES];
+        registered_addresses[1] = Some(address.encode());
+        registered_addresses[2] = Some(b"garbage".to_vec());
+        let (ManagerActions { commands, .. }, _) = manager
+            .update_validator_session(PreValidatorSession {
+                session_id,
+                verifier: verifier.clone(),
+                node_id,
+                pen: pen.clone(),
+                registered_addresses: registered_addresses.clone(),
+            })
+            .unwrap();
+        assert_eq!(
+            commands,
+            vec![ConnectionCommand::AddReserved(
+                iter::once(address).collect()
+            )]
+        );
+        // The registered node has not authenticated yet, so it is not sent any data.
+        assert!(manager
+            .on_user_message(2137, session_id, Recipient::Everyone)
+            .is_empty());
+
+        let (ManagerActions { commands, .. }, _) = manager
+            .update_validator_session(PreValidatorSession {
+                session_id,
+                verifier,
+                node_id,
+                pen,
+                registered_addresses,
+            })
+            .unwrap();
+        assert!(commands.is_empty());
+    }
 }
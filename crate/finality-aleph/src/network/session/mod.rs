@@ -98,13 +98,15 @@ pub trait SessionManager<D: Data>: Send + Sync + 'static {
 
     /// Start participating or update the information about the given session where you are a
     /// validator. Returns a session network to be used for sending and receiving data within the
-    /// session.
+    /// session. The registered addresses are the encoded addressing information the authorities
+    /// registered on chain, indexed like the authorities of the verifier.
     async fn start_validator_session(
         &self,
         session_id: SessionId,
         verifier: AuthorityVerifier,
         node_id: NodeIndex,
         pen: AuthorityPen,
+        registered_addresses: Vec<Option<Vec<u8>>>,
     ) -> Result<Network<D>, Self::Error>;
 
     /// Start participating or update the information about the given session where you are a
@@ -116,6 +118,7 @@ pub trait SessionManager<D: Data>: Send + Sync + 'static {
         verifier: AuthorityVerifier,
         node_id: NodeIndex,
         pen: AuthorityPen,
+        registered_addresses: Vec<Option<Vec<u8>>>,
     ) -> Result<(), Self::Error>;
 
     /// Stop participating in the given session.
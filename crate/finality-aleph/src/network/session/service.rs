@@ -17,6 +17,7 @@ use crate::{
     crypto::{AuthorityPen, AuthorityVerifier},
     network::{
         address_cache::ValidatorAddressCacheUpdater,
+        address_registration::AddressRegistrationSigner,
         session::{
             data::DataInSession,
             manager::{
@@ -38,6 +39,7 @@ enum SessionCommand<D: Data> {
         AuthorityVerifier,
         NodeIndex,
         AuthorityPen,
+        Vec<Option<Vec<u8>>>,
         Option<oneshot::Sender<mpsc::UnboundedReceiver<D>>>,
     ),
     StartNonvalidator(SessionId, AuthorityVerifier),
@@ -87,6 +89,7 @@ impl<D: Data> SessionManager<D> for ManagerInterface<D> {
         verifier: AuthorityVerifier,
         node_id: NodeIndex,
         pen: AuthorityPen,
+        registered_addresses: Vec<Option<Vec<u8>>>,
     ) -> Result<Network<D>, Self::Error> {
         let (result_for_us, result_from_service) = oneshot::channel();
         self.commands_for_service
@@ -95,6 +98,7 @@ impl<D: Data> SessionManager<D> for ManagerInterface<D> {
                 verifier,
                 node_id,
                 pen,
+                registered_addresses,
                 Some(result_for_us),
             ))
             .map_err(|_| ManagerError::CommandSendFailed)?;
@@ -119,10 +123,16 @@ impl<D: Data> SessionManager<D> for ManagerInterface<D> {
         verifier: AuthorityVerifier,
         node_id: NodeIndex,
         pen: AuthorityPen,
+        registered_addresses: Vec<Option<Vec<u8>>>,
     ) -> Result<(), Self::Error> {
         self.commands_for_service
             .unbounded_send(SessionCommand::StartValidator(
-                session_id, verifier, node_id, pen, None,
+                session_id,
+                verifier,
+                node_id,
+                pen,
+                registered_addresses,
+                None,
             ))
             .map_err(|_| ManagerError::CommandSendFailed)
     }
@@ -226,6 +236,7 @@ where
         validator_network: CN,
         gossip_network: GN,
         validator_address_cache_updater: VCU,
+        address_registration_signer: AddressRegistrationSigner,
         config: Config,
     ) -> (
         Service<D, NI, CN, GN, VCU>,
@@ -239,6 +250,7 @@ where
         let manager = Manager::new(
             network_identity,
             validator_address_cache_updater,
+            address_registration_signer,
             discovery_cooldown,
         );
         let (commands_for_service, commands_from_user) = mpsc::unbounded();
@@ -298,11 +310,11 @@ where
     fn handle_manager_actions(
         &mut self,
         ManagerActions {
-            maybe_command,
+            commands,
             maybe_message,
         }: ManagerActions<NI::AddressingInformation>,
     ) -> Result<(), Error<GN::Error>> {
-        if let Some(command) = maybe_command {
+        for command in commands {
             self.handle_connection_command(command);
         }
         if let Some(message) = maybe_message {
@@ -319,12 +331,20 @@ where
     ) -> Result<ManagerActions<NI::AddressingInformation>, SessionHandlerError> {
         use SessionCommand::*;
         match command {
-            StartValidator(session_id, verifier, node_id, pen, result_for_user) => {
+            StartValidator(
+                session_id,
+                verifier,
+                node_id,
+                pen,
+                registered_addresses,
+                result_for_user,
+            ) => {
                 let pre_session = PreValidatorSession {
                     session_id,
                     verifier,
                     node_id,
                     pen,
+                    registered_addresses,
                 };
                 let (actions, data_from_network) =
                     self.manager.update_validator_session(pre_session)?;
@@ -1,12 +1,15 @@
 use std::{
     borrow::{Borrow, BorrowMut},
-    collections::HashSet,
+    cmp::max,
+    collections::{HashMap, HashSet, VecDeque},
     fmt::{Debug, Display, Error as FmtError, Formatter},
+    time::Instant,
 };
 
 use log::{debug, info, trace, warn};
 use parity_scale_codec::DecodeAll;
 use rand::{seq::IteratorRandom, thread_rng};
+use rate_limiter::PerPeerRateLimiter;
 pub use sc_network::types::PeerId;
 use sc_network::{
     service::traits::{NotificationEvent as SubstrateEvent, ValidationResult},
@@ -15,19 +18,86 @@ use sc_network::{
 use tokio::time;
 
 use crate::{
-    network::{Data, GossipNetwork, LOG_TARGET},
+    metrics::NetworkComponent,
+    network::{Data, GossipNetwork, TrafficControl, LOG_TARGET},
     STATUS_REPORT_INTERVAL,
 };
 
 pub type BoxedNotificationService = Box<dyn sc_network::config::NotificationService>;
 
+/// How many messages over the limits are kept for a single peer, any further ones are dropped.
+const MAX_HELD_BACK_PER_PEER: usize = 16;
+
+/// Messages over the limits, queued separately for every peer so that a peer exceeding its
+/// limit only delays its own messages.
+#[derive(Default)]
+struct HeldBackMessages {
+    queues: HashMap<PeerId, VecDeque<(Vec<u8>, Instant)>>,
+}
+
+impl HeldBackMessages {
+    fn is_holding(&self, peer_id: &PeerId) -> bool {
+        self.queues.contains_key(peer_id)
+    }
+
+    /// Queues the message to be returned at the deadline, returns whether there was space for it.
+    fn hold(&mut self, peer_id: PeerId, message: Vec<u8>, deadline: Instant) -> bool {
+        let queue = self.queues.entry(peer_id).or_default();
+        if queue.len() >= MAX_HELD_BACK_PER_PEER {
+            return false;
+        }
+        // Later messages of a peer are never returned before the earlier ones.
+        let deadline = queue
+            .back()
+            .map_or(deadline, |(_, last)| max(*last, deadline));
+        queue.push_back((message, deadline));
+        true
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.queues
+            .values()
+            .filter_map(|queue| queue.front().map(|(_, deadline)| *deadline))
+            .min()
+    }
+
+    /// The message with the earliest deadline, if it already passed.
+    fn pop_ready(&mut self, now: Instant) -> Option<(Vec<u8>, PeerId)> {
+        let peer_id = *self
+            .queues
+            .iter()
+            .filter_map(|(peer_id, queue)| queue.front().map(|(_, deadline)| (peer_id, deadline)))
+            .filter(|(_, deadline)| **deadline <= now)
+            .min_by_key(|(_, deadline)| **deadline)?
+            .0;
+        let queue = self.queues.get_mut(&peer_id)?;
+        let (message, _) = queue.pop_front()?;
+        if queue.is_empty() {
+            self.queues.remove(&peer_id);
+        }
+        Some((message, peer_id))
+    }
+
+    fn forget(&mut self, peer_id: &PeerId) {
+        self.queues.remove(peer_id);
+    }
+}
+
 /// A thin wrapper around sc_network::config::NotificationService that stores a list
 /// of all currently connected peers, and introduces a few convenience methods to
 /// allow broadcasting messages and sending data to random peers.
+///
+/// The traffic is accounted as belonging to a single component, and messages exceeding the
+/// limits are held back in a queue of the sending peer before being returned, while messages of
+/// other peers keep flowing. Messages that do not fit into the queue are dropped.
 pub struct ProtocolNetwork {
     service: BoxedNotificationService,
     connected_peers: HashSet<PeerId>,
     last_status_report: time::Instant,
+    component: NetworkComponent,
+    traffic_control: TrafficControl,
+    peer_rate_limiter: PerPeerRateLimiter<PeerId>,
+    held_back: HeldBackMessages,
 }
 
 impl Borrow<BoxedNotificationService> for ProtocolNetwork {
@@ -43,11 +113,28 @@ impl BorrowMut<BoxedNotificationService> for ProtocolNetwork {
 }
 
 impl ProtocolNetwork {
-    pub fn new(service: BoxedNotificationService) -> Self {
+    pub fn new(service: BoxedNotificationService, component: NetworkComponent) -> Self {
         Self {
             service,
             connected_peers: HashSet::new(),
             last_status_report: time::Instant::now(),
+            component,
+            traffic_control: TrafficControl::noop(),
+            peer_rate_limiter: PerPeerRateLimiter::unlimited(),
+            held_back: HeldBackMessages::default(),
+        }
+    }
+
+    /// Accounts the traffic using the given control and limits the data received from every peer.
+    pub fn with_traffic_control(
+        self,
+        traffic_control: TrafficControl,
+        peer_rate_limiter: PerPeerRateLimiter<PeerId>,
+    ) -> Self {
+        Self {
+            traffic_control,
+            peer_rate_limiter,
+            ..self
         }
     }
 
@@ -78,7 +165,10 @@ impl ProtocolNetwork {
                 None
             }
             NotificationStreamClosed { peer } => {
-                self.connected_peers.remove(&peer.into());
+                let peer: PeerId = peer.into();
+                self.connected_peers.remove(&peer);
+                self.peer_rate_limiter.remove(&peer);
+                self.held_back.forget(&peer);
                 None
             }
             NotificationReceived { peer, notification } => Some((notification, peer.into())),
@@ -111,6 +201,12 @@ impl Display for ProtocolNetworkError {
     }
 }
 
+fn decode<D: Data>(message: &[u8]) -> Option<D> {
+    D::decode_all(&mut &message[..])
+        .map_err(|e| warn!(target: LOG_TARGET, "Error decoding message: {}", e))
+        .ok()
+}
+
 #[async_trait::async_trait]
 impl<D: Data> GossipNetwork<D> for ProtocolNetwork {
     type Error = ProtocolNetworkError;
@@ -122,7 +218,9 @@ impl<D: Data> GossipNetwork<D> for ProtocolNetwork {
             "Sending block sync data to peer {:?}.",
             peer_id,
         );
-        self.service.send_sync_notification(&peer_id.into(), data.encode());
+        let data = data.encode();
+        self.traffic_control.sent(self.component, data.len());
+        self.service.send_sync_notification(&peer_id.into(), data);
         Ok(())
     }
 
@@ -161,20 +259,36 @@ impl<D: Data> GossipNetwork<D> for ProtocolNetwork {
             STATUS_REPORT_INTERVAL,
         );
         loop {
+            // Held back messages are kept in `self` while waiting, so that cancelling does not
+            // lose them.
+            if let Some((message, peer_id)) = self.held_back.pop_ready(Instant::now()) {
+                if let Some(message) = decode(&message) {
+                    return Ok((message, peer_id));
+                }
+                continue;
+            }
+            let wake_up = self.held_back.next_deadline();
             tokio::select! {
                 maybe_event = self.service.next_event() => {
                     let event = maybe_event.ok_or(Self::Error::NetworkStreamTerminated)?;
                     let Some((message, peer_id)) = self.handle_network_event(event) else { continue };
-                    match D::decode_all(&mut &message[..]) {
-                        Ok(message) => return Ok((message, peer_id)),
-                        Err(e) => {
-                            warn!(
-                                target: LOG_TARGET,
-                                "Error decoding message: {}", e
-                            )
-                        },
+                    let peer_deadline = self.peer_rate_limiter.rate_limit(&peer_id, 8 * message.len() as u64);
+                    let deadline = max(peer_deadline, self.traffic_control.received(self.component, message.len()));
+                    if deadline.is_none() && !self.held_back.is_holding(&peer_id) {
+                        if let Some(message) = decode(&message) {
+                            return Ok((message, peer_id));
+                        }
+                        continue;
+                    }
+                    if !self.held_back.hold(peer_id, message, deadline.unwrap_or_else(Instant::now)) {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Dropping a message from peer {:?}, too many of its messages are over the limits.",
+                            peer_id,
+                        );
                     }
                 },
+                _ = time::sleep_until(wake_up.unwrap_or_else(Instant::now).into()), if wake_up.is_some() => {},
                 _ = status_ticker.tick() => {
                     self.status_report();
                     self.last_status_report = time::Instant::now();
@@ -183,3 +297,56 @@ impl<D: Data> GossipNetwork<D> for ProtocolNetwork {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{HeldBackMessages, PeerId, MAX_HELD_BACK_PER_PEER};
+
+    #[test]
+    fn returns_held_back_messages_of_peers_independently() {
+        let mut held_back = HeldBackMessages::default();
+        let (slow, fast) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+        assert!(held_back.hold(slow, vec![1], now + Duration::from_secs(10)));
+        assert!(held_back.hold(fast, vec![2], now));
+        assert_eq!(held_back.next_deadline(), Some(now));
+        assert_eq!(held_back.pop_ready(now), Some((vec![2], fast)));
+        assert!(!held_back.is_holding(&fast));
+        assert_eq!(held_back.pop_ready(now), None);
+        assert_eq!(
+            held_back.pop_ready(now + Duration::from_secs(10)),
+            Some((vec![1], slow))
+        );
+        assert_eq!(held_back.next_deadline(), None);
+    }
+
+    #[test]
+    fn keeps_order_of_messages_of_a_peer() {
+        let mut held_back = HeldBackMessages::default();
+        let peer = PeerId::random();
+        let now = Instant::now();
+        assert!(held_back.hold(peer, vec![1], now + Duration::from_secs(5)));
+        assert!(held_back.hold(peer, vec![2], now));
+        assert_eq!(held_back.pop_ready(now), None);
+        let later = now + Duration::from_secs(5);
+        assert_eq!(held_back.pop_ready(later), Some((vec![1], peer)));
+        assert_eq!(held_back.pop_ready(later), Some((vec![2], peer)));
+    }
+
+    #[test]
+    fn drops_messages_over_the_queue_limit() {
+        let mut held_back = HeldBackMessages::default();
+        let (flooding, other) = (PeerId::random(), PeerId::random());
+        let deadline = Instant::now() + Duration::from_secs(1);
+        for i in 0..MAX_HELD_BACK_PER_PEER {
+            assert!(held_back.hold(flooding, vec![i as u8], deadline));
+        }
+        assert!(!held_back.hold(flooding, vec![0], deadline));
+        assert!(held_back.hold(other, vec![0], deadline));
+        held_back.forget(&flooding);
+        assert!(!held_back.is_holding(&flooding));
+        assert!(held_back.is_holding(&other));
+    }
+}
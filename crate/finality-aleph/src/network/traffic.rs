@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+use rate_limiter::{Priority, PriorityRateLimiter};
+
+use crate::metrics::{NetworkComponent, NetworkUsageMetrics};
+
+fn priority(component: NetworkComponent) -> Priority {
+    use NetworkComponent::*;
+    match component {
+        Abft | Aggregation | Authentication => Priority::High,
+        Sync => Priority::Low,
+    }
+}
+
+/// Accounts the traffic of all the components and holds back the low priority ones, so that a
+/// flood of sync messages cannot take the bandwidth needed by AlephBFT.
+#[derive(Clone)]
+pub struct TrafficControl {
+    metrics: NetworkUsageMetrics,
+    rate_limiter: Option<PriorityRateLimiter>,
+}
+
+impl TrafficControl {
+    pub fn new(metrics: NetworkUsageMetrics, rate_limiter: Option<PriorityRateLimiter>) -> Self {
+        TrafficControl {
+            metrics,
+            rate_limiter,
+        }
+    }
+
+    /// Neither accounts nor limits anything.
+    pub fn noop() -> Self {
+        TrafficControl::new(NetworkUsageMetrics::noop(), None)
+    }
+
+    /// Accounts a received message. Returns the instant until which processing further messages
+    /// of the component should be delayed, which never happens for the high priority ones.
+    pub fn received(&self, component: NetworkComponent, bytes: usize) -> Option<Instant> {
+        self.metrics.report_received(component, bytes);
+        self.rate_limiter
+            .as_ref()?
+            .rate_limit(priority(component), 8 * bytes as u64)
+    }
+
+    pub fn sent(&self, component: NetworkComponent, bytes: usize) {
+        self.metrics.report_sent(component, bytes);
+    }
+}
@@ -6,7 +6,7 @@ use log::{debug, error};
 use network_clique::{RateLimitingDialer, RateLimitingListener, Service, SpawnHandleT};
 use pallet_aleph_runtime_api::AlephSessionApi;
 use primitives::TransactionHash;
-use rate_limiter::SharedRateLimiter;
+use rate_limiter::{PerPeerRateLimiter, PriorityRateLimiter, SharedRateLimiter};
 use sc_client_api::Backend;
 use sc_keystore::{Keystore, LocalKeystore};
 use sc_transaction_pool_api::{LocalTransactionPool, TransactionPool};
@@ -21,11 +21,12 @@ use crate::{
     crypto::AuthorityPen,
     finalization::AlephFinalizer,
     idx_to_account::ValidatorIndexToAccountIdConverterImpl,
-    metrics::{run_metrics_service, ScoreMetrics, SloMetrics},
+    metrics::{run_metrics_service, BackupMetrics, NetworkUsageMetrics, ScoreMetrics, SloMetrics},
     network::{
         address_cache::validator_address_cache_updater,
         session::{ConnectionManager, ConnectionManagerConfig},
         tcp::{new_tcp_network, KEY_TYPE},
+        TrafficControl,
     },
     party::{
         impls::ChainStateImpl, manager::NodeSessionManagerImpl, ConsensusParty,
@@ -37,7 +38,7 @@ use crate::{
         AuthorityProviderImpl, FinalityNotifierImpl, FinalizedBlockProviderImpl, SessionMapUpdater,
     },
     sync::{DatabaseIO as SyncDatabaseIO, Service as SyncService, IO as SyncIO},
-    AlephConfig,
+    AlephConfig, RateLimiterConfig,
 };
 
 // How many sessions we remember.
@@ -55,6 +56,20 @@ pub fn new_pen(mnemonic: &str, keystore: Arc<LocalKeystore>) -> AuthorityPen {
         .expect("we just generated this key so everything should work")
 }
 
+/// The budget shared by AlephBFT and block sync, of which the latter can use at most the
+/// configured share.
+fn priority_rate_limiter(config: &RateLimiterConfig) -> Option<PriorityRateLimiter> {
+    let sync_share = config.sync_bandwidth_share?;
+    let total = config
+        .alephbft_network_bit_rate
+        .saturating_add(config.substrate_network_bit_rate);
+    let sync_cap = total / 100 * u64::from(sync_share);
+    Some(PriorityRateLimiter::new(
+        total.try_into().ok()?,
+        sync_cap.try_into().ok()?,
+    ))
+}
+
 pub async fn run_validator_node<C, BE, TP>(aleph_config: AlephConfig<C, TP>)
 where
     C: crate::ClientForAleph<Block, BE> + Send + Sync + 'static,
@@ -80,12 +95,13 @@ where
         score_submission_period,
         justification_channel_provider,
         block_rx,
-        backup_saving_path,
+        backup_config,
         external_addresses,
         validator_port,
         rate_limiter_config,
         sync_oracle,
         validator_address_cache,
+        address_registration_signer,
         transaction_pool,
     } = aleph_config;
 
@@ -115,6 +131,11 @@ where
         SharedRateLimiter::new(rate_limiter_config.alephbft_network_bit_rate.into());
     let dialer = RateLimitingDialer::new(dialer, alephbft_rate_limiter.share());
     let listener = RateLimitingListener::new(listener, alephbft_rate_limiter);
+    let validator_peer_rate_limiter = PerPeerRateLimiter::new(
+        rate_limiter_config
+            .validator_network_peer_bit_rate
+            .and_then(|rate| rate.try_into().ok()),
+    );
 
     let (validator_network_service, validator_network) = Service::new(
         dialer,
@@ -122,6 +143,7 @@ where
         network_authority_pen,
         spawn_handle.clone(),
         registry.clone(),
+        validator_peer_rate_limiter,
     );
     let (_validator_network_exit, exit) = oneshot::channel();
     spawn_handle.spawn("aleph/validator_network", async move {
@@ -156,6 +178,29 @@ where
         ScoreMetrics::noop()
     });
 
+    let backup_metrics = BackupMetrics::new(registry.as_ref()).unwrap_or_else(|e| {
+        debug!(target: LOG_TARGET, "Failed to create backup metrics: {}.", e);
+        BackupMetrics::noop()
+    });
+
+    let network_usage_metrics = NetworkUsageMetrics::new(registry.as_ref()).unwrap_or_else(|e| {
+        debug!(target: LOG_TARGET, "Failed to create network usage metrics: {}.", e);
+        NetworkUsageMetrics::noop()
+    });
+    let traffic_control = TrafficControl::new(
+        network_usage_metrics,
+        priority_rate_limiter(&rate_limiter_config),
+    );
+    let sync_peer_rate_limiter = PerPeerRateLimiter::new(
+        rate_limiter_config
+            .sync_peer_bit_rate
+            .and_then(|rate| rate.try_into().ok()),
+    );
+    let block_sync_network =
+        block_sync_network.with_traffic_control(traffic_control.clone(), sync_peer_rate_limiter);
+    let authentication_network = authentication_network
+        .with_traffic_control(traffic_control.clone(), PerPeerRateLimiter::unlimited());
+
     let slo_metrics = SloMetrics::new(registry.as_ref(), chain_status.clone());
     let timing_metrics = slo_metrics.timing_metrics().clone();
 
@@ -239,6 +284,7 @@ where
         validator_network,
         authentication_network,
         validator_address_cache_updater,
+        address_registration_signer,
         ConnectionManagerConfig::with_session_period(&session_period, &millisecs_per_block),
     );
 
@@ -257,7 +303,8 @@ where
     let party = ConsensusParty::new(ConsensusPartyParams {
         session_authorities,
         sync_oracle,
-        backup_saving_path,
+        backup_config,
+        backup_metrics,
         chain_state: ChainStateImpl {
             client: client.clone(),
             _phantom: PhantomData,
@@ -278,6 +325,7 @@ where
             keystore,
             runtime_api,
             score_metrics,
+            traffic_control,
         ),
         session_info,
         score_submission_period,
@@ -0,0 +1,456 @@
+use std::{
+    fmt, fs,
+    fs::{File, OpenOptions},
+    io::{Error as IoError, Read, Result as IoResult, Write},
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use futures::io::{empty, sink, AsyncRead, AsyncWrite, Cursor};
+use log::{debug, warn};
+
+use crate::{metrics::BackupMetrics, BackupConfig};
+
+mod record;
+
+const BACKUP_FILE_EXTENSION: &str = ".abfts";
+
+#[derive(Debug)]
+pub enum BackupLoadError {
+    BackupIncomplete(Vec<usize>),
+    BackupCorrupted { file: usize, offset: usize },
+    IOError(IoError),
+}
+
+impl fmt::Display for BackupLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupLoadError::BackupIncomplete(backups) => {
+                write!(
+                    f,
+                    "Backup is not complete. Got backup for runs numbered: {backups:?}"
+                )
+            }
+            BackupLoadError::BackupCorrupted { file, offset } => {
+                write!(
+                    f,
+                    "Backup of run {file} is corrupted, the record at byte {offset} is damaged"
+                )
+            }
+            BackupLoadError::IOError(err) => {
+                write!(f, "Backup could not be loaded because of IO error: {err}")
+            }
+        }
+    }
+}
+
+impl From<IoError> for BackupLoadError {
+    fn from(err: IoError) -> Self {
+        Self::IOError(err)
+    }
+}
+
+impl std::error::Error for BackupLoadError {}
+
+pub type Saver = Pin<Box<dyn AsyncWrite + Send + Sync + Unpin>>;
+pub type Loader = Pin<Box<dyn AsyncRead + Send + Sync + Unpin>>;
+pub type ABFTBackup = (Saver, Loader);
+
+/// Writes the data AlephBFT flushes as a single checksummed record.
+struct BackupSaver {
+    file: File,
+    buffer: Vec<u8>,
+    compression: bool,
+    session_size: u64,
+    max_size: Option<u64>,
+    metrics: BackupMetrics,
+}
+
+impl BackupSaver {
+    fn write_record(&mut self) -> IoResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let start = Instant::now();
+        let record = record::encode_record(&self.buffer, self.compression);
+        self.file.write_all(&record)?;
+        self.buffer.clear();
+        let written = record.len() as u64;
+        self.metrics.report_write(written, start.elapsed());
+
+        let previous_size = self.session_size;
+        self.session_size += written;
+        if let Some(max_size) = self.max_size {
+            if previous_size <= max_size && self.session_size > max_size {
+                warn!(target: "aleph-party", "Backup of the current session takes {} bytes, above the limit of {} bytes. It will not be removed until the session ends.", self.session_size, max_size);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AsyncWrite for BackupSaver {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<IoResult<usize>> {
+        self.get_mut().buffer.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(self.get_mut().write_record())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Poll::Ready(self.get_mut().write_record())
+    }
+}
+
+/// Total size of all the files under `path`.
+fn directory_size(path: &Path) -> IoResult<u64> {
+    let mut size = 0;
+    for item in fs::read_dir(path)? {
+        let item = item?;
+        let metadata = item.metadata()?;
+        size += match metadata.is_dir() {
+            true => directory_size(&item.path())?,
+            false => metadata.len(),
+        };
+    }
+    Ok(size)
+}
+
+/// Find all `*.abfts` files at `session_path` and return their indexes sorted, if all are present.
+fn get_session_backup_idxs(session_path: &Path) -> Result<Vec<usize>, BackupLoadError> {
+    fs::create_dir_all(session_path)?;
+    let mut session_backups: Vec<_> = fs::read_dir(session_path)?
+        .filter_map(|r| r.ok())
+        .filter_map(|x| x.file_name().into_string().ok())
+        .filter_map(|s| usize::from_str(s.strip_suffix(BACKUP_FILE_EXTENSION)?).ok())
+        .collect();
+    session_backups.sort_unstable();
+    if !session_backups.iter().cloned().eq(0..session_backups.len()) {
+        return Err(BackupLoadError::BackupIncomplete(session_backups));
+    }
+    Ok(session_backups)
+}
+
+/// Load session backup at path `session_path` from all `session_idxs`.
+///
+/// A record torn by a crash can only be at the end of a file. It was never reported as saved to
+/// AlephBFT, so it is dropped and the file is truncated to its correct part.
+fn load_backup(session_path: &Path, session_idxs: &[usize]) -> Result<Loader, BackupLoadError> {
+    let mut buffer = Vec::new();
+    for index in session_idxs.iter() {
+        let load_path = session_path.join(format!("{index}{BACKUP_FILE_EXTENSION}"));
+        let mut contents = Vec::new();
+        File::open(&load_path)?.read_to_end(&mut contents)?;
+        let decoded =
+            record::decode_file(&contents).map_err(|e| BackupLoadError::BackupCorrupted {
+                file: *index,
+                offset: e.offset,
+            })?;
+        if decoded.valid_length < contents.len() {
+            warn!(target: "aleph-party", "Dropping {} bytes of an interrupted write at the end of backup file {:?}.", contents.len() - decoded.valid_length, load_path);
+            OpenOptions::new()
+                .write(true)
+                .open(&load_path)?
+                .set_len(decoded.valid_length as u64)?;
+        }
+        buffer.extend(decoded.data);
+    }
+    Ok(Box::pin(Cursor::new(buffer)))
+}
+
+/// Get path of next backup file in session.
+fn get_next_path(session_path: &Path, session_idxs: &[usize]) -> PathBuf {
+    session_path.join(format!(
+        "{}{}",
+        session_idxs.last().map_or(0, |i| i + 1),
+        BACKUP_FILE_EXTENSION,
+    ))
+}
+
+/// Loads the existing backups, and opens a new backup file to write to.
+///
+/// `config.path` is the path to the backup directory (i.e. the argument to `--backup-saving-path`).
+///
+/// Returns the newly-created file (opened for writing), and the concatenation of the contents of
+/// all existing files.
+///
+/// Current directory structure (this is an implementation detail, not part of the public API):
+///   backup-stash/      - the main directory, backup_path/--backup-saving-path
+///   `-- 18723/         - subdirectory for the current session
+///       |-- 0.abfts    - files containing data
+///       |-- 1.abfts    - each restart after a crash will cause another one to be created
+///       |-- 2.abfts    - these numbers count up sequentially
+///       `-- 3.abfts
+pub fn rotate(
+    config: &BackupConfig,
+    metrics: BackupMetrics,
+    session_id: u32,
+) -> Result<ABFTBackup, BackupLoadError> {
+    debug!(target: "aleph-party", "Loading AlephBFT backup for session {:?}", session_id);
+    let (backup_path, session_path) = if let Some(path) = &config.path {
+        (path, path.join(format!("{session_id}")))
+    } else {
+        debug!(target: "aleph-party", "Passing empty backup for session {:?} as no backup argument was provided", session_id);
+        return Ok((Box::pin(sink()), Box::pin(empty())));
+    };
+    debug!(target: "aleph-party", "Loading backup for session {:?} at path {:?}", session_id, session_path);
+
+    let session_backup_idxs = get_session_backup_idxs(&session_path)?;
+
+    let backup_loader = load_backup(&session_path, &session_backup_idxs)?;
+
+    let next_backup_path = get_next_path(&session_path, &session_backup_idxs);
+    debug!(target: "aleph-party", "Loaded backup for session {:?}. Creating new backup file at {:?}", session_id, next_backup_path);
+    let mut file = File::create(next_backup_path)?;
+    file.write_all(record::FILE_HEADER)?;
+    metrics.set_size(directory_size(backup_path)?);
+    let backup_saver = Box::pin(BackupSaver {
+        file,
+        buffer: Vec::new(),
+        compression: config.compression,
+        session_size: directory_size(&session_path)?,
+        max_size: config.max_size,
+        metrics,
+    });
+
+    debug!(target: "aleph-party", "Backup rotation done for session {:?}", session_id);
+    Ok((backup_saver, backup_loader))
+}
+
+/// Removes the backup directories of old sessions, except for the current one and the ones
+/// within the retention window. If the remaining backups take more space than allowed, the
+/// oldest sessions are removed until they fit, but never the current session.
+///
+/// If `config.path` is `None`, nothing is done.
+///
+/// Any filesystem errors are returned.
+///
+/// This should be done at the beginning of the new session.
+pub fn remove_old_backups(
+    config: &BackupConfig,
+    metrics: &BackupMetrics,
+    current_session: u32,
+) -> IoResult<()> {
+    let path = match &config.path {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut old_sessions = Vec::new();
+    for read_dir in fs::read_dir(path)? {
+        let item = read_dir?;
+        match item.file_name().to_str() {
+            Some(name) => match name.parse::<u32>() {
+                Ok(session_id) => {
+                    if session_id < current_session {
+                        old_sessions.push((session_id, item.path()));
+                    }
+                }
+                Err(_) => {
+                    debug!(target: "aleph-party", "backup directory contains unexpected data.")
+                }
+            },
+            None => debug!(target: "aleph-party", "backup directory contains unexpected data."),
+        };
+    }
+    old_sessions.sort_unstable();
+
+    let oldest_retained = current_session.saturating_sub(config.retention_sessions);
+    let mut retained = Vec::new();
+    for (session_id, session_path) in old_sessions {
+        match session_id < oldest_retained {
+            true => fs::remove_dir_all(session_path)?,
+            false => retained.push((session_id, session_path)),
+        }
+    }
+
+    let mut total_size = directory_size(path)?;
+    if let Some(max_size) = config.max_size {
+        for (session_id, session_path) in retained {
+            if total_size <= max_size {
+                break;
+            }
+            let session_size = directory_size(&session_path)?;
+            fs::remove_dir_all(session_path)?;
+            total_size = total_size.saturating_sub(session_size);
+            debug!(target: "aleph-party", "Removed backup of session {} to stay within the size limit.", session_id);
+        }
+        if total_size > max_size {
+            warn!(target: "aleph-party", "Backups take {} bytes, above the limit of {} bytes, even after removing all the old sessions.", total_size, max_size);
+        }
+    }
+    metrics.set_size(total_size);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write, path::Path};
+
+    use futures::{executor::block_on, AsyncReadExt, AsyncWriteExt};
+    use tempfile::TempDir;
+
+    use super::{
+        directory_size, record::FILE_HEADER, remove_old_backups, rotate, BackupLoadError,
+        BACKUP_FILE_EXTENSION,
+    };
+    use crate::{metrics::BackupMetrics, BackupConfig};
+
+    fn config(path: &Path) -> BackupConfig {
+        BackupConfig {
+            path: Some(path.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    /// Starts the session once, saving the items, and returns what was loaded at the start.
+    fn run_session(config: &BackupConfig, session_id: u32, items: &[&[u8]]) -> Vec<u8> {
+        let (mut saver, mut loader) =
+            rotate(config, BackupMetrics::noop(), session_id).expect("rotation should succeed");
+        let mut loaded = Vec::new();
+        block_on(async {
+            loader
+                .read_to_end(&mut loaded)
+                .await
+                .expect("loading works");
+            for item in items {
+                saver.write_all(item).await.expect("saving works");
+                saver.flush().await.expect("flushing works");
+            }
+        });
+        loaded
+    }
+
+    fn backup_file(path: &Path, session_id: u32, index: usize) -> std::path::PathBuf {
+        path.join(format!("{session_id}"))
+            .join(format!("{index}{BACKUP_FILE_EXTENSION}"))
+    }
+
+    #[test]
+    fn restores_saved_items_across_restarts() {
+        let dir = TempDir::new().expect("can create directory");
+        for compression in [false, true] {
+            let config = BackupConfig {
+                compression,
+                ..config(dir.path())
+            };
+            let session_id = compression as u32;
+            assert!(run_session(&config, session_id, &[b"unit 1", &[7; 300]]).is_empty());
+            assert_eq!(
+                run_session(&config, session_id, &[b"unit 3"]),
+                [&b"unit 1"[..], &[7; 300]].concat()
+            );
+            assert_eq!(
+                run_session(&config, session_id, &[]),
+                [&b"unit 1"[..], &[7; 300], b"unit 3"].concat()
+            );
+        }
+    }
+
+    #[test]
+    fn drops_and_truncates_torn_write() {
+        let dir = TempDir::new().expect("can create directory");
+        let config = config(dir.path());
+        run_session(&config, 0, &[b"unit 1"]);
+        let file = backup_file(dir.path(), 0, 0);
+        let valid_length = fs::metadata(&file).expect("file exists").len();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .and_then(|mut file| file.write_all(&[0, 100, 0, 0, 0, 1, 2, 3]))
+            .expect("can append");
+        assert_eq!(run_session(&config, 0, &[]), b"unit 1");
+        assert_eq!(
+            fs::metadata(&file).expect("file exists").len(),
+            valid_length
+        );
+    }
+
+    #[test]
+    fn loads_legacy_backup() {
+        let dir = TempDir::new().expect("can create directory");
+        let config = config(dir.path());
+        fs::create_dir_all(dir.path().join("0")).expect("can create directory");
+        fs::write(backup_file(dir.path(), 0, 0), b"legacy units").expect("can write");
+        assert_eq!(run_session(&config, 0, &[b"unit"]), b"legacy units");
+        assert_eq!(run_session(&config, 0, &[]), b"legacy unitsunit");
+    }
+
+    #[test]
+    fn rejects_corrupted_backup() {
+        let dir = TempDir::new().expect("can create directory");
+        let config = config(dir.path());
+        run_session(&config, 0, &[b"unit 1", b"unit 2"]);
+        let file = backup_file(dir.path(), 0, 0);
+        let mut contents = fs::read(&file).expect("file exists");
+        contents[FILE_HEADER.len() + 14] ^= 1;
+        fs::write(&file, contents).expect("can write");
+        match rotate(&config, BackupMetrics::noop(), 0) {
+            Err(BackupLoadError::BackupCorrupted { file: 0, offset }) => {
+                assert_eq!(offset, FILE_HEADER.len())
+            }
+            _ => panic!("corrupted backup should not load"),
+        }
+    }
+
+    fn sessions(path: &Path) -> Vec<u32> {
+        let mut sessions: Vec<u32> = fs::read_dir(path)
+            .expect("directory exists")
+            .map(|item| {
+                item.expect("item exists")
+                    .file_name()
+                    .to_str()
+                    .expect("names are strings")
+                    .parse()
+                    .expect("names are sessions")
+            })
+            .collect();
+        sessions.sort_unstable();
+        sessions
+    }
+
+    #[test]
+    fn keeps_sessions_within_retention() {
+        let dir = TempDir::new().expect("can create directory");
+        let config = BackupConfig {
+            retention_sessions: 2,
+            ..config(dir.path())
+        };
+        for session_id in 0..6 {
+            run_session(&config, session_id, &[b"unit"]);
+        }
+        remove_old_backups(&config, &BackupMetrics::noop(), 5).expect("removal works");
+        assert_eq!(sessions(dir.path()), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn removes_oldest_sessions_above_size_limit() {
+        let dir = TempDir::new().expect("can create directory");
+        let mut config = BackupConfig {
+            retention_sessions: 10,
+            ..config(dir.path())
+        };
+        for session_id in 0..4 {
+            run_session(&config, session_id, &[&[session_id as u8; 100]]);
+        }
+        let session_size = directory_size(&dir.path().join("0")).expect("directory exists");
+        config.max_size = Some(2 * session_size);
+        remove_old_backups(&config, &BackupMetrics::noop(), 3).expect("removal works");
+        assert_eq!(sessions(dir.path()), vec![2, 3]);
+
+        config.max_size = Some(0);
+        remove_old_backups(&config, &BackupMetrics::noop(), 3).expect("removal works");
+        assert_eq!(sessions(dir.path()), vec![3]);
+    }
+}
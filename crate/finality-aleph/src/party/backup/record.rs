@@ -0,0 +1,246 @@
+//! On-disk format of a single backup file.
+//!
+//! A file starts with [`FILE_HEADER`] followed by records, each of them holding everything
+//! AlephBFT wrote between two flushes:
+//!
+//! | flags: u8 | length: u32 LE | checksum: u64 LE | payload: `length` bytes |
+//!
+//! The checksum is the `twox_64` hash of the flags, the length and the payload, so a record
+//! that was only partially persisted before a crash is detected. Files without the header were
+//! written by older versions of the node and contain the raw data.
+
+use std::io::{Read, Write};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use sp_core::twox_64;
+
+/// Marks files in the record format, the last byte is the version of the format.
+pub const FILE_HEADER: &[u8] = b"ABFTBKP\x01";
+
+const RECORD_HEADER_LENGTH: usize = 1 + 4 + 8;
+const COMPRESSED: u8 = 1;
+
+fn checksum(flags: u8, length: &[u8], payload: &[u8]) -> [u8; 8] {
+    twox_64(&[&[flags][..], length, payload].concat())
+}
+
+/// Encodes the data as a single record, compressing it if asked to and if it helps.
+pub fn encode_record(data: &[u8], compress: bool) -> Vec<u8> {
+    let compressed = compress
+        .then(|| {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(data).and_then(|_| encoder.finish()).ok()
+        })
+        .flatten()
+        .filter(|compressed| compressed.len() < data.len());
+    let (flags, payload) = match &compressed {
+        Some(compressed) => (COMPRESSED, compressed.as_slice()),
+        None => (0, data),
+    };
+    let length = (payload.len() as u32).to_le_bytes();
+    let mut record = Vec::with_capacity(RECORD_HEADER_LENGTH + payload.len());
+    record.push(flags);
+    record.extend_from_slice(&length);
+    record.extend_from_slice(&checksum(flags, &length, payload));
+    record.extend_from_slice(payload);
+    record
+}
+
+/// Contents of a backup file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecodedFile {
+    /// The data written to the file, with the records unpacked.
+    pub data: Vec<u8>,
+    /// Length of the correct prefix of the file. Anything past it is the remainder of a write
+    /// interrupted by a crash.
+    pub valid_length: usize,
+}
+
+/// A record in the middle of the file is damaged, so it cannot be a result of a crash.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CorruptedRecord {
+    pub offset: usize,
+}
+
+enum Record {
+    Correct {
+        data: Vec<u8>,
+        length: usize,
+    },
+    /// The record could have been torn by a crash, it ends exactly at the end of the file or
+    /// claims to extend past it.
+    Torn,
+    Damaged,
+}
+
+fn decode_record(bytes: &[u8]) -> Record {
+    if bytes.len() < RECORD_HEADER_LENGTH {
+        return Record::Torn;
+    }
+    let flags = bytes[0];
+    let length_bytes = &bytes[1..5];
+    let expected_checksum = &bytes[5..RECORD_HEADER_LENGTH];
+    let length = u32::from_le_bytes(length_bytes.try_into().expect("slice has 4 bytes")) as usize;
+    let remaining = &bytes[RECORD_HEADER_LENGTH..];
+    let damaged = match remaining.len() <= length {
+        true => Record::Torn,
+        false => Record::Damaged,
+    };
+    let payload = match remaining.get(..length) {
+        Some(payload) => payload,
+        None => return damaged,
+    };
+    if checksum(flags, length_bytes, payload) != expected_checksum {
+        return damaged;
+    }
+    let data = match flags {
+        0 => payload.to_vec(),
+        COMPRESSED => {
+            let mut data = Vec::new();
+            if DeflateDecoder::new(payload).read_to_end(&mut data).is_err() {
+                return damaged;
+            }
+            data
+        }
+        _ => return damaged,
+    };
+    Record::Correct {
+        data,
+        length: RECORD_HEADER_LENGTH + length,
+    }
+}
+
+/// Decodes the contents of a backup file. Only the last record is allowed to be damaged, as only
+/// the last write could have been interrupted.
+pub fn decode_file(bytes: &[u8]) -> Result<DecodedFile, CorruptedRecord> {
+    if !bytes.starts_with(FILE_HEADER) {
+        // The node crashed before the header was written in full.
+        if FILE_HEADER.starts_with(bytes) {
+            return Ok(DecodedFile {
+                data: Vec::new(),
+                valid_length: 0,
+            });
+        }
+        return Ok(DecodedFile {
+            data: bytes.to_vec(),
+            valid_length: bytes.len(),
+        });
+    }
+    let mut data = Vec::new();
+    let mut offset = FILE_HEADER.len();
+    while offset < bytes.len() {
+        match decode_record(&bytes[offset..]) {
+            Record::Correct {
+                data: record,
+                length,
+            } => {
+                data.extend(record);
+                offset += length;
+            }
+            Record::Torn => break,
+            // Some filesystems extend the file before the data reaches the disk, leaving zeros.
+            Record::Damaged if bytes[offset..].iter().all(|byte| *byte == 0) => break,
+            Record::Damaged => return Err(CorruptedRecord { offset }),
+        }
+    }
+    Ok(DecodedFile {
+        data,
+        valid_length: offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_file, encode_record, CorruptedRecord, DecodedFile, FILE_HEADER};
+
+    fn file(records: &[&[u8]], compress: bool) -> Vec<u8> {
+        let mut file = FILE_HEADER.to_vec();
+        for record in records {
+            file.extend(encode_record(record, compress));
+        }
+        file
+    }
+
+    #[test]
+    fn decodes_records() {
+        let records: [&[u8]; 3] = [b"first unit", &[7; 1000], b""];
+        for compress in [false, true] {
+            let file = file(&records, compress);
+            assert_eq!(
+                decode_file(&file),
+                Ok(DecodedFile {
+                    data: records.concat(),
+                    valid_length: file.len(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn compresses_only_when_smaller() {
+        let data = [7; 1000];
+        assert!(encode_record(&data, true).len() < encode_record(&data, false).len());
+        let data = b"abc";
+        assert_eq!(encode_record(data, true), encode_record(data, false));
+    }
+
+    #[test]
+    fn decodes_legacy_file() {
+        let legacy = b"raw unit data".to_vec();
+        assert_eq!(
+            decode_file(&legacy),
+            Ok(DecodedFile {
+                data: legacy.clone(),
+                valid_length: legacy.len(),
+            })
+        );
+    }
+
+    #[test]
+    fn drops_torn_tail() {
+        let complete = file(&[b"first unit", b"second unit"], false);
+        let valid_length = file(&[b"first unit"], false).len();
+        for length in valid_length..complete.len() {
+            assert_eq!(
+                decode_file(&complete[..length]),
+                Ok(DecodedFile {
+                    data: b"first unit".to_vec(),
+                    valid_length,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn drops_torn_header() {
+        assert_eq!(
+            decode_file(&FILE_HEADER[..3]),
+            Ok(DecodedFile {
+                data: Vec::new(),
+                valid_length: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn drops_zeroed_tail() {
+        let mut file = file(&[b"first unit"], false);
+        let valid_length = file.len();
+        file.extend([0; 40]);
+        assert_eq!(
+            decode_file(&file),
+            Ok(DecodedFile {
+                data: b"first unit".to_vec(),
+                valid_length,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_damage_before_last_record() {
+        let mut file = file(&[b"first unit", b"second unit"], false);
+        let offset = FILE_HEADER.len();
+        file[offset + 15] ^= 1;
+        assert_eq!(decode_file(&file), Err(CorruptedRecord { offset }));
+    }
+}
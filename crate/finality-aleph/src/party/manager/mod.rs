@@ -23,7 +23,7 @@ use crate::{
     },
     crypto::{AuthorityPen, AuthorityVerifier},
     data_io::{ChainTracker, DataStore, OrderedDataInterpreter, SubstrateChainInfoProvider},
-    metrics::{ScoreMetrics, TimingBlockMetrics},
+    metrics::{NetworkComponent, ScoreMetrics, TimingBlockMetrics},
     mpsc,
     network::{
         data::{
@@ -31,6 +31,7 @@ use crate::{
             split::split,
         },
         session::{SessionManager, SessionSender},
+        TrafficControl,
     },
     party::{
         backup::ABFTBackup, manager::aggregator::AggregatorVersion, traits::NodeSessionManager,
@@ -125,6 +126,7 @@ where
     keystore: Arc<LocalKeystore>,
     runtime_api: RA,
     score_metrics: ScoreMetrics,
+    traffic_control: TrafficControl,
     _phantom: PhantomData<(B, H)>,
 }
 
@@ -161,6 +163,7 @@ where
         keystore: Arc<LocalKeystore>,
         runtime_api: RA,
         score_metrics: ScoreMetrics,
+        traffic_control: TrafficControl,
     ) -> Self {
         Self {
             client,
@@ -178,6 +181,7 @@ where
             keystore,
             runtime_api,
             score_metrics,
+            traffic_control,
             _phantom: PhantomData,
         }
     }
@@ -218,8 +222,12 @@ where
             legacy_create_aleph_config(n_members, node_id, session_id, self.unit_creation_delay);
         let data_network = data_network.map();
 
-        let (unfiltered_aleph_network, rmc_network) =
-            split(data_network, "aleph_network", "rmc_network");
+        let (unfiltered_aleph_network, rmc_network) = split(
+            data_network,
+            NetworkComponent::Abft,
+            NetworkComponent::Aggregation,
+            self.traffic_control.clone(),
+        );
         let (data_store, aleph_network) = DataStore::new(
             session_boundaries.clone(),
             self.header_backend.clone(),
@@ -311,8 +319,12 @@ where
             current_create_aleph_config(n_members, node_id, session_id, self.unit_creation_delay);
         let data_network = data_network.map();
 
-        let (unfiltered_aleph_network, rmc_network) =
-            split(data_network, "aleph_network", "rmc_network");
+        let (unfiltered_aleph_network, rmc_network) = split(
+            data_network,
+            NetworkComponent::Abft,
+            NetworkComponent::Aggregation,
+            self.traffic_control.clone(),
+        );
         let (data_store, aleph_network) = DataStore::new(
             session_boundaries.clone(),
             self.header_backend.clone(),
@@ -387,7 +399,13 @@ where
 
         let data_network = match self
             .session_manager
-            .start_validator_session(session_id, authority_verifier, node_id, authority_pen)
+            .start_validator_session(
+                session_id,
+                authority_verifier,
+                node_id,
+                authority_pen,
+                self.registered_addresses(authorities),
+            )
             .await
         {
             Ok(data_network) => data_network,
@@ -458,6 +476,18 @@ where
         }
     }
 
+    /// Addresses registered on chain for the authorities, used to connect to them before
+    /// discovery does. Failing to read them is not fatal, discovery works without them.
+    fn registered_addresses(&self, authorities: &[AuthorityId]) -> Vec<Option<Vec<u8>>> {
+        match self.runtime_api.registered_validator_addresses(authorities) {
+            Ok(addresses) => addresses,
+            Err(e) => {
+                debug!(target: LOG_TARGET, "Failed to read registered validator addresses: {}", e);
+                vec![None; authorities.len()]
+            }
+        }
+    }
+
     #[cfg(feature = "only_legacy")]
     fn only_legacy(&self) -> bool {
         std::env::var(ONLY_LEGACY_ENV)
@@ -532,6 +562,7 @@ where
             authority_verifier,
             node_id,
             authority_pen,
+            self.registered_addresses(authorities),
         )
     }
 
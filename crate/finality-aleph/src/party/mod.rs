@@ -1,4 +1,4 @@
-use std::{default::Default, path::PathBuf, time::Duration};
+use std::{default::Default, time::Duration};
 
 use futures::FutureExt;
 use futures_timer::Delay;
@@ -7,13 +7,14 @@ use primitives::AuthorityId;
 use tokio::{task::spawn_blocking, time::sleep};
 
 use crate::{
+    metrics::BackupMetrics,
     party::{
         manager::{Handle, Task, TaskCommon as AuthoritySubtaskCommon},
         traits::{ChainState, NodeSessionManager},
     },
     session::SessionBoundaryInfo,
     session_map::ReadOnlySessionMap,
-    SessionId, SyncOracle,
+    BackupConfig, SessionId, SyncOracle,
 };
 
 pub(crate) mod backup;
@@ -30,7 +31,8 @@ pub(crate) struct ConsensusPartyParams<CS, NSM> {
     pub session_authorities: ReadOnlySessionMap,
     pub chain_state: CS,
     pub sync_oracle: SyncOracle,
-    pub backup_saving_path: Option<PathBuf>,
+    pub backup_config: BackupConfig,
+    pub backup_metrics: BackupMetrics,
     pub session_manager: NSM,
     pub session_info: SessionBoundaryInfo,
     pub score_submission_period: u32,
@@ -44,7 +46,8 @@ where
     session_authorities: ReadOnlySessionMap,
     chain_state: CS,
     sync_oracle: SyncOracle,
-    backup_saving_path: Option<PathBuf>,
+    backup_config: BackupConfig,
+    backup_metrics: BackupMetrics,
     session_manager: NSM,
     session_info: SessionBoundaryInfo,
     score_submission_period: u32,
@@ -61,7 +64,8 @@ where
         let ConsensusPartyParams {
             session_authorities,
             sync_oracle,
-            backup_saving_path,
+            backup_config,
+            backup_metrics,
             chain_state,
             session_manager,
             session_info,
@@ -71,7 +75,8 @@ where
         Self {
             sync_oracle,
             session_authorities,
-            backup_saving_path,
+            backup_config,
+            backup_metrics,
             chain_state,
             session_manager,
             session_info,
@@ -108,9 +113,12 @@ where
     async fn run_session(&mut self, session_id: SessionId) {
         let last_block = self.session_info.last_block_of_session(session_id);
         if session_id.0.checked_sub(1).is_some() {
-            let backup_saving_path = self.backup_saving_path.clone();
+            let backup_config = self.backup_config.clone();
+            let backup_metrics = self.backup_metrics.clone();
             spawn_blocking(move || {
-                if let Err(e) = backup::remove_old_backups(backup_saving_path, session_id.0) {
+                if let Err(e) =
+                    backup::remove_old_backups(&backup_config, &backup_metrics, session_id.0)
+                {
                     warn!(target: "aleph-party", "Error when clearing old backups: {}", e);
                 }
             });
@@ -152,7 +160,11 @@ where
         let mut maybe_authority_task = if let Some(node_id) =
             self.session_manager.node_idx(authorities)
         {
-            match backup::rotate(self.backup_saving_path.clone(), session_id.0) {
+            match backup::rotate(
+                &self.backup_config,
+                self.backup_metrics.clone(),
+                session_id.0,
+            ) {
                 Ok(backup) => {
                     debug!(target: "aleph-party", "Running session {:?} as authority id {:?}", session_id, node_id);
                     Some(
@@ -272,13 +284,14 @@ mod tests {
 
     use crate::{
         selendra_primitives::{AuthorityId, SessionAuthorityData},
+        metrics::BackupMetrics,
         party::{
             mocks::{MockChainState, MockNodeSessionManager},
             ConsensusParty, ConsensusPartyParams, SESSION_STATUS_CHECK_PERIOD,
         },
         session::SessionBoundaryInfo,
         session_map::SharedSessionMap,
-        SessionId, SessionPeriod, SyncOracle,
+        BackupConfig, SessionId, SessionPeriod, SyncOracle,
     };
 
     type Party = ConsensusParty<Arc<MockChainState>, Arc<MockNodeSessionManager>>;
@@ -521,7 +534,8 @@ mod tests {
             session_authorities: readonly_session_authorities,
             chain_state,
             sync_oracle,
-            backup_saving_path: None,
+            backup_config: BackupConfig::default(),
+            backup_metrics: BackupMetrics::noop(),
             session_manager,
             session_info,
             score_submission_period: SCORE_SUBMISSION_PERIOD,
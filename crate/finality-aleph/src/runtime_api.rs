@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use frame_support::StorageHasher;
+use frame_support::{StorageHasher, Twox64Concat};
 use pallet_aleph_runtime_api::AlephSessionApi;
 use parity_scale_codec::{Decode, DecodeAll, Encode, Error as DecodeError};
 use sc_client_api::Backend;
@@ -15,8 +15,10 @@ use sp_core::twox_128;
 use sp_runtime::traits::{Block, OpaqueKeys};
 
 use crate::{
-    selendra_primitives::{crypto::SignatureSet, AccountId, AuraId, AuthoritySignature, Score},
-    BlockHash, ClientForAleph,
+    selendra_primitives::{
+        crypto::SignatureSet, AccountId, AuraId, AuthoritySignature, Balance, Score, SessionIndex,
+    },
+    AuthorityId, BlockHash, ClientForAleph,
 };
 
 /// Trait handling connection between host code and runtime storage
@@ -33,6 +35,14 @@ pub trait RuntimeApi: Clone + Send + Sync + 'static {
         score: Score,
         signature: SignatureSet<AuthoritySignature>,
     ) -> Result<(), Self::Error>;
+
+    /// Returns the validator network addresses registered on chain for the authorities, using
+    /// the state of the top finalized block. The addresses are encoded addressing information,
+    /// `None` for authorities without a registered address.
+    fn registered_validator_addresses(
+        &self,
+        authorities: &[AuthorityId],
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error>;
 }
 
 pub struct RuntimeApiImpl<C, B, BE>
@@ -113,7 +123,6 @@ where
         }
     }
 
-    #[allow(dead_code)]
     fn read_storage_map<H: StorageHasher, D: Decode, E: Encode>(
         &self,
         pallet: &str,
@@ -167,6 +176,17 @@ impl Display for ApiError {
 
 type QueuedKeys = Vec<(AccountId, primitives::SelendraNodeSessionKeys)>;
 
+/// Mirrors `pallet_validator_addresses::RegisteredAddress`, the bounded address is encoded the
+/// same way as a vector.
+#[derive(Decode)]
+#[allow(dead_code)]
+struct RegisteredAddress {
+    owner: AccountId,
+    address: Vec<u8>,
+    session: SessionIndex,
+    deposit: Balance,
+}
+
 impl<C, B, BE> RuntimeApi for RuntimeApiImpl<C, B, BE>
 where
     C: ClientForAleph<B, BE> + Send + Sync + 'static,
@@ -210,6 +230,28 @@ where
             Err(_) => Err(ApiError::CallFailed),
         }
     }
+
+    fn registered_validator_addresses(
+        &self,
+        authorities: &[AuthorityId],
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        let finalized = self.client.info().finalized_hash;
+        authorities
+            .iter()
+            .map(|authority| {
+                match self.read_storage_map::<Twox64Concat, RegisteredAddress, _>(
+                    "ValidatorAddresses",
+                    "Addresses",
+                    authority,
+                    finalized,
+                ) {
+                    Ok(registered) => Ok(Some(registered.address)),
+                    Err(ApiError::NoStorageMapEntry(_, _)) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -219,7 +261,6 @@ mod test {
         sync::Arc,
     };
 
-    use frame_support::Twox64Concat;
     use parity_scale_codec::Encode;
     use primitives::Hash;
     use sc_transaction_pool_api::RejectAllTxPool;
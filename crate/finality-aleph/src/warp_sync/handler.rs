@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use futures::{channel::oneshot, StreamExt};
+use log::debug;
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::Backend;
+use sc_network::{
+    request_responses::{IncomingRequest, OutgoingResponse},
+    NetworkBackend, ProtocolName, MAX_RESPONSE_SIZE,
+};
+use sp_runtime::traits::{Block, Header};
+
+use crate::{
+    warp_sync::{
+        proof::{WarpProofProvider, WarpProofRequest},
+        LOG_TARGET,
+    },
+    BlockHash, BlockNumber, ClientForAleph,
+};
+
+/// Name of the network protocol used to request warp proofs.
+const WARP_SYNC_PROTOCOL_NAME: &str = "/warp/0";
+
+/// How many incoming requests can wait for being handled.
+const MAX_WARP_REQUEST_QUEUE: usize = 20;
+
+/// The full name of the warp proof protocol for the given chain.
+pub fn protocol_name(genesis_hash: &BlockHash) -> ProtocolName {
+    format!("/{genesis_hash}{WARP_SYNC_PROTOCOL_NAME}").into()
+}
+
+/// Answers warp proof requests of other nodes.
+pub struct RequestHandler<C, B, BE> {
+    provider: WarpProofProvider<C, B, BE>,
+    request_receiver: async_channel::Receiver<IncomingRequest>,
+}
+
+impl<C, B, BE> RequestHandler<C, B, BE>
+where
+    B: Block<Hash = BlockHash>,
+    B::Header: Header<Number = BlockNumber>,
+    BE: Backend<B>,
+    C: ClientForAleph<B, BE>,
+{
+    pub fn new<N: NetworkBackend<B, B::Hash>>(
+        genesis_hash: &BlockHash,
+        provider: WarpProofProvider<C, B, BE>,
+    ) -> (Self, N::RequestResponseProtocolConfig) {
+        let (tx, request_receiver) = async_channel::bounded(MAX_WARP_REQUEST_QUEUE);
+        let protocol_config = N::request_response_config(
+            protocol_name(genesis_hash),
+            // no fallback names
+            vec![],
+            32,
+            MAX_RESPONSE_SIZE,
+            Duration::from_secs(10),
+            Some(tx),
+        );
+        (
+            RequestHandler {
+                provider,
+                request_receiver,
+            },
+            protocol_config,
+        )
+    }
+
+    fn handle_request(
+        &self,
+        payload: Vec<u8>,
+        pending_response: oneshot::Sender<OutgoingResponse>,
+    ) -> Result<(), String> {
+        let request = WarpProofRequest::decode(&mut payload.as_slice())
+            .map_err(|e| format!("failed to decode request: {e}"))?;
+        let proof = self
+            .provider
+            .generate(request.begin)
+            .map_err(|e| format!("failed to generate proof: {e}"))?;
+        pending_response
+            .send(OutgoingResponse {
+                result: Ok(proof.encode()),
+                reputation_changes: Vec::new(),
+                sent_feedback: None,
+            })
+            .map_err(|_| "failed to send response".to_string())
+    }
+
+    pub async fn run(mut self) {
+        while let Some(IncomingRequest {
+            peer,
+            payload,
+            pending_response,
+        }) = self.request_receiver.next().await
+        {
+            match self.handle_request(payload, pending_response) {
+                Ok(()) => debug!(target: LOG_TARGET, "Handled warp proof request from {peer}."),
+                Err(e) => debug!(
+                    target: LOG_TARGET,
+                    "Failed to handle warp proof request from {peer}: {e}."
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,587 @@
+//! Warp sync for nodes starting from genesis.
+//!
+//! Instead of importing every block, the node downloads the justification of the last block of
+//! every session, each verified with the authority set proven in the state of the previous one.
+//! Then it downloads the state at the last verified block and imports that block as finalized,
+//! leaving the remaining blocks to the block sync.
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use log::{debug, info, warn};
+use pallet_aleph_runtime_api::AlephSessionApi;
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::Backend;
+use sc_consensus::{
+    BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, ImportedState, StateAction,
+    StorageChanges,
+};
+use sc_network::{IfDisconnected, NetworkRequest, PeerId, ProtocolName};
+use sc_network_sync::{
+    strategy::state_sync::{ImportResult as StateImportResult, StateSync, StateSyncProvider},
+    SyncingService,
+};
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_runtime::{traits::Header as _, Justifications};
+use tokio::time::{sleep, timeout};
+
+use crate::{
+    selendra_primitives::{Block, BlockHash, Header, ALEPH_ENGINE_ID},
+    session::SessionBoundaryInfo,
+    ClientForAleph, SessionPeriod, SyncOracle,
+};
+
+mod handler;
+mod proof;
+mod snapshot;
+
+pub use handler::{protocol_name, RequestHandler};
+pub use proof::WarpProofProvider;
+use proof::{WarpProof, WarpProofFragment, WarpProofRequest, WarpProofVerifier};
+pub use snapshot::{export_snapshot, import_snapshot, Error as SnapshotError};
+
+const LOG_TARGET: &str = "aleph-warp-sync";
+
+/// How long to wait before asking the peers again.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How long to wait for any peer to connect before giving up on the warp sync.
+const PEERS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Names of the request-response protocols used by the warp sync.
+#[derive(Clone, Debug)]
+pub struct WarpSyncProtocols {
+    /// Warp proofs, answered by `RequestHandler`.
+    pub warp: ProtocolName,
+    /// Substrate state requests.
+    pub state: ProtocolName,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    RuntimeApi(ApiError),
+    NetworkClosed,
+    NoPeers,
+    Import(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            RuntimeApi(e) => write!(f, "runtime api error: {e}"),
+            NetworkClosed => write!(f, "the network service has stopped"),
+            NoPeers => write!(f, "no peers connected within {PEERS_TIMEOUT:?}"),
+            Import(e) => write!(f, "failed to import the target block: {e}"),
+        }
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(e: ApiError) -> Self {
+        Error::RuntimeApi(e)
+    }
+}
+
+/// Source of the peers to warp sync from.
+#[async_trait]
+trait PeerProvider {
+    /// The currently connected peers, `None` if the network has stopped.
+    async fn connected_peers(&self) -> Option<Vec<PeerId>>;
+}
+
+#[async_trait]
+impl PeerProvider for SyncingService<Block> {
+    async fn connected_peers(&self) -> Option<Vec<PeerId>> {
+        // The best numbers of the peers are not tracked here, blocks are synced by our own
+        // protocol, so they are useless for choosing whom to ask.
+        self.peers_info()
+            .await
+            .ok()
+            .map(|peers| peers.into_iter().map(|(peer, _)| peer).collect())
+    }
+}
+
+/// Connected peers, waiting up to `PEERS_TIMEOUT` for the first one to show up.
+async fn peers<P: PeerProvider>(provider: &P) -> Result<Vec<PeerId>, Error> {
+    let wait = async {
+        loop {
+            let Some(peers) = provider.connected_peers().await else {
+                return Err(Error::NetworkClosed);
+            };
+            if !peers.is_empty() {
+                return Ok(peers);
+            }
+            debug!(target: LOG_TARGET, "No peers to warp sync from, waiting.");
+            sleep(RETRY_DELAY).await;
+        }
+    };
+    timeout(PEERS_TIMEOUT, wait)
+        .await
+        .map_err(|_| Error::NoPeers)?
+}
+
+async fn request<N: NetworkRequest>(
+    network: &N,
+    peer: PeerId,
+    protocol: ProtocolName,
+    payload: Vec<u8>,
+) -> Option<Vec<u8>> {
+    match network
+        .request(
+            peer,
+            protocol,
+            payload,
+            None,
+            IfDisconnected::ImmediateError,
+        )
+        .await
+    {
+        Ok((response, _)) => Some(response),
+        Err(e) => {
+            debug!(target: LOG_TARGET, "Request to {peer} failed: {e}.");
+            None
+        }
+    }
+}
+
+/// Downloads and verifies warp proofs, returns the last verified session end.
+async fn download_proofs<N: NetworkRequest, P: PeerProvider>(
+    network: &N,
+    peer_provider: &P,
+    protocol: &ProtocolName,
+    genesis: BlockHash,
+    mut verifier: WarpProofVerifier,
+    sync_oracle: &SyncOracle,
+) -> Result<Option<WarpProofFragment<Header>>, Error> {
+    let mut target: Option<WarpProofFragment<Header>> = None;
+    loop {
+        for peer in peers(peer_provider).await? {
+            let begin = target
+                .as_ref()
+                .map(|fragment| fragment.header.hash())
+                .unwrap_or(genesis);
+            let payload = WarpProofRequest { begin }.encode();
+            let Some(response) = request(network, peer, protocol.clone(), payload).await else {
+                continue;
+            };
+            let proof = match WarpProof::<Header>::decode(&mut response.as_slice()) {
+                Ok(proof) => proof,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Undecodable warp proof from {peer}: {e}.");
+                    continue;
+                }
+            };
+            let is_finished = proof.is_finished;
+            match verifier.verify(proof) {
+                Ok(Some(fragment)) => {
+                    let number = *fragment.header.number();
+                    info!(target: LOG_TARGET, "Verified warp proof up to block #{number}.");
+                    // Nothing past genesis is imported yet, so we are at least this far behind.
+                    sync_oracle.update_behind(number);
+                    target = Some(fragment);
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Incorrect warp proof from {peer}: {e}.");
+                    continue;
+                }
+            }
+            if is_finished {
+                return Ok(target);
+            }
+        }
+        sleep(RETRY_DELAY).await;
+    }
+}
+
+/// Imports the block together with its whole state as finalized, without its ancestors.
+async fn import_with_state<C, BE>(
+    client: &C,
+    origin: BlockOrigin,
+    header: Header,
+    justifications: Option<Justifications>,
+    state: ImportedState<Block>,
+) -> Result<(), Error>
+where
+    BE: Backend<Block>,
+    C: ClientForAleph<Block, BE>,
+{
+    let number = *header.number();
+    let hash = header.hash();
+    let mut block = BlockImportParams::new(origin, header);
+    block.justifications = justifications;
+    block.post_hash = Some(hash);
+    block.state_action = StateAction::ApplyChanges(StorageChanges::Import(state));
+    block.finalized = true;
+    block.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+    match client.import_block(block).await {
+        Ok(ImportResult::Imported(_)) | Ok(ImportResult::AlreadyInChain) => {
+            info!(target: LOG_TARGET, "Imported state at block #{number}.");
+            Ok(())
+        }
+        Ok(result) => Err(Error::Import(format!("{result:?}"))),
+        Err(e) => Err(Error::Import(e.to_string())),
+    }
+}
+
+/// Downloads the state at the block of `target` and imports the block as finalized.
+async fn download_state<C, BE, N, P>(
+    client: Arc<C>,
+    network: &N,
+    peer_provider: &P,
+    protocol: &ProtocolName,
+    target: WarpProofFragment<Header>,
+    sync_oracle: &SyncOracle,
+) -> Result<(), Error>
+where
+    BE: Backend<Block>,
+    C: ClientForAleph<Block, BE> + Send + Sync,
+    N: NetworkRequest,
+    P: PeerProvider,
+{
+    let WarpProofFragment {
+        header,
+        justification,
+        ..
+    } = target;
+    let number = *header.number();
+    let justifications = Justifications::from((ALEPH_ENGINE_ID, justification));
+    let mut state_sync = StateSync::new(client.clone(), header, None, Some(justifications), false);
+    loop {
+        for peer in peers(peer_provider).await? {
+            let payload = prost::Message::encode_to_vec(&state_sync.next_request());
+            let Some(response) = request(network, peer, protocol.clone(), payload).await else {
+                continue;
+            };
+            let response = match prost::Message::decode(response.as_slice()) {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Undecodable state response from {peer}: {e}.");
+                    continue;
+                }
+            };
+            match state_sync.import(response) {
+                StateImportResult::Import(_, header, state, _, justifications) => {
+                    return import_with_state(
+                        &*client,
+                        BlockOrigin::NetworkInitialSync,
+                        header,
+                        justifications,
+                        state,
+                    )
+                    .await;
+                }
+                StateImportResult::Continue => {
+                    debug!(target: LOG_TARGET, "Downloaded a state chunk from {peer}.");
+                    sync_oracle.update_behind(number);
+                }
+                StateImportResult::BadResponse => {
+                    warn!(target: LOG_TARGET, "Incorrect state response from {peer}.")
+                }
+            }
+        }
+        sleep(RETRY_DELAY).await;
+    }
+}
+
+/// Brings a node with an empty database up to the last finalized session end known to its
+/// peers. Does nothing if anything past genesis is already finalized.
+pub async fn warp_sync<C, BE, N>(
+    client: Arc<C>,
+    network: Arc<N>,
+    sync_service: Arc<SyncingService<Block>>,
+    protocols: WarpSyncProtocols,
+    sync_oracle: SyncOracle,
+) -> Result<(), Error>
+where
+    BE: Backend<Block>,
+    C: ClientForAleph<Block, BE> + Send + Sync,
+    C::Api: AlephSessionApi<Block>,
+    N: NetworkRequest,
+{
+    let info = client.info();
+    if info.finalized_number != 0 {
+        debug!(target: LOG_TARGET, "Chain already past genesis, skipping warp sync.");
+        return Ok(());
+    }
+    let genesis = info.genesis_hash;
+    let verifier = {
+        let runtime_api = client.runtime_api();
+        let session_info =
+            SessionBoundaryInfo::new(SessionPeriod(runtime_api.session_period(genesis)?));
+        WarpProofVerifier::new(session_info, runtime_api.authority_data(genesis)?)
+    };
+
+    info!(target: LOG_TARGET, "Starting warp sync.");
+    let target = download_proofs(
+        &*network,
+        &*sync_service,
+        &protocols.warp,
+        genesis,
+        verifier,
+        &sync_oracle,
+    )
+    .await?;
+    match target {
+        Some(target) => {
+            download_state(
+                client,
+                &*network,
+                &*sync_service,
+                &protocols.state,
+                target,
+                &sync_oracle,
+            )
+            .await
+        }
+        None => {
+            info!(target: LOG_TARGET, "No session has ended yet, nothing to warp to.");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::Mutex,
+    };
+
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use parity_scale_codec::{Decode, Encode};
+    use sc_network::{IfDisconnected, NetworkRequest, PeerId, ProtocolName, RequestFailure};
+    use sp_consensus::SyncOracle as _;
+    use sp_core::Pair;
+    use sp_runtime::traits::Header as _;
+
+    use super::{
+        download_proofs, peers,
+        proof::{
+            testing::{authority_data, fragment},
+            WarpProof, WarpProofRequest, WarpProofVerifier,
+        },
+        Error, PeerProvider,
+    };
+    use crate::{
+        selendra_primitives::{AuthorityPair, BlockHash, Header},
+        session::{SessionBoundaryInfo, SessionId},
+        SessionPeriod, SyncOracle,
+    };
+
+    const SESSION_PERIOD: u32 = 10;
+
+    struct MockPeers(Vec<PeerId>);
+
+    #[async_trait]
+    impl PeerProvider for MockPeers {
+        async fn connected_peers(&self) -> Option<Vec<PeerId>> {
+            Some(self.0.clone())
+        }
+    }
+
+    /// Answers with the responses queued for each peer, recording the requests.
+    #[derive(Default)]
+    struct MockNetwork {
+        responses: Mutex<HashMap<PeerId, VecDeque<Vec<u8>>>>,
+        requests: Mutex<Vec<(PeerId, BlockHash)>>,
+    }
+
+    impl MockNetwork {
+        fn respond(&self, peer: PeerId, response: Vec<u8>) {
+            self.responses
+                .lock()
+                .expect("mutex works")
+                .entry(peer)
+                .or_default()
+                .push_back(response);
+        }
+
+        fn requests(&self) -> Vec<(PeerId, BlockHash)> {
+            self.requests.lock().expect("mutex works").clone()
+        }
+    }
+
+    #[async_trait]
+    impl NetworkRequest for MockNetwork {
+        async fn request(
+            &self,
+            target: PeerId,
+            protocol: ProtocolName,
+            request: Vec<u8>,
+            _fallback_request: Option<(Vec<u8>, ProtocolName)>,
+            _connect: IfDisconnected,
+        ) -> Result<(Vec<u8>, ProtocolName), RequestFailure> {
+            let request = WarpProofRequest::decode(&mut request.as_slice())
+                .expect("the warp sync sends warp proof requests");
+            self.requests
+                .lock()
+                .expect("mutex works")
+                .push((target, request.begin));
+            self.responses
+                .lock()
+                .expect("mutex works")
+                .get_mut(&target)
+                .and_then(VecDeque::pop_front)
+                .map(|response| (response, protocol))
+                .ok_or(RequestFailure::Refused)
+        }
+
+        fn start_request(
+            &self,
+            _target: PeerId,
+            _protocol: ProtocolName,
+            _request: Vec<u8>,
+            _fallback_request: Option<(Vec<u8>, ProtocolName)>,
+            _tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+            _connect: IfDisconnected,
+        ) {
+            unimplemented!("the warp sync only uses `request`")
+        }
+    }
+
+    fn session_info() -> SessionBoundaryInfo {
+        SessionBoundaryInfo::new(SessionPeriod(SESSION_PERIOD))
+    }
+
+    fn pair(seed: u8) -> AuthorityPair {
+        AuthorityPair::from_seed(&[seed; 32])
+    }
+
+    fn encoded_proof(
+        fragments: Vec<super::WarpProofFragment<Header>>,
+        is_finished: bool,
+    ) -> Vec<u8> {
+        WarpProof {
+            fragments,
+            is_finished,
+        }
+        .encode()
+    }
+
+    async fn download(
+        network: &MockNetwork,
+        peers: Vec<PeerId>,
+        finalizer: &AuthorityPair,
+        sync_oracle: &SyncOracle,
+    ) -> Result<Option<super::WarpProofFragment<Header>>, Error> {
+        download_proofs(
+            network,
+            &MockPeers(peers),
+            &ProtocolName::from("/warp"),
+            BlockHash::default(),
+            WarpProofVerifier::new(session_info(), authority_data(finalizer)),
+            sync_oracle,
+        )
+        .await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn downloads_proof_in_parts() {
+        let (first, second) = (pair(1), pair(2));
+        let first_fragment = fragment(&session_info(), SessionId(0), &first, &second);
+        let first_hash = first_fragment.header.hash();
+        let peer = PeerId::random();
+        let network = MockNetwork::default();
+        network.respond(peer, encoded_proof(vec![first_fragment], false));
+        network.respond(
+            peer,
+            encoded_proof(
+                vec![fragment(&session_info(), SessionId(1), &second, &second)],
+                true,
+            ),
+        );
+        let sync_oracle = SyncOracle::new();
+
+        let target = download(&network, vec![peer], &first, &sync_oracle)
+            .await
+            .expect("download should succeed")
+            .expect("some session has ended");
+
+        assert_eq!(*target.header.number(), 2 * SESSION_PERIOD - 1);
+        assert_eq!(
+            network.requests(),
+            vec![(peer, BlockHash::default()), (peer, first_hash)]
+        );
+        assert!(!sync_oracle.is_offline());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn skips_peers_with_incorrect_proofs() {
+        let (first, impostor) = (pair(1), pair(3));
+        let (undecodable, forging, honest) = (PeerId::random(), PeerId::random(), PeerId::random());
+        let network = MockNetwork::default();
+        network.respond(undecodable, vec![7; 3]);
+        network.respond(
+            forging,
+            encoded_proof(
+                vec![fragment(
+                    &session_info(),
+                    SessionId(0),
+                    &impostor,
+                    &impostor,
+                )],
+                true,
+            ),
+        );
+        network.respond(
+            honest,
+            encoded_proof(
+                vec![fragment(&session_info(), SessionId(0), &first, &first)],
+                true,
+            ),
+        );
+
+        let target = download(
+            &network,
+            vec![undecodable, forging, honest],
+            &first,
+            &SyncOracle::new(),
+        )
+        .await
+        .expect("download should succeed")
+        .expect("some session has ended");
+
+        assert_eq!(*target.header.number(), SESSION_PERIOD - 1);
+        assert_eq!(
+            network.requests(),
+            vec![
+                (undecodable, BlockHash::default()),
+                (forging, BlockHash::default()),
+                (honest, BlockHash::default()),
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn asks_again_after_failed_requests() {
+        let first = pair(1);
+        let peer = PeerId::random();
+        let network = MockNetwork::default();
+
+        let pending = download(&network, vec![peer], &first, &SyncOracle::new());
+        futures::pin_mut!(pending);
+        // No response queued, so the request fails and is retried after a delay.
+        assert!(futures::poll!(pending.as_mut()).is_pending());
+        network.respond(peer, encoded_proof(Vec::new(), true));
+
+        assert!(pending.await.expect("download should succeed").is_none());
+        assert_eq!(network.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_without_peers() {
+        assert!(matches!(
+            peers(&MockPeers(Vec::new())).await,
+            Err(Error::NoPeers)
+        ));
+    }
+}
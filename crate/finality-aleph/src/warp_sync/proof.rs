@@ -0,0 +1,471 @@
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::{Backend, BlockBackend, ProofProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::twox_128;
+use sp_runtime::traits::{BlakeTwo256, Block, Header};
+use sp_state_machine::{read_proof_check, StorageProof};
+
+use crate::{
+    block::substrate::SessionVerifier,
+    justification::backwards_compatible_decode,
+    selendra_primitives::{AuthorityId, SessionAuthorityData, ALEPH_ENGINE_ID},
+    session::{SessionBoundaryInfo, SessionId},
+    BlockHash, BlockNumber, ClientForAleph,
+};
+
+/// Maximal encoded size of a single warp proof, the requester asks again for the rest.
+pub const MAX_WARP_PROOF_SIZE: usize = 8 * 1024 * 1024;
+
+const ALEPH_PALLET: &str = "Aleph";
+
+fn storage_key(item: &str) -> Vec<u8> {
+    [twox_128(ALEPH_PALLET.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Storage keys of the authority data for the next session, in the order it is built in the runtime.
+fn next_authority_data_keys() -> [Vec<u8>; 2] {
+    [
+        storage_key("NextAuthorities"),
+        storage_key("QueuedEmergencyFinalizer"),
+    ]
+}
+
+/// Request for a warp proof starting after the given block.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct WarpProofRequest {
+    /// Hash of the last session-ending block the requester already verified, or genesis.
+    pub begin: BlockHash,
+}
+
+/// Proof that the last block of a session is finalized, together with the authority data
+/// for the following session.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct WarpProofFragment<H> {
+    /// Header of the last block of the session.
+    pub header: H,
+    /// The Aleph justification of the header, in the form it is stored in the database.
+    pub justification: Vec<u8>,
+    /// Read proof of the next session authority data against the state root of the header.
+    pub authority_proof: StorageProof,
+}
+
+/// A chain of fragments, one per session.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct WarpProof<H> {
+    pub fragments: Vec<WarpProofFragment<H>>,
+    /// Whether the proof reaches the last session-ending block finalized by the responder.
+    pub is_finished: bool,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Client(sp_blockchain::Error),
+    UnknownBlock(BlockHash),
+    NotSessionEnd(BlockNumber),
+    MissingJustification(BlockNumber),
+    UnexpectedHeader(BlockNumber, BlockNumber),
+    BadJustification(BlockNumber, String),
+    BadAuthorityProof(BlockNumber, String),
+    MissingAuthorities(BlockNumber),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            Client(e) => write!(f, "client error: {e}"),
+            UnknownBlock(hash) => write!(f, "block {hash:?} is not known"),
+            NotSessionEnd(number) => {
+                write!(f, "block #{number} is not the last block of a session")
+            }
+            MissingJustification(number) => write!(f, "no justification for block #{number}"),
+            UnexpectedHeader(expected, got) => {
+                write!(f, "expected the header of block #{expected}, got #{got}")
+            }
+            BadJustification(number, e) => {
+                write!(f, "incorrect justification of block #{number}: {e}")
+            }
+            BadAuthorityProof(number, e) => {
+                write!(f, "incorrect authority proof for block #{number}: {e}")
+            }
+            MissingAuthorities(number) => {
+                write!(
+                    f,
+                    "no next session authorities in the state of block #{number}"
+                )
+            }
+        }
+    }
+}
+
+impl From<sp_blockchain::Error> for Error {
+    fn from(e: sp_blockchain::Error) -> Self {
+        Error::Client(e)
+    }
+}
+
+/// Generates warp proofs from the local database.
+pub struct WarpProofProvider<C, B, BE> {
+    client: Arc<C>,
+    session_info: SessionBoundaryInfo,
+    _phantom: std::marker::PhantomData<(B, BE)>,
+}
+
+impl<C, B, BE> WarpProofProvider<C, B, BE>
+where
+    B: Block<Hash = BlockHash>,
+    B::Header: Header<Number = BlockNumber>,
+    BE: Backend<B>,
+    C: ClientForAleph<B, BE>,
+{
+    pub fn new(client: Arc<C>, session_info: SessionBoundaryInfo) -> Self {
+        WarpProofProvider {
+            client,
+            session_info,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// The session following the one ending with `begin`, or the first session for genesis.
+    fn first_session(&self, begin: BlockHash) -> Result<SessionId, Error> {
+        let number = self
+            .client
+            .number(begin)?
+            .ok_or(Error::UnknownBlock(begin))?;
+        if number == 0 {
+            return Ok(SessionId(0));
+        }
+        let session = self.session_info.session_id_from_block_num(number);
+        match self.session_info.last_block_of_session(session) == number {
+            true => Ok(session.next()),
+            false => Err(Error::NotSessionEnd(number)),
+        }
+    }
+
+    fn fragment(&self, number: BlockNumber) -> Result<WarpProofFragment<B::Header>, Error> {
+        let hash = self
+            .client
+            .hash(number)?
+            .ok_or(Error::MissingJustification(number))?;
+        let header = self.client.header(hash)?.ok_or(Error::UnknownBlock(hash))?;
+        let justification = self
+            .client
+            .justifications(hash)?
+            .and_then(|justifications| justifications.into_justification(ALEPH_ENGINE_ID))
+            .ok_or(Error::MissingJustification(number))?;
+        let keys = next_authority_data_keys();
+        let authority_proof = self
+            .client
+            .read_proof(hash, &mut keys.iter().map(|key| key.as_slice()))?;
+        Ok(WarpProofFragment {
+            header,
+            justification,
+            authority_proof,
+        })
+    }
+
+    /// Collects fragments for the sessions following `begin`, up to the last finalized
+    /// session end or the size limit.
+    pub fn generate(&self, begin: BlockHash) -> Result<WarpProof<B::Header>, Error> {
+        let finalized = self.client.info().finalized_number;
+        let mut session = self.first_session(begin)?;
+        let mut fragments = Vec::new();
+        let mut proof_size = 0;
+        loop {
+            let last_block = self.session_info.last_block_of_session(session);
+            if last_block > finalized {
+                return Ok(WarpProof {
+                    fragments,
+                    is_finished: true,
+                });
+            }
+            let fragment = self.fragment(last_block)?;
+            proof_size += fragment.encoded_size();
+            if proof_size > MAX_WARP_PROOF_SIZE && !fragments.is_empty() {
+                return Ok(WarpProof {
+                    fragments,
+                    is_finished: false,
+                });
+            }
+            fragments.push(fragment);
+            session = session.next();
+        }
+    }
+}
+
+/// Checks warp proofs session by session, starting from a trusted authority set.
+pub struct WarpProofVerifier {
+    session_info: SessionBoundaryInfo,
+    session: SessionId,
+    verifier: SessionVerifier,
+}
+
+impl WarpProofVerifier {
+    /// Creates a verifier expecting the end of the first session, signed by `authority_data`.
+    pub fn new(session_info: SessionBoundaryInfo, authority_data: SessionAuthorityData) -> Self {
+        WarpProofVerifier {
+            session_info,
+            session: SessionId(0),
+            verifier: authority_data.into(),
+        }
+    }
+
+    fn verify_fragment<H>(
+        &self,
+        session: SessionId,
+        verifier: &SessionVerifier,
+        fragment: &WarpProofFragment<H>,
+    ) -> Result<SessionVerifier, Error>
+    where
+        H: Header<Hash = BlockHash, Number = BlockNumber>,
+    {
+        let expected = self.session_info.last_block_of_session(session);
+        let number = *fragment.header.number();
+        if number != expected {
+            return Err(Error::UnexpectedHeader(expected, number));
+        }
+        let justification = backwards_compatible_decode(fragment.justification.clone())
+            .map_err(|e| Error::BadJustification(number, e.to_string()))?;
+        verifier
+            .verify_bytes(&justification, fragment.header.hash().encode())
+            .map_err(|e| Error::BadJustification(number, e.to_string()))?;
+
+        let [authorities_key, emergency_finalizer_key] = next_authority_data_keys();
+        let mut values = read_proof_check::<BlakeTwo256, _>(
+            *fragment.header.state_root(),
+            fragment.authority_proof.clone(),
+            [&authorities_key, &emergency_finalizer_key],
+        )
+        .map_err(|e| Error::BadAuthorityProof(number, e.to_string()))?;
+        let authorities = values
+            .remove(&authorities_key)
+            .flatten()
+            .ok_or(Error::MissingAuthorities(number))?;
+        let authorities = Vec::<AuthorityId>::decode(&mut authorities.as_slice())
+            .map_err(|e| Error::BadAuthorityProof(number, e.to_string()))?;
+        let emergency_finalizer = values
+            .remove(&emergency_finalizer_key)
+            .flatten()
+            .map(|finalizer| AuthorityId::decode(&mut finalizer.as_slice()))
+            .transpose()
+            .map_err(|e| Error::BadAuthorityProof(number, e.to_string()))?;
+        Ok(SessionAuthorityData::new(authorities, emergency_finalizer).into())
+    }
+
+    /// Verifies the fragments in order, moving on to the next session after each one.
+    /// Returns the last verified fragment, if any. Nothing is kept if any fragment is incorrect.
+    pub fn verify<H>(&mut self, proof: WarpProof<H>) -> Result<Option<WarpProofFragment<H>>, Error>
+    where
+        H: Header<Hash = BlockHash, Number = BlockNumber>,
+    {
+        let mut session = self.session;
+        let mut verifier = self.verifier.clone();
+        let mut last = None;
+        for fragment in proof.fragments {
+            verifier = self.verify_fragment(session, &verifier, &fragment)?;
+            session = session.next();
+            last = Some(fragment);
+        }
+        self.session = session;
+        self.verifier = verifier;
+        Ok(last)
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use parity_scale_codec::Encode;
+    use sp_core::{
+        storage::{StateVersion, Storage},
+        Pair,
+    };
+    use sp_runtime::traits::{BlakeTwo256, Header as _};
+    use sp_state_machine::{prove_read, InMemoryBackend};
+
+    use super::{next_authority_data_keys, WarpProofFragment};
+    use crate::{
+        justification::{versioned_encode, AlephJustification},
+        selendra_primitives::{AuthorityPair, Header, SessionAuthorityData},
+        session::{SessionBoundaryInfo, SessionId},
+    };
+
+    /// Authority data of a session run by the single `finalizer`, which is also its emergency
+    /// finalizer.
+    pub fn authority_data(finalizer: &AuthorityPair) -> SessionAuthorityData {
+        SessionAuthorityData::new(vec![finalizer.public()], Some(finalizer.public()))
+    }
+
+    /// Fragment for the end of `session`, emergency finalized by `finalizer` and handing the
+    /// next session over to `next_finalizer`.
+    pub fn fragment(
+        session_info: &SessionBoundaryInfo,
+        session: SessionId,
+        finalizer: &AuthorityPair,
+        next_finalizer: &AuthorityPair,
+    ) -> WarpProofFragment<Header> {
+        let [authorities_key, emergency_finalizer_key] = next_authority_data_keys();
+        let storage = Storage {
+            top: [
+                (authorities_key, vec![next_finalizer.public()].encode()),
+                (emergency_finalizer_key, next_finalizer.public().encode()),
+            ]
+            .into(),
+            children_default: Default::default(),
+        };
+        let backend = InMemoryBackend::<BlakeTwo256>::from((storage, StateVersion::V1));
+        let header = Header::new(
+            session_info.last_block_of_session(session),
+            Default::default(),
+            *backend.root(),
+            Default::default(),
+            Default::default(),
+        );
+        let authority_proof =
+            prove_read(backend, next_authority_data_keys()).expect("keys should be provable");
+        let signature = finalizer.sign(&header.hash().encode());
+        WarpProofFragment {
+            header,
+            justification: versioned_encode(AlephJustification::EmergencySignature(signature)),
+            authority_proof,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::Pair;
+    use sp_runtime::traits::Header as _;
+
+    use super::{
+        testing::{authority_data, fragment},
+        Error, WarpProof, WarpProofVerifier,
+    };
+    use crate::{
+        selendra_primitives::{AuthorityPair, Header},
+        session::{SessionBoundaryInfo, SessionId},
+        SessionPeriod,
+    };
+
+    const SESSION_PERIOD: u32 = 10;
+
+    fn session_info() -> SessionBoundaryInfo {
+        SessionBoundaryInfo::new(SessionPeriod(SESSION_PERIOD))
+    }
+
+    fn pair(seed: u8) -> AuthorityPair {
+        AuthorityPair::from_seed(&[seed; 32])
+    }
+
+    fn proof(fragments: Vec<super::WarpProofFragment<Header>>) -> WarpProof<Header> {
+        WarpProof {
+            fragments,
+            is_finished: true,
+        }
+    }
+
+    #[test]
+    fn verifies_chain_of_sessions() {
+        let (first, second) = (pair(1), pair(2));
+        let mut verifier = WarpProofVerifier::new(session_info(), authority_data(&first));
+        let fragments = vec![
+            fragment(&session_info(), SessionId(0), &first, &second),
+            fragment(&session_info(), SessionId(1), &second, &second),
+        ];
+
+        let last = verifier
+            .verify(proof(fragments))
+            .expect("proof should verify")
+            .expect("proof is not empty");
+        assert_eq!(*last.header.number(), 2 * SESSION_PERIOD - 1);
+    }
+
+    #[test]
+    fn continues_from_last_verified_session() {
+        let (first, second) = (pair(1), pair(2));
+        let mut verifier = WarpProofVerifier::new(session_info(), authority_data(&first));
+
+        verifier
+            .verify(proof(vec![fragment(
+                &session_info(),
+                SessionId(0),
+                &first,
+                &second,
+            )]))
+            .expect("first part should verify");
+        let last = verifier
+            .verify(proof(vec![fragment(
+                &session_info(),
+                SessionId(1),
+                &second,
+                &second,
+            )]))
+            .expect("second part should verify");
+        assert_eq!(
+            last.map(|fragment| *fragment.header.number()),
+            Some(2 * SESSION_PERIOD - 1)
+        );
+        assert!(verifier.verify(proof(Vec::new())).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_justification_of_previous_authorities() {
+        let (first, second) = (pair(1), pair(2));
+        let mut verifier = WarpProofVerifier::new(session_info(), authority_data(&first));
+        let fragments = vec![
+            fragment(&session_info(), SessionId(0), &first, &second),
+            // Session 1 is run by `second`, `first` may not finalize it anymore.
+            fragment(&session_info(), SessionId(1), &first, &first),
+        ];
+
+        assert!(matches!(
+            verifier.verify(proof(fragments)),
+            Err(Error::BadJustification(number, _)) if number == 2 * SESSION_PERIOD - 1
+        ));
+        // Nothing was kept, the first session is expected again.
+        assert!(verifier
+            .verify(proof(vec![fragment(
+                &session_info(),
+                SessionId(0),
+                &first,
+                &second
+            )]))
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_skipped_session() {
+        let first = pair(1);
+        let mut verifier = WarpProofVerifier::new(session_info(), authority_data(&first));
+
+        assert!(matches!(
+            verifier.verify(proof(vec![fragment(
+                &session_info(),
+                SessionId(1),
+                &first,
+                &first
+            )])),
+            Err(Error::UnexpectedHeader(expected, got))
+                if expected == SESSION_PERIOD - 1 && got == 2 * SESSION_PERIOD - 1
+        ));
+    }
+
+    #[test]
+    fn rejects_authority_proof_for_other_state() {
+        let (first, second) = (pair(1), pair(2));
+        let mut verifier = WarpProofVerifier::new(session_info(), authority_data(&first));
+        let mut forged = fragment(&session_info(), SessionId(0), &first, &second);
+        // A proof made for a state handing the next session over to someone else.
+        forged.authority_proof =
+            fragment(&session_info(), SessionId(0), &first, &pair(3)).authority_proof;
+
+        assert!(matches!(
+            verifier.verify(proof(vec![forged])),
+            Err(Error::BadAuthorityProof(number, _)) if number == SESSION_PERIOD - 1
+        ));
+    }
+}
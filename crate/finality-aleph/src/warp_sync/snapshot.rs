@@ -0,0 +1,583 @@
+//! Snapshots of the finalized state, for bootstrapping nodes without the network.
+//!
+//! A snapshot contains the same warp proof a peer would serve, covering every session from
+//! genesis up to the last finalized session end, and the whole state at that block. Importing it
+//! verifies the proof starting from the genesis authorities of the local chain spec, so a
+//! snapshot of a different chain, or one forged by anybody other than the authorities, is
+//! rejected. The state is checked against the state root of the last proven header on import.
+//!
+//! The state is written in batches of key-values, first of the top trie and then of the child
+//! tries, so that exporting holds a single batch in memory. Importing reads the batches straight
+//! into the form in which the state is imported, after the proof has been verified.
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    io::{Read, Write},
+    mem,
+    sync::Arc,
+};
+
+use log::info;
+use pallet_aleph_runtime_api::AlephSessionApi;
+use parity_scale_codec::{Decode, Encode, IoReader};
+use sc_client_api::Backend;
+use sc_consensus::ImportedState;
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_core::storage::{well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, ChildInfo};
+use sp_runtime::{traits::Header as _, Justifications};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+
+use super::{
+    import_with_state,
+    proof::{
+        Error as ProofError, WarpProof, WarpProofFragment, WarpProofProvider, WarpProofVerifier,
+    },
+    Error as WarpSyncError, LOG_TARGET,
+};
+use crate::{
+    selendra_primitives::{Block, BlockHash, BlockNumber, Header, ALEPH_ENGINE_ID},
+    session::SessionBoundaryInfo,
+    ClientForAleph, SessionPeriod,
+};
+
+/// Version of the snapshot format, written before anything else.
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// Total length of the keys and values in a batch of the state, after which the batch is written.
+const BATCH_SIZE: usize = 1024 * 1024;
+
+type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// A child trie, identified by its key in the top trie, which holds its root.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct ChildState {
+    prefixed_storage_key: Vec<u8>,
+    key_values: KeyValues,
+}
+
+/// The whole state at a block.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct State {
+    top: KeyValues,
+    children: Vec<ChildState>,
+}
+
+impl State {
+    fn into_imported(self, block: BlockHash) -> ImportedState<Block> {
+        let State { top, children } = self;
+        let children: Vec<_> = children
+            .into_iter()
+            .map(
+                |ChildState {
+                     prefixed_storage_key,
+                     key_values,
+                 }| KeyValueStorageLevel {
+                    state_root: top
+                        .iter()
+                        .find(|(key, _)| key == &prefixed_storage_key)
+                        .map(|(_, root)| root.clone())
+                        .unwrap_or_default(),
+                    parent_storage_keys: vec![prefixed_storage_key],
+                    key_values,
+                },
+            )
+            .collect();
+        let mut levels = vec![KeyValueStorageLevel {
+            state_root: Vec::new(),
+            parent_storage_keys: Vec::new(),
+            key_values: top,
+        }];
+        levels.extend(children);
+        ImportedState {
+            block,
+            state: KeyValueStates(levels),
+        }
+    }
+}
+
+/// A part of the state, as it is written to the snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+enum StateBatch {
+    Top(KeyValues),
+    Child {
+        prefixed_storage_key: Vec<u8>,
+        key_values: KeyValues,
+    },
+    /// Closes the state.
+    End,
+}
+
+/// Everything in a snapshot before the state.
+#[derive(Clone, Debug, Encode, Decode)]
+struct SnapshotHeader {
+    genesis: BlockHash,
+    proof: WarpProof<Header>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Decode(parity_scale_codec::Error),
+    Client(sp_blockchain::Error),
+    RuntimeApi(ApiError),
+    Proof(ProofError),
+    UnsupportedVersion(u32),
+    WrongGenesis(BlockHash, BlockHash),
+    NoSessionEnded,
+    AlreadyStarted(BlockNumber),
+    Import(WarpSyncError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            Io(e) => write!(f, "io error: {e}"),
+            Decode(e) => write!(f, "malformed snapshot: {e}"),
+            Client(e) => write!(f, "client error: {e}"),
+            RuntimeApi(e) => write!(f, "runtime api error: {e}"),
+            Proof(e) => write!(f, "incorrect finality proof: {e}"),
+            UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported snapshot version {version}, expected {SNAPSHOT_VERSION}"
+                )
+            }
+            WrongGenesis(expected, got) => write!(
+                f,
+                "snapshot of a chain with genesis {got:?}, while the local genesis is {expected:?}"
+            ),
+            NoSessionEnded => write!(f, "no session has ended yet, there is nothing to export"),
+            AlreadyStarted(number) => write!(
+                f,
+                "the database already contains finalized block #{number}, purge the chain first"
+            ),
+            Import(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<parity_scale_codec::Error> for Error {
+    fn from(e: parity_scale_codec::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl From<sp_blockchain::Error> for Error {
+    fn from(e: sp_blockchain::Error) -> Self {
+        Error::Client(e)
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(e: ApiError) -> Self {
+        Error::RuntimeApi(e)
+    }
+}
+
+impl From<ProofError> for Error {
+    fn from(e: ProofError) -> Self {
+        Error::Proof(e)
+    }
+}
+
+/// Encoding straight into the writer would panic on io errors, so every item is encoded in memory
+/// first.
+fn write_item<W: Write, T: Encode>(output: &mut W, item: &T) -> Result<(), Error> {
+    output.write_all(&item.encode())?;
+    Ok(())
+}
+
+fn write_batches<W, I, F>(output: &mut W, key_values: I, into_batch: F) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>,
+    F: Fn(KeyValues) -> StateBatch,
+{
+    let mut batch = Vec::new();
+    let mut size = 0;
+    for key_value in key_values {
+        let (key, value) = key_value?;
+        size += key.len() + value.len();
+        batch.push((key, value));
+        if size >= BATCH_SIZE {
+            write_item(output, &into_batch(mem::take(&mut batch)))?;
+            size = 0;
+        }
+    }
+    if !batch.is_empty() {
+        write_item(output, &into_batch(batch))?;
+    }
+    Ok(())
+}
+
+/// Writes the snapshot, reading the state as it goes. The state consists of the key-values of the
+/// top trie and the ones of every child trie, given its key without the prefix.
+fn write_snapshot<W, T, C, I>(
+    header: &SnapshotHeader,
+    top: T,
+    mut child: C,
+    mut output: W,
+) -> Result<(), Error>
+where
+    W: Write,
+    T: IntoIterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>,
+    C: FnMut(&[u8]) -> Result<I, Error>,
+    I: IntoIterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>,
+{
+    write_item(&mut output, &SNAPSHOT_VERSION)?;
+    write_item(&mut output, header)?;
+    let mut child_keys = Vec::new();
+    let top = top.into_iter().inspect(|key_value| {
+        if let Ok((key, _)) = key_value {
+            if key.starts_with(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+                child_keys.push(key.clone());
+            }
+        }
+    });
+    write_batches(&mut output, top, StateBatch::Top)?;
+    for prefixed_storage_key in child_keys {
+        let key_values = child(&prefixed_storage_key[DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..])?;
+        write_batches(&mut output, key_values, |key_values| StateBatch::Child {
+            prefixed_storage_key: prefixed_storage_key.clone(),
+            key_values,
+        })?;
+    }
+    write_item(&mut output, &StateBatch::End)?;
+    output.flush()?;
+    Ok(())
+}
+
+fn decode_header<R: Read>(input: &mut IoReader<R>) -> Result<SnapshotHeader, Error> {
+    let version = u32::decode(input)?;
+    if version != SNAPSHOT_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    Ok(SnapshotHeader::decode(input)?)
+}
+
+fn read_state<R: Read>(input: &mut IoReader<R>) -> Result<State, Error> {
+    let mut top = Vec::new();
+    let mut children: Vec<ChildState> = Vec::new();
+    loop {
+        match StateBatch::decode(input)? {
+            StateBatch::Top(key_values) => top.extend(key_values),
+            StateBatch::Child {
+                prefixed_storage_key,
+                key_values,
+            } => match children.last_mut() {
+                Some(child) if child.prefixed_storage_key == prefixed_storage_key => {
+                    child.key_values.extend(key_values)
+                }
+                _ => children.push(ChildState {
+                    prefixed_storage_key,
+                    key_values,
+                }),
+            },
+            StateBatch::End => return Ok(State { top, children }),
+        }
+    }
+}
+
+/// Writes a snapshot at the last finalized session end. Requires the states of all the earlier
+/// session ends, so only works on archive nodes. Returns the number of the snapshot block.
+pub fn export_snapshot<C, BE, W>(client: Arc<C>, output: W) -> Result<BlockNumber, Error>
+where
+    BE: Backend<Block>,
+    C: ClientForAleph<Block, BE>,
+    C::Api: AlephSessionApi<Block>,
+    W: Write,
+{
+    let info = client.info();
+    let genesis = info.genesis_hash;
+    let session_period = SessionPeriod(client.runtime_api().session_period(info.finalized_hash)?);
+    let provider = WarpProofProvider::<_, Block, _>::new(
+        client.clone(),
+        SessionBoundaryInfo::new(session_period),
+    );
+
+    let mut fragments: Vec<WarpProofFragment<Header>> = Vec::new();
+    loop {
+        let begin = fragments
+            .last()
+            .map(|fragment| fragment.header.hash())
+            .unwrap_or(genesis);
+        let proof = provider.generate(begin)?;
+        fragments.extend(proof.fragments);
+        if proof.is_finished {
+            break;
+        }
+    }
+    let header = fragments
+        .last()
+        .map(|fragment| fragment.header.clone())
+        .ok_or(Error::NoSessionEnded)?;
+    let number = *header.number();
+    info!(
+        target: LOG_TARGET,
+        "Exporting the state at block #{number} with finality proofs of {} sessions.",
+        fragments.len()
+    );
+
+    let snapshot_header = SnapshotHeader {
+        genesis,
+        proof: WarpProof {
+            fragments,
+            is_finished: true,
+        },
+    };
+    let (client, hash) = (&*client, header.hash());
+    let top = client
+        .storage_pairs(hash, None, None)?
+        .map(|(key, value)| Ok((key.0, value.0)));
+    let child = move |storage_key: &[u8]| -> Result<_, Error> {
+        let child_info = ChildInfo::new_default(storage_key);
+        let keys = client.child_storage_keys(hash, child_info.clone(), None, None)?;
+        Ok(keys.filter_map(
+            move |key| match client.child_storage(hash, &child_info, &key) {
+                Ok(value) => value.map(|value| Ok((key.0, value.0))),
+                Err(e) => Some(Err(Error::from(e))),
+            },
+        ))
+    };
+    write_snapshot(&snapshot_header, top, child, output)?;
+    Ok(number)
+}
+
+/// Verifies a snapshot against the genesis authorities and imports its block with the whole
+/// state as finalized, so that the node continues syncing from there. The database must not
+/// contain anything finalized beyond genesis. Returns the number of the imported block.
+pub async fn import_snapshot<C, BE, R>(client: Arc<C>, input: R) -> Result<BlockNumber, Error>
+where
+    BE: Backend<Block>,
+    C: ClientForAleph<Block, BE> + Send + Sync,
+    C::Api: AlephSessionApi<Block>,
+    R: Read,
+{
+    let info = client.info();
+    if info.finalized_number != 0 {
+        return Err(Error::AlreadyStarted(info.finalized_number));
+    }
+    let genesis = info.genesis_hash;
+    let mut input = IoReader(input);
+    let SnapshotHeader {
+        genesis: snapshot_genesis,
+        proof,
+    } = decode_header(&mut input)?;
+    if snapshot_genesis != genesis {
+        return Err(Error::WrongGenesis(genesis, snapshot_genesis));
+    }
+
+    let mut verifier = {
+        let runtime_api = client.runtime_api();
+        let session_info =
+            SessionBoundaryInfo::new(SessionPeriod(runtime_api.session_period(genesis)?));
+        WarpProofVerifier::new(session_info, runtime_api.authority_data(genesis)?)
+    };
+    let target = verifier.verify(proof)?.ok_or(Error::NoSessionEnded)?;
+    let number = *target.header.number();
+    info!(
+        target: LOG_TARGET,
+        "Verified finality of block #{number}, importing its state."
+    );
+
+    // The state is imported as a whole, so it cannot be read in parts any further.
+    let state = read_state(&mut input)?.into_imported(target.header.hash());
+    let justifications = Justifications::from((ALEPH_ENGINE_ID, target.justification));
+    import_with_state(
+        &*client,
+        BlockOrigin::File,
+        target.header,
+        Some(justifications),
+        state,
+    )
+    .await
+    .map_err(Error::Import)?;
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error as IoError, ErrorKind, Write};
+
+    use parity_scale_codec::{Decode, Encode, IoReader};
+    use sp_core::storage::well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+
+    use super::{
+        decode_header, read_state, write_snapshot, ChildState, Error, SnapshotHeader, State,
+        StateBatch, BATCH_SIZE, SNAPSHOT_VERSION,
+    };
+    use crate::{selendra_primitives::BlockHash, warp_sync::proof::WarpProof};
+
+    fn child_key() -> Vec<u8> {
+        [DEFAULT_CHILD_STORAGE_KEY_PREFIX, b"child"].concat()
+    }
+
+    fn state() -> State {
+        State {
+            top: vec![
+                (b"key".to_vec(), b"value".to_vec()),
+                (child_key(), vec![7; 32]),
+            ],
+            children: vec![ChildState {
+                prefixed_storage_key: child_key(),
+                key_values: vec![(b"child key".to_vec(), b"child value".to_vec())],
+            }],
+        }
+    }
+
+    fn header() -> SnapshotHeader {
+        SnapshotHeader {
+            genesis: BlockHash::repeat_byte(1),
+            proof: WarpProof {
+                fragments: Vec::new(),
+                is_finished: true,
+            },
+        }
+    }
+
+    fn write<W: Write>(state: &State, output: W) -> Result<(), Error> {
+        let top = state.top.iter().cloned().map(Ok);
+        let child = |storage_key: &[u8]| {
+            let prefixed_storage_key = [DEFAULT_CHILD_STORAGE_KEY_PREFIX, storage_key].concat();
+            let key_values = state
+                .children
+                .iter()
+                .find(|child| child.prefixed_storage_key == prefixed_storage_key)
+                .map(|child| child.key_values.clone())
+                .unwrap_or_default();
+            Ok(key_values.into_iter().map(Ok))
+        };
+        write_snapshot(&header(), top, child, output)
+    }
+
+    fn encoded(version: u32) -> Vec<u8> {
+        let mut result = version.encode();
+        header().encode_to(&mut result);
+        StateBatch::Top(state().top).encode_to(&mut result);
+        StateBatch::Child {
+            prefixed_storage_key: child_key(),
+            key_values: state().children[0].key_values.clone(),
+        }
+        .encode_to(&mut result);
+        StateBatch::End.encode_to(&mut result);
+        result
+    }
+
+    fn decode(encoded: &[u8]) -> Result<(SnapshotHeader, State), Error> {
+        let mut input = IoReader(encoded);
+        let header = decode_header(&mut input)?;
+        Ok((header, read_state(&mut input)?))
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(IoError::other("disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn decodes_encoded_snapshot() {
+        let (header, decoded) =
+            decode(encoded(SNAPSHOT_VERSION).as_slice()).expect("snapshot should decode");
+        assert_eq!(header.genesis, BlockHash::repeat_byte(1));
+        assert_eq!(decoded, state());
+    }
+
+    #[test]
+    fn writes_decodable_snapshot() {
+        let mut output = Vec::new();
+        write(&state(), &mut output).expect("writing to memory should work");
+        assert_eq!(output, encoded(SNAPSHOT_VERSION));
+        let (_, decoded) = decode(output.as_slice()).expect("snapshot should decode");
+        assert_eq!(decoded, state());
+    }
+
+    #[test]
+    fn splits_state_into_batches() {
+        let value = vec![0; BATCH_SIZE / 2];
+        let key_values: Vec<_> = (0u8..5).map(|key| (vec![key], value.clone())).collect();
+        let state = State {
+            top: [key_values.clone(), vec![(child_key(), vec![7; 32])]].concat(),
+            children: vec![ChildState {
+                prefixed_storage_key: child_key(),
+                key_values,
+            }],
+        };
+        let mut output = Vec::new();
+        write(&state, &mut output).expect("writing to memory should work");
+
+        let mut input = IoReader(output.as_slice());
+        decode_header(&mut input).expect("header should decode");
+        let mut batches = Vec::new();
+        loop {
+            match StateBatch::decode(&mut input).expect("batch should decode") {
+                StateBatch::End => break,
+                batch => batches.push(batch),
+            }
+        }
+        let top = batches
+            .iter()
+            .filter(|batch| matches!(batch, StateBatch::Top(_)))
+            .count();
+        assert_eq!((top, batches.len() - top), (3, 3));
+        let (_, decoded) = decode(output.as_slice()).expect("snapshot should decode");
+        assert_eq!(decoded, state);
+    }
+
+    #[test]
+    fn returns_write_errors() {
+        assert!(matches!(
+            write(&state(), FailingWriter),
+            Err(Error::Io(e)) if e.kind() == ErrorKind::Other
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        assert!(matches!(
+            decode(encoded(SNAPSHOT_VERSION + 1).as_slice()),
+            Err(Error::UnsupportedVersion(version)) if version == SNAPSHOT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_state() {
+        let encoded = encoded(SNAPSHOT_VERSION);
+        assert!(matches!(
+            decode(&encoded[..encoded.len() - 1]),
+            Err(Error::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn imports_child_tries_under_their_roots() {
+        let block = BlockHash::repeat_byte(2);
+        let imported = state().into_imported(block);
+        assert_eq!(imported.block, block);
+        let levels = imported.state.0;
+        assert_eq!(levels.len(), 2);
+        assert!(levels[0].state_root.is_empty());
+        assert!(levels[0].parent_storage_keys.is_empty());
+        assert_eq!(levels[1].state_root, vec![7; 32]);
+        assert_eq!(levels[1].parent_storage_keys, vec![child_key()]);
+        assert_eq!(
+            levels[1].key_values,
+            vec![(b"child key".to_vec(), b"child value".to_vec())]
+        );
+    }
+}
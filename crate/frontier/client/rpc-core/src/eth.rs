@@ -181,6 +181,16 @@ pub trait EthApi {
 		number_or_hash: Option<BlockNumberOrHash>,
 	) -> RpcResult<Bytes>;
 
+	/// Returns the account and storage values of the given address along with their state
+	/// proofs (EIP-1186, over the Substrate state trie). At most 1024 storage keys are accepted.
+	#[method(name = "eth_getProof")]
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccountProof>;
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
@@ -25,6 +25,7 @@ mod debug;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod trace;
 #[cfg(feature = "txpool")]
 mod txpool;
 mod web3;
@@ -36,5 +37,6 @@ pub use self::{
 	eth::{EthApiServer, EthFilterApiServer},
 	eth_pubsub::EthPubSubApiServer,
 	net::NetApiServer,
+	trace::{DebugTraceApiServer, TraceApiServer},
 	web3::Web3ApiServer,
 };
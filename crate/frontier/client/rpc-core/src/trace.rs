@@ -0,0 +1,64 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! EVM tracing rpc interfaces.
+
+use ethereum_types::H256;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::{
+	BlockNumberOrHash, BlockTraceResult, FilteredTrace, TraceFilter, TraceParams, TraceResult,
+};
+
+/// Debug tracing rpc interface.
+#[rpc(server)]
+#[async_trait]
+pub trait DebugTraceApi {
+	/// Replays the transaction with the given hash and returns its trace.
+	#[method(name = "debug_traceTransaction")]
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<TraceResult>;
+
+	/// Replays the block with the given number and returns the traces of its transactions.
+	#[method(name = "debug_traceBlockByNumber")]
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<BlockTraceResult>>;
+
+	/// Replays the block with the given hash and returns the traces of its transactions.
+	#[method(name = "debug_traceBlockByHash")]
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<BlockTraceResult>>;
+}
+
+/// Trace rpc interface.
+#[rpc(server)]
+#[async_trait]
+pub trait TraceApi {
+	/// Returns the flattened call traces of a block range matching the filter.
+	#[method(name = "trace_filter")]
+	async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<FilteredTrace>>;
+}
@@ -52,6 +52,27 @@ pub struct EthAccount {
 	pub storage_proof: Vec<StorageProof>,
 }
 
+/// Response of `eth_getProof`.
+///
+/// Proofs are Substrate state proofs rather than Merkle Patricia proofs, `storageHash` is the
+/// state root of the block they are checked against. See `fp-evm-proof` for the layout and
+/// verification of the proofs.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+	pub address: H160,
+	/// Free balance of the account.
+	pub balance: U256,
+	pub nonce: U256,
+	pub code_hash: H256,
+	pub storage_hash: H256,
+	/// Storage key of the `System.Account` entry of the account the address maps to.
+	pub account_key: Bytes,
+	/// Trie nodes proving the `System.Account` and `EVM.AccountCodes` entries of the account.
+	pub account_proof: Vec<Bytes>,
+	pub storage_proof: Vec<StorageProof>,
+}
+
 /// Extended account information (used by `parity_allAccountInfo`).
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct ExtAccountInfo {
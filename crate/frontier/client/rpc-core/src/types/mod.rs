@@ -30,6 +30,7 @@ mod index;
 mod log;
 mod receipt;
 mod sync;
+mod trace;
 mod transaction;
 mod transaction_request;
 #[cfg(feature = "txpool")]
@@ -44,7 +45,9 @@ use ethereum_types::H160;
 #[cfg(feature = "txpool")]
 pub use self::txpool::{Summary, TransactionMap, TxPoolResult};
 pub use self::{
-	account_info::{AccountInfo, EthAccount, ExtAccountInfo, RecoveredAccount, StorageProof},
+	account_info::{
+		AccountInfo, AccountProof, EthAccount, ExtAccountInfo, RecoveredAccount, StorageProof,
+	},
 	block::{Block, BlockTransactions, Header, Rich, RichBlock, RichHeader},
 	block_count::BlockCount,
 	block_number::BlockNumberOrHash,
@@ -62,6 +65,10 @@ pub use self::{
 		ChainStatus, EthProtocolInfo, PeerCount, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 		Peers, PipProtocolInfo, SyncInfo, SyncStatus, TransactionStats,
 	},
+	trace::{
+		BlockTraceResult, CallFrame, FilteredTrace, StructLog, StructLoggerResult, TraceAction,
+		TraceActionResult, TraceFilter, TraceParams, TraceResult,
+	},
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
 	transaction_request::{TransactionMessage, TransactionRequest},
 	work::Work,
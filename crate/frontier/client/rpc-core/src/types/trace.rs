@@ -0,0 +1,191 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Types of the EVM tracing RPCs.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockNumberOrHash, Bytes};
+
+/// Options of `debug_trace*` calls, following geth's `TraceConfig`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+	/// `callTracer` for a call tree, the struct logger is used otherwise.
+	pub tracer: Option<String>,
+	#[serde(default)]
+	pub disable_stack: bool,
+	#[serde(default)]
+	pub disable_storage: bool,
+	#[serde(default)]
+	pub enable_memory: bool,
+}
+
+/// A call frame, as returned by geth's `callTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// `CALL`, `STATICCALL`, `CREATE2`, ...
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	pub to: H160,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Bytes,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+/// A single execution step, as returned by geth's struct logger.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+	pub pc: u64,
+	pub op: String,
+	pub gas: u64,
+	pub gas_cost: u64,
+	pub depth: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// Memory as 32 byte words, hex encoded without prefix.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Storage slots of the executing contract, hex encoded without prefix.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+/// Output of geth's struct logger.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerResult {
+	pub gas: u64,
+	pub failed: bool,
+	/// Hex encoded without prefix.
+	pub return_value: String,
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// Trace of a transaction, depending on the requested tracer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+	CallTracer(CallFrame),
+	StructLogger(StructLoggerResult),
+}
+
+/// Trace of a transaction of a traced block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTraceResult {
+	pub tx_hash: H256,
+	pub result: TraceResult,
+}
+
+/// Filter of `trace_filter`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+	/// Defaults to the latest block.
+	pub from_block: Option<BlockNumberOrHash>,
+	/// Defaults to the latest block.
+	pub to_block: Option<BlockNumberOrHash>,
+	/// Only keep traces made by one of these addresses.
+	pub from_address: Option<Vec<H160>>,
+	/// Only keep traces targeting one of these addresses.
+	pub to_address: Option<Vec<H160>>,
+	/// Number of matching traces to skip.
+	pub after: Option<u32>,
+	/// Maximum number of traces to return.
+	pub count: Option<u32>,
+}
+
+/// A flattened, OpenEthereum style, trace.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilteredTrace {
+	pub action: TraceAction,
+	pub block_hash: H256,
+	pub block_number: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub result: Option<TraceActionResult>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Number of calls made by this one.
+	pub subtraces: usize,
+	/// Position of the call in the call tree.
+	pub trace_address: Vec<usize>,
+	pub transaction_hash: H256,
+	pub transaction_position: u32,
+	/// `call`, `create` or `suicide`.
+	#[serde(rename = "type")]
+	pub trace_type: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+	#[serde(rename_all = "camelCase")]
+	Call {
+		from: H160,
+		to: H160,
+		value: U256,
+		gas: U256,
+		input: Bytes,
+		/// `call`, `callcode`, `delegatecall` or `staticcall`.
+		call_type: String,
+	},
+	#[serde(rename_all = "camelCase")]
+	Create {
+		from: H160,
+		value: U256,
+		gas: U256,
+		init: Bytes,
+		/// `create` or `create2`.
+		creation_method: String,
+	},
+	#[serde(rename_all = "camelCase")]
+	Suicide {
+		address: H160,
+		refund_address: H160,
+		balance: U256,
+	},
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceActionResult {
+	#[serde(rename_all = "camelCase")]
+	Call { gas_used: U256, output: Bytes },
+	#[serde(rename_all = "camelCase")]
+	Create {
+		gas_used: U256,
+		code: Bytes,
+		address: H160,
+	},
+}
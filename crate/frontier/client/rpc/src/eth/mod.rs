@@ -34,7 +34,10 @@ use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
 use ethereum_types::{H160, H256, H64, U256, U64};
 use jsonrpsee::core::{async_trait, RpcResult};
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider,
+};
 use sc_network_sync::SyncingService;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::{CallApiAt, ProvideRuntimeApi};
@@ -46,6 +49,7 @@ use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
 // Frontier
 use fc_rpc_core::{types::*, EthApiServer};
 use fc_storage::StorageOverride;
+use fp_evm_proof::EvmProofApi;
 use fp_rpc::{
 	ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi,
 	RuntimeStorageOverride, TransactionStatus,
@@ -298,8 +302,11 @@ impl<B, C, P, CT, BE, CIDP, EC> EthApiServer for Eth<B, C, P, CT, BE, CIDP, EC>
 where
 	B: BlockT,
 	C: CallApiAt<B> + ProvideRuntimeApi<B>,
-	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	C::Api: BlockBuilderApi<B>
+		+ ConvertTransactionRuntimeApi<B>
+		+ EthereumRuntimeRPCApi<B>
+		+ EvmProofApi<B>,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B, Hash = B::Hash> + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
@@ -459,6 +466,15 @@ where
 		self.code_at(address, number_or_hash).await
 	}
 
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccountProof> {
+		self.proof(address, storage_keys, number_or_hash).await
+	}
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
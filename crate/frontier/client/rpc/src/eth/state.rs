@@ -20,19 +20,29 @@ use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::RpcResult;
 use scale_codec::Encode;
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider,
+};
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 // Frontier
 use fc_rpc_core::types::*;
+use fp_evm_proof::{
+	account_code_key, account_storage_key, verify_account, verify_storage, EvmProofApi,
+};
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{eth::Eth, frontier_backend_client, internal_err};
 
+/// Maximum number of storage keys a single `eth_getProof` request may ask for, each of them
+/// costing a separate trie read.
+const MAX_PROOF_STORAGE_KEYS: usize = 1024;
+
 impl<B, C, P, CT, BE, CIDP, EC> Eth<B, C, P, CT, BE, CIDP, EC>
 where
 	B: BlockT,
@@ -203,3 +213,96 @@ where
 		}
 	}
 }
+
+impl<B, C, P, CT, BE, CIDP, EC> Eth<B, C, P, CT, BE, CIDP, EC>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EvmProofApi<B>,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	pub async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccountProof> {
+		if storage_keys.len() > MAX_PROOF_STORAGE_KEYS {
+			return Err(internal_err(format!(
+				"Too many storage keys, at most {MAX_PROOF_STORAGE_KEYS} are allowed"
+			)));
+		}
+		let number_or_hash = number_or_hash.unwrap_or(BlockNumberOrHash::Latest);
+		if number_or_hash == BlockNumberOrHash::Pending {
+			return Err(internal_err(
+				"Proofs of the pending block are not supported",
+			));
+		}
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number_or_hash),
+		)
+		.await?
+		.ok_or_else(|| internal_err("Block not found"))?;
+		let substrate_hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
+		let state_root = *self
+			.client
+			.expect_header(substrate_hash)
+			.map_err(|err| internal_err(format!("Fetch header failed: {err}")))?
+			.state_root();
+		let state_root = H256::from_slice(state_root.as_ref());
+
+		let api = self.client.runtime_api();
+		let has_api = api
+			.has_api::<dyn EvmProofApi<B>>(substrate_hash)
+			.map_err(|err| internal_err(format!("Runtime api access error: {err}")))?;
+		if !has_api {
+			return Err(internal_err("Runtime does not support eth_getProof"));
+		}
+		let account_key = api
+			.system_account_key(substrate_hash, address)
+			.map_err(|err| internal_err(format!("Fetch account key failed: {err}")))?;
+
+		let read_proof = |keys: &[&[u8]]| -> RpcResult<Vec<Vec<u8>>> {
+			Ok(self
+				.client
+				.read_proof(substrate_hash, &mut keys.iter().copied())
+				.map_err(|err| internal_err(format!("Read proof failed: {err}")))?
+				.into_iter_nodes()
+				.collect())
+		};
+
+		// Values are read back from the proofs so they always match what a verifier recovers.
+		let account_proof = read_proof(&[account_key.as_slice(), &account_code_key(address)])?;
+		let account = verify_account(state_root, address, &account_key, &account_proof)
+			.map_err(|err| internal_err(format!("Invalid account proof: {err:?}")))?;
+
+		let mut storage_proof = Vec::with_capacity(storage_keys.len());
+		for index in storage_keys {
+			let proof = read_proof(&[account_storage_key(address, index).as_slice()])?;
+			let value = verify_storage(state_root, address, index, &proof)
+				.map_err(|err| internal_err(format!("Invalid storage proof: {err:?}")))?;
+			storage_proof.push(StorageProof {
+				key: index,
+				value: U256::from_big_endian(value.as_bytes()),
+				proof: proof.into_iter().map(Bytes).collect(),
+			});
+		}
+
+		Ok(AccountProof {
+			address,
+			balance: account.balance,
+			nonce: account.nonce,
+			code_hash: account.code_hash,
+			storage_hash: state_root,
+			account_key: Bytes(account_key),
+			account_proof: account_proof.into_iter().map(Bytes).collect(),
+			storage_proof,
+		})
+	}
+}
@@ -32,6 +32,7 @@ mod eth;
 mod eth_pubsub;
 mod net;
 mod signer;
+mod trace;
 #[cfg(feature = "txpool")]
 mod txpool;
 mod web3;
@@ -45,14 +46,15 @@ pub use self::{
 	eth_pubsub::{EthPubSub, EthereumSubIdProvider},
 	net::Net,
 	signer::{EthDevSigner, EthSigner},
+	trace::{Trace, TraceFilterLimits},
 	web3::Web3,
 };
 pub use ethereum::TransactionV2 as EthereumTransaction;
 #[cfg(feature = "txpool")]
 pub use fc_rpc_core::TxPoolApiServer;
 pub use fc_rpc_core::{
-	DebugApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer,
-	Web3ApiServer,
+	DebugApiServer, DebugTraceApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer,
+	NetApiServer, TraceApiServer, Web3ApiServer,
 };
 pub use fc_storage::{overrides::*, StorageOverrideHandler};
 
@@ -0,0 +1,956 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H256, U256};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	types::error::INVALID_PARAMS_CODE,
+};
+// Substrate
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	BlockBackend,
+};
+use sp_api::{ApiExt, ApiRef, Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto, Zero},
+	DispatchError,
+};
+// Frontier
+use fc_rpc_core::{types::*, DebugTraceApiServer, TraceApiServer};
+use fp_evm_tracing::{
+	CallTrace, CallType, EvmTracingApi, TracerConfig, TracerKind, TransactionTrace,
+};
+
+use crate::{cache::EthBlockDataCacheTask, err, frontier_backend_client, internal_err};
+
+/// Limits of the `trace_filter` RPC.
+#[derive(Clone, Copy, Debug)]
+pub struct TraceFilterLimits {
+	/// Maximum number of blocks replayed by a single request.
+	pub max_block_range: u32,
+	/// Maximum number of traces returned by a single request.
+	pub max_count: u32,
+}
+
+/// EVM tracing API implementation.
+///
+/// Traces are built by replaying blocks on top of their parent state, so the node must keep the
+/// state of the traced blocks (archive mode) and run a runtime built with the `evm-tracing`
+/// feature of `pallet-evm`, usually through `--wasm-runtime-overrides`.
+pub struct Trace<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	limits: TraceFilterLimits,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Clone for Trace<B, C, BE> {
+	fn clone(&self) -> Self {
+		Self {
+			client: self.client.clone(),
+			backend: self.backend.clone(),
+			block_data_cache: self.block_data_cache.clone(),
+			limits: self.limits,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> Trace<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EvmTracingApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		limits: TraceFilterLimits,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			block_data_cache,
+			limits,
+			_marker: PhantomData,
+		}
+	}
+
+	async fn substrate_hash(&self, number: BlockNumberOrHash) -> RpcResult<B::Hash> {
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+		.await?
+		.ok_or_else(|| internal_err("Block not found"))?;
+
+		self.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block hash from id: {}", id)))
+	}
+
+	async fn block_number(&self, number: BlockNumberOrHash) -> RpcResult<u64> {
+		let substrate_hash = self.substrate_hash(number).await?;
+		let header = self
+			.client
+			.header(substrate_hash)
+			.map_err(|e| internal_err(format!("Failed to load header: {:?}", e)))?
+			.ok_or_else(|| internal_err("Block header not found"))?;
+		Ok((*header.number()).unique_saturated_into())
+	}
+
+	/// Replays the block `substrate_hash` on top of its parent state, with `f` applying its
+	/// extrinsics through the tracing runtime API.
+	fn replay<R>(
+		&self,
+		substrate_hash: B::Hash,
+		f: impl FnOnce(
+			&ApiRef<'_, C::Api>,
+			B::Hash,
+			Vec<B::Extrinsic>,
+		) -> Result<Result<R, DispatchError>, sp_api::ApiError>,
+	) -> RpcResult<R> {
+		let header = self
+			.client
+			.header(substrate_hash)
+			.map_err(|e| internal_err(format!("Failed to load header: {:?}", e)))?
+			.ok_or_else(|| internal_err("Block header not found"))?;
+		if header.number().is_zero() {
+			return Err(internal_err("Genesis block can't be traced"));
+		}
+		let extrinsics = self
+			.client
+			.block_body(substrate_hash)
+			.map_err(|e| internal_err(format!("Failed to load block body: {:?}", e)))?
+			.ok_or_else(|| internal_err("Block body not found"))?;
+		let parent_hash = *header.parent_hash();
+
+		let api = self.client.runtime_api();
+		let has_api = api
+			.has_api::<dyn EvmTracingApi<B>>(parent_hash)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+		if !has_api {
+			return Err(internal_err("Runtime does not support EVM tracing"));
+		}
+
+		api.initialize_block(parent_hash, &header)
+			.map_err(|e| internal_err(format!("Failed to initialize block: {:?}", e)))?;
+		f(&api, parent_hash, extrinsics)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?
+			.map_err(|e| internal_err(format!("Failed to trace: {:?}", e)))
+	}
+
+	async fn trace_block(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<BlockTraceResult>> {
+		let config = tracer_config(params)?;
+		let substrate_hash = self.substrate_hash(number).await?;
+		let block = self
+			.block_data_cache
+			.current_block(substrate_hash)
+			.await
+			.ok_or_else(|| internal_err("Ethereum block not found"))?;
+		let hashes: Vec<H256> = block.transactions.iter().map(|tx| tx.hash()).collect();
+		if hashes.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let traces = self.replay(substrate_hash, |api, at, extrinsics| {
+			api.trace_block(at, extrinsics, hashes.clone(), config)
+		})?;
+		if traces.len() != hashes.len() {
+			return Err(internal_err(
+				"Some transactions of the block were not traced",
+			));
+		}
+
+		Ok(hashes
+			.into_iter()
+			.zip(traces)
+			.map(|(tx_hash, trace)| BlockTraceResult {
+				tx_hash,
+				result: trace_result(trace),
+			})
+			.collect())
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> DebugTraceApiServer for Trace<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EvmTracingApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<TraceResult> {
+		let config = tracer_config(params)?;
+		let (eth_block_hash, index) = frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			transaction_hash,
+			true,
+		)
+		.await?
+		.ok_or_else(|| internal_err("Transaction not found"))?;
+		let substrate_hash = frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			eth_block_hash,
+		)
+		.await?
+		.ok_or_else(|| internal_err("Block not found"))?;
+		let transaction = self
+			.block_data_cache
+			.current_block(substrate_hash)
+			.await
+			.and_then(|block| block.transactions.get(index as usize).cloned())
+			.ok_or_else(|| internal_err("Transaction not found"))?;
+
+		let trace = self.replay(substrate_hash, |api, at, extrinsics| {
+			api.trace_transaction(at, extrinsics, &transaction, config)
+		})?;
+		Ok(trace_result(trace))
+	}
+
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<BlockTraceResult>> {
+		self.trace_block(number, params).await
+	}
+
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<BlockTraceResult>> {
+		self.trace_block(
+			BlockNumberOrHash::Hash {
+				hash,
+				require_canonical: false,
+			},
+			params,
+		)
+		.await
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> TraceApiServer for Trace<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EvmTracingApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<FilteredTrace>> {
+		let from_block = self
+			.block_number(filter.from_block.unwrap_or(BlockNumberOrHash::Latest))
+			.await?;
+		let to_block = self
+			.block_number(filter.to_block.unwrap_or(BlockNumberOrHash::Latest))
+			.await?;
+		if to_block < from_block {
+			return Err(err(INVALID_PARAMS_CODE, "fromBlock is after toBlock", None));
+		}
+		if to_block - from_block >= u64::from(self.limits.max_block_range) {
+			return Err(err(
+				INVALID_PARAMS_CODE,
+				format!(
+					"Block range is limited to {} blocks",
+					self.limits.max_block_range
+				),
+				None,
+			));
+		}
+
+		let mut skip = filter.after.unwrap_or(0) as usize;
+		let count = filter
+			.count
+			.unwrap_or(self.limits.max_count)
+			.min(self.limits.max_count) as usize;
+		let matches = |trace: &CallTrace| {
+			filter
+				.from_address
+				.as_ref()
+				.map_or(true, |addresses| addresses.contains(&trace.from))
+				&& filter
+					.to_address
+					.as_ref()
+					.map_or(true, |addresses| addresses.contains(&trace.to))
+		};
+
+		let mut result = Vec::new();
+		for number in from_block..=to_block {
+			let substrate_hash = self.substrate_hash(BlockNumberOrHash::Num(number)).await?;
+			let Some(block) = self.block_data_cache.current_block(substrate_hash).await else {
+				continue;
+			};
+			let hashes: Vec<H256> = block.transactions.iter().map(|tx| tx.hash()).collect();
+			if hashes.is_empty() {
+				continue;
+			}
+			let block_hash = block.header.hash();
+
+			let traces = self.replay(substrate_hash, |api, at, extrinsics| {
+				api.trace_block(at, extrinsics, hashes.clone(), TracerConfig::call_tracer())
+			})?;
+			for (position, (transaction_hash, trace)) in hashes.iter().zip(traces).enumerate() {
+				let TransactionTrace::CallTracer(root) = trace else {
+					continue;
+				};
+				let mut frames = Vec::new();
+				flatten(&root, Vec::new(), &mut frames);
+				for (trace_address, frame) in frames {
+					if !matches(frame) {
+						continue;
+					}
+					if skip > 0 {
+						skip -= 1;
+						continue;
+					}
+					result.push(filtered_trace(
+						frame,
+						trace_address,
+						block_hash,
+						number,
+						*transaction_hash,
+						position as u32,
+					));
+					if result.len() >= count {
+						return Ok(result);
+					}
+				}
+			}
+		}
+		Ok(result)
+	}
+}
+
+fn tracer_config(params: Option<TraceParams>) -> RpcResult<TracerConfig> {
+	let params = params.unwrap_or_default();
+	match params.tracer.as_deref() {
+		Some("callTracer") => Ok(TracerConfig::call_tracer()),
+		Some(tracer) => Err(err(
+			INVALID_PARAMS_CODE,
+			format!("Unsupported tracer: {}", tracer),
+			None,
+		)),
+		None => Ok(TracerConfig {
+			kind: TracerKind::StructLogger,
+			disable_stack: params.disable_stack,
+			disable_memory: !params.enable_memory,
+			disable_storage: params.disable_storage,
+		}),
+	}
+}
+
+fn trace_result(trace: TransactionTrace) -> TraceResult {
+	match trace {
+		TransactionTrace::CallTracer(trace) => TraceResult::CallTracer(call_frame(trace)),
+		TransactionTrace::StructLogger {
+			gas,
+			failed,
+			return_value,
+			struct_logs,
+		} => TraceResult::StructLogger(StructLoggerResult {
+			gas,
+			failed,
+			return_value: hex::encode(return_value),
+			struct_logs: struct_logs
+				.into_iter()
+				.map(|log| StructLog {
+					pc: log.pc,
+					op: opcode_name(log.op),
+					gas: log.gas,
+					gas_cost: log.gas_cost,
+					depth: log.depth,
+					stack: log.stack.map(|stack| {
+						stack
+							.iter()
+							.map(|word| U256::from_big_endian(word.as_bytes()))
+							.collect()
+					}),
+					memory: log
+						.memory
+						.map(|memory| memory.chunks(32).map(hex::encode).collect()),
+					storage: log.storage.map(|storage| {
+						storage
+							.iter()
+							.map(|(key, value)| (hex::encode(key), hex::encode(value)))
+							.collect()
+					}),
+				})
+				.collect(),
+		}),
+	}
+}
+
+fn call_frame(trace: CallTrace) -> CallFrame {
+	CallFrame {
+		call_type: match trace.call_type {
+			CallType::Call => "CALL",
+			CallType::CallCode => "CALLCODE",
+			CallType::DelegateCall => "DELEGATECALL",
+			CallType::StaticCall => "STATICCALL",
+			CallType::Create => "CREATE",
+			CallType::Create2 => "CREATE2",
+			CallType::SelfDestruct => "SELFDESTRUCT",
+		}
+		.into(),
+		from: trace.from,
+		to: trace.to,
+		value: (trace.call_type != CallType::StaticCall).then_some(trace.value),
+		gas: trace.gas.into(),
+		gas_used: trace.gas_used.into(),
+		input: trace.input.into(),
+		output: (!trace.output.is_empty()).then(|| trace.output.into()),
+		error: trace
+			.error
+			.map(|error| String::from_utf8_lossy(&error).into_owned()),
+		calls: trace.calls.into_iter().map(call_frame).collect(),
+	}
+}
+
+/// Lists the frames of a call tree depth first, along with their position in the tree.
+fn flatten<'a>(
+	trace: &'a CallTrace,
+	trace_address: Vec<usize>,
+	frames: &mut Vec<(Vec<usize>, &'a CallTrace)>,
+) {
+	frames.push((trace_address.clone(), trace));
+	for (index, call) in trace.calls.iter().enumerate() {
+		let mut address = trace_address.clone();
+		address.push(index);
+		flatten(call, address, frames);
+	}
+}
+
+fn filtered_trace(
+	trace: &CallTrace,
+	trace_address: Vec<usize>,
+	block_hash: H256,
+	block_number: u64,
+	transaction_hash: H256,
+	transaction_position: u32,
+) -> FilteredTrace {
+	let (trace_type, action, result) = match trace.call_type {
+		CallType::Create | CallType::Create2 => (
+			"create",
+			TraceAction::Create {
+				from: trace.from,
+				value: trace.value,
+				gas: trace.gas.into(),
+				init: trace.input.clone().into(),
+				creation_method: if trace.call_type == CallType::Create {
+					"create".into()
+				} else {
+					"create2".into()
+				},
+			},
+			TraceActionResult::Create {
+				gas_used: trace.gas_used.into(),
+				code: trace.output.clone().into(),
+				address: trace.to,
+			},
+		),
+		CallType::SelfDestruct => {
+			return FilteredTrace {
+				action: TraceAction::Suicide {
+					address: trace.from,
+					refund_address: trace.to,
+					balance: trace.value,
+				},
+				block_hash,
+				block_number,
+				result: None,
+				error: None,
+				subtraces: 0,
+				trace_address,
+				transaction_hash,
+				transaction_position,
+				trace_type: "suicide".into(),
+			}
+		}
+		call_type => (
+			"call",
+			TraceAction::Call {
+				from: trace.from,
+				to: trace.to,
+				value: trace.value,
+				gas: trace.gas.into(),
+				input: trace.input.clone().into(),
+				call_type: match call_type {
+					CallType::CallCode => "callcode",
+					CallType::DelegateCall => "delegatecall",
+					CallType::StaticCall => "staticcall",
+					_ => "call",
+				}
+				.into(),
+			},
+			TraceActionResult::Call {
+				gas_used: trace.gas_used.into(),
+				output: trace.output.clone().into(),
+			},
+		),
+	};
+
+	let error = trace
+		.error
+		.as_ref()
+		.map(|error| String::from_utf8_lossy(error).into_owned());
+	FilteredTrace {
+		action,
+		block_hash,
+		block_number,
+		result: error.is_none().then_some(result),
+		error,
+		subtraces: trace.calls.len(),
+		trace_address,
+		transaction_hash,
+		transaction_position,
+		trace_type: trace_type.into(),
+	}
+}
+
+fn opcode_name(opcode: u8) -> String {
+	let name = match opcode {
+		0x00 => "STOP",
+		0x01 => "ADD",
+		0x02 => "MUL",
+		0x03 => "SUB",
+		0x04 => "DIV",
+		0x05 => "SDIV",
+		0x06 => "MOD",
+		0x07 => "SMOD",
+		0x08 => "ADDMOD",
+		0x09 => "MULMOD",
+		0x0a => "EXP",
+		0x0b => "SIGNEXTEND",
+		0x10 => "LT",
+		0x11 => "GT",
+		0x12 => "SLT",
+		0x13 => "SGT",
+		0x14 => "EQ",
+		0x15 => "ISZERO",
+		0x16 => "AND",
+		0x17 => "OR",
+		0x18 => "XOR",
+		0x19 => "NOT",
+		0x1a => "BYTE",
+		0x1b => "SHL",
+		0x1c => "SHR",
+		0x1d => "SAR",
+		0x20 => "SHA3",
+		0x30 => "ADDRESS",
+		0x31 => "BALANCE",
+		0x32 => "ORIGIN",
+		0x33 => "CALLER",
+		0x34 => "CALLVALUE",
+		0x35 => "CALLDATALOAD",
+		0x36 => "CALLDATASIZE",
+		0x37 => "CALLDATACOPY",
+		0x38 => "CODESIZE",
+		0x39 => "CODECOPY",
+		0x3a => "GASPRICE",
+		0x3b => "EXTCODESIZE",
+		0x3c => "EXTCODECOPY",
+		0x3d => "RETURNDATASIZE",
+		0x3e => "RETURNDATACOPY",
+		0x3f => "EXTCODEHASH",
+		0x40 => "BLOCKHASH",
+		0x41 => "COINBASE",
+		0x42 => "TIMESTAMP",
+		0x43 => "NUMBER",
+		0x44 => "DIFFICULTY",
+		0x45 => "GASLIMIT",
+		0x46 => "CHAINID",
+		0x47 => "SELFBALANCE",
+		0x48 => "BASEFEE",
+		0x49 => "BLOBHASH",
+		0x4a => "BLOBBASEFEE",
+		0x50 => "POP",
+		0x51 => "MLOAD",
+		0x52 => "MSTORE",
+		0x53 => "MSTORE8",
+		0x54 => "SLOAD",
+		0x55 => "SSTORE",
+		0x56 => "JUMP",
+		0x57 => "JUMPI",
+		0x58 => "PC",
+		0x59 => "MSIZE",
+		0x5a => "GAS",
+		0x5b => "JUMPDEST",
+		0x5c => "TLOAD",
+		0x5d => "TSTORE",
+		0x5e => "MCOPY",
+		0x5f => "PUSH0",
+		0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+		0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+		0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+		0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+		0xf0 => "CREATE",
+		0xf1 => "CALL",
+		0xf2 => "CALLCODE",
+		0xf3 => "RETURN",
+		0xf4 => "DELEGATECALL",
+		0xf5 => "CREATE2",
+		0xfa => "STATICCALL",
+		0xfd => "REVERT",
+		0xfe => "INVALID",
+		0xff => "SELFDESTRUCT",
+		_ => return format!("opcode {:#04x} not defined", opcode),
+	};
+	name.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::H160;
+
+	fn address(byte: u8) -> H160 {
+		H160::repeat_byte(byte)
+	}
+
+	fn call_trace(call_type: CallType, from: u8, to: u8, calls: Vec<CallTrace>) -> CallTrace {
+		CallTrace {
+			call_type,
+			from: address(from),
+			to: address(to),
+			value: U256::from(7),
+			gas: 100_000,
+			gas_used: 21_000,
+			input: vec![1, 2],
+			output: vec![3],
+			error: None,
+			reverted: false,
+			calls,
+		}
+	}
+
+	fn reverted(mut trace: CallTrace) -> CallTrace {
+		trace.error = Some(b"execution reverted".to_vec());
+		trace.reverted = true;
+		trace
+	}
+
+	#[test]
+	fn opcode_names() {
+		assert_eq!(opcode_name(0x00), "STOP");
+		assert_eq!(opcode_name(0x20), "SHA3");
+		assert_eq!(opcode_name(0x5f), "PUSH0");
+		assert_eq!(opcode_name(0x60), "PUSH1");
+		assert_eq!(opcode_name(0x7f), "PUSH32");
+		assert_eq!(opcode_name(0x80), "DUP1");
+		assert_eq!(opcode_name(0x8f), "DUP16");
+		assert_eq!(opcode_name(0x90), "SWAP1");
+		assert_eq!(opcode_name(0x9f), "SWAP16");
+		assert_eq!(opcode_name(0xa0), "LOG0");
+		assert_eq!(opcode_name(0xa4), "LOG4");
+		assert_eq!(opcode_name(0xf1), "CALL");
+		assert_eq!(opcode_name(0xfa), "STATICCALL");
+		assert_eq!(opcode_name(0xfd), "REVERT");
+		assert_eq!(opcode_name(0x0c), "opcode 0x0c not defined");
+		assert_eq!(opcode_name(0xa5), "opcode 0xa5 not defined");
+	}
+
+	#[test]
+	fn tracer_config_defaults_to_struct_logger_without_memory() {
+		assert_eq!(
+			tracer_config(None).unwrap(),
+			TracerConfig {
+				kind: TracerKind::StructLogger,
+				disable_stack: false,
+				disable_memory: true,
+				disable_storage: false,
+			}
+		);
+		assert_eq!(
+			tracer_config(Some(TraceParams {
+				tracer: None,
+				disable_stack: true,
+				disable_storage: true,
+				enable_memory: true,
+			}))
+			.unwrap(),
+			TracerConfig {
+				kind: TracerKind::StructLogger,
+				disable_stack: true,
+				disable_memory: false,
+				disable_storage: true,
+			}
+		);
+	}
+
+	#[test]
+	fn tracer_config_selects_call_tracer() {
+		let params = TraceParams {
+			tracer: Some("callTracer".into()),
+			enable_memory: true,
+			..Default::default()
+		};
+		assert_eq!(
+			tracer_config(Some(params)).unwrap(),
+			TracerConfig::call_tracer()
+		);
+	}
+
+	#[test]
+	fn tracer_config_rejects_unsupported_tracer() {
+		let params = TraceParams {
+			tracer: Some("prestateTracer".into()),
+			..Default::default()
+		};
+		let error = tracer_config(Some(params)).unwrap_err();
+		assert_eq!(error.code(), INVALID_PARAMS_CODE);
+		assert_eq!(error.message(), "Unsupported tracer: prestateTracer");
+	}
+
+	#[test]
+	fn call_frame_converts_nested_calls() {
+		let mut empty_output = call_trace(CallType::StaticCall, 2, 4, Vec::new());
+		empty_output.output = Vec::new();
+		let trace = call_trace(
+			CallType::Call,
+			1,
+			2,
+			vec![
+				reverted(call_trace(
+					CallType::DelegateCall,
+					2,
+					3,
+					vec![call_trace(CallType::Create2, 2, 5, Vec::new())],
+				)),
+				empty_output,
+			],
+		);
+
+		let frame = call_frame(trace);
+		assert_eq!(frame.call_type, "CALL");
+		assert_eq!(frame.from, address(1));
+		assert_eq!(frame.to, address(2));
+		assert_eq!(frame.value, Some(U256::from(7)));
+		assert_eq!(frame.gas, U256::from(100_000));
+		assert_eq!(frame.gas_used, U256::from(21_000));
+		assert_eq!(frame.input, vec![1, 2].into());
+		assert_eq!(frame.output, Some(vec![3].into()));
+		assert_eq!(frame.error, None);
+		assert_eq!(frame.calls.len(), 2);
+
+		let delegate = &frame.calls[0];
+		assert_eq!(delegate.call_type, "DELEGATECALL");
+		assert_eq!(delegate.error.as_deref(), Some("execution reverted"));
+		assert_eq!(delegate.calls.len(), 1);
+		assert_eq!(delegate.calls[0].call_type, "CREATE2");
+		assert_eq!(delegate.calls[0].to, address(5));
+
+		let static_call = &frame.calls[1];
+		assert_eq!(static_call.call_type, "STATICCALL");
+		assert_eq!(static_call.value, None);
+		assert_eq!(static_call.output, None);
+		assert!(static_call.calls.is_empty());
+	}
+
+	#[test]
+	fn flatten_walks_depth_first() {
+		let trace = call_trace(
+			CallType::Call,
+			1,
+			2,
+			vec![
+				call_trace(
+					CallType::Call,
+					2,
+					3,
+					vec![call_trace(CallType::Call, 3, 4, Vec::new())],
+				),
+				call_trace(CallType::Call, 2, 5, Vec::new()),
+			],
+		);
+
+		let mut frames = Vec::new();
+		flatten(&trace, Vec::new(), &mut frames);
+		let frames: Vec<_> = frames
+			.into_iter()
+			.map(|(trace_address, frame)| (trace_address, frame.to))
+			.collect();
+		assert_eq!(
+			frames,
+			vec![
+				(vec![], address(2)),
+				(vec![0], address(3)),
+				(vec![0, 0], address(4)),
+				(vec![1], address(5)),
+			]
+		);
+	}
+
+	#[test]
+	fn filtered_trace_of_call() {
+		let trace = call_trace(
+			CallType::StaticCall,
+			1,
+			2,
+			vec![call_trace(CallType::Call, 2, 3, Vec::new())],
+		);
+		let block_hash = H256::repeat_byte(0xbb);
+		let tx_hash = H256::repeat_byte(0xcc);
+
+		let filtered = filtered_trace(&trace, vec![0, 1], block_hash, 42, tx_hash, 3);
+		assert_eq!(
+			filtered,
+			FilteredTrace {
+				action: TraceAction::Call {
+					from: address(1),
+					to: address(2),
+					value: U256::from(7),
+					gas: U256::from(100_000),
+					input: vec![1, 2].into(),
+					call_type: "staticcall".into(),
+				},
+				block_hash,
+				block_number: 42,
+				result: Some(TraceActionResult::Call {
+					gas_used: U256::from(21_000),
+					output: vec![3].into(),
+				}),
+				error: None,
+				subtraces: 1,
+				trace_address: vec![0, 1],
+				transaction_hash: tx_hash,
+				transaction_position: 3,
+				trace_type: "call".into(),
+			}
+		);
+	}
+
+	#[test]
+	fn filtered_trace_of_failed_call_has_no_result() {
+		let trace = reverted(call_trace(CallType::CallCode, 1, 2, Vec::new()));
+
+		let filtered = filtered_trace(&trace, Vec::new(), H256::zero(), 1, H256::zero(), 0);
+		assert_eq!(filtered.trace_type, "call");
+		assert!(matches!(
+			filtered.action,
+			TraceAction::Call { ref call_type, .. } if call_type == "callcode"
+		));
+		assert_eq!(filtered.result, None);
+		assert_eq!(filtered.error.as_deref(), Some("execution reverted"));
+		assert_eq!(filtered.subtraces, 0);
+	}
+
+	#[test]
+	fn filtered_trace_of_create() {
+		let trace = call_trace(CallType::Create2, 1, 9, Vec::new());
+
+		let filtered = filtered_trace(&trace, vec![2], H256::zero(), 1, H256::zero(), 0);
+		assert_eq!(filtered.trace_type, "create");
+		assert_eq!(
+			filtered.action,
+			TraceAction::Create {
+				from: address(1),
+				value: U256::from(7),
+				gas: U256::from(100_000),
+				init: vec![1, 2].into(),
+				creation_method: "create2".into(),
+			}
+		);
+		assert_eq!(
+			filtered.result,
+			Some(TraceActionResult::Create {
+				gas_used: U256::from(21_000),
+				code: vec![3].into(),
+				address: address(9),
+			})
+		);
+	}
+
+	#[test]
+	fn filtered_trace_of_self_destruct() {
+		let trace = call_trace(CallType::SelfDestruct, 1, 2, Vec::new());
+
+		let filtered = filtered_trace(&trace, vec![0], H256::zero(), 1, H256::zero(), 0);
+		assert_eq!(filtered.trace_type, "suicide");
+		assert_eq!(
+			filtered.action,
+			TraceAction::Suicide {
+				address: address(1),
+				refund_address: address(2),
+				balance: U256::from(7),
+			}
+		);
+		assert_eq!(filtered.result, None);
+		assert_eq!(filtered.error, None);
+	}
+
+	#[test]
+	fn trace_result_converts_struct_logs() {
+		let trace = TransactionTrace::StructLogger {
+			gas: 21_000,
+			failed: false,
+			return_value: vec![0xab],
+			struct_logs: vec![fp_evm_tracing::StructLog {
+				pc: 2,
+				op: 0x55,
+				gas: 50_000,
+				gas_cost: 20_000,
+				depth: 1,
+				stack: Some(vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)]),
+				memory: Some(vec![0u8; 64]),
+				storage: Some(vec![(H256::from_low_u64_be(1), H256::from_low_u64_be(2))]),
+			}],
+		};
+
+		let TraceResult::StructLogger(result) = trace_result(trace) else {
+			panic!("expected struct logs");
+		};
+		assert_eq!(result.return_value, "ab");
+		let log = &result.struct_logs[0];
+		assert_eq!(log.op, "SSTORE");
+		assert_eq!(log.stack, Some(vec![U256::from(1), U256::from(2)]));
+		assert_eq!(log.memory, Some(vec!["00".repeat(32), "00".repeat(32)]));
+		assert_eq!(
+			log.storage,
+			Some(
+				[(format!("{:064x}", 1), format!("{:064x}", 2))]
+					.into_iter()
+					.collect()
+			)
+		);
+	}
+}
@@ -0,0 +1,158 @@
+// This file is part of Frontier.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Arkworks
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger256, PrimeField, Zero};
+use ark_serialize::CanonicalDeserialize;
+use hex_literal::hex;
+
+// Frontier
+use fp_evm::{
+	ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+	PrecompileResult,
+};
+
+/// Version byte of a KZG commitment versioned hash.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// `FIELD_ELEMENTS_PER_BLOB` followed by `BLS_MODULUS`, both as 32 byte big endian values.
+const RETURN_VALUE: [u8; 64] = hex!(
+	"0000000000000000000000000000000000000000000000000000000000001000"
+	"73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+);
+
+/// `[τ]G2` from the Ethereum KZG ceremony trusted setup, compressed.
+const TAU_G2: [u8; 96] = hex!(
+	"b5bfd7dd8cdeb128843bc287230af38926187075cbfbefa81009a2ce615ac53d"
+	"2914e5870cb452d2afaaab24f3499f72185cbfee53492714734429b7b38608e2"
+	"3926c911cceceac9a36851477ba4c60b087041de621000edc98edada20c1def2"
+);
+
+fn error(reason: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error {
+		exit_status: ExitError::Other(reason.into()),
+	}
+}
+
+/// `VERSIONED_HASH_VERSION_KZG ++ sha256(commitment)[1..]`
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> [u8; 32] {
+	let mut hash = sp_io::hashing::sha2_256(commitment);
+	hash[0] = VERSIONED_HASH_VERSION_KZG;
+	hash
+}
+
+/// Reads a canonical, big endian encoded BLS12-381 scalar.
+fn read_scalar(bytes: &[u8]) -> Result<Fr, PrecompileFailure> {
+	let mut limbs = [0u64; 4];
+	for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+		let mut buf = [0u8; 8];
+		buf.copy_from_slice(chunk);
+		*limb = u64::from_be_bytes(buf);
+	}
+	Fr::from_bigint(BigInteger256::new(limbs)).ok_or_else(|| error("Invalid field element"))
+}
+
+/// Reads a compressed G1 point, checking it is on the curve and in the right subgroup.
+fn read_g1(bytes: &[u8]) -> Result<G1Affine, PrecompileFailure> {
+	G1Affine::deserialize_compressed(bytes).map_err(|_| error("Invalid G1 point"))
+}
+
+/// Checks that `proof` proves `p(z) = y` for the polynomial committed to by `commitment`,
+/// i.e. `e(commitment - [y]G1, G2) == e(proof, [τ]G2 - [z]G2)`.
+fn verify_kzg_proof(commitment: G1Affine, z: Fr, y: Fr, proof: G1Affine) -> bool {
+	let Ok(tau_g2) = G2Affine::deserialize_compressed(&TAU_G2[..]) else {
+		return false;
+	};
+	let g1 = G1Affine::generator();
+	let g2 = G2Affine::generator();
+
+	let commitment_minus_y = (commitment.into_group() - g1 * y).into_affine();
+	let tau_minus_z = (tau_g2.into_group() - g2 * z).into_affine();
+
+	Bls12_381::multi_pairing([commitment_minus_y, proof], [-g2, tau_minus_z]).is_zero()
+}
+
+/// The EIP-4844 point evaluation builtin.
+///
+/// Input is `versioned_hash | z | y | commitment | proof` of 32, 32, 32, 48 and 48 bytes.
+pub struct KzgPointEvaluation;
+
+impl KzgPointEvaluation {
+	const GAS_COST: u64 = 50_000; // https://eips.ethereum.org/EIPS/eip-4844
+	const INPUT_LENGTH: usize = 192;
+}
+
+impl Precompile for KzgPointEvaluation {
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(KzgPointEvaluation::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != KzgPointEvaluation::INPUT_LENGTH {
+			return Err(error("Invalid input length"));
+		}
+
+		let versioned_hash = &input[0..32];
+		let commitment_bytes = &input[96..144];
+		if kzg_to_versioned_hash(commitment_bytes) != versioned_hash {
+			return Err(error("Mismatched versioned hash"));
+		}
+
+		let z = read_scalar(&input[32..64])?;
+		let y = read_scalar(&input[64..96])?;
+		let commitment = read_g1(commitment_bytes)?;
+		let proof = read_g1(&input[144..192])?;
+
+		if !verify_kzg_proof(commitment, z, y, proof) {
+			return Err(error("Invalid KZG proof"));
+		}
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: RETURN_VALUE.to_vec(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_evm_test_vector_support::{
+		test_precompile_failure_test_vectors, test_precompile_test_vectors,
+	};
+
+	#[test]
+	fn trusted_setup_point_is_valid() {
+		assert!(G2Affine::deserialize_compressed(&TAU_G2[..]).is_ok());
+	}
+
+	#[test]
+	fn process_consensus_tests() -> Result<(), String> {
+		test_precompile_test_vectors::<KzgPointEvaluation>("../testdata/kzgPointEvaluation.json")?;
+		Ok(())
+	}
+
+	#[test]
+	fn process_failure_tests() -> Result<(), String> {
+		test_precompile_failure_test_vectors::<KzgPointEvaluation>(
+			"../testdata/fail-kzgPointEvaluation.json",
+		)?;
+		Ok(())
+	}
+}
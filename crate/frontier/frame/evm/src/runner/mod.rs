@@ -17,6 +17,8 @@
 
 pub mod meter;
 pub mod stack;
+#[cfg(feature = "evm-tracing")]
+pub mod tracing;
 
 use crate::{Config, Weight};
 use alloc::vec::Vec;
@@ -309,6 +309,9 @@ where
 				(reason, retv, used_gas, effective_gas)
 			});
 
+		#[cfg(feature = "evm-tracing")]
+		super::tracing::on_execution_end(effective_gas.unique_saturated_into());
+
 		let actual_fee = effective_gas.saturating_mul(total_fee_per_gas);
 		let actual_base_fee = effective_gas.saturating_mul(base_fee);
 
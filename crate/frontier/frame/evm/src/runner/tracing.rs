@@ -0,0 +1,500 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! EVM execution tracing.
+//!
+//! [`trace`] runs a closure while listening to the events of the EVM executor, interpreter and
+//! gasometer, and builds the requested [`TransactionTrace`] out of them. The runner reports the
+//! gas charged for the whole transaction once the execution is over, since the executor events
+//! only know about the gas used by each frame.
+
+use alloc::{collections::btree_map::BTreeMap, format, rc::Rc, vec::Vec};
+use core::{cell::RefCell, mem};
+use evm::{tracing::Event as EvmEvent, CreateScheme, ExitError, ExitReason, Transfer};
+use evm_gasometer::tracing::{Event as GasometerEvent, Snapshot};
+use evm_runtime::tracing::Event as RuntimeEvent;
+// Substrate
+use sp_core::{H160, H256, U256};
+// Frontier
+use fp_evm_tracing::{CallTrace, CallType, StructLog, TracerConfig, TracerKind, TransactionTrace};
+
+environmental::environmental!(runner_listener: dyn RunnerListener + 'static);
+
+/// Events of the runner, around the execution of the EVM.
+trait RunnerListener {
+	fn execution_end(&mut self, used_gas: u64);
+}
+
+/// Reports the gas charged for the transaction, including its intrinsic cost and refunds.
+pub(crate) fn on_execution_end(used_gas: u64) {
+	runner_listener::with(|listener| listener.execution_end(used_gas));
+}
+
+/// Runs `f`, tracing the EVM executions it performs.
+///
+/// Meant to wrap the application of a single Ethereum transaction.
+pub fn trace<R>(config: TracerConfig, f: impl FnOnce() -> R) -> (R, TransactionTrace) {
+	let tracer = Rc::new(RefCell::new(Tracer::new(config)));
+	let mut evm_proxy = ListenerProxy(tracer.clone());
+	let mut gasometer_proxy = ListenerProxy(tracer.clone());
+	let mut runtime_proxy = ListenerProxy(tracer.clone());
+	let mut runner_proxy = ListenerProxy(tracer.clone());
+
+	let result = evm::tracing::using(&mut evm_proxy, || {
+		evm_gasometer::tracing::using(&mut gasometer_proxy, || {
+			runner_listener::using(&mut runner_proxy, || {
+				// Step events are only needed for struct logs, and are by far the most frequent.
+				if config.kind == TracerKind::StructLogger {
+					evm_runtime::tracing::using(&mut runtime_proxy, f)
+				} else {
+					f()
+				}
+			})
+		})
+	});
+
+	let trace = tracer.borrow_mut().finish();
+	(result, trace)
+}
+
+/// Forwards the events of every listener to the same tracer.
+struct ListenerProxy(Rc<RefCell<Tracer>>);
+
+impl evm::tracing::EventListener for ListenerProxy {
+	fn event(&mut self, event: EvmEvent) {
+		self.0.borrow_mut().evm_event(event);
+	}
+}
+
+impl evm_gasometer::tracing::EventListener for ListenerProxy {
+	fn event(&mut self, event: GasometerEvent) {
+		self.0.borrow_mut().gasometer_event(event);
+	}
+}
+
+impl evm_runtime::tracing::EventListener for ListenerProxy {
+	fn event(&mut self, event: RuntimeEvent) {
+		self.0.borrow_mut().runtime_event(event);
+	}
+}
+
+impl RunnerListener for ListenerProxy {
+	fn execution_end(&mut self, used_gas: u64) {
+		self.0.borrow_mut().execution_end(used_gas);
+	}
+}
+
+/// A call frame being executed.
+struct Frame {
+	trace: CallTrace,
+	/// Address whose storage the frame executes against.
+	address: H160,
+	/// Latest state of the gasometer of the frame.
+	snapshot: Option<Snapshot>,
+}
+
+impl Frame {
+	fn remaining_gas(&self) -> u64 {
+		match &self.snapshot {
+			Some(snapshot) => snapshot
+				.gas_limit
+				.saturating_sub(snapshot.used_gas)
+				.saturating_sub(snapshot.memory_gas),
+			None => self.trace.gas,
+		}
+	}
+}
+
+struct Tracer {
+	config: TracerConfig,
+	frames: Vec<Frame>,
+	root: Option<CallTrace>,
+	used_gas: Option<u64>,
+	/// The next enter event is the one of the transaction frame already pushed.
+	skip_enter: bool,
+	struct_logs: Vec<StructLog>,
+	/// Step whose cost is not known yet.
+	pending_step: Option<StructLog>,
+	/// Storage accessed so far, by contract.
+	storages: BTreeMap<H160, BTreeMap<H256, H256>>,
+}
+
+impl Tracer {
+	fn new(config: TracerConfig) -> Self {
+		Self {
+			config,
+			frames: Vec::new(),
+			root: None,
+			used_gas: None,
+			skip_enter: false,
+			struct_logs: Vec::new(),
+			pending_step: None,
+			storages: BTreeMap::new(),
+		}
+	}
+
+	fn enter(
+		&mut self,
+		call_type: CallType,
+		from: H160,
+		to: H160,
+		value: U256,
+		gas: u64,
+		input: &[u8],
+	) {
+		self.flush_step();
+		self.frames.push(Frame {
+			trace: CallTrace {
+				call_type,
+				from,
+				to,
+				value,
+				gas,
+				gas_used: 0,
+				input: input.to_vec(),
+				output: Vec::new(),
+				error: None,
+				reverted: false,
+				calls: Vec::new(),
+			},
+			address: to,
+			snapshot: None,
+		});
+	}
+
+	fn enter_transaction(
+		&mut self,
+		call_type: CallType,
+		from: H160,
+		to: H160,
+		value: U256,
+		gas: u64,
+		input: &[u8],
+	) {
+		self.enter(call_type, from, to, value, gas, input);
+		self.skip_enter = true;
+	}
+
+	/// Enters a frame started by the executor, unless it is the transaction frame.
+	fn enter_subcall(
+		&mut self,
+		code_address: H160,
+		transfer: &Option<Transfer>,
+		input: &[u8],
+		target_gas: Option<u64>,
+		is_static: bool,
+		context: &evm::Context,
+	) {
+		if mem::take(&mut self.skip_enter) {
+			return;
+		}
+
+		let (from, gas) = match self.frames.last() {
+			Some(parent) => (
+				parent.address,
+				target_gas.unwrap_or_else(|| parent.remaining_gas()),
+			),
+			None => (context.caller, target_gas.unwrap_or_default()),
+		};
+		let call_type = if is_static {
+			CallType::StaticCall
+		} else if context.address == code_address {
+			CallType::Call
+		} else if context.caller == context.address {
+			CallType::CallCode
+		} else {
+			CallType::DelegateCall
+		};
+		let value = transfer
+			.as_ref()
+			.map_or(context.apparent_value, |transfer| transfer.value);
+
+		self.enter(call_type, from, code_address, value, gas, input);
+		// The code of the callee runs against the storage of the context.
+		if let Some(frame) = self.frames.last_mut() {
+			frame.address = context.address;
+		}
+	}
+
+	fn exit(&mut self, reason: &ExitReason, return_value: &[u8]) {
+		self.flush_step();
+		self.skip_enter = false;
+
+		let Some(frame) = self.frames.pop() else {
+			return;
+		};
+		let mut trace = frame.trace;
+		trace.gas_used = frame
+			.snapshot
+			.map(|snapshot| snapshot.used_gas.saturating_add(snapshot.memory_gas))
+			.unwrap_or_default();
+		trace.output = return_value.to_vec();
+		trace.reverted = matches!(reason, ExitReason::Revert(_));
+		trace.error = exit_error(reason);
+
+		match self.frames.last_mut() {
+			Some(parent) => parent.trace.calls.push(trace),
+			// Nested executions (e.g. through cross-VM calls) are reported as sub calls, only the
+			// outermost one is the transaction.
+			None => self.root = Some(trace),
+		}
+	}
+
+	fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::TransactCall {
+				caller,
+				address,
+				value,
+				data,
+				gas_limit,
+			} => self.enter_transaction(CallType::Call, caller, address, value, gas_limit, data),
+			EvmEvent::TransactCreate {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+			} => self.enter_transaction(
+				CallType::Create,
+				caller,
+				address,
+				value,
+				gas_limit,
+				init_code,
+			),
+			EvmEvent::TransactCreate2 {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+				..
+			} => self.enter_transaction(
+				CallType::Create2,
+				caller,
+				address,
+				value,
+				gas_limit,
+				init_code,
+			),
+			EvmEvent::Call {
+				code_address,
+				transfer,
+				input,
+				target_gas,
+				is_static,
+				context,
+			}
+			| EvmEvent::PrecompileSubcall {
+				code_address,
+				transfer,
+				input,
+				target_gas,
+				is_static,
+				context,
+			} => self.enter_subcall(
+				code_address,
+				transfer,
+				input,
+				target_gas,
+				is_static,
+				context,
+			),
+			EvmEvent::Create {
+				caller,
+				address,
+				scheme,
+				value,
+				init_code,
+				target_gas,
+			} => {
+				if mem::take(&mut self.skip_enter) {
+					return;
+				}
+				let call_type = match scheme {
+					CreateScheme::Create2 { .. } => CallType::Create2,
+					_ => CallType::Create,
+				};
+				let gas = target_gas
+					.or_else(|| self.frames.last().map(Frame::remaining_gas))
+					.unwrap_or_default();
+				self.enter(call_type, caller, address, value, gas, init_code);
+			}
+			EvmEvent::Suicide {
+				address,
+				target,
+				balance,
+			} => {
+				if let Some(frame) = self.frames.last_mut() {
+					frame.trace.calls.push(CallTrace {
+						call_type: CallType::SelfDestruct,
+						from: address,
+						to: target,
+						value: balance,
+						gas: 0,
+						gas_used: 0,
+						input: Vec::new(),
+						output: Vec::new(),
+						error: None,
+						reverted: false,
+						calls: Vec::new(),
+					});
+				}
+			}
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => self.exit(reason, return_value),
+		}
+	}
+
+	fn gasometer_event(&mut self, event: GasometerEvent) {
+		let snapshot = match event {
+			GasometerEvent::RecordCost { snapshot, .. }
+			| GasometerEvent::RecordRefund { snapshot, .. }
+			| GasometerEvent::RecordStipend { snapshot, .. }
+			| GasometerEvent::RecordDynamicCost { snapshot, .. }
+			| GasometerEvent::RecordTransaction { snapshot, .. } => snapshot,
+		};
+		let depth = self.frames.len() as u32;
+		let Some(frame) = self.frames.last_mut() else {
+			return;
+		};
+		if snapshot.is_some() {
+			frame.snapshot = snapshot;
+		}
+
+		let remaining = frame.remaining_gas();
+		if let Some(step) = self
+			.pending_step
+			.as_mut()
+			.filter(|step| step.depth == depth)
+		{
+			step.gas_cost = step.gas.saturating_sub(remaining);
+		}
+	}
+
+	fn runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step {
+				opcode,
+				position,
+				stack,
+				memory,
+				..
+			} => {
+				self.flush_step();
+				let gas = self
+					.frames
+					.last()
+					.map(Frame::remaining_gas)
+					.unwrap_or_default();
+				self.pending_step = Some(StructLog {
+					pc: position.as_ref().map_or(0, |pc| *pc as u64),
+					op: opcode.0,
+					gas,
+					gas_cost: 0,
+					depth: self.frames.len() as u32,
+					stack: (!self.config.disable_stack).then(|| stack.data().clone()),
+					memory: (!self.config.disable_memory).then(|| memory.data().clone()),
+					storage: None,
+				});
+			}
+			RuntimeEvent::StepResult { .. } => self.flush_step(),
+			RuntimeEvent::SLoad {
+				address,
+				index,
+				value,
+			}
+			| RuntimeEvent::SStore {
+				address,
+				index,
+				value,
+			} => {
+				if self.config.disable_storage {
+					return;
+				}
+				let storage = self.storages.entry(address).or_default();
+				storage.insert(index, value);
+				if let Some(step) = self.pending_step.as_mut() {
+					step.storage =
+						Some(storage.iter().map(|(key, value)| (*key, *value)).collect());
+				}
+			}
+		}
+	}
+
+	fn execution_end(&mut self, used_gas: u64) {
+		// Executions nested in the transaction end while its frame is still open.
+		if self.frames.is_empty() && self.used_gas.is_none() {
+			self.used_gas = Some(used_gas);
+		}
+	}
+
+	fn flush_step(&mut self) {
+		if let Some(step) = self.pending_step.take() {
+			self.struct_logs.push(step);
+		}
+	}
+
+	fn finish(&mut self) -> TransactionTrace {
+		self.flush_step();
+
+		let mut root = self.root.take().unwrap_or_else(|| CallTrace {
+			call_type: CallType::Call,
+			from: H160::zero(),
+			to: H160::zero(),
+			value: U256::zero(),
+			gas: 0,
+			gas_used: 0,
+			input: Vec::new(),
+			output: Vec::new(),
+			error: None,
+			reverted: false,
+			calls: Vec::new(),
+		});
+		if let Some(used_gas) = self.used_gas {
+			root.gas_used = used_gas;
+		}
+
+		match self.config.kind {
+			TracerKind::CallTracer => TransactionTrace::CallTracer(root),
+			TracerKind::StructLogger => TransactionTrace::StructLogger {
+				gas: root.gas_used,
+				failed: root.error.is_some(),
+				return_value: root.output,
+				struct_logs: mem::take(&mut self.struct_logs),
+			},
+		}
+	}
+}
+
+/// Error message of a failed frame, matching the ones of geth for the common cases.
+fn exit_error(reason: &ExitReason) -> Option<Vec<u8>> {
+	match reason {
+		ExitReason::Succeed(_) => None,
+		ExitReason::Revert(_) => Some(b"execution reverted".to_vec()),
+		ExitReason::Error(ExitError::OutOfGas) => Some(b"out of gas".to_vec()),
+		ExitReason::Error(ExitError::InvalidCode(_))
+		| ExitReason::Error(ExitError::DesignatedInvalid) => Some(b"invalid opcode".to_vec()),
+		ExitReason::Error(ExitError::StackUnderflow) => Some(b"stack underflow".to_vec()),
+		ExitReason::Error(ExitError::StackOverflow) => Some(b"stack overflow".to_vec()),
+		ExitReason::Error(error) => Some(format!("{:?}", error).into_bytes()),
+		ExitReason::Fatal(error) => Some(format!("{:?}", error).into_bytes()),
+	}
+}
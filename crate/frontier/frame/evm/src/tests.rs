@@ -1472,3 +1472,169 @@ fn metadata_empty_dont_code_gets_cached() {
 		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
 	});
 }
+
+#[cfg(feature = "evm-tracing")]
+mod tracing_test {
+	use super::*;
+	use fp_evm_tracing::{CallType, TracerConfig, TracerKind, TransactionTrace};
+
+	const CALLER: &str = "1000000000000000000000000000000000000010";
+	const CALLEE: &str = "1000000000000000000000000000000000000011";
+
+	/// Calls `CALLEE` with all the remaining gas, then stops.
+	fn caller_code() -> Vec<u8> {
+		let mut code = vec![
+			0x60, 0x00, // PUSH1 0 (retSize)
+			0x60, 0x00, // PUSH1 0 (retOffset)
+			0x60, 0x00, // PUSH1 0 (argsSize)
+			0x60, 0x00, // PUSH1 0 (argsOffset)
+			0x60, 0x00, // PUSH1 0 (value)
+			0x73, // PUSH20 CALLEE
+		];
+		code.extend_from_slice(H160::from_str(CALLEE).unwrap().as_bytes());
+		code.extend_from_slice(&[
+			0x5a, // GAS
+			0xf1, // CALL
+			0x00, // STOP
+		]);
+		code
+	}
+
+	/// Reverts with empty data.
+	fn callee_code() -> Vec<u8> {
+		vec![
+			0x60, 0x00, // PUSH1 0
+			0x60, 0x00, // PUSH1 0
+			0xfd, // REVERT
+		]
+	}
+
+	fn traced_call(config: TracerConfig) -> (CallInfo, TransactionTrace) {
+		EVM::create_account(H160::from_str(CALLER).unwrap(), caller_code());
+		EVM::create_account(H160::from_str(CALLEE).unwrap(), callee_code());
+
+		let (info, trace) = runner::tracing::trace(config, || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				H160::from_str(CALLER).unwrap(),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+		});
+		(info.expect("call succeeds"), trace)
+	}
+
+	fn struct_logger(disable_stack: bool) -> TracerConfig {
+		TracerConfig {
+			kind: TracerKind::StructLogger,
+			disable_stack,
+			disable_memory: true,
+			disable_storage: true,
+		}
+	}
+
+	#[test]
+	fn call_tracer_nests_reverted_subcall() {
+		new_test_ext().execute_with(|| {
+			let (info, trace) = traced_call(TracerConfig::call_tracer());
+			let TransactionTrace::CallTracer(root) = trace else {
+				panic!("expected a call trace, got {:?}", trace);
+			};
+
+			assert_eq!(root.call_type, CallType::Call);
+			assert_eq!(root.from, H160::default());
+			assert_eq!(root.to, H160::from_str(CALLER).unwrap());
+			assert_eq!(root.gas, 1000000);
+			assert_eq!(U256::from(root.gas_used), info.used_gas.effective);
+			assert_eq!(root.error, None);
+			assert!(!root.reverted);
+			assert_eq!(root.calls.len(), 1);
+
+			let subcall = &root.calls[0];
+			assert_eq!(subcall.call_type, CallType::Call);
+			assert_eq!(subcall.from, H160::from_str(CALLER).unwrap());
+			assert_eq!(subcall.to, H160::from_str(CALLEE).unwrap());
+			assert_eq!(subcall.value, U256::zero());
+			assert!(subcall.reverted);
+			assert_eq!(subcall.error, Some(b"execution reverted".to_vec()));
+			assert!(subcall.output.is_empty());
+			assert!(subcall.gas_used > 0 && subcall.gas_used < subcall.gas);
+			assert!(subcall.calls.is_empty());
+		});
+	}
+
+	#[test]
+	fn struct_logger_records_steps_of_each_frame() {
+		new_test_ext().execute_with(|| {
+			let (info, trace) = traced_call(struct_logger(true));
+			let TransactionTrace::StructLogger {
+				gas,
+				failed,
+				return_value,
+				struct_logs,
+			} = trace
+			else {
+				panic!("expected struct logs, got {:?}", trace);
+			};
+
+			assert_eq!(U256::from(gas), info.used_gas.effective);
+			assert!(!failed);
+			assert!(return_value.is_empty());
+
+			let steps = |depth| {
+				struct_logs
+					.iter()
+					.filter(|log| log.depth == depth)
+					.map(|log| (log.pc, log.op))
+					.collect::<Vec<_>>()
+			};
+			assert_eq!(
+				steps(1),
+				vec![
+					(0, 0x60),
+					(2, 0x60),
+					(4, 0x60),
+					(6, 0x60),
+					(8, 0x60),
+					(10, 0x73),
+					(31, 0x5a),
+					(32, 0xf1),
+					(33, 0x00),
+				]
+			);
+			assert_eq!(steps(2), vec![(0, 0x60), (2, 0x60), (4, 0xfd)]);
+
+			assert!(struct_logs.iter().all(|log| log.stack.is_none()));
+			assert!(struct_logs.iter().all(|log| log.memory.is_none()));
+			assert!(struct_logs.iter().all(|log| log.storage.is_none()));
+		});
+	}
+
+	#[test]
+	fn struct_logger_records_stack_when_enabled() {
+		new_test_ext().execute_with(|| {
+			let (_, trace) = traced_call(struct_logger(false));
+			let TransactionTrace::StructLogger { struct_logs, .. } = trace else {
+				panic!("expected struct logs, got {:?}", trace);
+			};
+
+			let revert = struct_logs
+				.iter()
+				.find(|log| log.op == 0xfd)
+				.expect("callee reverts");
+			assert_eq!(revert.depth, 2);
+			assert_eq!(revert.stack, Some(vec![H256::zero(), H256::zero()]));
+			assert_eq!(struct_logs[0].stack, Some(Vec::new()));
+		});
+	}
+}
@@ -0,0 +1,239 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage proofs of EVM accounts, as served by `eth_getProof`.
+//!
+//! The state of a Substrate chain is a single base-16 Patricia trie hashed with Blake2-256, not
+//! Ethereum's Merkle Patricia tries with one storage trie per account. EIP-1186 fields are thus
+//! filled as follows:
+//!
+//! - `storageHash` is the state root of the Substrate block every proof is checked against.
+//! - `accountProof` holds the trie nodes proving the `System.Account` entry of the account the
+//!   address maps to (its key is returned as `accountKey`) and the `EVM.AccountCodes` entry of
+//!   the address.
+//! - `storageProof[i].proof` holds the trie nodes proving the `EVM.AccountStorages` entry of the
+//!   requested slot.
+//!
+//! Proofs are unordered sets of SCALE encoded trie nodes. `balance` and `nonce` are the free
+//! balance and nonce stored in `System.Account`, which is what [`verify_account`] recovers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ethereum_types::{H160, H256, U256};
+use scale_codec::Decode;
+// Substrate
+use sp_core::hashing::{blake2_128, keccak_256, twox_128};
+use sp_runtime::traits::BlakeTwo256;
+use sp_trie::{LayoutV1, StorageProof};
+// Frontier
+use fp_storage::constants::{EVM_ACCOUNT_CODES, EVM_ACCOUNT_STORAGES, PALLET_EVM};
+
+/// Why a proof was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProofError {
+	/// The proof doesn't contain the nodes needed to read the key under the state root.
+	InvalidProof,
+	/// The proven value can't be decoded.
+	InvalidValue,
+}
+
+/// An account, as recovered from an account proof.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VerifiedAccount {
+	pub nonce: U256,
+	/// Free balance of the account.
+	pub balance: U256,
+	/// Keccak-256 of the code, the hash of empty code for accounts without code.
+	pub code_hash: H256,
+}
+
+fn blake2_128_concat(bytes: &[u8]) -> Vec<u8> {
+	let mut key = blake2_128(bytes).to_vec();
+	key.extend_from_slice(bytes);
+	key
+}
+
+/// Storage key of the `System.Account` entry of `account_id`.
+pub fn system_account_key(account_id: &[u8]) -> Vec<u8> {
+	let mut key = [twox_128(b"System"), twox_128(b"Account")].concat();
+	key.extend(blake2_128_concat(account_id));
+	key
+}
+
+/// Storage key of the `EVM.AccountCodes` entry of `address`.
+pub fn account_code_key(address: H160) -> Vec<u8> {
+	let mut key = [twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_CODES)].concat();
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key
+}
+
+/// Storage key of the `EVM.AccountStorages` entry of slot `index` of `address`.
+pub fn account_storage_key(address: H160, index: U256) -> Vec<u8> {
+	let mut key = [twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_STORAGES)].concat();
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key.extend(blake2_128_concat(&index.to_big_endian()));
+	key
+}
+
+/// Reads the value of `key` out of `proof`, `None` if the proof shows the key is absent.
+pub fn read_value(
+	state_root: H256,
+	proof: &[Vec<u8>],
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let db = StorageProof::new(proof.iter().cloned()).into_memory_db::<BlakeTwo256>();
+	sp_trie::read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, &state_root, key, None, None)
+		.map_err(|_| ProofError::InvalidProof)
+}
+
+/// Checks an `accountProof` of `address` against `state_root`.
+///
+/// `account_key` is the `System.Account` key of the account the address maps to. The mapping
+/// is chosen by the runtime, so callers that don't trust the key they were given must derive it
+/// themselves with [`system_account_key`].
+pub fn verify_account(
+	state_root: H256,
+	address: H160,
+	account_key: &[u8],
+	proof: &[Vec<u8>],
+) -> Result<VerifiedAccount, ProofError> {
+	// `AccountInfo<u32, AccountData<u128>>` starts with the nonce, three reference counters and
+	// the free balance.
+	let (nonce, balance) = match read_value(state_root, proof, account_key)? {
+		Some(value) => {
+			let (nonce, _, _, _, free) = <(u32, u32, u32, u32, u128)>::decode(&mut &value[..])
+				.map_err(|_| ProofError::InvalidValue)?;
+			(nonce, free)
+		}
+		None => (0, 0),
+	};
+	let code = match read_value(state_root, proof, &account_code_key(address))? {
+		Some(value) => Vec::<u8>::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue)?,
+		None => Vec::new(),
+	};
+
+	Ok(VerifiedAccount {
+		nonce: nonce.into(),
+		balance: balance.into(),
+		code_hash: H256(keccak_256(&code)),
+	})
+}
+
+/// Checks a `storageProof` entry of slot `index` of `address` against `state_root`, returning
+/// the proven value.
+pub fn verify_storage(
+	state_root: H256,
+	address: H160,
+	index: U256,
+	proof: &[Vec<u8>],
+) -> Result<H256, ProofError> {
+	match read_value(state_root, proof, &account_storage_key(address, index))? {
+		Some(value) => H256::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue),
+		None => Ok(H256::zero()),
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Storage keys needed to build `eth_getProof` responses.
+	pub trait EvmProofApi {
+		/// Key of the `System.Account` entry of the account `address` maps to.
+		fn system_account_key(address: H160) -> Vec<u8>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_codec::Encode;
+	use sp_state_machine::{prove_read, Backend, StateVersion, TrieBackend};
+	use sp_trie::PrefixedMemoryDB;
+	use std::collections::BTreeMap;
+
+	const ADDRESS: H160 = H160::repeat_byte(0x11);
+	const ACCOUNT_ID: [u8; 32] = [0x22; 32];
+
+	fn state() -> TrieBackend<PrefixedMemoryDB<BlakeTwo256>, BlakeTwo256> {
+		let mut storage = BTreeMap::new();
+		storage.insert(
+			system_account_key(&ACCOUNT_ID),
+			(7u32, 0u32, 1u32, 0u32, 1_000u128, 0u128, 0u128, 0u128).encode(),
+		);
+		storage.insert(account_code_key(ADDRESS), vec![0x60u8, 0x00].encode());
+		storage.insert(
+			account_storage_key(ADDRESS, U256::one()),
+			H256::repeat_byte(0x33).encode(),
+		);
+		(storage, StateVersion::V1).into()
+	}
+
+	fn proof_of(keys: &[Vec<u8>]) -> (H256, Vec<Vec<u8>>) {
+		let state = state();
+		let root = state.storage_root(std::iter::empty(), StateVersion::V1).0;
+		let proof = prove_read(state, keys).expect("keys can be proven");
+		(root, proof.into_iter_nodes().collect())
+	}
+
+	#[test]
+	fn verify_account_works() {
+		let account_key = system_account_key(&ACCOUNT_ID);
+		let (root, proof) = proof_of(&[account_key.clone(), account_code_key(ADDRESS)]);
+
+		assert_eq!(
+			verify_account(root, ADDRESS, &account_key, &proof),
+			Ok(VerifiedAccount {
+				nonce: 7.into(),
+				balance: 1_000.into(),
+				code_hash: H256(keccak_256(&[0x60, 0x00])),
+			})
+		);
+	}
+
+	#[test]
+	fn verify_storage_works() {
+		let (root, proof) = proof_of(&[
+			account_storage_key(ADDRESS, U256::one()),
+			account_storage_key(ADDRESS, 2.into()),
+		]);
+
+		assert_eq!(
+			verify_storage(root, ADDRESS, U256::one(), &proof),
+			Ok(H256::repeat_byte(0x33))
+		);
+		assert_eq!(
+			verify_storage(root, ADDRESS, 2.into(), &proof),
+			Ok(H256::zero())
+		);
+	}
+
+	#[test]
+	fn proofs_are_bound_to_the_state_root() {
+		let (root, proof) = proof_of(&[account_storage_key(ADDRESS, U256::one())]);
+
+		assert_eq!(
+			verify_storage(H256::repeat_byte(0x44), ADDRESS, U256::one(), &proof),
+			Err(ProofError::InvalidProof)
+		);
+		assert_eq!(
+			verify_storage(root, ADDRESS, U256::one(), &proof[..proof.len() - 1]),
+			Err(ProofError::InvalidProof)
+		);
+	}
+}
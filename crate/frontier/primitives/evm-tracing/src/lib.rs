@@ -0,0 +1,216 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types shared between the runtime and the client for EVM execution tracing.
+//!
+//! Traces are built inside the runtime while replaying a block, by listening to the events of
+//! the EVM executor, and handed over to the client through [`EvmTracingApi`]. Runtimes only
+//! produce them when `pallet-evm` is built with its `evm-tracing` feature, which is meant for
+//! the runtime overrides of tracing nodes rather than for the on-chain runtime.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum_types::{H160, H256, U256};
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+// Substrate
+use sp_runtime::{traits::Block as BlockT, DispatchError, RuntimeDebug};
+
+/// Kind of trace to build.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub enum TracerKind {
+	/// Tree of the calls made by the transaction, like geth's `callTracer`.
+	CallTracer,
+	/// Step by step execution logs, like geth's default struct logger.
+	StructLogger,
+}
+
+/// What to trace and how much of the machine state to record.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct TracerConfig {
+	pub kind: TracerKind,
+	/// Skip the stack of each step (struct logger only).
+	pub disable_stack: bool,
+	/// Skip the memory of each step (struct logger only).
+	pub disable_memory: bool,
+	/// Skip the storage of each step (struct logger only).
+	pub disable_storage: bool,
+}
+
+impl TracerConfig {
+	pub fn call_tracer() -> Self {
+		Self {
+			kind: TracerKind::CallTracer,
+			disable_stack: true,
+			disable_memory: true,
+			disable_storage: true,
+		}
+	}
+}
+
+/// How a call frame was entered.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub enum CallType {
+	Call,
+	CallCode,
+	DelegateCall,
+	StaticCall,
+	Create,
+	Create2,
+	SelfDestruct,
+}
+
+/// A call frame and the frames it entered.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct CallTrace {
+	pub call_type: CallType,
+	pub from: H160,
+	/// Callee, created contract or self destruct beneficiary.
+	pub to: H160,
+	pub value: U256,
+	pub gas: u64,
+	pub gas_used: u64,
+	/// Call data or init code.
+	pub input: Vec<u8>,
+	/// Return data, or the deployed code of a successful create.
+	pub output: Vec<u8>,
+	/// Why the frame failed, `None` on success.
+	pub error: Option<Vec<u8>>,
+	/// Whether the frame failed by reverting, output then holds the revert data.
+	pub reverted: bool,
+	pub calls: Vec<CallTrace>,
+}
+
+/// State of the machine before executing an opcode.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct StructLog {
+	pub pc: u64,
+	pub op: u8,
+	/// Remaining gas before the opcode.
+	pub gas: u64,
+	pub gas_cost: u64,
+	/// Call depth, starting at 1.
+	pub depth: u32,
+	pub stack: Option<Vec<H256>>,
+	pub memory: Option<Vec<u8>>,
+	/// Storage of the executing contract accessed so far, set on `SLOAD` and `SSTORE`.
+	pub storage: Option<Vec<(H256, H256)>>,
+}
+
+/// Trace of a single Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub enum TransactionTrace {
+	CallTracer(CallTrace),
+	StructLogger {
+		gas: u64,
+		failed: bool,
+		return_value: Vec<u8>,
+		struct_logs: Vec<StructLog>,
+	},
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for replaying Ethereum transactions with EVM tracing enabled.
+	///
+	/// Must be called on top of the parent state of the traced block, after the block was
+	/// initialized with its own header.
+	pub trait EvmTracingApi {
+		/// Apply `extrinsics` in order until `transaction`, and trace it.
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &EthereumTransaction,
+			config: TracerConfig,
+		) -> Result<TransactionTrace, DispatchError>;
+
+		/// Apply all `extrinsics`, tracing the Ethereum transactions whose hashes are in
+		/// `known_transactions`. Traces are returned in execution order.
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			known_transactions: Vec<H256>,
+			config: TracerConfig,
+		) -> Result<Vec<TransactionTrace>, DispatchError>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn call_tracer_records_no_machine_state() {
+		let config = TracerConfig::call_tracer();
+		assert_eq!(config.kind, TracerKind::CallTracer);
+		assert!(config.disable_stack);
+		assert!(config.disable_memory);
+		assert!(config.disable_storage);
+	}
+
+	#[test]
+	fn traces_round_trip_through_scale() {
+		let subcall = CallTrace {
+			call_type: CallType::StaticCall,
+			from: H160::repeat_byte(2),
+			to: H160::repeat_byte(3),
+			value: U256::zero(),
+			gas: 50_000,
+			gas_used: 10_000,
+			input: vec![4, 5],
+			output: vec![6],
+			error: Some(b"execution reverted".to_vec()),
+			reverted: true,
+			calls: Vec::new(),
+		};
+		let call_trace = TransactionTrace::CallTracer(CallTrace {
+			call_type: CallType::Call,
+			from: H160::repeat_byte(1),
+			to: H160::repeat_byte(2),
+			value: U256::from(7),
+			gas: 100_000,
+			gas_used: 21_000,
+			input: vec![1],
+			output: Vec::new(),
+			error: None,
+			reverted: false,
+			calls: vec![subcall],
+		});
+		let struct_logger = TransactionTrace::StructLogger {
+			gas: 21_000,
+			failed: true,
+			return_value: vec![0xab],
+			struct_logs: vec![StructLog {
+				pc: 1,
+				op: 0x54,
+				gas: 30_000,
+				gas_cost: 2_100,
+				depth: 1,
+				stack: Some(vec![H256::repeat_byte(1)]),
+				memory: None,
+				storage: Some(vec![(H256::repeat_byte(1), H256::repeat_byte(2))]),
+			}],
+		};
+
+		for trace in [call_trace, struct_logger] {
+			let encoded = trace.encode();
+			assert_eq!(TransactionTrace::decode(&mut &encoded[..]).unwrap(), trace);
+		}
+	}
+}
@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{ed25519::Pair, Pair as _};
+
+use crate::{
+    justification::{Justification, SignatureSet},
+    AuthorityId,
+};
+
+/// The authorities of a single session. Encodes the same way as the `SessionAuthorityData`
+/// returned by `AlephSessionApi`, so the result of the runtime call can be decoded directly.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct AuthoritySet {
+    authorities: Vec<AuthorityId>,
+    emergency_finalizer: Option<AuthorityId>,
+}
+
+/// Ways in which a justification can be wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    BadMultisignature,
+    BadEmergencySignature,
+    NoEmergencySigner,
+    NoBlsAuthorities,
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use VerificationError::*;
+        match self {
+            BadMultisignature => write!(f, "bad multisignature"),
+            BadEmergencySignature => write!(f, "bad emergency signature"),
+            NoEmergencySigner => write!(f, "no emergency signer defined"),
+            NoBlsAuthorities => write!(f, "no bls keys of authorities known"),
+        }
+    }
+}
+
+impl AuthoritySet {
+    pub fn new(authorities: Vec<AuthorityId>, emergency_finalizer: Option<AuthorityId>) -> Self {
+        AuthoritySet {
+            authorities,
+            emergency_finalizer,
+        }
+    }
+
+    pub fn authorities(&self) -> &[AuthorityId] {
+        &self.authorities
+    }
+
+    pub fn emergency_finalizer(&self) -> Option<&AuthorityId> {
+        self.emergency_finalizer.as_ref()
+    }
+
+    fn threshold(&self) -> usize {
+        2 * self.authorities.len() / 3 + 1
+    }
+
+    fn is_complete_multisignature(&self, msg: &[u8], signatures: &SignatureSet) -> bool {
+        if signatures.signatures().count() < self.threshold() {
+            return false;
+        }
+        signatures
+            .signatures()
+            .all(|(index, signature)| match self.authorities.get(index) {
+                Some(authority) => Pair::verify(signature, msg, authority),
+                None => false,
+            })
+    }
+
+    /// Verifies that the justification finalizes the message, which for blocks is the encoded
+    /// block hash.
+    pub fn verify(
+        &self,
+        justification: &Justification,
+        msg: &[u8],
+    ) -> Result<(), VerificationError> {
+        use Justification::*;
+        use VerificationError::*;
+        match justification {
+            CommitteeMultisignature(signatures) => {
+                match self.is_complete_multisignature(msg, signatures) {
+                    true => Ok(()),
+                    false => Err(BadMultisignature),
+                }
+            }
+            EmergencySignature(signature) => {
+                let signer = self.emergency_finalizer.as_ref().ok_or(NoEmergencySigner)?;
+                match Pair::verify(signature, msg, signer) {
+                    true => Ok(()),
+                    false => Err(BadEmergencySignature),
+                }
+            }
+            // The BLS keys of the authorities are not kept on chain yet.
+            CommitteeAggregateSignature(_) => Err(NoBlsAuthorities),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::{ed25519::Pair, Pair as _};
+
+    use super::{AuthoritySet, VerificationError};
+    use crate::justification::{AggregateSignature, Justification, SignatureSet};
+
+    const MESSAGE: &[u8] = b"finalize me";
+
+    fn authorities(count: usize) -> (Vec<Pair>, AuthoritySet) {
+        let pairs: Vec<_> = (0..count).map(|_| Pair::generate().0).collect();
+        let set = AuthoritySet::new(pairs.iter().map(|pair| pair.public()).collect(), None);
+        (pairs, set)
+    }
+
+    fn multisignature(pairs: &[Pair], signers: &[usize], msg: &[u8]) -> Justification {
+        Justification::CommitteeMultisignature(SignatureSet(
+            (0..pairs.len())
+                .map(|index| signers.contains(&index).then(|| pairs[index].sign(msg)))
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn accepts_complete_multisignature() {
+        let (pairs, set) = authorities(7);
+        let justification = multisignature(&pairs, &[0, 1, 3, 4, 6], MESSAGE);
+        assert_eq!(set.verify(&justification, MESSAGE), Ok(()));
+    }
+
+    #[test]
+    fn rejects_incomplete_multisignature() {
+        let (pairs, set) = authorities(7);
+        let justification = multisignature(&pairs, &[0, 1, 3, 4], MESSAGE);
+        assert_eq!(
+            set.verify(&justification, MESSAGE),
+            Err(VerificationError::BadMultisignature)
+        );
+    }
+
+    #[test]
+    fn rejects_multisignature_of_other_message() {
+        let (pairs, set) = authorities(4);
+        let justification = multisignature(&pairs, &[0, 1, 2], b"other");
+        assert_eq!(
+            set.verify(&justification, MESSAGE),
+            Err(VerificationError::BadMultisignature)
+        );
+    }
+
+    #[test]
+    fn verifies_emergency_signature() {
+        let (_, set) = authorities(4);
+        let finalizer = Pair::generate().0;
+        let justification = Justification::EmergencySignature(finalizer.sign(MESSAGE));
+        assert_eq!(
+            set.verify(&justification, MESSAGE),
+            Err(VerificationError::NoEmergencySigner)
+        );
+        let set = AuthoritySet::new(set.authorities().to_vec(), Some(finalizer.public()));
+        assert_eq!(set.verify(&justification, MESSAGE), Ok(()));
+        assert_eq!(
+            set.verify(&justification, b"other"),
+            Err(VerificationError::BadEmergencySignature)
+        );
+    }
+
+    #[test]
+    fn rejects_aggregate_signature() {
+        let (_, set) = authorities(4);
+        let justification = Justification::CommitteeAggregateSignature(AggregateSignature::new(
+            4,
+            vec![0b1101],
+            [0; 48],
+        ));
+        assert_eq!(
+            set.verify(&justification, MESSAGE),
+            Err(VerificationError::NoBlsAuthorities)
+        );
+    }
+}
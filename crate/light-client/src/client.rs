@@ -0,0 +1,351 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::Encode;
+use sp_runtime::traits::Header;
+use sp_trie::StorageProof;
+
+use crate::{
+    authorities::{AuthoritySet, VerificationError},
+    justification::{decode_justification, DecodeError},
+    storage::{read_next_authorities, read_proof, ProofError},
+    BlockHash, BlockNumber, SessionIndex,
+};
+
+/// A block the client verified to be final.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinalizedBlock {
+    pub number: BlockNumber,
+    pub hash: BlockHash,
+    pub state_root: BlockHash,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The block belongs to a session other than the current one.
+    WrongSession(SessionIndex, SessionIndex),
+    /// The block is not above the latest finalized block.
+    NotAboveFinalized(BlockNumber, BlockNumber),
+    BadJustificationEncoding(DecodeError),
+    BadJustification(VerificationError),
+    /// The last block of a session was imported without a proof of the next authorities.
+    MissingAuthorityProof(BlockNumber),
+    BadAuthorityProof(ProofError),
+    NothingFinalized,
+    BadStorageProof(ProofError),
+    /// The session would end past the largest block number.
+    SessionOutOfRange(SessionIndex),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            WrongSession(current, session) => write!(
+                f,
+                "block from session {session}, while the current session is {current}"
+            ),
+            NotAboveFinalized(finalized, number) => write!(
+                f,
+                "block #{number} is not above the finalized block #{finalized}"
+            ),
+            BadJustificationEncoding(e) => write!(f, "malformed justification: {e}"),
+            BadJustification(e) => write!(f, "incorrect justification: {e}"),
+            MissingAuthorityProof(number) => write!(
+                f,
+                "block #{number} ends a session, but came without a proof of the next authorities"
+            ),
+            BadAuthorityProof(e) => write!(f, "incorrect proof of the next authorities: {e}"),
+            NothingFinalized => write!(f, "no block was finalized yet"),
+            BadStorageProof(e) => write!(f, "incorrect storage proof: {e}"),
+            SessionOutOfRange(session) => {
+                write!(f, "session {session} ends past the largest block number")
+            }
+        }
+    }
+}
+
+/// Follows finality of the chain, keeping only the authorities of the current session and the
+/// latest finalized block.
+///
+/// The client has to see the last block of every session, as it carries the proof of the
+/// authorities of the next one. Blocks within a session can be skipped.
+#[derive(Clone, Debug)]
+pub struct LightClient {
+    session_period: u32,
+    session: SessionIndex,
+    authorities: AuthoritySet,
+    finalized: Option<FinalizedBlock>,
+}
+
+impl LightClient {
+    /// Creates a client trusting the authorities of the given session, as returned by
+    /// `AlephSessionApi::authority_data` or read from genesis.
+    ///
+    /// Panics if the session period is zero.
+    pub fn new(session_period: u32, session: SessionIndex, authorities: AuthoritySet) -> Self {
+        assert!(session_period > 0, "session period has to be positive");
+        LightClient {
+            session_period,
+            session,
+            authorities,
+            finalized: None,
+        }
+    }
+
+    pub fn session(&self) -> SessionIndex {
+        self.session
+    }
+
+    pub fn authorities(&self) -> &AuthoritySet {
+        &self.authorities
+    }
+
+    pub fn finalized(&self) -> Option<&FinalizedBlock> {
+        self.finalized.as_ref()
+    }
+
+    fn session_of(&self, number: BlockNumber) -> SessionIndex {
+        number / self.session_period
+    }
+
+    fn last_block_of_session(&self, session: SessionIndex) -> Result<BlockNumber, Error> {
+        session
+            .checked_add(1)
+            .and_then(|next| next.checked_mul(self.session_period))
+            .map(|first_of_next| first_of_next - 1)
+            .ok_or(Error::SessionOutOfRange(session))
+    }
+
+    fn verify_justification<H>(&self, header: &H, justification: &[u8]) -> Result<(), Error>
+    where
+        H: Header<Hash = BlockHash, Number = BlockNumber>,
+    {
+        let session = self.session_of(*header.number());
+        if session != self.session {
+            return Err(Error::WrongSession(self.session, session));
+        }
+        let justification =
+            decode_justification(justification).map_err(Error::BadJustificationEncoding)?;
+        self.authorities
+            .verify(&justification, &header.hash().encode())
+            .map_err(Error::BadJustification)
+    }
+
+    /// Checks whether the justification proves the block to be final. Only blocks of the current
+    /// session can be checked, the state of the client is not changed.
+    pub fn is_final<H>(&self, header: &H, justification: &[u8]) -> Result<(), Error>
+    where
+        H: Header<Hash = BlockHash, Number = BlockNumber>,
+    {
+        self.verify_justification(header, justification)
+    }
+
+    /// Imports a finalized block of the current session, making it the latest finalized block.
+    /// The last block of a session has to come with a proof of the authorities of the next session
+    /// against its state root, after which the client moves on to the next session.
+    pub fn import<H>(
+        &mut self,
+        header: &H,
+        justification: &[u8],
+        authority_proof: Option<StorageProof>,
+    ) -> Result<(), Error>
+    where
+        H: Header<Hash = BlockHash, Number = BlockNumber>,
+    {
+        let number = *header.number();
+        if let Some(finalized) = &self.finalized {
+            if number <= finalized.number {
+                return Err(Error::NotAboveFinalized(finalized.number, number));
+            }
+        }
+        self.verify_justification(header, justification)?;
+        if number == self.last_block_of_session(self.session)? {
+            let proof = authority_proof.ok_or(Error::MissingAuthorityProof(number))?;
+            self.authorities = read_next_authorities(header.state_root(), proof)
+                .map_err(Error::BadAuthorityProof)?;
+            self.session += 1;
+        }
+        self.finalized = Some(FinalizedBlock {
+            number,
+            hash: header.hash(),
+            state_root: *header.state_root(),
+        });
+        Ok(())
+    }
+
+    /// Reads the values under the keys from a proof against the state of the latest finalized
+    /// block.
+    pub fn verify_storage_proof<'a, I: IntoIterator<Item = &'a [u8]>>(
+        &self,
+        proof: StorageProof,
+        keys: I,
+    ) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        let finalized = self.finalized.as_ref().ok_or(Error::NothingFinalized)?;
+        read_proof(&finalized.state_root, proof, keys).map_err(Error::BadStorageProof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::Encode;
+    use sp_core::{ed25519::Pair, Pair as _};
+    use sp_runtime::{
+        generic,
+        traits::{BlakeTwo256, Header as _},
+    };
+    use sp_state_machine::{prove_read, InMemoryBackend};
+
+    use super::{Error, LightClient};
+    use crate::{
+        authorities::{AuthoritySet, VerificationError},
+        justification::{Justification, SignatureSet},
+        storage::{aleph_storage_key, next_authority_data_keys},
+        BlockHash, StorageProof,
+    };
+
+    type Header = generic::Header<u32, BlakeTwo256>;
+
+    const SESSION_PERIOD: u32 = 10;
+
+    struct Committee {
+        pairs: Vec<Pair>,
+    }
+
+    impl Committee {
+        fn new(count: usize) -> Self {
+            Committee {
+                pairs: (0..count).map(|_| Pair::generate().0).collect(),
+            }
+        }
+
+        fn authority_set(&self) -> AuthoritySet {
+            AuthoritySet::new(self.pairs.iter().map(|pair| pair.public()).collect(), None)
+        }
+
+        fn justify(&self, header: &Header) -> Vec<u8> {
+            let msg = header.hash().encode();
+            let justification = Justification::CommitteeMultisignature(SignatureSet(
+                self.pairs
+                    .iter()
+                    .map(|pair| Some(pair.sign(&msg)))
+                    .collect(),
+            ));
+            let payload = justification.encode();
+            let mut result = 3u16.encode();
+            result.extend((payload.len() as u16).encode());
+            result.extend(payload);
+            result
+        }
+    }
+
+    fn state(entries: Vec<(Vec<u8>, Vec<u8>)>) -> InMemoryBackend<BlakeTwo256> {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .collect();
+        InMemoryBackend::<BlakeTwo256>::from((vec![(None, entries)], Default::default()))
+    }
+
+    fn header(number: u32, state_root: BlockHash) -> Header {
+        Header::new(
+            number,
+            Default::default(),
+            state_root,
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    fn session_end(next: &Committee) -> (Header, StorageProof) {
+        let authorities: Vec<_> = next.pairs.iter().map(|pair| pair.public()).collect();
+        let [authorities_key, _, _] = next_authority_data_keys();
+        let backend = state(vec![(authorities_key, authorities.encode())]);
+        let header = header(SESSION_PERIOD - 1, *backend.root());
+        let proof = prove_read(backend, next_authority_data_keys()).expect("keys are provable");
+        (header, proof)
+    }
+
+    #[test]
+    fn verifies_finality_within_session() {
+        let committee = Committee::new(4);
+        let client = LightClient::new(SESSION_PERIOD, 0, committee.authority_set());
+        let block = header(3, Default::default());
+        assert_eq!(client.is_final(&block, &committee.justify(&block)), Ok(()));
+        let impostors = Committee::new(4);
+        assert_eq!(
+            client.is_final(&block, &impostors.justify(&block)),
+            Err(Error::BadJustification(
+                VerificationError::BadMultisignature
+            ))
+        );
+        let later = header(SESSION_PERIOD, Default::default());
+        assert_eq!(
+            client.is_final(&later, &committee.justify(&later)),
+            Err(Error::WrongSession(0, 1))
+        );
+    }
+
+    #[test]
+    fn follows_authorities_across_sessions() {
+        let committee = Committee::new(4);
+        let next = Committee::new(7);
+        let mut client = LightClient::new(SESSION_PERIOD, 0, committee.authority_set());
+        let (end, proof) = session_end(&next);
+        assert_eq!(
+            client.import(&end, &committee.justify(&end), None),
+            Err(Error::MissingAuthorityProof(SESSION_PERIOD - 1))
+        );
+        assert_eq!(
+            client.import(&end, &committee.justify(&end), Some(proof)),
+            Ok(())
+        );
+        assert_eq!(client.session(), 1);
+        assert_eq!(client.authorities(), &next.authority_set());
+
+        let block = header(SESSION_PERIOD + 2, Default::default());
+        assert!(client.is_final(&block, &committee.justify(&block)).is_err());
+        assert_eq!(client.import(&block, &next.justify(&block), None), Ok(()));
+        assert_eq!(
+            client.import(&end, &committee.justify(&end), None),
+            Err(Error::NotAboveFinalized(
+                SESSION_PERIOD + 2,
+                SESSION_PERIOD - 1
+            ))
+        );
+    }
+
+    #[test]
+    fn verifies_storage_against_finalized_state() {
+        let committee = Committee::new(4);
+        let mut client = LightClient::new(SESSION_PERIOD, 0, committee.authority_set());
+        let key = aleph_storage_key("Value");
+        let backend = state(vec![(key.clone(), vec![21, 37])]);
+        let block = header(5, *backend.root());
+        let proof = prove_read(backend, [key.clone()]).expect("key is provable");
+        assert_eq!(
+            client.verify_storage_proof(proof.clone(), [key.as_slice()]),
+            Err(Error::NothingFinalized)
+        );
+        client
+            .import(&block, &committee.justify(&block), None)
+            .expect("block is finalized");
+        assert_eq!(
+            client.verify_storage_proof(proof, [key.as_slice()]),
+            Ok(vec![Some(vec![21, 37])])
+        );
+    }
+
+    #[test]
+    fn rejects_blocks_of_session_ending_past_largest_number() {
+        let committee = Committee::new(4);
+        let last_session = u32::MAX / SESSION_PERIOD;
+        let mut client = LightClient::new(SESSION_PERIOD, last_session, committee.authority_set());
+        let block = header(last_session * SESSION_PERIOD, Default::default());
+        assert_eq!(
+            client.import(&block, &committee.justify(&block), None),
+            Err(Error::SessionOutOfRange(last_session))
+        );
+        assert_eq!(client.finalized(), None);
+    }
+}
@@ -0,0 +1,284 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::{Decode, DecodeAll, Encode, Input};
+
+use crate::AuthoritySignature;
+
+/// Length of a serialized aggregated BLS signature.
+const AGGREGATE_SIGNATURE_LENGTH: usize = 48;
+
+/// Signatures of the committee, indexed by the position of the authority in the committee.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct SignatureSet(pub Vec<Option<AuthoritySignature>>);
+
+impl SignatureSet {
+    /// Signatures together with the indices of their signers.
+    pub fn signatures(&self) -> impl Iterator<Item = (usize, &AuthoritySignature)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, signature)| signature.as_ref().map(|signature| (index, signature)))
+    }
+}
+
+/// A single BLS signature of all the authorities marked in the bitmap.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct AggregateSignature {
+    #[codec(compact)]
+    size: u32,
+    bits: Vec<u8>,
+    signature: [u8; AGGREGATE_SIGNATURE_LENGTH],
+}
+
+impl AggregateSignature {
+    #[cfg(test)]
+    pub(crate) fn new(
+        size: u32,
+        bits: Vec<u8>,
+        signature: [u8; AGGREGATE_SIGNATURE_LENGTH],
+    ) -> Self {
+        AggregateSignature {
+            size,
+            bits,
+            signature,
+        }
+    }
+
+    /// Number of authorities the bitmap was created for.
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        index < self.size() && self.bits[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Indices of the authorities that signed, in increasing order.
+    pub fn signers(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.size()).filter(|index| self.contains(*index))
+    }
+
+    /// Decoded bitmaps might have the wrong number of bytes or bits set past the size.
+    pub(crate) fn is_well_formed(&self) -> bool {
+        if self.bits.len() != self.size().div_ceil(8) {
+            return false;
+        }
+        match self.size() % 8 {
+            0 => true,
+            used => self.bits.last().copied().unwrap_or(0) >> used == 0,
+        }
+    }
+
+    pub(crate) fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+/// A proof of block finality, in any of the formats produced by the nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Justification {
+    CommitteeMultisignature(SignatureSet),
+    EmergencySignature(AuthoritySignature),
+    CommitteeAggregateSignature(AggregateSignature),
+}
+
+/// Signature format of the oldest justifications, which also contained the signer index.
+#[derive(Clone, Debug, Encode, Decode)]
+struct SignatureV1 {
+    _id: u64,
+    signature: AuthoritySignature,
+}
+
+/// Oldest justification format.
+#[derive(Clone, Debug, Encode, Decode)]
+struct JustificationV1(Vec<Option<SignatureV1>>);
+
+impl From<JustificationV1> for Justification {
+    fn from(justification: JustificationV1) -> Self {
+        Justification::CommitteeMultisignature(SignatureSet(
+            justification
+                .0
+                .into_iter()
+                .map(|signature| signature.map(|signature| signature.signature))
+                .collect(),
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    BadFormat,
+    UnknownVersion(u16),
+    AggregateNotProduced,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use DecodeError::*;
+        match self {
+            BadFormat => write!(f, "malformed encoding"),
+            UnknownVersion(version) => {
+                write!(f, "justification encoded with unknown version {version}")
+            }
+            AggregateNotProduced => write!(f, "aggregated justifications are not produced yet"),
+        }
+    }
+}
+
+fn decode_pre_compatibility(raw: &[u8]) -> Result<Justification, DecodeError> {
+    match SignatureSet::decode_all(&mut &raw[..]) {
+        Ok(signatures) => Ok(Justification::CommitteeMultisignature(signatures)),
+        Err(_) => JustificationV1::decode_all(&mut &raw[..])
+            .map(Into::into)
+            .map_err(|_| DecodeError::BadFormat),
+    }
+}
+
+fn decode_versioned(raw: &[u8]) -> Result<Result<Justification, u16>, parity_scale_codec::Error> {
+    let input = &mut &raw[..];
+    let version = u16::decode(input)?;
+    // The byte count is only needed to skip justifications of unknown versions.
+    let _byte_count = u16::decode(input)?;
+    let justification = match version {
+        1 => JustificationV1::decode(input)?.into(),
+        2 => Justification::CommitteeMultisignature(SignatureSet::decode(input)?),
+        3 => Justification::decode(input)?,
+        4 => Justification::CommitteeAggregateSignature(AggregateSignature::decode(input)?),
+        version => return Ok(Err(version)),
+    };
+    match input.remaining_len()? {
+        Some(0) => Ok(Ok(justification)),
+        _ => Err("trailing bytes after justification".into()),
+    }
+}
+
+/// Decodes a justification in any of the formats the nodes ever stored, including the ones from
+/// before versioning was introduced.
+pub fn decode_justification(raw: &[u8]) -> Result<Justification, DecodeError> {
+    match decode_versioned(raw) {
+        // No node aggregates signatures yet, so an aggregate cannot be an honest one.
+        Ok(Ok(Justification::CommitteeAggregateSignature(_))) => {
+            Err(DecodeError::AggregateNotProduced)
+        }
+        Ok(Ok(justification)) => Ok(justification),
+        // Unversioned justifications are sometimes mistaken for ones from the future.
+        Ok(Err(version)) => {
+            decode_pre_compatibility(raw).map_err(|_| DecodeError::UnknownVersion(version))
+        }
+        Err(_) => decode_pre_compatibility(raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::Encode;
+    use sp_core::{ed25519::Pair, Pair as _};
+
+    use super::{
+        decode_justification, AggregateSignature, DecodeError, Justification, JustificationV1,
+        SignatureSet, SignatureV1,
+    };
+
+    fn versioned(version: u16, payload: Vec<u8>) -> Vec<u8> {
+        let mut result = version.encode();
+        result.extend((payload.len() as u16).encode());
+        result.extend(payload);
+        result
+    }
+
+    fn signature_set() -> SignatureSet {
+        SignatureSet(
+            (0..4)
+                .map(|i| match i {
+                    2 => None,
+                    _ => Some(Pair::generate().0.sign(b"block")),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn decodes_v1() {
+        let signatures = signature_set();
+        let v1 = JustificationV1(
+            signatures
+                .0
+                .iter()
+                .enumerate()
+                .map(|(id, signature)| {
+                    signature.map(|signature| SignatureV1 {
+                        _id: id as u64,
+                        signature,
+                    })
+                })
+                .collect(),
+        );
+        let expected = Justification::CommitteeMultisignature(signatures);
+        assert_eq!(decode_justification(&v1.encode()), Ok(expected.clone()));
+        assert_eq!(
+            decode_justification(&versioned(1, v1.encode())),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn decodes_v2_and_v3() {
+        let signatures = signature_set();
+        let expected = Justification::CommitteeMultisignature(signatures.clone());
+        assert_eq!(
+            decode_justification(&versioned(2, signatures.encode())),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            decode_justification(&versioned(3, expected.encode())),
+            Ok(expected)
+        );
+        let emergency = Justification::EmergencySignature(Pair::generate().0.sign(b"block"));
+        assert_eq!(
+            decode_justification(&versioned(3, emergency.encode())),
+            Ok(emergency)
+        );
+    }
+
+    #[test]
+    fn rejects_v4_until_produced() {
+        let aggregate = AggregateSignature {
+            size: 10,
+            bits: vec![0b1011_0111, 0b01],
+            signature: [7; 48],
+        };
+        assert!(aggregate.is_well_formed());
+        assert_eq!(
+            aggregate.signers().collect::<Vec<_>>(),
+            vec![0, 1, 2, 4, 5, 7, 8]
+        );
+        assert_eq!(
+            decode_justification(&versioned(4, aggregate.encode())),
+            Err(DecodeError::AggregateNotProduced)
+        );
+        let justification = Justification::CommitteeAggregateSignature(aggregate);
+        assert_eq!(
+            decode_justification(&versioned(3, justification.encode())),
+            Err(DecodeError::AggregateNotProduced)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        assert_eq!(
+            decode_justification(&versioned(43, vec![21, 37])),
+            Err(DecodeError::UnknownVersion(43))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_bitmap() {
+        let aggregate = AggregateSignature {
+            size: 10,
+            bits: vec![0, 0b100],
+            signature: [7; 48],
+        };
+        assert!(!aggregate.is_well_formed());
+    }
+}
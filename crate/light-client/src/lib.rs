@@ -0,0 +1,36 @@
+//! Verification of Selendra finality without running a node.
+//!
+//! Starting from an authority set it trusts, a [`LightClient`] follows the chain session by
+//! session. The last block of every session has to come with a justification of the current
+//! committee and a storage proof of the committee of the next session, the same data that
+//! `finality-aleph` uses for warp sync. Afterwards the client can tell whether a block of the
+//! current session is final and check storage proofs against the latest finalized state root.
+//!
+//! Only `no_std` primitives are used, so the crate can be used in runtimes, ink! contracts and
+//! wallets alike.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod authorities;
+mod client;
+mod justification;
+mod storage;
+
+pub use authorities::{AuthoritySet, VerificationError};
+pub use client::{Error, FinalizedBlock, LightClient};
+pub use justification::{
+    decode_justification, AggregateSignature, DecodeError, Justification, SignatureSet,
+};
+pub use sp_trie::StorageProof;
+pub use storage::{
+    aleph_storage_key, next_authority_data_keys, read_next_authorities, read_proof, ProofError,
+};
+
+/// The finality key of an authority, the `aleph` part of the session keys.
+pub type AuthorityId = sp_core::ed25519::Public;
+pub type AuthoritySignature = sp_core::ed25519::Signature;
+pub type BlockNumber = u32;
+pub type BlockHash = sp_core::H256;
+pub type SessionIndex = u32;
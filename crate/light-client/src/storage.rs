@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::Decode;
+use sp_core::twox_128;
+use sp_runtime::traits::BlakeTwo256;
+use sp_trie::{read_trie_value, HashDBT, LayoutV1, StorageProof, EMPTY_PREFIX};
+
+use crate::{authorities::AuthoritySet, AuthorityId, BlockHash};
+
+const ALEPH_PALLET: &str = "Aleph";
+
+/// Key of a plain storage item of the Aleph pallet.
+pub fn aleph_storage_key(item: &str) -> Vec<u8> {
+    [twox_128(ALEPH_PALLET.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Storage keys of the authority data for the next session, in the order it is built in the
+/// runtime.
+pub fn next_authority_data_keys() -> [Vec<u8>; 2] {
+    [
+        aleph_storage_key("NextAuthorities"),
+        aleph_storage_key("QueuedEmergencyFinalizer"),
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof does not contain the root node.
+    MissingRoot,
+    /// The proof does not contain all the nodes needed to read the key.
+    IncompleteProof(Vec<u8>),
+    /// A value under the key could not be decoded.
+    BadValue(Vec<u8>),
+    /// The value under the key is required, but absent.
+    MissingValue(Vec<u8>),
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use ProofError::*;
+        match self {
+            MissingRoot => write!(f, "storage proof does not contain the state root"),
+            IncompleteProof(key) => write!(f, "storage proof is incomplete for key {key:?}"),
+            BadValue(key) => write!(f, "malformed value under key {key:?}"),
+            MissingValue(key) => write!(f, "no value under key {key:?}"),
+        }
+    }
+}
+
+/// Reads the values under the keys from a storage proof against the state root. Absent values are
+/// proven to be absent, a proof that does not allow reading some key results in an error.
+pub fn read_proof<'a, I: IntoIterator<Item = &'a [u8]>>(
+    state_root: &BlockHash,
+    proof: StorageProof,
+    keys: I,
+) -> Result<Vec<Option<Vec<u8>>>, ProofError> {
+    let db = proof.into_memory_db::<BlakeTwo256>();
+    if !db.contains(state_root, EMPTY_PREFIX) {
+        return Err(ProofError::MissingRoot);
+    }
+    keys.into_iter()
+        .map(|key| {
+            read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, state_root, key, None, None)
+                .map_err(|_| ProofError::IncompleteProof(key.to_vec()))
+        })
+        .collect()
+}
+
+fn decode_value<T: Decode>(key: &[u8], value: Option<Vec<u8>>) -> Result<Option<T>, ProofError> {
+    value
+        .map(|value| T::decode(&mut value.as_slice()))
+        .transpose()
+        .map_err(|_| ProofError::BadValue(key.to_vec()))
+}
+
+/// Reads the authorities of the next session from a proof against the state root of a block of
+/// the current session.
+pub fn read_next_authorities(
+    state_root: &BlockHash,
+    proof: StorageProof,
+) -> Result<AuthoritySet, ProofError> {
+    let [authorities_key, emergency_finalizer_key] = next_authority_data_keys();
+    let keys = [
+        authorities_key.as_slice(),
+        emergency_finalizer_key.as_slice(),
+    ];
+    let mut values = read_proof(state_root, proof, keys)?.into_iter();
+    let mut next = || values.next().flatten();
+    let authorities = decode_value::<Vec<AuthorityId>>(&authorities_key, next())?
+        .ok_or(ProofError::MissingValue(authorities_key))?;
+    let emergency_finalizer = decode_value::<AuthorityId>(&emergency_finalizer_key, next())?;
+    Ok(AuthoritySet::new(authorities, emergency_finalizer))
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::Encode;
+    use sp_core::{ed25519::Pair, Pair as _};
+    use sp_runtime::traits::BlakeTwo256;
+    use sp_state_machine::{prove_read, InMemoryBackend};
+
+    use super::{
+        aleph_storage_key, next_authority_data_keys, read_next_authorities, read_proof, ProofError,
+    };
+    use crate::{AuthorityId, BlockHash, StorageProof};
+
+    fn backend(entries: Vec<(Vec<u8>, Vec<u8>)>) -> InMemoryBackend<BlakeTwo256> {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .collect();
+        InMemoryBackend::<BlakeTwo256>::from((vec![(None, entries)], Default::default()))
+    }
+
+    fn prove(
+        backend: InMemoryBackend<BlakeTwo256>,
+        keys: Vec<Vec<u8>>,
+    ) -> (BlockHash, StorageProof) {
+        let root = *backend.root();
+        let proof = prove_read(backend, keys).expect("keys should be provable");
+        (root, proof)
+    }
+
+    #[test]
+    fn reads_present_and_absent_values() {
+        let key = aleph_storage_key("Present");
+        let absent = aleph_storage_key("Absent");
+        let (root, proof) = prove(
+            backend(vec![(key.clone(), vec![21, 37])]),
+            vec![key.clone(), absent.clone()],
+        );
+        assert_eq!(
+            read_proof(&root, proof, [key.as_slice(), absent.as_slice()]),
+            Ok(vec![Some(vec![21, 37]), None])
+        );
+    }
+
+    #[test]
+    fn rejects_proof_for_other_root() {
+        let key = aleph_storage_key("Present");
+        let (_, proof) = prove(backend(vec![(key.clone(), vec![1])]), vec![key.clone()]);
+        assert_eq!(
+            read_proof(&BlockHash::repeat_byte(7), proof, [key.as_slice()]),
+            Err(ProofError::MissingRoot)
+        );
+    }
+
+    #[test]
+    fn reads_next_authorities() {
+        let authorities: Vec<AuthorityId> = (0..4).map(|_| Pair::generate().0.public()).collect();
+        let finalizer = Pair::generate().0.public();
+        let [authorities_key, emergency_finalizer_key] = next_authority_data_keys();
+        let (root, proof) = prove(
+            backend(vec![
+                (authorities_key, authorities.encode()),
+                (emergency_finalizer_key, finalizer.encode()),
+            ]),
+            next_authority_data_keys().to_vec(),
+        );
+        let next = read_next_authorities(&root, proof).expect("proof should be correct");
+        assert_eq!(next.authorities(), authorities.as_slice());
+        assert_eq!(next.emergency_finalizer(), Some(&finalizer));
+    }
+
+    #[test]
+    fn requires_next_authorities() {
+        let (root, proof) = prove(backend(vec![]), next_authority_data_keys().to_vec());
+        let [authorities_key, _] = next_authority_data_keys();
+        assert_eq!(
+            read_next_authorities(&root, proof),
+            Err(ProofError::MissingValue(authorities_key))
+        );
+    }
+}
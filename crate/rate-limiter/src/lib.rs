@@ -1,3 +1,5 @@
+mod per_peer;
+mod priority;
 mod rate_limiter;
 mod token_bucket;
 
@@ -7,7 +9,12 @@ use futures::{future::BoxFuture, ready, FutureExt};
 use rate_limiter::RateLimiterFacade;
 use tokio::io::AsyncRead;
 
-pub use crate::{rate_limiter::SharedRateLimiter, token_bucket::SharedTokenBucket};
+pub use crate::{
+    per_peer::PerPeerRateLimiter,
+    priority::{Priority, PriorityRateLimiter},
+    rate_limiter::SharedRateLimiter,
+    token_bucket::SharedTokenBucket,
+};
 
 const LOG_TARGET: &str = "rate-limiter";
 
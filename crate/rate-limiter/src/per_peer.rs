@@ -0,0 +1,113 @@
+use std::{collections::HashMap, hash::Hash, sync::Arc, time::Instant};
+
+use parking_lot::Mutex;
+
+use crate::{
+    token_bucket::{TimeProvider, TokenBucket, TokioTimeProvider},
+    NonZeroRatePerSecond,
+};
+
+/// Keeps a separate [TokenBucket] for every peer, so that a single peer cannot use more than its
+/// own cap, regardless of how much bandwidth is left for the others. Clones share the buckets, so
+/// all connections to the same peer are limited together.
+pub struct PerPeerRateLimiter<P, TP = TokioTimeProvider> {
+    rate: Option<NonZeroRatePerSecond>,
+    buckets: Arc<Mutex<HashMap<P, TokenBucket<TP>>>>,
+    time_provider: TP,
+}
+
+impl<P, TP: Clone> Clone for PerPeerRateLimiter<P, TP> {
+    fn clone(&self) -> Self {
+        Self {
+            rate: self.rate,
+            buckets: self.buckets.clone(),
+            time_provider: self.time_provider.clone(),
+        }
+    }
+}
+
+impl<P: Hash + Eq + Clone> PerPeerRateLimiter<P> {
+    /// Constructs a limiter allowing every peer to send `rate` bits per second, or any amount of
+    /// data if `rate` is `None`.
+    pub fn new(rate: Option<NonZeroRatePerSecond>) -> Self {
+        Self::new_internal(rate, TokioTimeProvider)
+    }
+
+    /// A limiter that never delays anything.
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<P: Hash + Eq + Clone, TP: TimeProvider + Clone> PerPeerRateLimiter<P, TP> {
+    fn new_internal(rate: Option<NonZeroRatePerSecond>, time_provider: TP) -> Self {
+        Self {
+            rate,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            time_provider,
+        }
+    }
+
+    /// Accounts `requested` bits received from the peer. Returns the instant until which reading
+    /// further data from this peer should be delayed, if any.
+    pub fn rate_limit(&self, peer: &P, requested: u64) -> Option<Instant> {
+        let rate = self.rate?;
+        self.buckets
+            .lock()
+            .entry(peer.clone())
+            .or_insert_with(|| TokenBucket::new_internal(rate, self.time_provider.clone()))
+            .rate_limit(requested)
+    }
+
+    /// Forgets about the peer, should be called after all connections to it are closed.
+    pub fn remove(&self, peer: &P) {
+        self.buckets.lock().remove(peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::PerPeerRateLimiter;
+
+    #[test]
+    fn unlimited_never_delays() {
+        let limiter = PerPeerRateLimiter::<u32>::unlimited();
+        assert_eq!(limiter.rate_limit(&0, u64::MAX), None);
+    }
+
+    #[test]
+    fn peers_are_limited_separately() {
+        let now = Instant::now();
+        let limiter =
+            PerPeerRateLimiter::new_internal(Some(10.try_into().expect("10 > 0 qed")), move || now);
+
+        assert_eq!(
+            limiter.rate_limit(&0, 10),
+            Some(now + Duration::from_secs(1))
+        );
+        assert_eq!(
+            limiter.rate_limit(&0, 10),
+            Some(now + Duration::from_secs(2))
+        );
+        assert_eq!(
+            limiter.clone().rate_limit(&1, 10),
+            Some(now + Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn removed_peer_starts_over() {
+        let now = Instant::now();
+        let limiter =
+            PerPeerRateLimiter::new_internal(Some(10.try_into().expect("10 > 0 qed")), move || now);
+
+        limiter.rate_limit(&0, 100);
+        limiter.remove(&0);
+        assert_eq!(
+            limiter.rate_limit(&0, 10),
+            Some(now + Duration::from_secs(1))
+        );
+    }
+}
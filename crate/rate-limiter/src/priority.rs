@@ -0,0 +1,123 @@
+use std::{cmp::max, sync::Arc, time::Instant};
+
+use parking_lot::Mutex;
+
+use crate::{
+    token_bucket::{TimeProvider, TokenBucket, TokioTimeProvider},
+    NonZeroRatePerSecond,
+};
+
+/// Classes of traffic sharing a [PriorityRateLimiter].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    /// Never delayed, but uses up the shared budget.
+    High,
+    /// Delayed whenever the shared budget is used up, or when it exceeds its own cap.
+    Low,
+}
+
+struct Buckets<TP> {
+    total: TokenBucket<TP>,
+    low: TokenBucket<TP>,
+}
+
+/// Divides a budget between traffic of different priorities, so that low priority traffic can never
+/// take the bandwidth needed by high priority traffic. Clones share the budget.
+pub struct PriorityRateLimiter<TP = TokioTimeProvider> {
+    buckets: Arc<Mutex<Buckets<TP>>>,
+}
+
+impl<TP> Clone for PriorityRateLimiter<TP> {
+    fn clone(&self) -> Self {
+        Self {
+            buckets: self.buckets.clone(),
+        }
+    }
+}
+
+impl PriorityRateLimiter {
+    /// Constructs a limiter with the `total` budget in bits per second, of which low priority
+    /// traffic can use at most `low_priority_cap`.
+    pub fn new(total: NonZeroRatePerSecond, low_priority_cap: NonZeroRatePerSecond) -> Self {
+        Self::new_internal(total, low_priority_cap, TokioTimeProvider)
+    }
+}
+
+impl<TP: TimeProvider + Clone> PriorityRateLimiter<TP> {
+    fn new_internal(
+        total: NonZeroRatePerSecond,
+        low_priority_cap: NonZeroRatePerSecond,
+        time_provider: TP,
+    ) -> Self {
+        Self {
+            buckets: Arc::new(Mutex::new(Buckets {
+                total: TokenBucket::new_internal(total, time_provider.clone()),
+                low: TokenBucket::new_internal(low_priority_cap, time_provider),
+            })),
+        }
+    }
+
+    /// Accounts `requested` bits of traffic of the given priority. Returns the instant until which
+    /// processing of further traffic of this priority should be delayed, if any.
+    pub fn rate_limit(&self, priority: Priority, requested: u64) -> Option<Instant> {
+        let mut buckets = self.buckets.lock();
+        match priority {
+            Priority::High => {
+                buckets.total.rate_limit(requested);
+                None
+            }
+            Priority::Low => {
+                let own = buckets.low.rate_limit(requested);
+                max(own, buckets.total.rate_limit(requested))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Priority, PriorityRateLimiter};
+
+    fn limiter(now: Instant) -> PriorityRateLimiter<impl Fn() -> Instant + Clone> {
+        PriorityRateLimiter::new_internal(
+            100.try_into().expect("100 > 0 qed"),
+            10.try_into().expect("10 > 0 qed"),
+            move || now,
+        )
+    }
+
+    #[test]
+    fn high_priority_is_never_delayed() {
+        let limiter = limiter(Instant::now());
+        for _ in 0..10 {
+            assert_eq!(limiter.rate_limit(Priority::High, 1000), None);
+        }
+    }
+
+    #[test]
+    fn low_priority_is_capped() {
+        let now = Instant::now();
+        let limiter = limiter(now);
+        assert_eq!(
+            limiter.rate_limit(Priority::Low, 10),
+            Some(now + Duration::from_secs(1))
+        );
+        assert_eq!(
+            limiter.rate_limit(Priority::Low, 10),
+            Some(now + Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn low_priority_waits_for_high_priority() {
+        let now = Instant::now();
+        let limiter = limiter(now);
+        limiter.rate_limit(Priority::High, 500);
+        assert_eq!(
+            limiter.clone().rate_limit(Priority::Low, 10),
+            Some(now + Duration::from_millis(5100))
+        );
+    }
+}
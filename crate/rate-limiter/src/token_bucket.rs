@@ -50,7 +50,7 @@ impl SleepUntil for TokioSleepUntil {
 
 /// Implementation of the `Token Bucket` algorithm for the purpose of rate-limiting access to some abstract resource, e.g. data received via network.
 #[derive(Clone)]
-struct TokenBucket<T = TokioTimeProvider> {
+pub(crate) struct TokenBucket<T = TokioTimeProvider> {
     last_update: Instant,
     rate_per_second: NonZeroU64,
     requested: u64,
@@ -78,7 +78,7 @@ impl<TP> TokenBucket<TP>
 where
     TP: TimeProvider,
 {
-    fn new_internal(rate_per_second: NonZeroRatePerSecond, time_provider: TP) -> Self {
+    pub(crate) fn new_internal(rate_per_second: NonZeroRatePerSecond, time_provider: TP) -> Self {
         let now = time_provider.now();
         Self {
             time_provider,
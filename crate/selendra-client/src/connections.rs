@@ -95,6 +95,19 @@ pub trait ConnectionApi: Sync {
         at: Option<BlockHash>,
     ) -> Option<T>;
 
+    /// Retrieves a value stored under a raw storage key, decoded as `T`.
+    ///
+    /// # Panic
+    /// This method `panic`s, in case storage cannot be accessed, or in case value cannot be decoded,
+    /// but does _not_ `panic` if there is no such value
+    /// * `key` - full storage key, e.g. built with [`crate::raw::storage_map_key`]
+    /// * `at` - optional block hash to query state from
+    async fn get_raw_storage_entry_maybe<T: Decode + Send>(
+        &self,
+        key: Vec<u8>,
+        at: Option<BlockHash>,
+    ) -> Option<T>;
+
     /// Submit a RPC call.
     ///
     /// * `func_name` - name of a RPC call
@@ -299,6 +312,26 @@ impl<C: AsConnection + Sync> ConnectionApi for C {
         block.fetch(addrs).await.expect("Should access storage")
     }
 
+    async fn get_raw_storage_entry_maybe<T: Decode + Send>(
+        &self,
+        key: Vec<u8>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        info!(target: "aleph-client", "accessing storage at key 0x{} at block {:?}", hex::encode(&key), at);
+
+        let storage = self.as_connection().as_client().storage();
+        let block = match at {
+            Some(block_hash) => storage.at(block_hash),
+            None => storage.at_latest().await.expect("Should access storage"),
+        };
+
+        block
+            .fetch_raw(&key)
+            .await
+            .expect("Should access storage")
+            .map(|bytes| T::decode(&mut &bytes[..]).expect("Should decode storage value"))
+    }
+
     async fn rpc_call<R: Decode>(&self, func_name: String, params: RpcParams) -> anyhow::Result<R> {
         info!(target: "aleph-client", "submitting rpc call `{}`, with params {:?}", func_name, params.clone().build());
         let bytes: Bytes = self
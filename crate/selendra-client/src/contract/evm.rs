@@ -0,0 +1,312 @@
+//! Solidity contracts deployed to the EVM of the chain, with calls encoded from an ABI the same
+//! way [`ContractInstance`](super::ContractInstance) encodes ink! messages from metadata.
+
+use std::{
+    fmt::{Debug, Formatter},
+    fs::File,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+pub use ethabi::Token;
+use ethabi::{
+    token::{LenientTokenizer, Tokenizer},
+    Contract, Function, ParamType,
+};
+
+use super::{ExecCallParams, ReadonlyCallParams};
+use crate::{
+    connections::TxInfo,
+    pallets::xvm::{CallFailure, XvmApi, XvmTarget, XvmUserApi},
+    sp_core::H160,
+    sp_weights::weight_v2::Weight,
+    AccountId, Balance, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// Selector of the `Error(string)` revert of solidity `require` and `revert`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` revert of failed solidity assertions.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Represents an EVM contract deployed on the chain.
+pub struct EvmContractInstance {
+    address: H160,
+    abi: Contract,
+}
+
+impl EvmContractInstance {
+    /// Creates a new contract instance under `address` with the ABI read from `abi_path`, i.e. a
+    /// JSON file with the ABI array as produced by `solc --abi`.
+    pub fn new(address: H160, abi_path: &str) -> Result<Self> {
+        let file = File::open(abi_path).with_context(|| format!("Can't open {abi_path}"))?;
+
+        Ok(Self::from_abi(address, Contract::load(file)?))
+    }
+
+    /// Creates a new contract instance under `address` with the given ABI.
+    pub fn from_abi(address: H160, abi: Contract) -> Self {
+        Self { address, abi }
+    }
+
+    /// The address of this contract instance.
+    pub fn address(&self) -> &H160 {
+        &self.address
+    }
+
+    /// The ABI of this contract instance.
+    pub fn abi(&self) -> &Contract {
+        &self.abi
+    }
+
+    /// Reads the value of a view function by dry-running it through XVM. If `params` has no
+    /// sender, the call is made from the all zero account.
+    pub async fn read<S: AsRef<str> + Debug, C: ConnectionApi>(
+        &self,
+        conn: &C,
+        function: &str,
+        args: &[S],
+        params: ReadonlyCallParams,
+    ) -> Result<Vec<Token>> {
+        let sender = params.sender.unwrap_or(AccountId::new([0; 32]));
+        let output = self
+            .dry_run(conn, sender, function, args, 0, params)
+            .await?;
+
+        self.decode(function, &output)
+    }
+
+    /// Calls a function through `EthereumChecked::transact` with the given params. Without an
+    /// explicit gas limit, the call is dry-run first to measure it.
+    pub async fn exec<C: SignedConnectionApi, S: AsRef<str> + Debug>(
+        &self,
+        conn: &C,
+        function: &str,
+        args: &[S],
+        params: ExecCallParams,
+    ) -> Result<TxInfo> {
+        let gas_limit = match params.max_gas {
+            Some(max_gas) => max_gas,
+            None => {
+                self.exec_dry_run(conn, function, args, params.clone())
+                    .await?
+            }
+        };
+
+        conn.xvm_call(
+            XvmTarget::Evm(self.address),
+            self.encode(function, args)?,
+            params.value,
+            gas_limit,
+            TxStatus::Finalized,
+        )
+        .await
+    }
+
+    /// Dry-runs a call of a function from the signer of `conn` and returns the weight it used.
+    pub async fn exec_dry_run<C: SignedConnectionApi, S: AsRef<str> + Debug>(
+        &self,
+        conn: &C,
+        function: &str,
+        args: &[S],
+        params: ExecCallParams,
+    ) -> Result<Weight> {
+        let input = self.encode(function, args)?;
+        let target = XvmTarget::Evm(self.address);
+
+        match conn
+            .xvm_dry_run(
+                conn.account_id().clone(),
+                target,
+                input,
+                params.value,
+                params.max_gas,
+                None,
+            )
+            .await?
+        {
+            Ok(output) => Ok(output.used_weight),
+            Err(failure) => Err(self.describe_failure(failure)),
+        }
+    }
+
+    async fn dry_run<S: AsRef<str> + Debug, C: ConnectionApi>(
+        &self,
+        conn: &C,
+        sender: AccountId,
+        function: &str,
+        args: &[S],
+        value: Balance,
+        params: ReadonlyCallParams,
+    ) -> Result<Vec<u8>> {
+        let input = self.encode(function, args)?;
+        let target = XvmTarget::Evm(self.address);
+
+        match conn
+            .xvm_dry_run(sender, target, input, value, None, params.at)
+            .await
+            .context("RPC request error - there may be more info in node logs.")?
+        {
+            Ok(output) => Ok(output.output),
+            Err(failure) => Err(self.describe_failure(failure)),
+        }
+    }
+
+    /// Encodes a call of `function` with arguments given as strings, e.g. `"0x12..."` for an
+    /// address or `"100"` for an integer.
+    pub fn encode<S: AsRef<str> + Debug>(&self, function: &str, args: &[S]) -> Result<Vec<u8>> {
+        let function = self.function(function)?;
+        let tokens = tokenize(&function.inputs, args)?;
+
+        Ok(function.encode_input(&tokens)?)
+    }
+
+    /// Decodes output of `function`.
+    pub fn decode(&self, function: &str, data: &[u8]) -> Result<Vec<Token>> {
+        Ok(self.function(function)?.decode_output(data)?)
+    }
+
+    /// Encodes a deployment of `bytecode` with constructor arguments given as strings, to be
+    /// passed to [`EthereumCheckedUserApi::ethereum_checked_create`] or
+    /// [`create`](crate::pallets::evm::create).
+    ///
+    /// [`EthereumCheckedUserApi::ethereum_checked_create`]:
+    /// crate::pallets::evm::EthereumCheckedUserApi::ethereum_checked_create
+    pub fn encode_deployment<S: AsRef<str> + Debug>(
+        abi: &Contract,
+        bytecode: Vec<u8>,
+        args: &[S],
+    ) -> Result<Vec<u8>> {
+        match abi.constructor() {
+            Some(constructor) => {
+                let tokens = tokenize(&constructor.inputs, args)?;
+                Ok(constructor.encode_input(bytecode, &tokens)?)
+            }
+            None if args.is_empty() => Ok(bytecode),
+            None => bail!(
+                "The contract has no constructor, but got arguments {:?}",
+                args
+            ),
+        }
+    }
+
+    fn function(&self, name: &str) -> Result<&Function> {
+        self.abi
+            .function(name)
+            .with_context(|| format!("No function {name} in the ABI"))
+    }
+
+    fn describe_failure(&self, failure: CallFailure) -> anyhow::Error {
+        match failure.reason.revert_data() {
+            Some(data) => anyhow!("{}, reason: {}", failure, revert_reason(data)),
+            None => anyhow!(failure),
+        }
+    }
+}
+
+fn tokenize<S: AsRef<str> + Debug>(inputs: &[ethabi::Param], args: &[S]) -> Result<Vec<Token>> {
+    if inputs.len() != args.len() {
+        bail!("Expected {} arguments, got {:?}", inputs.len(), args);
+    }
+
+    inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            LenientTokenizer::tokenize(&param.kind, arg.as_ref())
+                .with_context(|| format!("Can't parse {arg:?} as {}", param.kind))
+        })
+        .collect()
+}
+
+/// Returns a human readable reason of an EVM revert with the given data.
+pub fn revert_reason(data: &[u8]) -> String {
+    let decoded = match data.get(..4) {
+        Some(selector) if selector == ERROR_SELECTOR => {
+            ethabi::decode(&[ParamType::String], &data[4..]).ok()
+        }
+        Some(selector) if selector == PANIC_SELECTOR => {
+            ethabi::decode(&[ParamType::Uint(256)], &data[4..]).ok()
+        }
+        _ => None,
+    };
+
+    match decoded.as_deref() {
+        Some([Token::String(message)]) => message.clone(),
+        Some([Token::Uint(code)]) => format!("panic code {code:#x}"),
+        _ => format!("0x{}", hex::encode(data)),
+    }
+}
+
+impl Debug for EvmContractInstance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmContractInstance")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethabi::{ethereum_types::U256, Token};
+
+    use super::*;
+
+    const ABI: &str = r#"[
+        {"type": "constructor", "inputs": [{"name": "supply", "type": "uint256"}]},
+        {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+         "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "bool"}]}
+    ]"#;
+
+    fn instance() -> EvmContractInstance {
+        let abi = Contract::load(ABI.as_bytes()).expect("ABI is valid");
+        EvmContractInstance::from_abi(H160::repeat_byte(1), abi)
+    }
+
+    #[test]
+    fn encodes_calls_from_string_arguments() {
+        let encoded = instance()
+            .encode(
+                "transfer",
+                &["0x0202020202020202020202020202020202020202", "100"],
+            )
+            .expect("arguments match the ABI");
+
+        assert_eq!(encoded[..4], hex::decode("a9059cbb").unwrap());
+        assert_eq!(encoded.len(), 4 + 2 * 32);
+        assert_eq!(encoded[4 + 32 + 31], 100);
+    }
+
+    #[test]
+    fn rejects_wrong_number_of_arguments() {
+        assert!(instance().encode("transfer", &["100"]).is_err());
+    }
+
+    #[test]
+    fn appends_constructor_arguments_to_bytecode() {
+        let deployment =
+            EvmContractInstance::encode_deployment(instance().abi(), vec![0x60, 0x80], &["7"])
+                .expect("arguments match the constructor");
+
+        assert_eq!(deployment[..2], [0x60, 0x80]);
+        assert_eq!(deployment.len(), 2 + 32);
+        assert_eq!(deployment[2 + 31], 7);
+    }
+
+    #[test]
+    fn decodes_revert_reasons() {
+        let error = [
+            ERROR_SELECTOR.to_vec(),
+            ethabi::encode(&[Token::String("not enough balance".into())]),
+        ]
+        .concat();
+        let panic = [
+            PANIC_SELECTOR.to_vec(),
+            ethabi::encode(&[Token::Uint(U256::from(0x11))]),
+        ]
+        .concat();
+
+        assert_eq!(revert_reason(&error), "not enough balance");
+        assert_eq!(revert_reason(&panic), "panic code 0x11");
+        assert_eq!(revert_reason(&[1, 2]), "0x0102");
+    }
+}
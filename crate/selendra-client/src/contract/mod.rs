@@ -46,12 +46,14 @@
 
 mod convertible_value;
 pub mod event;
+mod evm;
 
 use std::fmt::{Debug, Formatter};
 
 use anyhow::{anyhow, Context, Result};
 use contract_transcode::ContractMessageTranscoder;
 pub use convertible_value::ConvertibleValue;
+pub use evm::{revert_reason, EvmContractInstance, Token};
 use log::info;
 use pallet_contracts::ContractExecResult;
 
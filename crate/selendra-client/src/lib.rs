@@ -36,9 +36,14 @@ pub mod primitives;
 
 mod connections;
 pub mod contract;
+mod multi_connection;
 /// API for pallets.
 pub mod pallets;
+/// Calls, storage and events of pallets missing from the static metadata.
+pub mod raw;
 mod runtime_types;
+/// Long-lived, reorg-aware subscriptions to pallet events.
+pub mod subscription;
 /// Block / session / era API.
 pub mod utility;
 /// Waiting for some events API.
@@ -67,6 +72,7 @@ pub use connections::{
     AsConnection, AsSigned, Connection, ConnectionApi, RootConnection, SignedConnection,
     SignedConnectionApi, SignedConnectionApiExt, SubmittableExtrinsic, SudoCall, TxInfo,
 };
+pub use multi_connection::{EndpointHealth, HealthPolicy, MultiConnection, SignedMultiConnection};
 
 /// An alias for a configuration of live chain, e.g. block index type, hash type.
 pub enum AlephConfig {}
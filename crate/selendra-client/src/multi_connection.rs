@@ -0,0 +1,866 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context};
+use codec::Decode;
+use futures::future::join_all;
+use log::{info, warn};
+use serde::Deserialize;
+use subxt::{
+    ext::sp_core::Bytes,
+    metadata::DecodeWithMetadata,
+    rpc::RpcParams,
+    rpc_params,
+    storage::{
+        address::{Address, StaticStorageMapKey, Yes},
+        StorageAddress,
+    },
+    tx::{SubmittableExtrinsic as SubxtSubmittable, TxPayload},
+};
+use tokio::time::timeout;
+
+use crate::{
+    connections::TxInfo, primitives::Nonce, AccountId, AlephConfig, BlockHash, BlockNumber,
+    Connection, ConnectionApi, KeyPair, ParamsBuilder, SignedConnectionApi, SubxtClient, TxStatus,
+};
+
+/// Conditions an endpoint of a [`MultiConnection`] has to meet to be used.
+#[derive(Clone, Debug)]
+pub struct HealthPolicy {
+    /// How many blocks the finalized block of an endpoint can be behind the highest finalized
+    /// block among all endpoints.
+    pub max_finality_lag: BlockNumber,
+    /// The least number of peers an endpoint has to be connected to.
+    pub min_peers: usize,
+    /// How many times a request is sent to another endpoint after the one it was sent to went
+    /// down.
+    pub retries: u32,
+    /// How long connecting to an endpoint or checking its health can take before the endpoint
+    /// is considered down.
+    pub timeout: Duration,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            max_finality_lag: 20,
+            min_peers: 1,
+            retries: 3,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// State of an endpoint of a [`MultiConnection`] as of the last health check.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EndpointHealth {
+    /// The address of the endpoint.
+    pub address: String,
+    /// The number of the finalized block, `None` if the endpoint could not be reached.
+    pub finalized: Option<BlockNumber>,
+    /// The number of peers of the node.
+    pub peers: usize,
+    /// Whether the node is syncing.
+    pub is_syncing: bool,
+    /// Whether the endpoint meets the [`HealthPolicy`].
+    pub healthy: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemHealth {
+    peers: usize,
+    is_syncing: bool,
+}
+
+struct Endpoints {
+    addresses: Vec<String>,
+    policy: HealthPolicy,
+    // Clients of endpoints, `None` for the ones that are down until they are reconnected.
+    connections: Mutex<Vec<Option<Connection>>>,
+    active: Mutex<Option<usize>>,
+}
+
+/// A connection to several nodes of the same chain, which sends requests to one of them and
+/// moves on to another one when it goes down, is syncing, has too few peers or lags behind in
+/// finality, see [`HealthPolicy`].
+///
+/// Storage reads and rpc calls are retried on another endpoint when the one they were sent to
+/// stops responding, except for `author_*` rpc methods which are not idempotent. Endpoints that
+/// went down are reconnected on the next health check, which happens whenever a request fails
+/// or [`MultiConnection::check_health`] is called, e.g. periodically.
+///
+/// Unlike [`Connection`], it doesn't implement [`crate::AsConnection`]. APIs that need one can
+/// use [`MultiConnection::connection`], which returns the connection to the current endpoint.
+#[derive(Clone)]
+pub struct MultiConnection {
+    endpoints: Arc<Endpoints>,
+}
+
+impl MultiConnection {
+    /// Connects to given endpoints with the default [`HealthPolicy`].
+    /// * `addresses` - addresses in websocket format, e.g. `ws://127.0.0.1:9943`
+    pub async fn new(addresses: Vec<String>) -> anyhow::Result<Self> {
+        Self::with_policy(addresses, HealthPolicy::default()).await
+    }
+
+    /// Connects to given endpoints. Fails if none of them can be reached.
+    /// * `addresses` - addresses in websocket format, e.g. `ws://127.0.0.1:9943`
+    /// * `policy` - conditions an endpoint has to meet to be used
+    pub async fn with_policy(addresses: Vec<String>, policy: HealthPolicy) -> anyhow::Result<Self> {
+        if addresses.is_empty() {
+            return Err(anyhow!("No endpoints to connect to"));
+        }
+        let connections = Mutex::new(vec![None; addresses.len()]);
+        let connection = Self {
+            endpoints: Arc::new(Endpoints {
+                addresses,
+                policy,
+                connections,
+                active: Mutex::new(None),
+            }),
+        };
+        connection.check_health().await;
+        connection.active().await?;
+
+        Ok(connection)
+    }
+
+    /// Checks the health of all endpoints, reconnecting the ones that went down, and switches to
+    /// another endpoint if the current one is unhealthy. If no endpoint is healthy, the one with
+    /// the highest finalized block is used.
+    pub async fn check_health(&self) -> Vec<EndpointHealth> {
+        let reports =
+            join_all((0..self.endpoints.addresses.len()).map(|index| self.check_endpoint(index)))
+                .await;
+        let reports = judge(reports, &self.endpoints.policy);
+
+        let mut active = self.endpoints.active.lock().expect("lock is not poisoned");
+        let selected = select(&reports, *active);
+        if selected != *active {
+            match selected {
+                Some(index) => {
+                    info!(target: "aleph-client", "switching to endpoint {}", reports[index].address)
+                }
+                None => warn!(target: "aleph-client", "none of the endpoints can be reached"),
+            }
+        }
+        *active = selected;
+
+        reports
+    }
+
+    /// Returns the connection to the endpoint currently in use.
+    pub async fn connection(&self) -> anyhow::Result<Connection> {
+        Ok(self.active().await?.1)
+    }
+
+    async fn active(&self) -> anyhow::Result<(usize, Connection)> {
+        if let Some(active) = self.current() {
+            return Ok(active);
+        }
+        self.check_health().await;
+
+        self.current()
+            .ok_or_else(|| anyhow!("None of the endpoints can be reached"))
+    }
+
+    fn current(&self) -> Option<(usize, Connection)> {
+        let index = (*self.endpoints.active.lock().expect("lock is not poisoned"))?;
+
+        self.connected(index).map(|connection| (index, connection))
+    }
+
+    fn connected(&self, index: usize) -> Option<Connection> {
+        self.endpoints
+            .connections
+            .lock()
+            .expect("lock is not poisoned")[index]
+            .clone()
+    }
+
+    async fn check_endpoint(&self, index: usize) -> EndpointHealth {
+        let address = self.endpoints.addresses[index].clone();
+        let unreachable = EndpointHealth {
+            address: address.clone(),
+            finalized: None,
+            peers: 0,
+            is_syncing: false,
+            healthy: false,
+        };
+
+        let connection = match self.endpoint(index).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(target: "aleph-client", "can't connect to {address}: {e:?}");
+                return unreachable;
+            }
+        };
+        match probe(&connection, self.endpoints.policy.timeout).await {
+            Ok((finalized, health)) => EndpointHealth {
+                address,
+                finalized: Some(finalized),
+                peers: health.peers,
+                is_syncing: health.is_syncing,
+                healthy: false,
+            },
+            Err(e) => {
+                warn!(target: "aleph-client", "endpoint {address} is down: {e:?}");
+                self.drop_endpoint(index);
+                unreachable
+            }
+        }
+    }
+
+    async fn endpoint(&self, index: usize) -> anyhow::Result<Connection> {
+        if let Some(connection) = self.connected(index) {
+            return Ok(connection);
+        }
+
+        let address = &self.endpoints.addresses[index];
+        let client = timeout(
+            self.endpoints.policy.timeout,
+            SubxtClient::from_url(address),
+        )
+        .await
+        .map_err(|_| anyhow!("Connecting to {address} timed out"))??;
+        let connection = Connection { client };
+        self.endpoints
+            .connections
+            .lock()
+            .expect("lock is not poisoned")[index] = Some(connection.clone());
+
+        Ok(connection)
+    }
+
+    fn drop_endpoint(&self, index: usize) {
+        self.endpoints
+            .connections
+            .lock()
+            .expect("lock is not poisoned")[index] = None;
+    }
+
+    /// Tells whether `error` returned by the endpoint under `index` means it went down, in which
+    /// case the endpoint is dropped until the next health check reconnects it.
+    async fn went_down(
+        &self,
+        index: usize,
+        connection: &Connection,
+        error: &anyhow::Error,
+    ) -> bool {
+        if probe(connection, self.endpoints.policy.timeout)
+            .await
+            .is_ok()
+        {
+            return false;
+        }
+        warn!(target: "aleph-client", "endpoint {} went down: {error:?}", self.endpoints.addresses[index]);
+        self.drop_endpoint(index);
+
+        true
+    }
+
+    /// Runs `request` with the current endpoint, and with other ones as long as the endpoints it
+    /// was sent to go down.
+    async fn with_retries<T, F, Fut>(&self, request: F) -> anyhow::Result<T>
+    where
+        F: Fn(Connection) -> Fut + Send + Sync,
+        Fut: Future<Output = anyhow::Result<T>> + Send,
+        T: Send,
+    {
+        let mut retries = self.endpoints.policy.retries;
+        loop {
+            let (index, connection) = self.active().await?;
+            match request(connection.clone()).await {
+                Ok(result) => return Ok(result),
+                Err(e) if retries > 0 && self.went_down(index, &connection, &e).await => {
+                    retries -= 1;
+                    self.check_health().await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Returns the finalized block and health of the endpoint, failing if the endpoint doesn't
+/// answer within `limit`.
+async fn probe(
+    connection: &Connection,
+    limit: Duration,
+) -> anyhow::Result<(BlockNumber, SystemHealth)> {
+    let rpc = connection.as_client().rpc();
+    let probe = async {
+        let health = rpc.request("system_health", rpc_params![]).await?;
+        let finalized = rpc.finalized_head().await?;
+        let header = rpc
+            .header(Some(finalized))
+            .await?
+            .context("Finalized block without a header")?;
+
+        Ok::<_, anyhow::Error>((header.number, health))
+    };
+
+    timeout(limit, probe)
+        .await
+        .map_err(|_| anyhow!("Health check timed out"))?
+}
+
+/// Marks endpoints which meet `policy` as healthy.
+fn judge(mut reports: Vec<EndpointHealth>, policy: &HealthPolicy) -> Vec<EndpointHealth> {
+    let highest = reports.iter().filter_map(|report| report.finalized).max();
+    for report in reports.iter_mut() {
+        report.healthy = match (report.finalized, highest) {
+            (Some(finalized), Some(highest)) => {
+                highest - finalized <= policy.max_finality_lag
+                    && report.peers >= policy.min_peers
+                    && !report.is_syncing
+            }
+            _ => false,
+        };
+    }
+
+    reports
+}
+
+/// Selects the endpoint to use: the `current` one if it is healthy, otherwise the first healthy
+/// one, otherwise the reachable one with the highest finalized block.
+fn select(reports: &[EndpointHealth], current: Option<usize>) -> Option<usize> {
+    if let Some(current) = current.filter(|current| reports[*current].healthy) {
+        return Some(current);
+    }
+    if let Some(healthy) = reports.iter().position(|report| report.healthy) {
+        return Some(healthy);
+    }
+
+    reports
+        .iter()
+        .enumerate()
+        .filter_map(|(index, report)| report.finalized.map(|finalized| (finalized, index)))
+        .max_by_key(|(finalized, index)| (*finalized, std::cmp::Reverse(*index)))
+        .map(|(_, index)| index)
+}
+
+#[async_trait::async_trait]
+impl ConnectionApi for MultiConnection {
+    async fn get_storage_entry<T: DecodeWithMetadata + Sync, Defaultable: Sync, Iterable: Sync>(
+        &self,
+        addrs: &Address<StaticStorageMapKey, T, Yes, Defaultable, Iterable>,
+        at: Option<BlockHash>,
+    ) -> T {
+        self.get_storage_entry_maybe(addrs, at)
+            .await
+            .expect("There should be a value")
+    }
+
+    async fn get_storage_entry_maybe<
+        T: DecodeWithMetadata + Sync,
+        Defaultable: Sync,
+        Iterable: Sync,
+    >(
+        &self,
+        addrs: &Address<StaticStorageMapKey, T, Yes, Defaultable, Iterable>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        info!(target: "aleph-client", "accessing storage at {}::{} at block {:?}", addrs.pallet_name(), addrs.entry_name(), at);
+
+        self.with_retries(|connection| async move {
+            let storage = connection.as_client().storage();
+            let block = match at {
+                Some(block_hash) => storage.at(block_hash),
+                None => storage.at_latest().await?,
+            };
+
+            Ok::<_, anyhow::Error>(block.fetch(addrs).await?)
+        })
+        .await
+        .expect("Should access storage")
+    }
+
+    async fn get_raw_storage_entry_maybe<T: Decode + Send>(
+        &self,
+        key: Vec<u8>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        info!(target: "aleph-client", "accessing storage at key 0x{} at block {:?}", hex::encode(&key), at);
+
+        self.with_retries(|connection| {
+            let key = key.clone();
+            async move {
+                let storage = connection.as_client().storage();
+                let block = match at {
+                    Some(block_hash) => storage.at(block_hash),
+                    None => storage.at_latest().await?,
+                };
+
+                Ok::<_, anyhow::Error>(block.fetch_raw(&key).await?)
+            }
+        })
+        .await
+        .expect("Should access storage")
+        .map(|bytes| T::decode(&mut &bytes[..]).expect("Should decode storage value"))
+    }
+
+    async fn rpc_call<R: Decode>(&self, func_name: String, params: RpcParams) -> anyhow::Result<R> {
+        info!(target: "aleph-client", "submitting rpc call `{}`, with params {:?}", func_name, params.clone().build());
+        let bytes: Bytes = self.request(&func_name, params).await?;
+
+        Ok(R::decode(&mut bytes.as_ref())?)
+    }
+
+    async fn rpc_call_no_return(&self, func_name: String, params: RpcParams) -> anyhow::Result<()> {
+        info!(target: "aleph-client", "submitting rpc call `{}`, with params {:?}", func_name, params.clone().build());
+        self.request::<()>(&func_name, params).await
+    }
+}
+
+impl MultiConnection {
+    async fn request<R: serde::de::DeserializeOwned + Send>(
+        &self,
+        method: &str,
+        params: RpcParams,
+    ) -> anyhow::Result<R> {
+        let request = |connection: Connection| {
+            let params = params.clone();
+            async move {
+                let response: R = connection.as_client().rpc().request(method, params).await?;
+                Ok::<_, anyhow::Error>(response)
+            }
+        };
+
+        if method.starts_with("author_") {
+            request(self.connection().await?).await
+        } else {
+            self.with_retries(request).await
+        }
+    }
+}
+
+/// How long the nonce of a transaction sent with [`TxStatus::Submitted`] stays reserved if no
+/// endpoint reports it used, after which the transaction is assumed dropped.
+const SUBMITTED_NONCE_EXPIRY: Duration = Duration::from_secs(60);
+
+/// Nonces of transactions of a [`SignedMultiConnection`] which are in flight, i.e. still being
+/// sent, or submitted without waiting for their inclusion.
+///
+/// Endpoints learn about transactions submitted to other endpoints only once they propagate, so
+/// after a switch the next nonce reported by the new endpoint may still be used by a transaction
+/// in flight. A new transaction therefore gets the lowest nonce, starting from the reported one,
+/// which no transaction in flight uses. Once a transaction is no longer in flight, whether it was
+/// executed, rejected, dropped, invalidated or usurped, its nonce is left to the endpoints, so a
+/// nonce that didn't make it to the chain is used again instead of leaving a gap.
+///
+/// The fate of a submitted transaction is not known, so its nonce stays reserved until an
+/// endpoint reports a later one, or until [`SUBMITTED_NONCE_EXPIRY`] passes.
+#[derive(Debug, Default)]
+struct NonceTracker {
+    in_flight: BTreeSet<Nonce>,
+    submitted: BTreeMap<Nonce, Instant>,
+}
+
+impl NonceTracker {
+    /// Returns the nonce for a new transaction, given the next nonce reported by the endpoint.
+    fn reserve(&mut self, reported: Nonce, now: Instant) -> Nonce {
+        // The endpoint knows about the submitted transactions with earlier nonces.
+        self.submitted = self.submitted.split_off(&reported);
+        self.submitted
+            .retain(|_, submitted_at| now.duration_since(*submitted_at) < SUBMITTED_NONCE_EXPIRY);
+
+        let mut nonce = reported;
+        while self.in_flight.contains(&nonce) || self.submitted.contains_key(&nonce) {
+            nonce += 1;
+        }
+        self.in_flight.insert(nonce);
+
+        nonce
+    }
+
+    /// Keeps `nonce` reserved after its transaction is no longer in flight, as it was submitted
+    /// without waiting for its inclusion.
+    fn submit(&mut self, nonce: Nonce, now: Instant) {
+        self.submitted.insert(nonce, now);
+    }
+
+    /// Marks the transaction with `nonce` as no longer in flight.
+    fn release(&mut self, nonce: Nonce) {
+        self.in_flight.remove(&nonce);
+    }
+}
+
+/// A nonce reserved for a transaction, released when the transaction is no longer in flight.
+struct ReservedNonce {
+    nonce: Nonce,
+    nonces: Arc<Mutex<NonceTracker>>,
+}
+
+impl ReservedNonce {
+    /// Keeps the nonce reserved once it is released, see [`NonceTracker::submit`].
+    fn submit(&self) {
+        self.nonces
+            .lock()
+            .expect("lock is not poisoned")
+            .submit(self.nonce, Instant::now());
+    }
+}
+
+impl Drop for ReservedNonce {
+    fn drop(&mut self) {
+        self.nonces
+            .lock()
+            .expect("lock is not poisoned")
+            .release(self.nonce);
+    }
+}
+
+/// A [`MultiConnection`] signed by some key, which keeps nonces of its transactions consistent
+/// across switches of endpoints.
+///
+/// A transaction whose endpoint goes down before accepting it is submitted again to another
+/// endpoint, as the same signed extrinsic, so it can't be executed twice.
+#[derive(Clone)]
+pub struct SignedMultiConnection {
+    connection: MultiConnection,
+    signer: KeyPair,
+    nonces: Arc<Mutex<NonceTracker>>,
+}
+
+impl SignedMultiConnection {
+    /// Creates a new signed connection from an existing [`MultiConnection`].
+    /// * `connection` - existing connection
+    /// * `signer` - a [`KeyPair`] of signing account
+    pub fn from_connection(connection: MultiConnection, signer: KeyPair) -> Self {
+        Self {
+            connection,
+            signer,
+            nonces: Arc::new(Mutex::new(NonceTracker::default())),
+        }
+    }
+
+    /// Returns the underlying [`MultiConnection`].
+    pub fn as_multi_connection(&self) -> &MultiConnection {
+        &self.connection
+    }
+
+    async fn reserve_nonce(&self) -> anyhow::Result<ReservedNonce> {
+        let account = self.signer.account_id();
+        let reported = self
+            .connection
+            .with_retries(|connection| async move {
+                let next = connection
+                    .as_client()
+                    .rpc()
+                    .system_account_next_index(account)
+                    .await?;
+                Ok::<_, anyhow::Error>(next)
+            })
+            .await?;
+
+        let nonce = self
+            .nonces
+            .lock()
+            .expect("lock is not poisoned")
+            .reserve(reported.try_into()?, Instant::now());
+
+        Ok(ReservedNonce {
+            nonce,
+            nonces: self.nonces.clone(),
+        })
+    }
+}
+
+enum SubmitError {
+    // The endpoint did not accept the transaction, or went down before it did.
+    NotAccepted(anyhow::Error),
+    // The transaction is in the pool, but it failed or its status could not be tracked.
+    Accepted(anyhow::Error),
+}
+
+async fn submit(
+    submittable: SubxtSubmittable<AlephConfig, SubxtClient>,
+    status: TxStatus,
+) -> Result<TxInfo, SubmitError> {
+    if let TxStatus::Submitted = status {
+        let tx_hash = submittable
+            .submit()
+            .await
+            .map_err(|e| SubmitError::NotAccepted(e.into()))?;
+        return Ok(TxInfo {
+            block_hash: Default::default(),
+            tx_hash,
+        });
+    }
+
+    let progress = submittable
+        .submit_and_watch()
+        .await
+        .map_err(|e| SubmitError::NotAccepted(e.into()))?;
+    let events = match status {
+        TxStatus::InBlock => match progress.wait_for_in_block().await {
+            Ok(in_block) => in_block.wait_for_success().await,
+            Err(e) => Err(e),
+        },
+        _ => progress.wait_for_finalized_success().await,
+    };
+
+    events
+        .map(TxInfo::from)
+        .map_err(|e| SubmitError::Accepted(e.into()))
+}
+
+#[async_trait::async_trait]
+impl ConnectionApi for SignedMultiConnection {
+    async fn get_storage_entry<T: DecodeWithMetadata + Sync, Defaultable: Sync, Iterable: Sync>(
+        &self,
+        addrs: &Address<StaticStorageMapKey, T, Yes, Defaultable, Iterable>,
+        at: Option<BlockHash>,
+    ) -> T {
+        self.connection.get_storage_entry(addrs, at).await
+    }
+
+    async fn get_storage_entry_maybe<
+        T: DecodeWithMetadata + Sync,
+        Defaultable: Sync,
+        Iterable: Sync,
+    >(
+        &self,
+        addrs: &Address<StaticStorageMapKey, T, Yes, Defaultable, Iterable>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        self.connection.get_storage_entry_maybe(addrs, at).await
+    }
+
+    async fn get_raw_storage_entry_maybe<T: Decode + Send>(
+        &self,
+        key: Vec<u8>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        self.connection.get_raw_storage_entry_maybe(key, at).await
+    }
+
+    async fn rpc_call<R: Decode>(&self, func_name: String, params: RpcParams) -> anyhow::Result<R> {
+        self.connection.rpc_call(func_name, params).await
+    }
+
+    async fn rpc_call_no_return(&self, func_name: String, params: RpcParams) -> anyhow::Result<()> {
+        self.connection.rpc_call_no_return(func_name, params).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SignedConnectionApi for SignedMultiConnection {
+    async fn send_tx<Call: TxPayload + Send + Sync>(
+        &self,
+        tx: Call,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        self.send_tx_with_params(tx, Default::default(), status)
+            .await
+    }
+
+    async fn send_tx_with_params<Call: TxPayload + Send + Sync>(
+        &self,
+        tx: Call,
+        params: ParamsBuilder,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        if let Some(details) = tx.validation_details() {
+            info!(
+                target:"aleph-client", "Sending extrinsic {}.{} with params: {:?}",
+                details.pallet_name,
+                details.call_name,
+                params,
+            );
+        }
+
+        // Released when the transaction is no longer in flight, whichever way this returns, but
+        // kept reserved if it was only submitted.
+        let nonce = self.reserve_nonce().await?;
+        let (mut index, mut connection) = self.connection.active().await?;
+        let encoded = connection
+            .as_client()
+            .tx()
+            .create_signed_with_nonce(&tx, &self.signer.inner, nonce.nonce.into(), params)?
+            .into_encoded();
+
+        let mut retries = self.connection.endpoints.policy.retries;
+        loop {
+            let submittable =
+                SubxtSubmittable::from_bytes(connection.as_client().clone(), encoded.clone());
+            match submit(submittable, status).await {
+                Ok(info) => {
+                    if let TxStatus::Submitted = status {
+                        nonce.submit();
+                    }
+                    info!(target: "aleph-client", "tx with hash {:?} included in block {:?}", info.tx_hash, info.block_hash);
+                    return Ok(info);
+                }
+                Err(SubmitError::NotAccepted(e))
+                    if retries > 0 && self.connection.went_down(index, &connection, &e).await =>
+                {
+                    retries -= 1;
+                    self.connection.check_health().await;
+                    (index, connection) = self.connection.active().await?;
+                }
+                Err(SubmitError::NotAccepted(e)) | Err(SubmitError::Accepted(e)) => return Err(e),
+            }
+        }
+    }
+
+    fn account_id(&self) -> &AccountId {
+        self.signer.account_id()
+    }
+
+    fn signer(&self) -> &KeyPair {
+        &self.signer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(finalized: Option<BlockNumber>, peers: usize) -> EndpointHealth {
+        EndpointHealth {
+            address: String::new(),
+            finalized,
+            peers,
+            is_syncing: false,
+            healthy: false,
+        }
+    }
+
+    fn policy() -> HealthPolicy {
+        HealthPolicy {
+            max_finality_lag: 5,
+            min_peers: 2,
+            retries: 1,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn judges_endpoints_by_lag_and_peers() {
+        let mut syncing = report(Some(100), 3);
+        syncing.is_syncing = true;
+        let reports = vec![
+            report(Some(100), 3),
+            report(Some(94), 3),
+            report(Some(95), 3),
+            report(Some(100), 1),
+            report(None, 3),
+            syncing,
+        ];
+
+        let healthy: Vec<_> = judge(reports, &policy())
+            .into_iter()
+            .map(|report| report.healthy)
+            .collect();
+
+        assert_eq!(healthy, vec![true, false, true, false, false, false]);
+    }
+
+    #[test]
+    fn stays_with_a_healthy_endpoint() {
+        let reports = judge(vec![report(Some(100), 3), report(Some(100), 3)], &policy());
+
+        assert_eq!(select(&reports, Some(1)), Some(1));
+        assert_eq!(select(&reports, None), Some(0));
+    }
+
+    #[test]
+    fn switches_to_a_healthy_endpoint() {
+        let reports = judge(
+            vec![report(Some(90), 3), report(None, 3), report(Some(100), 3)],
+            &policy(),
+        );
+
+        assert_eq!(select(&reports, Some(0)), Some(2));
+        assert_eq!(select(&reports, Some(1)), Some(2));
+    }
+
+    #[test]
+    fn falls_back_to_the_most_finalized_endpoint() {
+        let reports = judge(
+            vec![report(Some(90), 0), report(None, 3), report(Some(100), 0)],
+            &policy(),
+        );
+
+        assert_eq!(select(&reports, Some(0)), Some(2));
+        assert_eq!(
+            select(&judge(vec![report(None, 3)], &policy()), Some(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn never_hands_out_a_nonce_twice() {
+        let mut nonces = NonceTracker::default();
+        let now = Instant::now();
+
+        assert_eq!(nonces.reserve(5, now), 5);
+        // Another endpoint which hasn't seen the transaction yet.
+        assert_eq!(nonces.reserve(5, now), 6);
+        // Transactions sent with the same account elsewhere.
+        assert_eq!(nonces.reserve(10, now), 10);
+    }
+
+    #[test]
+    fn resyncs_with_the_chain_when_nothing_is_in_flight() {
+        let mut nonces = NonceTracker::default();
+        let now = Instant::now();
+        let first = nonces.reserve(5, now);
+        let second = nonces.reserve(5, now);
+
+        // Both transactions were dropped, so the chain still reports the first nonce.
+        nonces.release(first);
+        nonces.release(second);
+        assert_eq!(nonces.reserve(5, now), 5);
+    }
+
+    #[test]
+    fn reuses_nonces_of_dropped_transactions() {
+        let mut nonces = NonceTracker::default();
+        let now = Instant::now();
+        let first = nonces.reserve(5, now);
+        nonces.reserve(5, now);
+
+        // The first transaction was invalidated, so the second one waits for its nonce.
+        nonces.release(first);
+        assert_eq!(nonces.reserve(5, now), 5);
+        assert_eq!(nonces.reserve(5, now), 7);
+    }
+
+    #[test]
+    fn keeps_nonces_of_submitted_transactions_after_failover() {
+        let mut nonces = NonceTracker::default();
+        let now = Instant::now();
+        let submitted = nonces.reserve(5, now);
+        nonces.submit(submitted, now);
+        nonces.release(submitted);
+
+        // The endpoint went down after accepting the transaction, and the new one hasn't seen it
+        // yet.
+        assert_eq!(nonces.reserve(5, now), 6);
+    }
+
+    #[test]
+    fn releases_nonces_of_submitted_transactions_once_used_or_expired() {
+        let mut nonces = NonceTracker::default();
+        let now = Instant::now();
+        for _ in 0..2 {
+            let submitted = nonces.reserve(5, now);
+            nonces.submit(submitted, now);
+            nonces.release(submitted);
+        }
+
+        // The first transaction made it to the chain, the second one was dropped.
+        let next = nonces.reserve(6, now);
+        assert_eq!(next, 7);
+        nonces.release(next);
+        assert_eq!(nonces.reserve(6, now + SUBMITTED_NONCE_EXPIRY), 6);
+    }
+}
@@ -0,0 +1,402 @@
+use anyhow::anyhow;
+use codec::{Compact, Decode};
+
+use crate::{
+    connections::TxInfo,
+    raw::{find_event, identity, storage_key, storage_map_key, Encoded, RawCall},
+    sp_core::{blake2_256, H256},
+    sp_weights::weight_v2::Weight,
+    utility::BlocksApi,
+    AccountId, AsConnection, BlockHash, BlockNumber, ConnectionApi, SignedConnection,
+    SignedConnectionApi, TxStatus,
+};
+
+/// An alias for a hash of a proposal of a collective.
+pub type ProposalHash = H256;
+
+/// Upper bound on the weight of a motion executed by `close`. Mirrors
+/// [`DEFAULT_MAX_WEIGHT`](crate::pallets::multisig::DEFAULT_MAX_WEIGHT) but with room for runtime
+/// upgrades and other heavy admin calls.
+pub const DEFAULT_CLOSE_WEIGHT: Weight = Weight::new(50_000_000_000, 5 * 1024 * 1024);
+
+/// Collectives of the runtime, i.e. instances of pallet collective.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Collective {
+    /// The council, which is elected with pallet elections-phragmen.
+    Council,
+    /// The technical committee, which can fast track external referenda.
+    TechnicalCommittee,
+}
+
+impl Collective {
+    /// Name of the pallet instance in the runtime.
+    pub fn pallet_name(&self) -> &'static str {
+        match self {
+            Collective::Council => "Council",
+            Collective::TechnicalCommittee => "TechnicalCommittee",
+        }
+    }
+}
+
+/// Votes on a motion, as in pallet collective storage `Voting`.
+#[derive(Clone, Debug, Decode, Eq, PartialEq)]
+pub struct Votes {
+    /// The index of the motion.
+    pub index: u32,
+    /// The number of approval votes needed to pass the motion.
+    pub threshold: u32,
+    /// Members who voted in favour.
+    pub ayes: Vec<AccountId>,
+    /// Members who voted against.
+    pub nays: Vec<AccountId>,
+    /// The block at which the motion can be closed regardless of the votes.
+    pub end: BlockNumber,
+}
+
+/// A motion proposed to a collective.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Motion {
+    /// Hash of the proposed call.
+    pub hash: ProposalHash,
+    /// The index of the motion.
+    pub index: u32,
+    /// Length of the encoded call, needed to close the motion.
+    pub length: u32,
+}
+
+#[derive(Decode)]
+struct Proposed {
+    _account: AccountId,
+    proposal_index: u32,
+    proposal_hash: ProposalHash,
+    _threshold: u32,
+}
+
+/// Pallet collective read-only api.
+#[async_trait::async_trait]
+pub trait CollectiveApi {
+    /// Returns storage `members` of a collective.
+    /// * `collective` - the collective
+    /// * `at` - an optional block hash to query state from
+    async fn collective_members(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Vec<AccountId>;
+
+    /// Returns storage `prime` of a collective.
+    /// * `collective` - the collective
+    /// * `at` - an optional block hash to query state from
+    async fn collective_prime(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Option<AccountId>;
+
+    /// Returns storage `proposals`, i.e. hashes of the open motions of a collective.
+    /// * `collective` - the collective
+    /// * `at` - an optional block hash to query state from
+    async fn collective_proposals(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Vec<ProposalHash>;
+
+    /// Returns storage `voting` for a given motion.
+    /// * `collective` - the collective
+    /// * `hash` - hash of the motion
+    /// * `at` - an optional block hash to query state from
+    async fn collective_voting(
+        &self,
+        collective: Collective,
+        hash: ProposalHash,
+        at: Option<BlockHash>,
+    ) -> Option<Votes>;
+}
+
+/// Pallet collective api.
+#[async_trait::async_trait]
+pub trait CollectiveUserApi {
+    /// API for [`propose`](https://paritytech.github.io/substrate/master/pallet_collective/pallet/struct.Pallet.html#method.propose) call.
+    /// Returns the motion, or `None` if `threshold` was low enough for the call to be executed
+    /// right away.
+    async fn propose_motion(
+        &self,
+        collective: Collective,
+        threshold: u32,
+        call: RawCall,
+        status: TxStatus,
+    ) -> anyhow::Result<(TxInfo, Option<Motion>)>;
+
+    /// API for [`execute`](https://paritytech.github.io/substrate/master/pallet_collective/pallet/struct.Pallet.html#method.execute) call.
+    async fn execute_as_member(
+        &self,
+        collective: Collective,
+        call: RawCall,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`vote`](https://paritytech.github.io/substrate/master/pallet_collective/pallet/struct.Pallet.html#method.vote) call.
+    async fn vote_motion(
+        &self,
+        collective: Collective,
+        hash: ProposalHash,
+        index: u32,
+        approve: bool,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`close`](https://paritytech.github.io/substrate/master/pallet_collective/pallet/struct.Pallet.html#method.close) call.
+    async fn close_motion(
+        &self,
+        collective: Collective,
+        motion: Motion,
+        weight_bound: Weight,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> CollectiveApi for C {
+    async fn collective_members(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Vec<AccountId> {
+        let key = storage_key(collective.pallet_name(), "Members");
+
+        self.get_raw_storage_entry_maybe(key, at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn collective_prime(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Option<AccountId> {
+        let key = storage_key(collective.pallet_name(), "Prime");
+
+        self.get_raw_storage_entry_maybe(key, at).await
+    }
+
+    async fn collective_proposals(
+        &self,
+        collective: Collective,
+        at: Option<BlockHash>,
+    ) -> Vec<ProposalHash> {
+        let key = storage_key(collective.pallet_name(), "Proposals");
+
+        self.get_raw_storage_entry_maybe(key, at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn collective_voting(
+        &self,
+        collective: Collective,
+        hash: ProposalHash,
+        at: Option<BlockHash>,
+    ) -> Option<Votes> {
+        let key = storage_map_key(collective.pallet_name(), "Voting", identity(&hash));
+
+        self.get_raw_storage_entry_maybe(key, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi + AsConnection> CollectiveUserApi for S {
+    async fn propose_motion(
+        &self,
+        collective: Collective,
+        threshold: u32,
+        call: RawCall,
+        status: TxStatus,
+    ) -> anyhow::Result<(TxInfo, Option<Motion>)> {
+        let metadata = self.as_connection().as_client().metadata();
+        let encoded = call.encode_with(&metadata)?;
+        let hash = ProposalHash::from(blake2_256(&encoded));
+        let length = encoded.len() as u32;
+        let tx = RawCall::new(
+            collective.pallet_name(),
+            "propose",
+            (Compact(threshold), Encoded(encoded), Compact(length)),
+        );
+
+        let tx_info = self.send_tx(tx, status).await?;
+        let events = self.get_tx_events(tx_info).await?;
+        let motion = match find_event::<Proposed>(&events, collective.pallet_name(), "Proposed")? {
+            Some(proposed) if proposed.proposal_hash != hash => {
+                return Err(anyhow!(
+                    "Proposed motion has hash {:?}, expected {hash:?}",
+                    proposed.proposal_hash
+                ))
+            }
+            Some(proposed) => Some(Motion {
+                hash: proposed.proposal_hash,
+                index: proposed.proposal_index,
+                length,
+            }),
+            None => None,
+        };
+
+        Ok((tx_info, motion))
+    }
+
+    async fn execute_as_member(
+        &self,
+        collective: Collective,
+        call: RawCall,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let metadata = self.as_connection().as_client().metadata();
+        let encoded = call.encode_with(&metadata)?;
+        let length = encoded.len() as u32;
+        let tx = RawCall::new(
+            collective.pallet_name(),
+            "execute",
+            (Encoded(encoded), Compact(length)),
+        );
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn vote_motion(
+        &self,
+        collective: Collective,
+        hash: ProposalHash,
+        index: u32,
+        approve: bool,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new(
+            collective.pallet_name(),
+            "vote",
+            (hash, Compact(index), approve),
+        );
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn close_motion(
+        &self,
+        collective: Collective,
+        motion: Motion,
+        weight_bound: Weight,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new(
+            collective.pallet_name(),
+            "close",
+            (
+                motion.hash,
+                Compact(motion.index),
+                weight_bound,
+                Compact(motion.length),
+            ),
+        );
+
+        self.send_tx(tx, status).await
+    }
+}
+
+/// A signed connection of a collective member, which dispatches calls with the origin of the
+/// collective instead of the sudo account.
+///
+/// Every call is wrapped into a motion with a threshold of `numerator / denominator` of the
+/// members, rounded up, which matches the `EnsureProportionAtLeast` origins of the runtime. The
+/// defaults, council and 3/5, are what `AdminOrigin` and most other admin origins require.
+#[derive(Clone)]
+pub struct GovernanceConnection {
+    connection: SignedConnection,
+    collective: Collective,
+    numerator: u32,
+    denominator: u32,
+}
+
+impl GovernanceConnection {
+    /// Creates a connection proposing motions to the council with a 3/5 threshold.
+    /// * `connection` - a signed connection of a council member
+    pub fn new(connection: SignedConnection) -> Self {
+        Self {
+            connection,
+            collective: Collective::Council,
+            numerator: 3,
+            denominator: 5,
+        }
+    }
+
+    /// Proposes motions to a given collective instead.
+    pub fn with_collective(mut self, collective: Collective) -> Self {
+        self.collective = collective;
+        self
+    }
+
+    /// Proposes motions with a threshold of `numerator / denominator` of the members instead.
+    pub fn with_proportion(mut self, numerator: u32, denominator: u32) -> Self {
+        self.numerator = numerator;
+        self.denominator = denominator.max(1);
+        self
+    }
+
+    /// Returns the collective motions are proposed to.
+    pub fn collective(&self) -> Collective {
+        self.collective
+    }
+
+    /// Returns the number of approvals a motion needs given the current members.
+    pub async fn threshold(&self) -> u32 {
+        let members = self
+            .connection
+            .collective_members(self.collective, None)
+            .await
+            .len() as u32;
+
+        (members * self.numerator).div_ceil(self.denominator).max(1)
+    }
+
+    /// Proposes `call` as a motion and votes for it. The motion still needs votes of other
+    /// members before it can be closed with [`GovernanceConnection::close`]. Returns `None` if
+    /// the threshold is a single vote, in which case the call got executed right away.
+    pub async fn propose(
+        &self,
+        call: impl Into<RawCall>,
+        status: TxStatus,
+    ) -> anyhow::Result<(TxInfo, Option<Motion>)> {
+        let threshold = self.threshold().await;
+        let (tx_info, motion) = self
+            .connection
+            .propose_motion(self.collective, threshold, call.into(), status)
+            .await?;
+
+        match motion {
+            Some(motion) => Ok((self.vote(motion, true, status).await?, Some(motion))),
+            None => Ok((tx_info, None)),
+        }
+    }
+
+    /// Votes on a motion proposed by another member.
+    pub async fn vote(
+        &self,
+        motion: Motion,
+        approve: bool,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        self.connection
+            .vote_motion(self.collective, motion.hash, motion.index, approve, status)
+            .await
+    }
+
+    /// Closes a motion, executing the call if it gathered enough votes.
+    pub async fn close(&self, motion: Motion, status: TxStatus) -> anyhow::Result<TxInfo> {
+        self.connection
+            .close_motion(self.collective, motion, DEFAULT_CLOSE_WEIGHT, status)
+            .await
+    }
+}
+
+impl crate::AsSigned for GovernanceConnection {
+    fn as_signed(&self) -> &SignedConnection {
+        &self.connection
+    }
+}
@@ -0,0 +1,348 @@
+use anyhow::{anyhow, bail};
+use codec::{Compact, Decode, Encode, Output};
+
+use crate::{
+    connections::TxInfo,
+    raw::{storage_key, storage_map_key, twox_64_concat, RawCall},
+    sp_core::{blake2_256, H256},
+    utility::BlocksApi,
+    waiting::{AlephWaiting, BlockStatus},
+    AsConnection, Balance, BlockHash, BlockNumber, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// An alias for an index of a referendum.
+pub type ReferendumIndex = u32;
+/// An alias for an index of a public proposal.
+pub type PropIndex = u32;
+
+/// A call referenced by a proposal, as `frame_support::traits::Bounded`.
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub enum BoundedCall {
+    /// Hash of a call noted with the legacy preimage api.
+    Legacy {
+        /// Hash of the encoded call.
+        hash: H256,
+    },
+    /// A short encoded call stored in place.
+    Inline(Vec<u8>),
+    /// Hash and length of a call noted with pallet preimage.
+    Lookup {
+        /// Hash of the encoded call.
+        hash: H256,
+        /// Length of the encoded call.
+        len: u32,
+    },
+}
+
+impl BoundedCall {
+    /// Returns a reference to an encoded call, which has to be noted with
+    /// [`PreimageUserApi::note_preimage`](crate::pallets::preimage::PreimageUserApi::note_preimage).
+    pub fn lookup(encoded_call: &[u8]) -> Self {
+        BoundedCall::Lookup {
+            hash: blake2_256(encoded_call).into(),
+            len: encoded_call.len() as u32,
+        }
+    }
+
+    /// Returns hash of the referenced call.
+    pub fn hash(&self) -> H256 {
+        match self {
+            BoundedCall::Legacy { hash } | BoundedCall::Lookup { hash, .. } => *hash,
+            BoundedCall::Inline(encoded) => blake2_256(encoded).into(),
+        }
+    }
+}
+
+/// How a referendum passes, as `pallet_democracy::VoteThreshold`.
+#[derive(Copy, Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub enum VoteThreshold {
+    /// A supermajority of approvals is needed to pass.
+    SuperMajorityApprove,
+    /// A supermajority of rejects is needed to fail.
+    SuperMajorityAgainst,
+    /// A simple majority of approvals is needed to pass.
+    SimpleMajority,
+}
+
+/// Votes of a referendum weighted by conviction.
+#[derive(Copy, Clone, Debug, Decode, Eq, PartialEq)]
+pub struct Tally {
+    /// Votes in favour.
+    pub ayes: Balance,
+    /// Votes against.
+    pub nays: Balance,
+    /// Total balance that voted, without conviction.
+    pub turnout: Balance,
+}
+
+/// Status of an ongoing referendum.
+#[derive(Clone, Debug, Decode, Eq, PartialEq)]
+pub struct ReferendumStatus {
+    /// The block at which voting ends.
+    pub end: BlockNumber,
+    /// The proposal being voted on.
+    pub proposal: BoundedCall,
+    /// How the referendum passes.
+    pub threshold: VoteThreshold,
+    /// Number of blocks between passing and enactment.
+    pub delay: BlockNumber,
+    /// Current votes.
+    pub tally: Tally,
+}
+
+/// Info of a referendum, as in pallet democracy storage `ReferendumInfoOf`.
+#[derive(Clone, Debug, Decode, Eq, PartialEq)]
+pub enum ReferendumInfo {
+    /// The referendum is being voted on.
+    Ongoing(ReferendumStatus),
+    /// The referendum is over.
+    Finished {
+        /// Whether the referendum passed.
+        approved: bool,
+        /// The block at which voting ended.
+        end: BlockNumber,
+    },
+}
+
+/// Multiplier of the balance of a vote, paid for with a longer lock.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Conviction {
+    /// 0.1x votes, unlocked.
+    None,
+    /// 1x votes, locked for an enactment period following a successful vote.
+    Locked1x,
+    /// 2x votes, locked for 2x enactment periods following a successful vote.
+    Locked2x,
+    /// 3x votes, locked for 4x enactment periods following a successful vote.
+    Locked3x,
+    /// 4x votes, locked for 8x enactment periods following a successful vote.
+    Locked4x,
+    /// 5x votes, locked for 16x enactment periods following a successful vote.
+    Locked5x,
+    /// 6x votes, locked for 32x enactment periods following a successful vote.
+    Locked6x,
+}
+
+/// A vote with conviction, encoded into a single byte like `pallet_democracy::Vote`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Vote {
+    /// Whether the vote is in favour.
+    pub aye: bool,
+    /// Conviction of the vote.
+    pub conviction: Conviction,
+}
+
+impl Encode for Vote {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        let aye = if self.aye { 0b1000_0000 } else { 0 };
+        dest.push_byte(self.conviction as u8 | aye);
+    }
+}
+
+/// A vote of an account, as `pallet_democracy::AccountVote`.
+#[derive(Copy, Clone, Debug, Encode, Eq, PartialEq)]
+pub enum AccountVote {
+    /// A vote with conviction.
+    Standard {
+        /// The vote.
+        vote: Vote,
+        /// Balance backing the vote.
+        balance: Balance,
+    },
+    /// A vote split between both sides, without conviction.
+    Split {
+        /// Balance in favour.
+        aye: Balance,
+        /// Balance against.
+        nay: Balance,
+    },
+}
+
+/// Returns the [`external_propose_majority`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.external_propose_majority)
+/// call, which the council proposes as a motion, e.g. with
+/// [`GovernanceConnection`](crate::pallets::collective::GovernanceConnection).
+pub fn external_propose_majority(proposal: BoundedCall) -> RawCall {
+    RawCall::new("Democracy", "external_propose_majority", (proposal,))
+}
+
+/// Returns the [`fast_track`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.fast_track)
+/// call, which the technical committee proposes as a motion.
+pub fn fast_track(proposal_hash: H256, voting_period: BlockNumber, delay: BlockNumber) -> RawCall {
+    RawCall::new(
+        "Democracy",
+        "fast_track",
+        (proposal_hash, voting_period, delay),
+    )
+}
+
+/// Pallet democracy read-only api.
+#[async_trait::async_trait]
+pub trait DemocracyApi {
+    /// Returns storage `referendum_count`, i.e. the index of the next referendum.
+    /// * `at` - an optional block hash to query state from
+    async fn referendum_count(&self, at: Option<BlockHash>) -> ReferendumIndex;
+
+    /// Returns storage `referendum_info_of` for a given referendum.
+    /// * `index` - index of the referendum
+    /// * `at` - an optional block hash to query state from
+    async fn referendum_info(
+        &self,
+        index: ReferendumIndex,
+        at: Option<BlockHash>,
+    ) -> Option<ReferendumInfo>;
+
+    /// Returns storage `public_prop_count`, i.e. the index of the next public proposal.
+    /// * `at` - an optional block hash to query state from
+    async fn public_prop_count(&self, at: Option<BlockHash>) -> PropIndex;
+
+    /// Waits until a referendum is over and, if it passed, until its proposal is enacted.
+    /// Returns whether the referendum passed.
+    /// * `index` - index of the referendum
+    /// * `status` - a [`BlockStatus`] of the blocks to wait for
+    async fn wait_for_referendum_enactment(
+        &self,
+        index: ReferendumIndex,
+        status: BlockStatus,
+    ) -> anyhow::Result<bool>;
+}
+
+/// Pallet democracy api.
+#[async_trait::async_trait]
+pub trait DemocracyUserApi {
+    /// API for [`propose`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.propose) call.
+    async fn propose_referendum(
+        &self,
+        proposal: BoundedCall,
+        value: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`second`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.second) call.
+    async fn second(&self, proposal: PropIndex, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`vote`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.vote) call.
+    async fn vote_referendum(
+        &self,
+        index: ReferendumIndex,
+        vote: AccountVote,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`remove_vote`](https://paritytech.github.io/substrate/master/pallet_democracy/pallet/struct.Pallet.html#method.remove_vote) call.
+    async fn remove_referendum_vote(
+        &self,
+        index: ReferendumIndex,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi + AsConnection> DemocracyApi for C {
+    async fn referendum_count(&self, at: Option<BlockHash>) -> ReferendumIndex {
+        let key = storage_key("Democracy", "ReferendumCount");
+
+        self.get_raw_storage_entry_maybe(key, at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn referendum_info(
+        &self,
+        index: ReferendumIndex,
+        at: Option<BlockHash>,
+    ) -> Option<ReferendumInfo> {
+        let key = storage_map_key("Democracy", "ReferendumInfoOf", twox_64_concat(&index));
+
+        self.get_raw_storage_entry_maybe(key, at).await
+    }
+
+    async fn public_prop_count(&self, at: Option<BlockHash>) -> PropIndex {
+        let key = storage_key("Democracy", "PublicPropCount");
+
+        self.get_raw_storage_entry_maybe(key, at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn wait_for_referendum_enactment(
+        &self,
+        index: ReferendumIndex,
+        status: BlockStatus,
+    ) -> anyhow::Result<bool> {
+        let ongoing = match self.referendum_info(index, None).await {
+            Some(ReferendumInfo::Ongoing(ongoing)) => ongoing,
+            Some(ReferendumInfo::Finished {
+                approved: false, ..
+            }) => return Ok(false),
+            // The delay is only known while the referendum is ongoing.
+            Some(ReferendumInfo::Finished { end, .. }) => {
+                let before_end = self
+                    .get_block_hash(end.saturating_sub(1))
+                    .await?
+                    .ok_or_else(|| anyhow!("Block {} does not exist", end.saturating_sub(1)))?;
+                match self.referendum_info(index, Some(before_end)).await {
+                    Some(ReferendumInfo::Ongoing(ongoing)) => ongoing,
+                    _ => bail!("Referendum {} was not ongoing at block {}", index, end - 1),
+                }
+            }
+            None => bail!("Referendum {} does not exist", index),
+        };
+
+        self.wait_for_block(|n| n >= ongoing.end, status).await;
+        let end_hash = self
+            .get_block_hash(ongoing.end)
+            .await?
+            .ok_or_else(|| anyhow!("Block {} does not exist", ongoing.end))?;
+        match self.referendum_info(index, Some(end_hash)).await {
+            Some(ReferendumInfo::Finished { approved: true, .. }) => {}
+            Some(ReferendumInfo::Finished { .. }) => return Ok(false),
+            _ => bail!("Referendum {} was cancelled", index),
+        }
+
+        let enactment = ongoing.end + ongoing.delay;
+        self.wait_for_block(|n| n >= enactment, status).await;
+
+        Ok(true)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> DemocracyUserApi for S {
+    async fn propose_referendum(
+        &self,
+        proposal: BoundedCall,
+        value: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("Democracy", "propose", (proposal, Compact(value)));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn second(&self, proposal: PropIndex, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("Democracy", "second", (Compact(proposal),));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn vote_referendum(
+        &self,
+        index: ReferendumIndex,
+        vote: AccountVote,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("Democracy", "vote", (Compact(index), vote));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn remove_referendum_vote(
+        &self,
+        index: ReferendumIndex,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("Democracy", "remove_vote", (index,));
+
+        self.send_tx(tx, status).await
+    }
+}
@@ -0,0 +1,156 @@
+use codec::{Compact, Decode, Encode};
+
+use crate::{
+    connections::TxInfo,
+    raw::{storage_key, storage_map_key, twox_64_concat, RawCall},
+    AccountId, Balance, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+const PALLET: &str = "CouncilElections";
+
+/// A council member or runner-up, as in pallet elections-phragmen storage `members`.
+#[derive(Clone, Debug, Decode, Eq, PartialEq)]
+pub struct SeatHolder {
+    /// The holder.
+    pub who: AccountId,
+    /// The total backing stake.
+    pub stake: Balance,
+    /// The deposit held on-chain.
+    pub deposit: Balance,
+}
+
+/// A voter, as in pallet elections-phragmen storage `voting`.
+#[derive(Clone, Debug, Default, Decode, Eq, PartialEq)]
+pub struct Voter {
+    /// The candidates being backed.
+    pub votes: Vec<AccountId>,
+    /// The stake placed on the votes.
+    pub stake: Balance,
+    /// The deposit reserved for the votes.
+    pub deposit: Balance,
+}
+
+/// The role an account renounces, see
+/// [`renounce_candidacy`](https://paritytech.github.io/substrate/master/pallet_elections_phragmen/pallet/struct.Pallet.html#method.renounce_candidacy).
+#[derive(Copy, Clone, Debug, Encode, Eq, PartialEq)]
+pub enum Renouncing {
+    /// A council member.
+    Member,
+    /// A runner-up.
+    RunnerUp,
+    /// A candidate, with the current number of candidates.
+    Candidate(#[codec(compact)] u32),
+}
+
+/// Pallet elections-phragmen read-only api.
+#[async_trait::async_trait]
+pub trait PhragmenElectionsApi {
+    /// Returns storage `members`, i.e. the current council members.
+    /// * `at` - an optional block hash to query state from
+    async fn council_members(&self, at: Option<BlockHash>) -> Vec<SeatHolder>;
+
+    /// Returns storage `runners_up`.
+    /// * `at` - an optional block hash to query state from
+    async fn council_runners_up(&self, at: Option<BlockHash>) -> Vec<SeatHolder>;
+
+    /// Returns storage `candidates` together with their deposits.
+    /// * `at` - an optional block hash to query state from
+    async fn council_candidates(&self, at: Option<BlockHash>) -> Vec<(AccountId, Balance)>;
+
+    /// Returns storage `voting` of a given account.
+    /// * `voter` - the account
+    /// * `at` - an optional block hash to query state from
+    async fn council_voting(&self, voter: AccountId, at: Option<BlockHash>) -> Voter;
+}
+
+/// Pallet elections-phragmen api.
+#[async_trait::async_trait]
+pub trait PhragmenElectionsUserApi {
+    /// API for [`vote`](https://paritytech.github.io/substrate/master/pallet_elections_phragmen/pallet/struct.Pallet.html#method.vote) call.
+    async fn vote_council(
+        &self,
+        candidates: Vec<AccountId>,
+        stake: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`remove_voter`](https://paritytech.github.io/substrate/master/pallet_elections_phragmen/pallet/struct.Pallet.html#method.remove_voter) call.
+    async fn remove_council_voter(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`submit_candidacy`](https://paritytech.github.io/substrate/master/pallet_elections_phragmen/pallet/struct.Pallet.html#method.submit_candidacy) call.
+    /// The current number of candidates is read from storage.
+    async fn submit_candidacy(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`renounce_candidacy`](https://paritytech.github.io/substrate/master/pallet_elections_phragmen/pallet/struct.Pallet.html#method.renounce_candidacy) call.
+    async fn renounce_candidacy(
+        &self,
+        renouncing: Renouncing,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> PhragmenElectionsApi for C {
+    async fn council_members(&self, at: Option<BlockHash>) -> Vec<SeatHolder> {
+        self.get_raw_storage_entry_maybe(storage_key(PALLET, "Members"), at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn council_runners_up(&self, at: Option<BlockHash>) -> Vec<SeatHolder> {
+        self.get_raw_storage_entry_maybe(storage_key(PALLET, "RunnersUp"), at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn council_candidates(&self, at: Option<BlockHash>) -> Vec<(AccountId, Balance)> {
+        self.get_raw_storage_entry_maybe(storage_key(PALLET, "Candidates"), at)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn council_voting(&self, voter: AccountId, at: Option<BlockHash>) -> Voter {
+        let key = storage_map_key(PALLET, "Voting", twox_64_concat(&voter));
+
+        self.get_raw_storage_entry_maybe(key, at)
+            .await
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> PhragmenElectionsUserApi for S {
+    async fn vote_council(
+        &self,
+        candidates: Vec<AccountId>,
+        stake: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new(PALLET, "vote", (candidates, Compact(stake)));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn remove_council_voter(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new(PALLET, "remove_voter", ());
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn submit_candidacy(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let candidate_count = self.council_candidates(None).await.len() as u32;
+        let tx = RawCall::new(PALLET, "submit_candidacy", (Compact(candidate_count),));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn renounce_candidacy(
+        &self,
+        renouncing: Renouncing,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new(PALLET, "renounce_candidacy", (renouncing,));
+
+        self.send_tx(tx, status).await
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::anyhow;
+use codec::Encode;
+use subxt::{ext::sp_core::Bytes, rpc_params};
+
+use crate::{
+    connections::TxInfo,
+    raw::{find_event, RawCall},
+    sp_core::{H160, H256, U256},
+    utility::BlocksApi,
+    AsConnection, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// A call of an EVM contract made from a native account, as
+/// `primitives::ethereum_checked::CheckedEthereumTx`.
+#[derive(Clone, Debug, Encode, Eq, PartialEq)]
+pub struct CheckedEthereumTx {
+    /// Gas limit of the call.
+    pub gas_limit: U256,
+    /// The called contract.
+    pub target: H160,
+    /// Value transferred with the call.
+    pub value: U256,
+    /// Encoded call data, at most 64 KiB.
+    pub input: Vec<u8>,
+    /// Optional access list, as specified in EIP-2930.
+    pub maybe_access_list: Option<Vec<(H160, Vec<H256>)>>,
+}
+
+/// A deployment of an EVM contract made from a native account, as
+/// `primitives::ethereum_checked::CheckedEthereumCreateTx`.
+#[derive(Clone, Debug, Encode, Eq, PartialEq)]
+pub struct CheckedEthereumCreateTx {
+    /// Gas limit of the deployment.
+    pub gas_limit: U256,
+    /// Value transferred to the contract.
+    pub value: U256,
+    /// Contract bytecode together with encoded constructor arguments, at most 64 KiB.
+    pub init: Vec<u8>,
+    /// Optional access list, as specified in EIP-2930.
+    pub maybe_access_list: Option<Vec<(H160, Vec<H256>)>>,
+}
+
+/// Returns the [`create`](https://paritytech.github.io/frontier/rustdocs/pallet_evm/pallet/struct.Pallet.html#method.create)
+/// call of pallet evm, which deploys a contract from `source`.
+///
+/// The runtime only accepts it from the root origin, so it has to be dispatched by governance,
+/// e.g. with [`GovernanceConnection`](crate::pallets::collective::GovernanceConnection). Native
+/// signers deploy contracts with [`EthereumCheckedUserApi::ethereum_checked_create`] instead.
+/// * `source` - the deployer, whose nonce determines the address of the contract
+/// * `init` - contract bytecode together with encoded constructor arguments
+/// * `value` - value transferred to the contract
+/// * `gas_limit` - gas limit of the deployment
+/// * `max_fee_per_gas` - at least the current base fee of the chain
+pub fn create(
+    source: H160,
+    init: Vec<u8>,
+    value: U256,
+    gas_limit: u64,
+    max_fee_per_gas: U256,
+) -> RawCall {
+    RawCall::new(
+        "EVM",
+        "create",
+        (
+            source,
+            init,
+            value,
+            gas_limit,
+            max_fee_per_gas,
+            None::<U256>,
+            None::<U256>,
+            Vec::<(H160, Vec<H256>)>::new(),
+        ),
+    )
+}
+
+/// Pallet evm read-only api, backed by the `EthereumRuntimeRPCApi` runtime api.
+#[async_trait::async_trait]
+pub trait EvmApi {
+    /// Returns the balance of an EVM account.
+    /// * `address` - the account
+    /// * `at` - an optional block hash to query state from
+    async fn evm_balance(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<U256>;
+
+    /// Returns the nonce of an EVM account.
+    /// * `address` - the account
+    /// * `at` - an optional block hash to query state from
+    async fn evm_nonce(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<U256>;
+
+    /// Returns the code of an EVM contract, empty for accounts without code.
+    /// * `address` - the contract
+    /// * `at` - an optional block hash to query state from
+    async fn evm_code(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<Vec<u8>>;
+
+    /// Returns a storage slot of an EVM contract.
+    /// * `address` - the contract
+    /// * `index` - index of the slot
+    /// * `at` - an optional block hash to query state from
+    async fn evm_storage(
+        &self,
+        address: H160,
+        index: U256,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<H256>;
+}
+
+/// Pallet ethereum-checked api.
+#[async_trait::async_trait]
+pub trait EthereumCheckedUserApi {
+    /// API for `transact` call of pallet ethereum-checked, which calls an EVM contract from the
+    /// EVM address mapped to the signer.
+    async fn ethereum_checked_transact(
+        &self,
+        tx: CheckedEthereumTx,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `create` call of pallet ethereum-checked, which deploys an EVM contract from the
+    /// EVM address mapped to the signer. Returns the address of the deployed contract, so
+    /// `status` has to be one for which the tx is already in a block.
+    async fn ethereum_checked_create(
+        &self,
+        tx: CheckedEthereumCreateTx,
+        status: TxStatus,
+    ) -> anyhow::Result<(TxInfo, H160)>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> EvmApi for C {
+    async fn evm_balance(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<U256> {
+        let params = rpc_params![
+            "EthereumRuntimeRPCApi_account_basic",
+            Bytes(address.encode()),
+            at
+        ];
+        // `fp_evm::Account` encodes the balance before the nonce.
+        let (balance, _nonce): (U256, U256) =
+            self.rpc_call("state_call".to_string(), params).await?;
+
+        Ok(balance)
+    }
+
+    async fn evm_nonce(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<U256> {
+        let params = rpc_params![
+            "EthereumRuntimeRPCApi_account_basic",
+            Bytes(address.encode()),
+            at
+        ];
+        let (_balance, nonce): (U256, U256) =
+            self.rpc_call("state_call".to_string(), params).await?;
+
+        Ok(nonce)
+    }
+
+    async fn evm_code(&self, address: H160, at: Option<BlockHash>) -> anyhow::Result<Vec<u8>> {
+        let params = rpc_params![
+            "EthereumRuntimeRPCApi_account_code_at",
+            Bytes(address.encode()),
+            at
+        ];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+
+    async fn evm_storage(
+        &self,
+        address: H160,
+        index: U256,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<H256> {
+        let params = rpc_params![
+            "EthereumRuntimeRPCApi_storage_at",
+            Bytes((address, index).encode()),
+            at
+        ];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi + AsConnection> EthereumCheckedUserApi for S {
+    async fn ethereum_checked_transact(
+        &self,
+        tx: CheckedEthereumTx,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("EthereumChecked", "transact", (tx,));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn ethereum_checked_create(
+        &self,
+        tx: CheckedEthereumCreateTx,
+        status: TxStatus,
+    ) -> anyhow::Result<(TxInfo, H160)> {
+        let tx = RawCall::new("EthereumChecked", "create", (tx,));
+
+        let tx_info = self.send_tx(tx, status).await?;
+        let events = self.get_tx_events(tx_info).await?;
+        // `Executed` starts with `from`, `to` and `transaction_hash`, followed by the exit reason,
+        // of which only the variant matters, `Succeed` being the first one.
+        let (_from, to, _hash, exit_reason) =
+            find_event::<(H160, H160, H256, u8)>(&events, "EthereumChecked", "Executed")?
+                .ok_or_else(|| anyhow!("Executed event not found"))?;
+        if exit_reason != 0 {
+            return Err(anyhow!("Deployment of the contract at {to:?} failed"));
+        }
+
+        Ok((tx_info, to))
+    }
+}
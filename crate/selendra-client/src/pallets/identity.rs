@@ -0,0 +1,308 @@
+use subxt::utils::{MultiAddress, Static};
+
+use crate::{
+    api,
+    connections::TxInfo,
+    pallet_identity::{
+        legacy::IdentityInfo,
+        types::{Data, Judgement, RegistrarInfo, Registration},
+    },
+    sp_core::H256,
+    AccountId, Balance, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// An alias for an index of a registrar, i.e. its position in [`IdentityApi::get_registrars`].
+pub type RegistrarIndex = u32;
+
+/// Pallet identity read-only api.
+#[async_trait::async_trait]
+pub trait IdentityApi {
+    /// Returns [`identity_of`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/type.IdentityOf.html)
+    /// for a given account, i.e. its identity together with judgements of registrars.
+    /// * `account` - an account id
+    /// * `at` - optional hash of a block to query state from
+    async fn get_identity(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<Registration<Balance, IdentityInfo>>;
+
+    /// Returns [`super_of`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/type.SuperOf.html)
+    /// for a given sub-account, i.e. its parent account and the name of the sub-account.
+    /// * `sub` - an account id of a sub-account
+    /// * `at` - optional hash of a block to query state from
+    async fn get_super_of(
+        &self,
+        sub: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<(AccountId, Data)>;
+
+    /// Returns sub-accounts of a given account, from [`subs_of`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/type.SubsOf.html).
+    /// * `account` - an account id
+    /// * `at` - optional hash of a block to query state from
+    async fn get_subs_of(&self, account: AccountId, at: Option<BlockHash>) -> Vec<AccountId>;
+
+    /// Returns [`registrars`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/type.Registrars.html),
+    /// indexed by [`RegistrarIndex`]. Removed registrars are `None`.
+    /// * `at` - optional hash of a block to query state from
+    async fn get_registrars(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Vec<Option<RegistrarInfo<Balance, AccountId, u64>>>;
+}
+
+/// Pallet identity api.
+#[async_trait::async_trait]
+pub trait IdentityUserApi {
+    /// API for [`set_identity`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.set_identity) call.
+    /// Fields of `info` can be built from strings with `Data::try_from`, the rest can be taken
+    /// from `IdentityInfo::default()`.
+    async fn set_identity(&self, info: IdentityInfo, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`clear_identity`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.clear_identity) call.
+    async fn clear_identity(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`request_judgement`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.request_judgement) call.
+    /// * `registrar` - index of the registrar
+    /// * `max_fee` - the most the signer is willing to pay the registrar
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    async fn request_judgement(
+        &self,
+        registrar: RegistrarIndex,
+        max_fee: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`cancel_request`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.cancel_request) call.
+    async fn cancel_judgement_request(
+        &self,
+        registrar: RegistrarIndex,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`provide_judgement`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.provide_judgement) call.
+    /// The signer has to be the registrar under `registrar` index.
+    /// * `registrar` - index of the registrar
+    /// * `target` - the account whose identity is judged
+    /// * `judgement` - the judgement
+    /// * `identity` - hash of the encoded judged [`IdentityInfo`], so that the judgement is not
+    ///   applied to an identity changed in the meantime
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    async fn provide_judgement(
+        &self,
+        registrar: RegistrarIndex,
+        target: AccountId,
+        judgement: Judgement<Balance>,
+        identity: H256,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`set_fee`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.set_fee) call.
+    /// The signer has to be the registrar under `registrar` index.
+    async fn set_registrar_fee(
+        &self,
+        registrar: RegistrarIndex,
+        fee: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`set_subs`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.set_subs) call,
+    /// which replaces all sub-accounts of the signer.
+    async fn set_subs(
+        &self,
+        subs: Vec<(AccountId, Data)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`add_sub`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.add_sub) call.
+    async fn add_sub(&self, sub: AccountId, name: Data, status: TxStatus)
+        -> anyhow::Result<TxInfo>;
+
+    /// API for [`rename_sub`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.rename_sub) call.
+    async fn rename_sub(
+        &self,
+        sub: AccountId,
+        name: Data,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`remove_sub`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.remove_sub) call.
+    async fn remove_sub(&self, sub: AccountId, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`quit_sub`](https://paritytech.github.io/substrate/master/pallet_identity/pallet/struct.Pallet.html#method.quit_sub) call,
+    /// made by a sub-account to leave its parent.
+    async fn quit_sub(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> IdentityApi for C {
+    async fn get_identity(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<Registration<Balance, IdentityInfo>> {
+        let addrs = api::storage().identity().identity_of(Static(account));
+
+        self.get_storage_entry_maybe(&addrs, at)
+            .await
+            .map(|(registration, _username)| registration)
+    }
+
+    async fn get_super_of(
+        &self,
+        sub: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<(AccountId, Data)> {
+        let addrs = api::storage().identity().super_of(Static(sub));
+
+        self.get_storage_entry_maybe(&addrs, at)
+            .await
+            .map(|(parent, name)| (parent.0, name))
+    }
+
+    async fn get_subs_of(&self, account: AccountId, at: Option<BlockHash>) -> Vec<AccountId> {
+        let addrs = api::storage().identity().subs_of(Static(account));
+        let (_deposit, subs) = self.get_storage_entry(&addrs, at).await;
+
+        subs.0.into_iter().map(|sub| sub.0).collect()
+    }
+
+    async fn get_registrars(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Vec<Option<RegistrarInfo<Balance, AccountId, u64>>> {
+        let addrs = api::storage().identity().registrars();
+
+        self.get_storage_entry(&addrs, at)
+            .await
+            .0
+            .into_iter()
+            .map(|registrar| {
+                registrar.map(|info| RegistrarInfo {
+                    account: info.account.0,
+                    fee: info.fee,
+                    fields: info.fields,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> IdentityUserApi for S {
+    async fn set_identity(&self, info: IdentityInfo, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().set_identity(info);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn clear_identity(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().clear_identity();
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn request_judgement(
+        &self,
+        registrar: RegistrarIndex,
+        max_fee: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().request_judgement(registrar, max_fee);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn cancel_judgement_request(
+        &self,
+        registrar: RegistrarIndex,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().cancel_request(registrar);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn provide_judgement(
+        &self,
+        registrar: RegistrarIndex,
+        target: AccountId,
+        judgement: Judgement<Balance>,
+        identity: H256,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().provide_judgement(
+            registrar,
+            MultiAddress::Id(Static(target)),
+            judgement,
+            identity,
+        );
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn set_registrar_fee(
+        &self,
+        registrar: RegistrarIndex,
+        fee: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().set_fee(registrar, fee);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn set_subs(
+        &self,
+        subs: Vec<(AccountId, Data)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let subs = subs
+            .into_iter()
+            .map(|(sub, name)| (Static(sub), name))
+            .collect();
+        let tx = api::tx().identity().set_subs(subs);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn add_sub(
+        &self,
+        sub: AccountId,
+        name: Data,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx()
+            .identity()
+            .add_sub(MultiAddress::Id(Static(sub)), name);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn rename_sub(
+        &self,
+        sub: AccountId,
+        name: Data,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx()
+            .identity()
+            .rename_sub(MultiAddress::Id(Static(sub)), name);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn remove_sub(&self, sub: AccountId, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = api::tx()
+            .identity()
+            .remove_sub(MultiAddress::Id(Static(sub)));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn quit_sub(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().identity().quit_sub();
+
+        self.send_tx(tx, status).await
+    }
+}
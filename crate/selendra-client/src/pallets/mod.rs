@@ -4,17 +4,31 @@ pub mod aleph;
 pub mod author;
 /// Pallet balances API
 pub mod balances;
+/// Pallet collective API, for the council and the technical committee
+pub mod collective;
 /// Pallet committee-management API
 pub mod committee_management;
 /// Pallet contracts API
 pub mod contract;
+/// Pallet democracy API
+pub mod democracy;
 /// Pallet elections API
 pub mod elections;
+/// Pallet elections-phragmen API, for electing the council
+pub mod elections_phragmen;
+/// Pallets evm and ethereum-checked API
+pub mod evm;
 
 /// Pallet transaction payment API
 pub mod fee;
+/// Pallet identity API
+pub mod identity;
 /// Pallet multisig API
 pub mod multisig;
+/// Pallet nomination pools API
+pub mod nomination_pools;
+/// Pallet preimage API
+pub mod preimage;
 /// Pallet Proxy API
 pub mod proxy;
 /// Pallet safe-mode API
@@ -33,5 +47,7 @@ pub mod treasury;
 pub mod utility;
 /// Pallet vesting API
 pub mod vesting;
+/// Pallet xvm API, for calls across the EVM and ink! contracts
+pub mod xvm;
 /// Pallet unified accounts API for native ↔ EVM account mapping
 pub mod unified_accounts;
@@ -0,0 +1,361 @@
+use codec::Encode;
+use subxt::{
+    ext::sp_core::Bytes,
+    rpc_params,
+    utils::{MultiAddress, Static},
+};
+
+use crate::{
+    api,
+    connections::TxInfo,
+    pallet_nomination_pools::{BondExtra, BondedPoolInner, PoolMember, RewardPool},
+    AccountId, Balance, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// An alias for an id of a nomination pool.
+pub type PoolId = u32;
+
+/// Pallet nomination pools read-only api.
+#[async_trait::async_trait]
+pub trait NominationPoolsApi {
+    /// Returns [`pool_members`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.PoolMembers.html) for a given account.
+    /// * `member` - an account id of a pool member
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pool_member(&self, member: AccountId, at: Option<BlockHash>)
+        -> Option<PoolMember>;
+
+    /// Returns [`bonded_pools`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.BondedPools.html) for a given pool.
+    /// * `pool_id` - an id of the pool
+    /// * `at` - optional hash of a block to query state from
+    async fn get_bonded_pool(
+        &self,
+        pool_id: PoolId,
+        at: Option<BlockHash>,
+    ) -> Option<BondedPoolInner>;
+
+    /// Returns [`reward_pools`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.RewardPools.html) for a given pool.
+    /// * `pool_id` - an id of the pool
+    /// * `at` - optional hash of a block to query state from
+    async fn get_reward_pool(&self, pool_id: PoolId, at: Option<BlockHash>) -> Option<RewardPool>;
+
+    /// Returns [`metadata`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.Metadata.html) of a given pool, empty if none was set.
+    /// * `pool_id` - an id of the pool
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pool_metadata(&self, pool_id: PoolId, at: Option<BlockHash>) -> Vec<u8>;
+
+    /// Returns [`last_pool_id`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.LastPoolId.html),
+    /// i.e. the id of the most recently created pool.
+    /// * `at` - optional hash of a block to query state from
+    async fn get_last_pool_id(&self, at: Option<BlockHash>) -> PoolId;
+
+    /// Returns [`min_join_bond`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.MinJoinBond.html).
+    /// * `at` - optional hash of a block to query state from
+    async fn get_min_join_bond(&self, at: Option<BlockHash>) -> Balance;
+
+    /// Returns [`min_create_bond`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/type.MinCreateBond.html).
+    /// * `at` - optional hash of a block to query state from
+    async fn get_min_create_bond(&self, at: Option<BlockHash>) -> Balance;
+
+    /// Returns rewards of a pool member that can be claimed with `claim_payout`, using the
+    /// `NominationPoolsApi_pending_rewards` runtime api. Zero for accounts that are not members.
+    /// * `member` - an account id of a pool member
+    /// * `at` - optional hash of a block to query state from
+    async fn get_pending_rewards(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance>;
+
+    /// Returns the balance of a pool member, bonded and unbonding, using the
+    /// `NominationPoolsApi_member_total_balance` runtime api.
+    /// * `member` - an account id of a pool member
+    /// * `at` - optional hash of a block to query state from
+    async fn get_member_total_balance(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance>;
+
+    /// Returns the balance `points` of a given pool are worth, using the
+    /// `NominationPoolsApi_points_to_balance` runtime api.
+    /// * `pool_id` - an id of the pool
+    /// * `points` - the points, e.g. [`PoolMember::points`]
+    /// * `at` - optional hash of a block to query state from
+    async fn get_points_to_balance(
+        &self,
+        pool_id: PoolId,
+        points: Balance,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance>;
+}
+
+/// Pallet nomination pools api.
+#[async_trait::async_trait]
+pub trait NominationPoolsUserApi {
+    /// API for [`create`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.create) call.
+    /// The signer becomes the depositor of the pool.
+    /// * `amount` - the initial bond of the depositor
+    /// * `root` - the account which can change roles and the state of the pool
+    /// * `nominator` - the account which nominates on behalf of the pool
+    /// * `bouncer` - the account which can block the pool and kick members
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    async fn create_pool(
+        &self,
+        amount: Balance,
+        root: AccountId,
+        nominator: AccountId,
+        bouncer: AccountId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`join`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.join) call.
+    async fn join_pool(
+        &self,
+        amount: Balance,
+        pool_id: PoolId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`bond_extra`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.bond_extra) call.
+    /// * `extra` - either more free balance of the signer or its pending rewards
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    async fn bond_extra_to_pool(
+        &self,
+        extra: BondExtra<Balance>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`unbond`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.unbond) call
+    /// of the signer's own points. Pending rewards are claimed along the way.
+    async fn unbond_from_pool(
+        &self,
+        unbonding_points: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`withdraw_unbonded`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.withdraw_unbonded) call
+    /// of the signer's own funds.
+    /// * `num_slashing_spans` - slashing spans of the pool's bonded account, an upper bound is fine
+    /// * `status` - a [`TxStatus`] of a tx to wait for
+    async fn withdraw_unbonded_from_pool(
+        &self,
+        num_slashing_spans: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`claim_payout`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.claim_payout) call.
+    async fn claim_pool_payout(&self, status: TxStatus) -> anyhow::Result<TxInfo>;
+
+    /// API for [`nominate`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.nominate) call.
+    /// The signer has to be the root or the nominator of the pool.
+    async fn nominate_for_pool(
+        &self,
+        pool_id: PoolId,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for [`set_metadata`](https://paritytech.github.io/substrate/master/pallet_nomination_pools/pallet/struct.Pallet.html#method.set_metadata) call.
+    /// The signer has to be the root or the depositor of the pool.
+    async fn set_pool_metadata(
+        &self,
+        pool_id: PoolId,
+        metadata: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> NominationPoolsApi for C {
+    async fn get_pool_member(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> Option<PoolMember> {
+        let addrs = api::storage()
+            .nomination_pools()
+            .pool_members(Static(member));
+
+        self.get_storage_entry_maybe(&addrs, at).await
+    }
+
+    async fn get_bonded_pool(
+        &self,
+        pool_id: PoolId,
+        at: Option<BlockHash>,
+    ) -> Option<BondedPoolInner> {
+        let addrs = api::storage().nomination_pools().bonded_pools(pool_id);
+
+        self.get_storage_entry_maybe(&addrs, at).await
+    }
+
+    async fn get_reward_pool(&self, pool_id: PoolId, at: Option<BlockHash>) -> Option<RewardPool> {
+        let addrs = api::storage().nomination_pools().reward_pools(pool_id);
+
+        self.get_storage_entry_maybe(&addrs, at).await
+    }
+
+    async fn get_pool_metadata(&self, pool_id: PoolId, at: Option<BlockHash>) -> Vec<u8> {
+        let addrs = api::storage().nomination_pools().metadata(pool_id);
+
+        self.get_storage_entry(&addrs, at).await.0
+    }
+
+    async fn get_last_pool_id(&self, at: Option<BlockHash>) -> PoolId {
+        let addrs = api::storage().nomination_pools().last_pool_id();
+
+        self.get_storage_entry(&addrs, at).await
+    }
+
+    async fn get_min_join_bond(&self, at: Option<BlockHash>) -> Balance {
+        let addrs = api::storage().nomination_pools().min_join_bond();
+
+        self.get_storage_entry(&addrs, at).await
+    }
+
+    async fn get_min_create_bond(&self, at: Option<BlockHash>) -> Balance {
+        let addrs = api::storage().nomination_pools().min_create_bond();
+
+        self.get_storage_entry(&addrs, at).await
+    }
+
+    async fn get_pending_rewards(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance> {
+        let params = rpc_params![
+            "NominationPoolsApi_pending_rewards",
+            Bytes(member.encode()),
+            at
+        ];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+
+    async fn get_member_total_balance(
+        &self,
+        member: AccountId,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance> {
+        let params = rpc_params![
+            "NominationPoolsApi_member_total_balance",
+            Bytes(member.encode()),
+            at
+        ];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+
+    async fn get_points_to_balance(
+        &self,
+        pool_id: PoolId,
+        points: Balance,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Balance> {
+        let params = rpc_params![
+            "NominationPoolsApi_points_to_balance",
+            Bytes((pool_id, points).encode()),
+            at
+        ];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> NominationPoolsUserApi for S {
+    async fn create_pool(
+        &self,
+        amount: Balance,
+        root: AccountId,
+        nominator: AccountId,
+        bouncer: AccountId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().nomination_pools().create(
+            amount,
+            MultiAddress::Id(Static(root)),
+            MultiAddress::Id(Static(nominator)),
+            MultiAddress::Id(Static(bouncer)),
+        );
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn join_pool(
+        &self,
+        amount: Balance,
+        pool_id: PoolId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().nomination_pools().join(amount, pool_id);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn bond_extra_to_pool(
+        &self,
+        extra: BondExtra<Balance>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().nomination_pools().bond_extra(extra);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn unbond_from_pool(
+        &self,
+        unbonding_points: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let member = MultiAddress::Id(Static(self.account_id().clone()));
+        let tx = api::tx()
+            .nomination_pools()
+            .unbond(member, unbonding_points);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn withdraw_unbonded_from_pool(
+        &self,
+        num_slashing_spans: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let member = MultiAddress::Id(Static(self.account_id().clone()));
+        let tx = api::tx()
+            .nomination_pools()
+            .withdraw_unbonded(member, num_slashing_spans);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn claim_pool_payout(&self, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().nomination_pools().claim_payout();
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn nominate_for_pool(
+        &self,
+        pool_id: PoolId,
+        validators: Vec<AccountId>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let validators = validators.into_iter().map(Static).collect();
+        let tx = api::tx().nomination_pools().nominate(pool_id, validators);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn set_pool_metadata(
+        &self,
+        pool_id: PoolId,
+        metadata: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = api::tx().nomination_pools().set_metadata(pool_id, metadata);
+
+        self.send_tx(tx, status).await
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    connections::TxInfo,
+    raw::{identity, storage_map_key, RawCall},
+    sp_core::{blake2_256, H256},
+    BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// Pallet preimage read-only api.
+#[async_trait::async_trait]
+pub trait PreimageApi {
+    /// Returns whether storage `request_status_for` has an entry for a given hash, i.e. the
+    /// preimage was noted or requested.
+    /// * `hash` - hash of the preimage
+    /// * `at` - an optional block hash to query state from
+    async fn is_preimage_noted(&self, hash: H256, at: Option<BlockHash>) -> bool;
+
+    /// Returns storage `preimage_for`.
+    /// * `hash` - hash of the preimage
+    /// * `len` - length of the preimage
+    /// * `at` - an optional block hash to query state from
+    async fn preimage(&self, hash: H256, len: u32, at: Option<BlockHash>) -> Option<Vec<u8>>;
+}
+
+/// Pallet preimage api.
+#[async_trait::async_trait]
+pub trait PreimageUserApi {
+    /// API for [`note_preimage`](https://paritytech.github.io/substrate/master/pallet_preimage/pallet/struct.Pallet.html#method.note_preimage) call.
+    /// Returns hash of the preimage together with the tx info.
+    async fn note_preimage(
+        &self,
+        bytes: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<(H256, TxInfo)>;
+
+    /// API for [`unnote_preimage`](https://paritytech.github.io/substrate/master/pallet_preimage/pallet/struct.Pallet.html#method.unnote_preimage) call.
+    async fn unnote_preimage(&self, hash: H256, status: TxStatus) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> PreimageApi for C {
+    async fn is_preimage_noted(&self, hash: H256, at: Option<BlockHash>) -> bool {
+        let key = storage_map_key("Preimage", "RequestStatusFor", identity(&hash));
+
+        // Only the variant index is decoded, as the deposit type depends on the runtime.
+        self.get_raw_storage_entry_maybe::<u8>(key, at)
+            .await
+            .is_some()
+    }
+
+    async fn preimage(&self, hash: H256, len: u32, at: Option<BlockHash>) -> Option<Vec<u8>> {
+        let key = storage_map_key("Preimage", "PreimageFor", identity(&(hash, len)));
+
+        self.get_raw_storage_entry_maybe(key, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> PreimageUserApi for S {
+    async fn note_preimage(
+        &self,
+        bytes: Vec<u8>,
+        status: TxStatus,
+    ) -> anyhow::Result<(H256, TxInfo)> {
+        let hash = blake2_256(&bytes).into();
+        let tx = RawCall::new("Preimage", "note_preimage", (bytes,));
+
+        Ok((hash, self.send_tx(tx, status).await?))
+    }
+
+    async fn unnote_preimage(&self, hash: H256, status: TxStatus) -> anyhow::Result<TxInfo> {
+        let tx = RawCall::new("Preimage", "unnote_preimage", (hash,));
+
+        self.send_tx(tx, status).await
+    }
+}
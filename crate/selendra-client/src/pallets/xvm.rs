@@ -0,0 +1,237 @@
+use anyhow::anyhow;
+use codec::{Compact, Decode, Encode};
+use subxt::{ext::sp_core::Bytes, rpc_params};
+
+use crate::{
+    connections::TxInfo,
+    pallets::{
+        contract::ContractsUserApi,
+        evm::{CheckedEthereumTx, EthereumCheckedUserApi},
+    },
+    sp_core::{H160, U256},
+    sp_weights::weight_v2::Weight,
+    AccountId, Balance, BlockHash, ConnectionApi, SignedConnectionApi, TxStatus,
+};
+
+/// A virtual machine of the runtime, as `primitives::xvm::VmId`.
+#[derive(Copy, Clone, Debug, Decode, Encode, Eq, PartialEq)]
+pub enum VmId {
+    /// The EVM, with contracts addressed by `H160`.
+    #[codec(index = 15)]
+    Evm,
+    /// The ink! contracts VM, with contracts addressed by `AccountId`.
+    #[codec(index = 31)]
+    Wasm,
+}
+
+/// Output of a successful XVM call.
+#[derive(Clone, Debug, Decode, Eq, PartialEq)]
+pub struct CallOutput {
+    /// Data returned by the called contract.
+    pub output: Vec<u8>,
+    /// Weight used by the call.
+    pub used_weight: Weight,
+}
+
+/// A failed XVM call, as `primitives::xvm::CallFailure`.
+#[derive(Clone, Debug, Decode, Eq, PartialEq, thiserror::Error)]
+#[error("{reason} (used weight: {used_weight:?})")]
+pub struct CallFailure {
+    /// Why the call failed.
+    pub reason: FailureReason,
+    /// Weight used by the call.
+    pub used_weight: Weight,
+}
+
+/// Reason of a failed XVM call.
+#[derive(Clone, Debug, Decode, Eq, PartialEq, thiserror::Error)]
+pub enum FailureReason {
+    /// The call was reverted.
+    #[error("XVM call reverted: {0}")]
+    Revert(FailureRevert),
+    /// The call could not be executed.
+    #[error("XVM call failed: {0}")]
+    Error(FailureError),
+}
+
+/// Details of a reverted XVM call.
+#[derive(Clone, Debug, Decode, Eq, PartialEq, thiserror::Error)]
+pub enum FailureRevert {
+    /// The target is not a valid address in the target VM.
+    #[error("invalid target address")]
+    InvalidTarget,
+    /// The input is too large for the target VM.
+    #[error("input too large")]
+    InputTooLarge,
+    /// The target contract reverted with the given data.
+    #[error("contract reverted with 0x{}", hex::encode(.0))]
+    VmRevert(Vec<u8>),
+}
+
+/// Details of an XVM call that could not be executed.
+#[derive(Clone, Debug, Decode, Eq, PartialEq, thiserror::Error)]
+pub enum FailureError {
+    /// The VM id is not known to the runtime.
+    #[error("invalid VM id")]
+    InvalidVmId,
+    /// The call targets the VM it was made from.
+    #[error("calls within the same VM are not allowed")]
+    SameVmCallDenied,
+    /// The call reentered XVM.
+    #[error("reentrance is not allowed")]
+    ReentranceDenied,
+    /// The target VM returned an error.
+    #[error("VM error: {}", String::from_utf8_lossy(.0))]
+    VmError(Vec<u8>),
+    /// The call ran out of gas.
+    #[error("out of gas")]
+    OutOfGas,
+}
+
+impl FailureReason {
+    /// Returns the data the target contract reverted with, if it did.
+    pub fn revert_data(&self) -> Option<&[u8]> {
+        match self {
+            FailureReason::Revert(FailureRevert::VmRevert(data)) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// A call of a contract in one of the VMs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum XvmTarget {
+    /// An EVM contract.
+    Evm(H160),
+    /// An ink! contract.
+    Wasm(AccountId),
+}
+
+impl XvmTarget {
+    /// Returns the VM of the target.
+    pub fn vm_id(&self) -> VmId {
+        match self {
+            XvmTarget::Evm(_) => VmId::Evm,
+            XvmTarget::Wasm(_) => VmId::Wasm,
+        }
+    }
+
+    fn encoded_address(&self) -> Vec<u8> {
+        match self {
+            XvmTarget::Evm(address) => address.as_bytes().to_vec(),
+            XvmTarget::Wasm(account) => account.encode(),
+        }
+    }
+}
+
+/// XVM read-only api.
+#[async_trait::async_trait]
+pub trait XvmApi {
+    /// Dry-runs an XVM call with `XvmApi_xvm_call` runtime api, as if it was made by a contract
+    /// of the other VM on behalf of `source`. The outer error is about the rpc call, the inner
+    /// one is the reason the XVM call failed.
+    /// * `source` - the caller
+    /// * `target` - the called contract, which determines the [`VmId`]
+    /// * `input` - encoded call data, e.g. with [`crate::contract::EvmContractInstance`]
+    /// * `value` - value transferred with the call
+    /// * `weight_limit` - an optional weight limit of the call, capped at and defaulting to the
+    ///   maximum weight of a block
+    /// * `at` - an optional block hash to query state from
+    async fn xvm_dry_run(
+        &self,
+        source: AccountId,
+        target: XvmTarget,
+        input: Vec<u8>,
+        value: Balance,
+        weight_limit: Option<Weight>,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Result<CallOutput, CallFailure>>;
+}
+
+/// XVM api.
+#[async_trait::async_trait]
+pub trait XvmUserApi {
+    /// Calls a contract in a VM selected by `target`. EVM contracts are called with
+    /// [`EthereumCheckedUserApi::ethereum_checked_transact`], ink! contracts with
+    /// [`ContractsUserApi::call`].
+    /// * `target` - the called contract
+    /// * `input` - encoded call data
+    /// * `value` - value transferred with the call
+    /// * `gas_limit` - weight limit of the call, converted to gas for EVM contracts
+    /// * `status` - a [`TxStatus`] for a tx to wait for
+    async fn xvm_call(
+        &self,
+        target: XvmTarget,
+        input: Vec<u8>,
+        value: Balance,
+        gas_limit: Weight,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+/// Ratio of weight to EVM gas in the runtime, `WEIGHT_PER_GAS`.
+pub const WEIGHT_PER_GAS: u64 = 25_000;
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> XvmApi for C {
+    async fn xvm_dry_run(
+        &self,
+        source: AccountId,
+        target: XvmTarget,
+        input: Vec<u8>,
+        value: Balance,
+        weight_limit: Option<Weight>,
+        at: Option<BlockHash>,
+    ) -> anyhow::Result<Result<CallOutput, CallFailure>> {
+        let args = (
+            source,
+            target.vm_id(),
+            target.encoded_address(),
+            input,
+            value,
+            weight_limit,
+            None::<Balance>,
+        );
+        let params = rpc_params!["XvmApi_xvm_call", Bytes(args.encode()), at];
+
+        self.rpc_call("state_call".to_string(), params).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> XvmUserApi for S {
+    async fn xvm_call(
+        &self,
+        target: XvmTarget,
+        input: Vec<u8>,
+        value: Balance,
+        gas_limit: Weight,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        match target {
+            XvmTarget::Evm(target) => {
+                let tx = CheckedEthereumTx {
+                    gas_limit: U256::from(gas_limit.ref_time / WEIGHT_PER_GAS),
+                    target,
+                    value: U256::from(value),
+                    input,
+                    maybe_access_list: None,
+                };
+                self.ethereum_checked_transact(tx, status).await
+            }
+            XvmTarget::Wasm(target) => {
+                let storage_limit = None::<Compact<Balance>>;
+                self.call(target, value, gas_limit, storage_limit, input, status)
+                    .await
+            }
+        }
+    }
+}
+
+/// Converts a dry-run result into an error carrying the [`CallFailure`], for callers that only
+/// care about the output.
+pub fn into_output(result: Result<CallOutput, CallFailure>) -> anyhow::Result<Vec<u8>> {
+    result
+        .map(|output| output.output)
+        .map_err(|failure| anyhow!(failure))
+}
@@ -0,0 +1,224 @@
+//! Calls are resolved against the metadata of the connected chain, so they keep working when
+//! pallet or call indices change. Storage is read under raw keys and decoded into the types
+//! declared next to the pallet api.
+//!
+//! This module only bridges the pallets missing from [`selendra.rs`](crate::api): Council,
+//! TechnicalCommittee, CouncilElections, Democracy, Preimage and EthereumChecked. The
+//! hand-mirrored types are not checked against the metadata, so a changed layout fails to decode
+//! instead of being caught by the codegen. Once `selendra.rs` is regenerated from a node running
+//! the current runtime with `check-runtime-metadata.sh`, the pallet apis should move to the typed
+//! `api::tx()` and `api::storage()` and this module should go away.
+
+use codec::{Decode, Encode};
+use subxt::{blocks::ExtrinsicEvents, error::MetadataError, tx::TxPayload, Error, Metadata};
+
+use crate::{
+    sp_core::{blake2_128, twox_128, twox_64},
+    AlephConfig, Call,
+};
+
+enum Inner {
+    Named {
+        pallet: &'static str,
+        call: &'static str,
+        args: Vec<u8>,
+    },
+    Encoded(Vec<u8>),
+}
+
+/// A call that can be submitted as a transaction or wrapped into another call.
+pub struct RawCall {
+    inner: Inner,
+}
+
+impl RawCall {
+    /// A call `pallet::call` with the given arguments, encoded as a tuple.
+    /// * `pallet` - name of the pallet in the runtime, e.g. `Council`
+    /// * `call` - name of the call, e.g. `propose`
+    /// * `args` - arguments of the call, compact ones have to be wrapped in [`codec::Compact`]
+    pub fn new<A: Encode>(pallet: &'static str, call: &'static str, args: A) -> Self {
+        Self {
+            inner: Inner::Named {
+                pallet,
+                call,
+                args: args.encode(),
+            },
+        }
+    }
+
+    /// Returns the call encoded as [`Call`] of the chain with the given metadata.
+    pub fn encode_with(&self, metadata: &Metadata) -> Result<Vec<u8>, Error> {
+        self.encode_call_data(metadata)
+    }
+}
+
+/// Bytes embedded into call arguments as they are, without a length prefix, e.g. an already
+/// encoded inner call.
+pub struct Encoded(pub Vec<u8>);
+
+impl Encode for Encoded {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.0)
+    }
+}
+
+impl From<Call> for RawCall {
+    fn from(call: Call) -> Self {
+        Self {
+            inner: Inner::Encoded(call.encode()),
+        }
+    }
+}
+
+impl TxPayload for RawCall {
+    fn encode_call_data_to(&self, metadata: &Metadata, out: &mut Vec<u8>) -> Result<(), Error> {
+        match &self.inner {
+            Inner::Named { pallet, call, args } => {
+                let pallet_metadata = metadata
+                    .pallet_by_name(pallet)
+                    .ok_or_else(|| MetadataError::PalletNameNotFound(pallet.to_string()))?;
+                let call_variant = pallet_metadata
+                    .call_variant_by_name(call)
+                    .ok_or_else(|| MetadataError::CallNameNotFound(call.to_string()))?;
+                pallet_metadata.index().encode_to(out);
+                call_variant.index.encode_to(out);
+                out.extend_from_slice(args);
+            }
+            Inner::Encoded(encoded) => out.extend_from_slice(encoded),
+        }
+        Ok(())
+    }
+}
+
+/// Returns the storage key of a value, or the prefix of a map, `pallet::item`.
+pub fn storage_key(pallet: &str, item: &str) -> Vec<u8> {
+    [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Returns the storage key of the map `pallet::item` entry under the already hashed `key`.
+pub fn storage_map_key(pallet: &str, item: &str, key: Vec<u8>) -> Vec<u8> {
+    let mut storage_key = storage_key(pallet, item);
+    storage_key.extend(key);
+    storage_key
+}
+
+/// Hashes a map key with `Twox64Concat`.
+pub fn twox_64_concat<K: Encode>(key: &K) -> Vec<u8> {
+    let encoded = key.encode();
+    [twox_64(&encoded).as_slice(), &encoded].concat()
+}
+
+/// Hashes a map key with `Blake2_128Concat`.
+pub fn blake2_128_concat<K: Encode>(key: &K) -> Vec<u8> {
+    let encoded = key.encode();
+    [blake2_128(&encoded).as_slice(), &encoded].concat()
+}
+
+/// Hashes a map key with `Identity`.
+pub fn identity<K: Encode>(key: &K) -> Vec<u8> {
+    key.encode()
+}
+
+/// Returns the first event `pallet::variant` among the events of a transaction, with its fields
+/// decoded as `E`.
+pub fn find_event<E: Decode>(
+    events: &ExtrinsicEvents<AlephConfig>,
+    pallet: &str,
+    variant: &str,
+) -> anyhow::Result<Option<E>> {
+    for event in events.iter() {
+        let event = event?;
+        if event.pallet_name() == pallet && event.variant_name() == variant {
+            return Ok(Some(E::decode(&mut event.field_bytes())?));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Compact;
+
+    use super::*;
+    use crate::{frame_system::pallet::Call::remark, AccountId, Call::System};
+
+    const SYSTEM_ACCOUNT: &str = "26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9";
+    const ALICE: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    fn alice() -> AccountId {
+        let mut raw = [0; 32];
+        raw.copy_from_slice(&hex::decode(ALICE).unwrap());
+        AccountId::new(raw)
+    }
+
+    #[test]
+    fn computes_storage_key_of_value() {
+        assert_eq!(
+            hex::encode(storage_key("System", "Account")),
+            SYSTEM_ACCOUNT
+        );
+    }
+
+    #[test]
+    fn computes_storage_key_of_blake2_128_concat_entry() {
+        let key = storage_map_key("System", "Account", blake2_128_concat(&alice()));
+
+        assert_eq!(
+            hex::encode(key),
+            format!("{SYSTEM_ACCOUNT}de1e86a9a8c739864cf3cc5ec2bea59f{ALICE}")
+        );
+    }
+
+    #[test]
+    fn computes_storage_key_of_twox_64_concat_entry() {
+        let key = storage_map_key("System", "BlockHash", twox_64_concat(&0u32));
+
+        assert_eq!(
+            hex::encode(key),
+            "26aa394eea5630e07c48ae0c9558cef7a44704b568d21667356a5a050c118746\
+             b4def25cfda6ef3a00000000"
+        );
+    }
+
+    #[test]
+    fn identity_key_is_encoded_key() {
+        assert_eq!(identity(&alice()), hex::decode(ALICE).unwrap());
+    }
+
+    #[test]
+    fn embeds_encoded_bytes_without_length_prefix() {
+        let args = (Compact(1u32), Encoded(vec![7, 8, 9]), Compact(3u32)).encode();
+
+        assert_eq!(args, vec![4, 7, 8, 9, 12]);
+    }
+
+    #[test]
+    fn named_call_keeps_encoded_args() {
+        let call = RawCall::new("Council", "propose", (Compact(1u32), 2u8));
+
+        match call.inner {
+            Inner::Named { pallet, call, args } => {
+                assert_eq!((pallet, call), ("Council", "propose"));
+                assert_eq!(args, vec![4, 2]);
+            }
+            Inner::Encoded(_) => panic!("call should be resolved by name"),
+        }
+    }
+
+    #[test]
+    fn runtime_call_is_passed_through_encoded() {
+        let call = System(remark {
+            remark: vec![1, 2, 3],
+        });
+        let encoded = call.encode();
+
+        match RawCall::from(call).inner {
+            Inner::Encoded(bytes) => assert_eq!(bytes, encoded),
+            Inner::Named { .. } => panic!("call should be already encoded"),
+        }
+    }
+}
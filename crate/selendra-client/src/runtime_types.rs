@@ -1,6 +1,10 @@
 pub use crate::selendra::api::runtime_types::*;
+use codec::{Decode, Encode};
+
 use crate::{
     api::runtime_types::{
+        bounded_collections::bounded_vec::BoundedVec,
+        pallet_identity::{legacy::IdentityInfo, types::Data},
         primitives::app::Public as AlephPublic,
         sp_consensus_aura::sr25519::app_sr25519::Public as AuraPublic,
         sp_core::{ed25519::Public as EdPublic, sr25519::Public as SrPublic},
@@ -59,3 +63,88 @@ impl Weight {
         }
     }
 }
+
+impl Default for IdentityInfo {
+    fn default() -> Self {
+        Self {
+            additional: BoundedVec(vec![]),
+            display: Data::None,
+            legal: Data::None,
+            web: Data::None,
+            riot: Data::None,
+            email: Data::None,
+            pgp_fingerprint: None,
+            image: Data::None,
+            twitter: Data::None,
+        }
+    }
+}
+
+impl Data {
+    /// Largest number of bytes a `Data::Raw*` variant holds.
+    pub const MAX_RAW_LEN: usize = 32;
+
+    /// Returns the bytes of a `Data::Raw*` variant, `None` for other variants.
+    pub fn raw_bytes(&self) -> Option<Vec<u8>> {
+        // Raw variants are encoded as `len + 1` followed by the bytes themselves.
+        let encoded = self.encode();
+        match encoded[0] as usize {
+            1..=33 => Some(encoded[1..].to_vec()),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Data {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() > Data::MAX_RAW_LEN {
+            anyhow::bail!(
+                "Identity data can have at most {} bytes, got {}",
+                Data::MAX_RAW_LEN,
+                bytes.len()
+            );
+        }
+        let encoded = [&[bytes.len() as u8 + 1], bytes].concat();
+
+        Ok(Data::decode(&mut &encoded[..])?)
+    }
+}
+
+impl TryFrom<&str> for Data {
+    type Error = anyhow::Error;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        Data::try_from(text.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(bytes: &[u8]) -> Data {
+        let data = Data::try_from(bytes).expect("data should fit");
+        assert_eq!(data.raw_bytes(), Some(bytes.to_vec()));
+        data
+    }
+
+    #[test]
+    fn raw_data_round_trips() {
+        assert_eq!(round_trip(&[]), Data::Raw0([]));
+        assert_eq!(round_trip(&[7]), Data::Raw1([7]));
+        assert_eq!(round_trip(&[7; 32]), Data::Raw32([7; 32]));
+    }
+
+    #[test]
+    fn too_long_data_is_rejected() {
+        assert!(Data::try_from(&[7; 33][..]).is_err());
+    }
+
+    #[test]
+    fn non_raw_data_has_no_raw_bytes() {
+        assert_eq!(Data::None.raw_bytes(), None);
+        assert_eq!(Data::BlakeTwo256([7; 32]).raw_bytes(), None);
+    }
+}
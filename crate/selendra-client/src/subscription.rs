@@ -0,0 +1,473 @@
+//! Long-lived subscriptions to pallet events, for indexers and other services which need every
+//! event of the chain exactly once.
+//!
+//! An [`EventSubscription`] follows either the best or the finalized chain, see [`BlockStatus`],
+//! and yields the decoded events of every block on it, in order and without gaps. Following the
+//! best chain, blocks that stop being part of it are reported with
+//! [`Notification::Retracted`] before the blocks of the new fork are imported, so that their
+//! events can be reverted.
+//!
+//! The subscription ends when the connection to the node does. A new one, created with another
+//! connection and the hash of [`EventSubscription::last_block`] as `resume_from`, continues right
+//! after the last block reported by the old one.
+//!
+//! ```no_run
+//! # use selendra_client::{api::balances::events::Transfer, Connection};
+//! # use selendra_client::subscription::{EventSubscription, Notification};
+//! # use selendra_client::waiting::BlockStatus;
+//! # async fn example(address: &str) -> anyhow::Result<()> {
+//! let mut resume_from = None;
+//! loop {
+//!     let conn = Connection::new(address).await;
+//!     let mut subscription =
+//!         EventSubscription::<Transfer>::new(&conn, BlockStatus::Best, resume_from).await?;
+//!     while let Some(notification) = subscription.next().await {
+//!         match notification? {
+//!             Notification::Imported { block, events } => println!("{block:?}: {events:?}"),
+//!             Notification::Retracted(block) => println!("{block:?} retracted"),
+//!         }
+//!     }
+//!     resume_from = subscription.last_block().map(|block| block.hash);
+//! }
+//! # }
+//! ```
+
+use std::{collections::VecDeque, future::Future, marker::PhantomData};
+
+use anyhow::{anyhow, bail, Result};
+use futures::{Stream, StreamExt};
+use subxt::{
+    config::Header,
+    events::{EventDetails, Phase, StaticEvent},
+    rpc::Subscription,
+    Config,
+};
+
+use crate::{
+    utility::BlocksApi, waiting::BlockStatus, AlephConfig, AsConnection, BlockHash, BlockNumber,
+    Connection,
+};
+
+/// How many of the most recently reported blocks are kept to walk back on reorgs without
+/// querying the node.
+const MAX_TRACKED_BLOCKS: usize = 256;
+
+/// A block of the chain.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlockRef {
+    /// The block hash.
+    pub hash: BlockHash,
+    /// The block number.
+    pub number: BlockNumber,
+}
+
+/// A decoded event together with its position in the block.
+#[derive(Clone, Debug)]
+pub struct BlockEvent<E> {
+    /// Index of the event among all events of the block.
+    pub index: u32,
+    /// The phase of block execution in which the event was emitted.
+    pub phase: Phase,
+    /// The event.
+    pub event: E,
+}
+
+/// A change of the chain followed by an [`EventSubscription`].
+#[derive(Clone, Debug)]
+pub enum Notification<E> {
+    /// The block became part of the followed chain. Its parent is the last block reported before.
+    Imported {
+        /// The block.
+        block: BlockRef,
+        /// Events of the block the subscription is interested in.
+        events: Vec<BlockEvent<E>>,
+    },
+    /// The block, the last one imported and not retracted so far, is no longer part of the best
+    /// chain. Never reported for the finalized chain.
+    Retracted(BlockRef),
+}
+
+/// Events an [`EventSubscription`] can decode. Implemented for every event of the static
+/// metadata, and can be implemented for an enum of several events.
+pub trait SubscribedEvent: Sized + Send {
+    /// Decodes `event`, or returns `None` if it is of a different kind.
+    fn decode(event: &EventDetails<AlephConfig>) -> Result<Option<Self>>;
+}
+
+impl<E: StaticEvent + Send> SubscribedEvent for E {
+    fn decode(event: &EventDetails<AlephConfig>) -> Result<Option<Self>> {
+        Ok(event.as_event::<E>()?)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Step {
+    Import(BlockRef),
+    Retract { block: BlockRef, parent: BlockRef },
+}
+
+/// A subscription to events of a given kind, see the [module docs](self).
+pub struct EventSubscription<E> {
+    connection: Connection,
+    status: BlockStatus,
+    heads: Subscription<<AlephConfig as Config>::Header>,
+    // Consecutive reported blocks, ending with the last one.
+    reported: VecDeque<BlockRef>,
+    pending: VecDeque<Step>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E: SubscribedEvent> EventSubscription<E> {
+    /// Subscribes to events of the chain in a given [`BlockStatus`].
+    /// * `conn` - a connection to the node
+    /// * `status` - whether to follow the best or the finalized chain
+    /// * `resume_from` - hash of the last block reported by a previous subscription. Without it,
+    ///   the subscription starts with the first block announced by the node
+    pub async fn new<C: AsConnection>(
+        conn: &C,
+        status: BlockStatus,
+        resume_from: Option<BlockHash>,
+    ) -> Result<Self> {
+        let connection = conn.as_connection().clone();
+        let rpc = connection.as_client().rpc();
+        let heads = match status {
+            BlockStatus::Best => rpc.subscribe_best_block_headers().await?,
+            BlockStatus::Finalized => rpc.subscribe_finalized_block_headers().await?,
+        };
+
+        let mut reported = VecDeque::new();
+        if let Some(hash) = resume_from {
+            let block = block_ref(&connection, hash).await?;
+            if let BlockStatus::Finalized = status {
+                let finalized = connection.get_finalized_block_hash().await?;
+                let finalized = block_ref(&connection, finalized).await?;
+                let canonical = connection.get_block_hash(block.number).await?;
+                if block.number > finalized.number || canonical != Some(hash) {
+                    bail!("Can't resume from block {hash:?}, it is not finalized");
+                }
+            }
+            reported.push_back(block);
+        }
+
+        Ok(Self {
+            connection,
+            status,
+            heads,
+            reported,
+            pending: VecDeque::new(),
+            _event: PhantomData,
+        })
+    }
+
+    /// Returns the next change of the followed chain, or `None` once the node closes the
+    /// subscription. After an error, the next call retries from where the failed one stopped.
+    pub async fn next(&mut self) -> Option<Result<Notification<E>>> {
+        loop {
+            if let Some(step) = self.pending.front().copied() {
+                return Some(self.apply(step).await);
+            }
+
+            let head = match self.heads.next().await? {
+                Ok(header) => BlockRef {
+                    hash: header.hash(),
+                    number: header.number,
+                },
+                Err(e) => return Some(Err(e.into())),
+            };
+            // A failed head is not lost, the route to the next one passes through it.
+            if let Err(e) = self.schedule(head).await {
+                return Some(Err(e));
+            }
+        }
+    }
+
+    /// Returns the last block reported as imported and not retracted since, or the block the
+    /// subscription resumed from if nothing was reported yet.
+    pub fn last_block(&self) -> Option<BlockRef> {
+        self.reported.back().copied()
+    }
+
+    /// Turns the subscription into a [`Stream`] of notifications.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Notification<E>>> {
+        futures::stream::unfold(self, |mut subscription| async move {
+            let notification = subscription.next().await?;
+            Some((notification, subscription))
+        })
+    }
+
+    async fn schedule(&mut self, head: BlockRef) -> Result<()> {
+        let steps = match (self.status, self.reported.back().copied()) {
+            (_, None) => vec![Step::Import(head)],
+            (BlockStatus::Best, Some(_)) => {
+                let connection = &self.connection;
+                route(&self.reported, head, |block| parent_of(connection, block)).await?
+            }
+            (BlockStatus::Finalized, Some(last)) => {
+                let mut steps = vec![];
+                // Finalized heads may skip blocks, which are then found by number.
+                for number in last.number + 1..head.number {
+                    let hash = self
+                        .connection
+                        .get_block_hash(number)
+                        .await?
+                        .ok_or_else(|| anyhow!("No finalized block with number {number}"))?;
+                    steps.push(Step::Import(BlockRef { hash, number }));
+                }
+                if head.number > last.number {
+                    steps.push(Step::Import(head));
+                }
+                steps
+            }
+        };
+        self.pending.extend(steps);
+
+        Ok(())
+    }
+
+    async fn apply(&mut self, step: Step) -> Result<Notification<E>> {
+        let notification = match step {
+            Step::Import(block) => {
+                let events = self.events_of(block).await?;
+                self.reported.push_back(block);
+                if self.reported.len() > MAX_TRACKED_BLOCKS {
+                    self.reported.pop_front();
+                }
+                Notification::Imported { block, events }
+            }
+            Step::Retract { block, parent } => {
+                self.reported.pop_back();
+                if self.reported.is_empty() {
+                    self.reported.push_back(parent);
+                }
+                Notification::Retracted(block)
+            }
+        };
+        self.pending.pop_front();
+
+        Ok(notification)
+    }
+
+    async fn events_of(&self, block: BlockRef) -> Result<Vec<BlockEvent<E>>> {
+        let events = self
+            .connection
+            .as_client()
+            .blocks()
+            .at(block.hash)
+            .await?
+            .events()
+            .await?;
+
+        events
+            .iter()
+            .filter_map(|event| {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => return Some(Err(e.into())),
+                };
+                E::decode(&event)
+                    .map(|decoded| {
+                        decoded.map(|decoded| BlockEvent {
+                            index: event.index(),
+                            phase: event.phase(),
+                            event: decoded,
+                        })
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+async fn block_ref(connection: &Connection, hash: BlockHash) -> Result<BlockRef> {
+    let number = connection
+        .get_block_number(hash)
+        .await?
+        .ok_or_else(|| anyhow!("Unknown block {hash:?}"))?;
+
+    Ok(BlockRef { hash, number })
+}
+
+async fn parent_of(connection: &Connection, block: BlockRef) -> Result<BlockRef> {
+    let header = connection
+        .as_client()
+        .rpc()
+        .header(Some(block.hash))
+        .await?
+        .ok_or_else(|| anyhow!("Unknown block {:?}", block.hash))?;
+
+    Ok(BlockRef {
+        hash: header.parent_hash,
+        number: block.number.saturating_sub(1),
+    })
+}
+
+/// Returns the steps from the last of `reported` blocks to `head`: retractions down to their
+/// common ancestor, from the highest block, followed by imports from the lowest block.
+async fn route<F, Fut>(
+    reported: &VecDeque<BlockRef>,
+    head: BlockRef,
+    parent_of: F,
+) -> Result<Vec<Step>>
+where
+    F: Fn(BlockRef) -> Fut,
+    Fut: Future<Output = Result<BlockRef>>,
+{
+    let mut tracked = reported.iter().rev().copied();
+    let mut old = match tracked.next() {
+        Some(last) => last,
+        None => return Ok(vec![Step::Import(head)]),
+    };
+    let mut new = head;
+    let mut retracted = vec![];
+    let mut imported = vec![];
+
+    while old != new {
+        if new.number > old.number {
+            imported.push(Step::Import(new));
+            new = parent_of(new).await?;
+        } else {
+            let parent = match tracked.next() {
+                Some(parent) => parent,
+                None => parent_of(old).await?,
+            };
+            retracted.push(Step::Retract { block: old, parent });
+            old = parent;
+        }
+    }
+    imported.reverse();
+    retracted.extend(imported);
+
+    Ok(retracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    struct Tree {
+        parents: HashMap<BlockHash, BlockRef>,
+    }
+
+    impl Tree {
+        fn new(branches: &[&[BlockRef]]) -> Self {
+            let parents = branches
+                .iter()
+                .flat_map(|branch| branch.windows(2).map(|pair| (pair[1].hash, pair[0])))
+                .collect();
+            Self { parents }
+        }
+
+        async fn parent_of(&self, block: BlockRef) -> Result<BlockRef> {
+            self.parents
+                .get(&block.hash)
+                .copied()
+                .ok_or_else(|| anyhow!("no parent of {block:?}"))
+        }
+    }
+
+    fn block(number: BlockNumber, fork: u8) -> BlockRef {
+        let mut hash = [fork; 32];
+        hash[..4].copy_from_slice(&number.to_le_bytes());
+        BlockRef {
+            hash: hash.into(),
+            number,
+        }
+    }
+
+    fn routed(reported: &[BlockRef], head: BlockRef, tree: &Tree) -> Vec<Step> {
+        let reported = reported.iter().copied().collect();
+        block_on(route(&reported, head, |block| tree.parent_of(block))).expect("blocks are known")
+    }
+
+    #[test]
+    fn imports_skipped_blocks_from_the_lowest() {
+        let chain = [block(1, 0), block(2, 0), block(3, 0)];
+        let tree = Tree::new(&[&chain]);
+
+        assert_eq!(
+            routed(&chain[..1], chain[2], &tree),
+            vec![Step::Import(chain[1]), Step::Import(chain[2])]
+        );
+    }
+
+    #[test]
+    fn does_nothing_for_the_last_reported_block() {
+        let chain = [block(1, 0), block(2, 0)];
+        let tree = Tree::new(&[&chain]);
+
+        assert!(routed(&chain, chain[1], &tree).is_empty());
+    }
+
+    #[test]
+    fn retracts_the_old_fork_before_importing_the_new_one() {
+        let old = [block(1, 0), block(2, 0), block(3, 0)];
+        let new = [block(1, 0), block(2, 1), block(3, 1), block(4, 1)];
+        let tree = Tree::new(&[&old, &new]);
+
+        assert_eq!(
+            routed(&old, new[3], &tree),
+            vec![
+                Step::Retract {
+                    block: old[2],
+                    parent: old[1]
+                },
+                Step::Retract {
+                    block: old[1],
+                    parent: old[0]
+                },
+                Step::Import(new[1]),
+                Step::Import(new[2]),
+                Step::Import(new[3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn retracts_down_to_a_lower_head() {
+        let old = [block(1, 0), block(2, 0), block(3, 0)];
+        let new = [block(1, 0), block(2, 1)];
+        let tree = Tree::new(&[&old, &new]);
+
+        assert_eq!(
+            routed(&old, new[1], &tree),
+            vec![
+                Step::Retract {
+                    block: old[2],
+                    parent: old[1]
+                },
+                Step::Retract {
+                    block: old[1],
+                    parent: old[0]
+                },
+                Step::Import(new[1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn asks_for_parents_beyond_the_tracked_blocks() {
+        let old = [block(1, 0), block(2, 0), block(3, 0)];
+        let new = [block(1, 0), block(2, 1), block(3, 1)];
+        let tree = Tree::new(&[&old, &new]);
+
+        assert_eq!(
+            routed(&old[2..], new[2], &tree),
+            vec![
+                Step::Retract {
+                    block: old[2],
+                    parent: old[1]
+                },
+                Step::Retract {
+                    block: old[1],
+                    parent: old[0]
+                },
+                Step::Import(new[1]),
+                Step::Import(new[2]),
+            ]
+        );
+    }
+}
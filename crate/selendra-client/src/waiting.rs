@@ -11,6 +11,7 @@ use crate::{
 };
 
 /// When using waiting API, what kind of block status we should wait for.
+#[derive(Copy, Clone)]
 pub enum BlockStatus {
     /// Wait for event or block to be in the best chain.
     Best,
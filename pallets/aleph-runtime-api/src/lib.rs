@@ -2,8 +2,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use primitives::{
-    crypto::SignatureSet, AccountId, ApiError, AuthorityId, AuthoritySignature, Balance, Perbill,
-    Score, SessionAuthorityData, SessionCommittee, SessionIndex, SessionValidatorError, Version,
+    crypto::SignatureSet,
+    xvm::{CallResult, VmId, Weight},
+    AccountId, ApiError, AuthorityId, AuthoritySignature, Balance, ElectionCandidates, Perbill,
+    Score, SessionAuthorityData, SessionCommittee, SessionIndex, SessionValidatorError,
+    ValidatorPerformance, ValidatorScores, Version,
 };
 pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_std::vec::Vec;
@@ -41,4 +44,50 @@ sp_api::decl_runtime_apis! {
         /// Submits score for a nonce in a session of performance of finality committee members.
         fn submit_abft_score(score: Score, signature: SignatureSet<AuthoritySignature>) -> Option<()>;
     }
+
+    pub trait ValidatorPerformanceApi {
+        /// Returns the performance of `validator` in sessions from `first_session` to
+        /// `last_session` inclusive, together with its underperformance counters and ban.
+        /// Committees are known only for the sessions of the current era, so `first_session`
+        /// must be one of them, and the range ends at the next session at the latest. Blocks
+        /// produced are known only for the ongoing session.
+        fn validator_performance(
+            validator: AccountId,
+            first_session: SessionIndex,
+            last_session: SessionIndex,
+        ) -> Result<ValidatorPerformance<AccountId>, SessionValidatorError>;
+    }
+
+    pub trait AbftScoresApi {
+        /// Returns the AlephBFT scores of the finality committee members in sessions from
+        /// `first_session` to `last_session` inclusive, grouped by validator. Only the sessions
+        /// within the retention period of the scores are reported.
+        fn abft_score_history(
+            first_session: SessionIndex,
+            last_session: SessionIndex,
+        ) -> Vec<ValidatorScores<AccountId>>;
+    }
+
+    pub trait ElectionsApi {
+        /// Returns the candidates for the non-reserved seats in the next era, split into the ones
+        /// that would be elected if the election took place now and the ones that would not,
+        /// together with the reasons.
+        fn election_candidates() -> ElectionCandidates<AccountId>;
+    }
+
+    pub trait XvmApi {
+        /// Dry-runs a call of the `target` contract in the `vm_id` VM on behalf of `source`, the
+        /// same way a contract of the other VM would make it. Nothing is committed, so the
+        /// result shows whether and why a real XVM call would fail. The call can use at most
+        /// `weight_limit`, capped at the maximum weight of a block, which is also the default.
+        fn xvm_call(
+            source: AccountId,
+            vm_id: VmId,
+            target: Vec<u8>,
+            input: Vec<u8>,
+            value: Balance,
+            weight_limit: Option<Weight>,
+            storage_deposit_limit: Option<Balance>,
+        ) -> CallResult;
+    }
 }
@@ -69,10 +69,6 @@ impl<T: Config> AbftScoresProvider for Pallet<T> {
         AbftScores::<T>::get(session_id)
     }
 
-    fn clear_scores() {
-        let _result = AbftScores::<T>::clear(u32::MAX, None);
-    }
-
     fn clear_nonce() {
         LastScoreNonce::<T>::kill();
     }
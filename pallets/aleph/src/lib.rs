@@ -7,6 +7,7 @@ mod mock;
 mod tests;
 
 mod impls;
+pub mod migration;
 pub mod traits;
 
 use frame_support::{
@@ -16,15 +17,18 @@ use frame_support::{
 pub use pallet::*;
 use primitives::{
     crypto::{AuthorityVerifier, SignatureSet},
-    Balance, SessionIndex, Version, VersionChange, DEFAULT_FINALITY_VERSION,
-    LEGACY_FINALITY_VERSION, TOKEN,
+    Balance, SessionCount, SessionIndex, Version, VersionChange, DEFAULT_ABFT_SCORES_RETENTION,
+    DEFAULT_FINALITY_VERSION, LEGACY_FINALITY_VERSION, TOKEN,
 };
 use sp_runtime::Perbill;
 use sp_std::prelude::*;
 
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 pub(crate) const LOG_TARGET: &str = "pallet-aleph";
+/// Maximal number of sessions whose scores are pruned in a single block, so that catching up
+/// after the retention period is shortened does not overload a block.
+const MAX_PRUNED_SESSIONS_PER_BLOCK: u32 = 16;
 
 #[frame_support::pallet]
 #[pallet_doc("../README.md")]
@@ -40,7 +44,10 @@ pub mod pallet {
         pallet_prelude::{BlockNumberFor, OriginFor},
     };
     use pallet_session::SessionManager;
-    use primitives::{Score, ScoreNonce, SessionInfoProvider, TotalIssuanceProvider};
+    use primitives::{
+        Score, ScoreNonce, SessionInfoProvider, SessionScore, TotalIssuanceProvider,
+        ValidatorScores,
+    };
     use sp_runtime::traits::{Hash, ValidateUnsigned};
     use sp_std::collections::btree_map::BTreeMap;
     #[cfg(feature = "std")]
@@ -87,6 +94,7 @@ pub mod pallet {
         ScheduleFinalityVersionChange(VersionChange),
         FinalityVersionChange(VersionChange),
         InflationParametersChange(Balance, u64),
+        AbftScoresRetentionChange(SessionCount),
     }
 
     #[pallet::pallet]
@@ -112,6 +120,12 @@ pub mod pallet {
         154_283_512_497
     }
 
+    /// Default number of past sessions whose scores are kept.
+    #[pallet::type_value]
+    pub fn DefaultAbftScoresRetention() -> SessionCount {
+        DEFAULT_ABFT_SCORES_RETENTION
+    }
+
     #[pallet::storage]
     pub type SelCap<T: Config> = StorageValue<_, Balance, ValueQuery, DefaultSelCap>;
 
@@ -134,6 +148,16 @@ pub mod pallet {
     pub type NextFinalityCommittee<T: Config> =
         StorageValue<_, BoundedVec<T::AccountId, T::MaxCommitteeSize>, ValueQuery>;
 
+    /// Accounts of the current finality committee, in the same order as `Authorities`.
+    #[pallet::storage]
+    pub(super) type AuthorityAccounts<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxAuthorities>, ValueQuery>;
+
+    /// Accounts of the next session finality committee, in the same order as `NextAuthorities`.
+    #[pallet::storage]
+    pub(super) type NextAuthorityAccounts<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxAuthorities>, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn emergency_finalizer)]
     pub(super) type EmergencyFinalizer<T: Config> = StorageValue<_, T::AuthorityId, OptionQuery>;
@@ -158,11 +182,27 @@ pub mod pallet {
     pub(super) type FinalityScheduledVersionChange<T: Config> =
         StorageValue<_, VersionChange, OptionQuery>;
 
-    // clear this storage on session end
+    /// Pruned in `on_initialize` once older than `AbftScoresRetention` sessions.
     #[pallet::storage]
     #[pallet::getter(fn abft_scores)]
     pub type AbftScores<T: Config> = StorageMap<_, Twox64Concat, SessionIndex, Score>;
 
+    /// Accounts of the finality committee of a session, recorded when the session starts, in the
+    /// order of the points of its score.
+    #[pallet::storage]
+    pub(super) type AbftScoreCommittees<T: Config> =
+        StorageMap<_, Twox64Concat, SessionIndex, BoundedVec<T::AccountId, T::MaxAuthorities>>;
+
+    /// Number of past sessions whose scores are kept.
+    #[pallet::storage]
+    #[pallet::getter(fn abft_scores_retention)]
+    pub type AbftScoresRetention<T: Config> =
+        StorageValue<_, SessionCount, ValueQuery, DefaultAbftScoresRetention>;
+
+    /// The oldest session whose score might still be in storage.
+    #[pallet::storage]
+    pub(super) type OldestAbftScoreSession<T: Config> = StorageValue<_, SessionIndex, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn last_score_nonce)]
     pub(super) type LastScoreNonce<T: Config> = StorageValue<_, ScoreNonce, ValueQuery>;
@@ -193,16 +233,32 @@ pub mod pallet {
             }
         }
 
+        pub(crate) fn initialize_authority_accounts(accounts: Vec<T::AccountId>) {
+            let bounded_accounts: BoundedVec<_, T::MaxAuthorities> = accounts
+                .try_into()
+                .expect("authorities count exceeds MaxAuthorities bound; qed");
+            <AuthorityAccounts<T>>::put(bounded_accounts.clone());
+            <NextAuthorityAccounts<T>>::put(bounded_accounts);
+        }
+
+        #[allow(clippy::type_complexity)]
         fn get_authorities_for_next_session(
             next_authorities: Vec<(&T::AccountId, T::AuthorityId)>,
-        ) -> BoundedVec<T::AuthorityId, T::MaxAuthorities> {
+        ) -> (
+            BoundedVec<T::AccountId, T::MaxAuthorities>,
+            BoundedVec<T::AuthorityId, T::MaxAuthorities>,
+        ) {
             let mut account_to_authority: BTreeMap<_, _> = next_authorities.into_iter().collect();
             let next_committee_accounts = NextFinalityCommittee::<T>::get();
             let expected_len = next_committee_accounts.len();
-            let next_committee_authorities: Vec<_> = next_committee_accounts
-                .into_iter()
-                .filter_map(|account_id| account_to_authority.remove(&account_id))
-                .collect();
+            let (next_committee_accounts, next_committee_authorities): (Vec<_>, Vec<_>) =
+                next_committee_accounts
+                    .into_iter()
+                    .filter_map(|account_id| {
+                        let authority = account_to_authority.remove(&account_id)?;
+                        Some((account_id, authority))
+                    })
+                    .unzip();
 
             if next_committee_authorities.len() != expected_len {
                 log::error!(
@@ -211,16 +267,24 @@ pub mod pallet {
                 );
             }
 
-            next_committee_authorities
-                .try_into()
-                .expect("committee size exceeds MaxAuthorities bound; qed")
+            (
+                next_committee_accounts
+                    .try_into()
+                    .expect("committee size exceeds MaxAuthorities bound; qed"),
+                next_committee_authorities
+                    .try_into()
+                    .expect("committee size exceeds MaxAuthorities bound; qed"),
+            )
         }
 
         pub(crate) fn update_authorities(next_authorities: Vec<(&T::AccountId, T::AuthorityId)>) {
-            let next_authorities = Self::get_authorities_for_next_session(next_authorities);
+            let (next_accounts, next_authorities) =
+                Self::get_authorities_for_next_session(next_authorities);
 
             <Authorities<T>>::put(<NextAuthorities<T>>::get());
             <NextAuthorities<T>>::put(next_authorities);
+            <AuthorityAccounts<T>>::put(<NextAuthorityAccounts<T>>::get());
+            <NextAuthorityAccounts<T>>::put(next_accounts);
         }
 
         pub(crate) fn update_emergency_finalizer() {
@@ -392,6 +456,79 @@ pub mod pallet {
             let xt = T::create_inherent(call.into());
             SubmitTransaction::<T, Call<T>>::submit_transaction(xt).ok()
         }
+
+        /// Remembers the accounts of the committee of the current session, so that its score can
+        /// be attributed to them after the committee changes.
+        pub(crate) fn record_score_committee() {
+            AbftScoreCommittees::<T>::insert(
+                Self::current_session(),
+                AuthorityAccounts::<T>::get(),
+            );
+        }
+
+        /// Removes the scores of sessions older than the retention period, at most
+        /// `MAX_PRUNED_SESSIONS_PER_BLOCK` of them.
+        pub(crate) fn prune_abft_scores() -> Weight {
+            let db_weight = T::DbWeight::get();
+            let first_retained =
+                Self::current_session().saturating_sub(AbftScoresRetention::<T>::get());
+            let oldest = OldestAbftScoreSession::<T>::get();
+            if oldest >= first_retained {
+                return db_weight.reads(3);
+            }
+
+            let pruned_until =
+                first_retained.min(oldest.saturating_add(MAX_PRUNED_SESSIONS_PER_BLOCK));
+            for session in oldest..pruned_until {
+                AbftScores::<T>::remove(session);
+                AbftScoreCommittees::<T>::remove(session);
+            }
+            OldestAbftScoreSession::<T>::put(pruned_until);
+
+            let pruned = (pruned_until - oldest) as u64;
+            db_weight
+                .reads(3)
+                .saturating_add(db_weight.writes(2 * pruned + 1))
+        }
+
+        /// Returns the scores of the finality committee members in sessions from
+        /// `first_session` to `last_session` inclusive, grouped by validator. Sessions that were
+        /// already pruned or have no score are skipped.
+        pub fn abft_score_history(
+            first_session: SessionIndex,
+            last_session: SessionIndex,
+        ) -> Vec<ValidatorScores<T::AccountId>> {
+            let first_session = first_session.max(OldestAbftScoreSession::<T>::get());
+            let last_session = last_session.min(Self::current_session());
+
+            let mut history: BTreeMap<T::AccountId, Vec<SessionScore>> = BTreeMap::new();
+            for session in first_session..=last_session {
+                let (Some(score), Some(committee)) = (
+                    AbftScores::<T>::get(session),
+                    AbftScoreCommittees::<T>::get(session),
+                ) else {
+                    continue;
+                };
+                for (validator, score) in committee.into_iter().zip(score.points) {
+                    history
+                        .entry(validator)
+                        .or_default()
+                        .push(SessionScore { session, score });
+                }
+            }
+
+            history
+                .into_iter()
+                .map(|(validator, scores)| ValidatorScores { validator, scores })
+                .collect()
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+            Self::prune_abft_scores()
+        }
     }
 
     #[pallet::call]
@@ -487,10 +624,35 @@ pub mod pallet {
             ensure_none(origin)?;
 
             <LastScoreNonce<T>>::put(score.nonce);
+            // Committees are recorded when sessions start, only the session during which the
+            // runtime got upgraded might lack one.
+            if !AbftScoreCommittees::<T>::contains_key(score.session_id) {
+                Self::record_score_committee();
+            }
             AbftScores::<T>::insert(score.session_id, score);
 
             Ok(Pays::No.into())
         }
+
+        /// Sets the number of past sessions whose AlephBFT scores are kept. Older scores are
+        /// pruned gradually at the beginning of the following blocks.
+        #[pallet::call_index(4)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn set_abft_scores_retention(
+            origin: OriginFor<T>,
+            retention: SessionCount,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            if retention == 0 {
+                return Err(DispatchError::Other(
+                    "Retention has to be at least one session!",
+                ));
+            }
+
+            AbftScoresRetention::<T>::put(retention);
+            Self::deposit_event(Event::AbftScoresRetentionChange(retention));
+            Ok(())
+        }
     }
 
     #[pallet::validate_unsigned]
@@ -523,9 +685,13 @@ pub mod pallet {
             I: 'a + Iterator<Item = (&'a T::AccountId, T::AuthorityId)>,
             T::AccountId: 'a,
         {
-            let (_, authorities): (Vec<_>, Vec<_>) = validators.unzip();
+            let (accounts, authorities): (Vec<_>, Vec<_>) = validators
+                .map(|(account, authority)| (account.clone(), authority))
+                .unzip();
             // it is guaranteed that the first validator set will also be used in the next session
             Self::initialize_authorities(authorities.as_slice(), authorities.as_slice());
+            Self::initialize_authority_accounts(accounts);
+            Self::record_score_committee();
         }
 
         fn on_new_session<'a, I>(changed: bool, _: I, queued_validators: I)
@@ -537,6 +703,7 @@ pub mod pallet {
             if changed {
                 Self::update_authorities(queued_validators.collect());
             }
+            Self::record_score_committee();
         }
 
         fn on_disabled(_validator_index: u32) {}
@@ -0,0 +1,131 @@
+use frame_support::{
+    pallet_prelude::{PalletInfoAccess, StorageVersion, Weight},
+    traits::OnRuntimeUpgrade,
+    BoundedVec,
+};
+use log::info;
+use sp_runtime::RuntimeAppPublic;
+use sp_std::vec::Vec;
+
+/// Ensure that the current pallet storage version matches `version`.
+pub fn ensure_storage_version<P: PalletInfoAccess>(version: u16) -> Result<(), &'static str> {
+    if StorageVersion::get::<P>() == StorageVersion::new(version) {
+        Ok(())
+    } else {
+        Err("Bad storage version")
+    }
+}
+
+/// Fills `AuthorityAccounts` and `NextAuthorityAccounts`, which are otherwise only complete two
+/// sessions after the upgrade, so that the scores of these sessions can be attributed.
+pub mod v3 {
+    use frame_support::traits::Get;
+
+    use super::*;
+    use crate::{
+        Authorities, AuthorityAccounts, Config, NextAuthorities, NextAuthorityAccounts,
+        NextFinalityCommittee, Pallet, LOG_TARGET,
+    };
+
+    const OLD_VERSION: u16 = 2;
+    const NEW_VERSION: u16 = 3;
+
+    pub struct Migration<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T> Migration<T>
+    where
+        T: Config + pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+    {
+        /// Owners of the keys, in the same order, if every key has one.
+        fn key_owners(keys: &[T::AuthorityId]) -> Option<Vec<T::AccountId>> {
+            keys.iter()
+                .map(|key| {
+                    pallet_session::Pallet::<T>::key_owner(T::AuthorityId::ID, &key.to_raw_vec())
+                })
+                .collect()
+        }
+
+        fn bounded(
+            name: &str,
+            accounts: Option<Vec<T::AccountId>>,
+        ) -> Option<BoundedVec<T::AccountId, T::MaxAuthorities>> {
+            let accounts = accounts.and_then(|accounts| BoundedVec::try_from(accounts).ok());
+            if accounts.is_none() {
+                log::error!(
+                    target: LOG_TARGET,
+                    "Could not find the accounts of all the {name}, they stay unknown until the committee changes."
+                );
+            }
+            accounts
+        }
+    }
+
+    impl<T> OnRuntimeUpgrade for Migration<T>
+    where
+        T: Config + pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+    {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != StorageVersion::new(OLD_VERSION) {
+                log::info!(
+                    target: LOG_TARGET,
+                    "Skipping migrations from STORAGE_VERSION 2 to 3 for pallet aleph."
+                );
+                return T::DbWeight::get().reads(1);
+            };
+
+            info!(target: LOG_TARGET, "Running migration from STORAGE_VERSION 2 to 3 for pallet aleph.");
+
+            let authorities = Authorities::<T>::get();
+            let next_authorities = NextAuthorities::<T>::get();
+            let next_committee = NextFinalityCommittee::<T>::get();
+            // StorageVersion, Authorities, NextAuthorities, NextFinalityCommittee
+            let mut reads = 4 + authorities.len() as u64;
+            // StorageVersion, AuthorityAccounts, NextAuthorityAccounts
+            let writes = 3;
+
+            let accounts = Self::key_owners(&authorities);
+            // The next authorities are the members of the next committee that have keys, in the
+            // same order, so if all of them have keys the committee is enough. Otherwise the
+            // owners of the keys have to be looked up, which fails for keys rotated since.
+            let next_accounts = match next_committee.len() == next_authorities.len() {
+                true => Some(next_committee.into_inner()),
+                false => {
+                    reads += next_authorities.len() as u64;
+                    Self::key_owners(&next_authorities)
+                }
+            };
+            if let Some(accounts) = Self::bounded("authorities", accounts) {
+                AuthorityAccounts::<T>::put(accounts);
+            }
+            if let Some(accounts) = Self::bounded("next authorities", next_accounts) {
+                NextAuthorityAccounts::<T>::put(accounts);
+            }
+
+            StorageVersion::new(NEW_VERSION).put::<Pallet<T>>();
+            info!(target: LOG_TARGET, "Finished migration from STORAGE_VERSION 2 to 3 for pallet aleph.");
+
+            T::DbWeight::get().reads(reads) + T::DbWeight::get().writes(writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+            ensure_storage_version::<Pallet<T>>(OLD_VERSION)?;
+
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+            ensure_storage_version::<Pallet<T>>(NEW_VERSION)?;
+            frame_support::ensure!(
+                AuthorityAccounts::<T>::decode_len() == Authorities::<T>::decode_len(),
+                "Not all the accounts of the authorities were found"
+            );
+            frame_support::ensure!(
+                NextAuthorityAccounts::<T>::decode_len() == NextAuthorities::<T>::decode_len(),
+                "Not all the accounts of the next authorities were found"
+            );
+            Ok(())
+        }
+    }
+}
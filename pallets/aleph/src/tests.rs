@@ -1,7 +1,14 @@
-use frame_support::{storage_alias, traits::OneSessionHandler, BoundedVec};
-use primitives::VersionChange;
-
-use crate::{mock::*, NextFinalityCommittee};
+use frame_support::{
+    storage_alias,
+    traits::{OnRuntimeUpgrade, OneSessionHandler, StorageVersion},
+    BoundedVec,
+};
+use primitives::{crypto::SignatureSet, Score, SessionScore, ValidatorScores, VersionChange};
+
+use crate::{
+    migration::v3, mock::*, AbftScoreCommittees, AbftScores, AbftScoresRetention, Authorities,
+    AuthorityAccounts, NextAuthorityAccounts, NextFinalityCommittee,
+};
 
 #[storage_alias]
 type SessionForValidatorsChange = StorageValue<Aleph, u32>;
@@ -81,6 +88,8 @@ fn test_session_rotation() {
         Aleph::on_new_session(true, new_validators, queued_validators);
         assert_eq!(Aleph::authorities(), to_authorities(&[1, 2]));
         assert_eq!(Aleph::next_authorities(), to_authorities(&[5, 6]));
+        assert_eq!(AuthorityAccounts::<Test>::get().to_vec(), vec![0, 1]);
+        assert_eq!(NextAuthorityAccounts::<Test>::get().to_vec(), vec![5, 6]);
     })
 }
 
@@ -161,3 +170,181 @@ fn test_finality_version_scheduling() {
         assert!(scheduling_result.is_err());
     })
 }
+
+fn insert_score(session: u32, committee: Vec<u64>, points: Vec<u16>) {
+    AbftScores::<Test>::insert(
+        session,
+        Score {
+            session_id: session,
+            nonce: 1,
+            points: points.try_into().expect("Should fit in BoundedVec"),
+        },
+    );
+    AbftScoreCommittees::<Test>::insert(
+        session,
+        BoundedVec::try_from(committee).expect("Should fit in BoundedVec"),
+    );
+}
+
+#[test]
+fn test_abft_scores_pruning() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        AbftScoresRetention::<Test>::put(2);
+        for session in 0..4 {
+            insert_score(session, vec![0, 1], vec![1, 2]);
+        }
+
+        run_session(5);
+
+        for session in 0..3 {
+            assert_eq!(Aleph::abft_scores(session), None);
+            assert_eq!(AbftScoreCommittees::<Test>::get(session), None);
+        }
+        assert!(Aleph::abft_scores(3).is_some());
+        assert!(AbftScoreCommittees::<Test>::get(3).is_some());
+    })
+}
+
+#[test]
+fn test_abft_score_history() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        run_session(3);
+        insert_score(1, vec![0, 1], vec![3, 5]);
+        insert_score(2, vec![1, 2], vec![4, 0]);
+        // Sessions without a known committee are skipped.
+        AbftScoreCommittees::<Test>::remove(3);
+        AbftScores::<Test>::insert(
+            3,
+            Score {
+                session_id: 3,
+                nonce: 1,
+                points: vec![7, 7].try_into().expect("Should fit in BoundedVec"),
+            },
+        );
+
+        assert_eq!(
+            Aleph::abft_score_history(0, 10),
+            vec![
+                ValidatorScores {
+                    validator: 0,
+                    scores: vec![SessionScore {
+                        session: 1,
+                        score: 3
+                    }],
+                },
+                ValidatorScores {
+                    validator: 1,
+                    scores: vec![
+                        SessionScore {
+                            session: 1,
+                            score: 5
+                        },
+                        SessionScore {
+                            session: 2,
+                            score: 4
+                        },
+                    ],
+                },
+                ValidatorScores {
+                    validator: 2,
+                    scores: vec![SessionScore {
+                        session: 2,
+                        score: 0
+                    }],
+                },
+            ]
+        );
+        assert_eq!(Aleph::abft_score_history(2, 2).len(), 2);
+    })
+}
+
+#[test]
+fn score_is_attributed_to_committee_from_session_start() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        run_session(1);
+        assert_eq!(
+            AbftScoreCommittees::<Test>::get(1).map(|committee| committee.to_vec()),
+            Some(AuthorityAccounts::<Test>::get().to_vec())
+        );
+        let committee = AuthorityAccounts::<Test>::get().to_vec();
+
+        // The committee changes within the session, before the score arrives.
+        AuthorityAccounts::<Test>::put(BoundedVec::try_from(vec![5, 6]).unwrap());
+        let score = Score {
+            session_id: 1,
+            nonce: 1,
+            points: vec![3, 4].try_into().expect("Should fit in BoundedVec"),
+        };
+        assert!(Aleph::unsigned_submit_abft_score(
+            RuntimeOrigin::none(),
+            score,
+            SignatureSet(vec![])
+        )
+        .is_ok());
+
+        assert_eq!(
+            AbftScoreCommittees::<Test>::get(1).map(|committee| committee.to_vec()),
+            Some(committee)
+        );
+    })
+}
+
+/// Forgets the accounts of the authorities, as before they were kept.
+fn downgrade_to_v2() {
+    AuthorityAccounts::<Test>::kill();
+    NextAuthorityAccounts::<Test>::kill();
+    StorageVersion::new(2).put::<Aleph>();
+}
+
+#[test]
+fn migration_fills_authority_accounts() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        run_session(1);
+        NextFinalityCommittee::<Test>::put(BoundedVec::try_from(vec![0, 1]).unwrap());
+        downgrade_to_v2();
+
+        v3::Migration::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageVersion::get::<Aleph>(), StorageVersion::new(3));
+        assert_eq!(AuthorityAccounts::<Test>::get().to_vec(), vec![0, 1]);
+        assert_eq!(NextAuthorityAccounts::<Test>::get().to_vec(), vec![0, 1]);
+
+        // The accounts are kept up to date from there on.
+        NextFinalityCommittee::<Test>::put(BoundedVec::try_from(vec![5, 6]).unwrap());
+        Aleph::on_new_session(
+            true,
+            new_session_validators(&[1, 2]),
+            new_session_validators(&[5, 6]),
+        );
+        assert_eq!(AuthorityAccounts::<Test>::get().to_vec(), vec![0, 1]);
+        assert_eq!(NextAuthorityAccounts::<Test>::get().to_vec(), vec![5, 6]);
+
+        // Running it again changes nothing.
+        NextFinalityCommittee::<Test>::put(BoundedVec::try_from(vec![8, 9]).unwrap());
+        v3::Migration::<Test>::on_runtime_upgrade();
+        assert_eq!(NextAuthorityAccounts::<Test>::get().to_vec(), vec![5, 6]);
+    })
+}
+
+#[test]
+fn migration_looks_up_owners_of_keys_missing_from_committee() {
+    new_test_ext(&[(1u64, 1u64), (2u64, 2u64)]).execute_with(|| {
+        initialize_session();
+        run_session(1);
+        // The committee does not match the next authorities, so their keys are looked up.
+        NextFinalityCommittee::<Test>::put(BoundedVec::try_from(vec![7]).unwrap());
+        // Nobody owns the key of 3 anymore.
+        Authorities::<Test>::put(BoundedVec::try_from(to_authorities(&[1, 3])).unwrap());
+        downgrade_to_v2();
+
+        v3::Migration::<Test>::on_runtime_upgrade();
+
+        assert_eq!(StorageVersion::get::<Aleph>(), StorageVersion::new(3));
+        assert!(AuthorityAccounts::<Test>::get().is_empty());
+        assert_eq!(NextAuthorityAccounts::<Test>::get().to_vec(), vec![0, 1]);
+    })
+}
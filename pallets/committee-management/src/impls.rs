@@ -1,10 +1,18 @@
+use frame_support::traits::Randomness;
 use log::info;
 use parity_scale_codec::Encode;
 use primitives::{
     AbftScoresProvider, BanHandler, BanInfo, BanReason, BannedValidators, CommitteeSeats,
-    EraValidators, SessionCommittee, SessionValidatorError, SessionValidators, ValidatorProvider,
+    CommitteeSelectionMode, EraValidators, SessionCommittee, SessionPerformance,
+    SessionValidatorError, SessionValidators, ValidatorPerformance, ValidatorProvider,
+};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+    traits::{Get, Zero},
+    Perbill, Perquintill,
 };
-use sp_runtime::{traits::Get, Perbill, Perquintill};
 use sp_staking::{EraIndex, SessionIndex};
 use sp_std::{
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
@@ -14,16 +22,17 @@ use sp_std::{
 
 use crate::{
     pallet::{
-        Banned, Config, CurrentAndNextSessionValidatorsStorage, Event, Pallet,
+        Banned, CommitteeSeed, Config, CurrentAndNextSessionValidatorsStorage, Event, Pallet,
         SessionValidatorBlockCount, UnderperformedFinalizerSessionCount,
         UnderperformedValidatorSessionCount, ValidatorEraTotalReward,
     },
-    traits::{EraInfoProvider, ValidatorRewardsHandler},
+    traits::{EraInfoProvider, ValidatorRewardsHandler, ValidatorSlasher},
     CurrentAndNextSessionValidators, LenientThreshold, ProductionBanConfigStruct,
     ValidatorExtractor, ValidatorTotalRewards, LOG_TARGET,
 };
 
 const MAX_REWARD: u32 = 1_000_000_000;
+const COMMITTEE_SEED_SUBJECT: &[u8] = b"committee-selection";
 
 impl<T: Config> BannedValidators for Pallet<T> {
     type AccountId = T::AccountId;
@@ -112,6 +121,92 @@ fn select_committee_inner<AccountId: Clone + PartialEq>(
     })
 }
 
+/// Choose `count` distinct items, each draw with probability proportional to the weight among
+/// the items not chosen yet.
+fn choose_weighted<T: Clone>(
+    candidates: &[(T, u128)],
+    count: usize,
+    rng: &mut Pcg32,
+) -> Option<Vec<T>> {
+    if candidates.is_empty() || count == 0 {
+        return None;
+    }
+
+    let mut remaining = candidates.to_vec();
+    let mut chosen = Vec::new();
+
+    while chosen.len() < count && !remaining.is_empty() {
+        let total = remaining
+            .iter()
+            .fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+        let mut point = rng.gen_range(0..total.max(1));
+        let index = remaining
+            .iter()
+            .position(|(_, weight)| match point < *weight {
+                true => true,
+                false => {
+                    point -= weight;
+                    false
+                }
+            })
+            .unwrap_or(0);
+        chosen.push(remaining.swap_remove(index).0);
+    }
+
+    Some(chosen)
+}
+
+fn session_rng(seed: &[u8], session: SessionIndex) -> Pcg32 {
+    let hash = blake2_256(&(seed, session).encode());
+    let mut rng_seed = [0u8; 16];
+    rng_seed.copy_from_slice(&hash[..16]);
+    Pcg32::from_seed(rng_seed)
+}
+
+fn select_committee_stake_weighted<AccountId: Clone + PartialEq>(
+    seed: &[u8],
+    current_session: SessionIndex,
+    reserved_seats: usize,
+    non_reserved_seats: usize,
+    non_reserved_finality_seats: usize,
+    reserved: &[AccountId],
+    non_reserved: &[(AccountId, u128)],
+) -> Option<SessionCommittee<AccountId>> {
+    // Reserved validators rotate as usual, the non reserved producers are sampled by stake and
+    // the non reserved finalizers are sampled by stake among them.
+    let mut rng = session_rng(seed, current_session);
+
+    let reserved_committee = choose_for_session(reserved, reserved_seats, current_session as usize);
+    let non_reserved_committee = choose_weighted(non_reserved, non_reserved_seats, &mut rng);
+
+    let non_reserved_finalizers = non_reserved_committee
+        .as_ref()
+        .map(|nrc| {
+            non_reserved
+                .iter()
+                .filter(|(v, _)| nrc.contains(v))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .and_then(|nrc| choose_weighted(&nrc, non_reserved_finality_seats, &mut rng))
+        .unwrap_or_default();
+
+    let mut finalizers = reserved_committee.clone().unwrap_or_default();
+    finalizers.extend(non_reserved_finalizers);
+
+    let producers = match (reserved_committee, non_reserved_committee) {
+        (Some(rc), Some(nrc)) => Some(rc.into_iter().chain(nrc.into_iter()).collect()),
+        (Some(rc), _) => Some(rc),
+        (_, Some(nrc)) => Some(nrc),
+        _ => None,
+    }?;
+
+    Some(SessionCommittee {
+        producers,
+        finalizers,
+    })
+}
+
 fn calculate_adjusted_session_points(
     sessions_per_era: EraIndex,
     blocks_to_produce_per_session: u32,
@@ -305,14 +400,43 @@ impl<T: Config> Pallet<T> {
             non_reserved_finality_seats,
         } = committee_seats;
 
-        select_committee_inner(
-            current_session,
-            reserved_seats as usize,
-            non_reserved_seats as usize,
-            non_reserved_finality_seats as usize,
-            reserved,
-            non_reserved,
-        )
+        match Self::committee_selection_mode() {
+            CommitteeSelectionMode::RoundRobin => select_committee_inner(
+                current_session,
+                reserved_seats as usize,
+                non_reserved_seats as usize,
+                non_reserved_finality_seats as usize,
+                reserved,
+                non_reserved,
+            ),
+            CommitteeSelectionMode::StakeWeighted => {
+                let era = T::EraInfoProvider::current_era().unwrap_or(0);
+                // Validators without a known stake still get a minimal chance.
+                let non_reserved: Vec<_> = non_reserved
+                    .iter()
+                    .map(|v| {
+                        let stake = T::ValidatorRewardsHandler::validator_total(era, v);
+                        (v.clone(), stake.unwrap_or(0).max(1))
+                    })
+                    .collect();
+
+                select_committee_stake_weighted(
+                    Self::committee_seed().as_ref(),
+                    current_session,
+                    reserved_seats as usize,
+                    non_reserved_seats as usize,
+                    non_reserved_finality_seats as usize,
+                    reserved,
+                    &non_reserved,
+                )
+            }
+        }
+    }
+
+    /// Fixes the seed of the stake weighted committee selection for the just planned era.
+    pub(crate) fn update_committee_seed() {
+        let (seed, _) = T::Randomness::random(COMMITTEE_SEED_SUBJECT);
+        CommitteeSeed::<T>::put(seed);
     }
 
     pub(crate) fn rotate_committee(
@@ -370,7 +494,10 @@ impl<T: Config> Pallet<T> {
                     });
                 if counter >= underperformed_session_count_threshold {
                     let reason = BanReason::InsufficientFinalization(counter);
-                    Self::ban_validator(validator, reason);
+                    if Self::ban_validator(validator, reason) {
+                        let slash = Self::underperformance_slash_config().finality_slash;
+                        Self::slash_underperformer(validator, slash, session_id);
+                    }
                     UnderperformedFinalizerSessionCount::<T>::remove(validator);
                 }
             }
@@ -379,7 +506,7 @@ impl<T: Config> Pallet<T> {
         underperf_finalizers
     }
 
-    pub(crate) fn calculate_underperforming_validators() {
+    pub(crate) fn calculate_underperforming_validators(session_id: SessionIndex) {
         let thresholds = Self::production_ban_config();
         let CurrentAndNextSessionValidators {
             current: SessionValidators { producers, .. },
@@ -395,7 +522,7 @@ impl<T: Config> Pallet<T> {
                 Err(_) => true,
             };
             if underperformance {
-                Self::mark_validator_underperformance(&thresholds, &validator);
+                Self::mark_validator_underperformance(&thresholds, &validator, session_id);
             }
         }
     }
@@ -403,6 +530,7 @@ impl<T: Config> Pallet<T> {
     pub(crate) fn mark_validator_underperformance(
         thresholds: &ProductionBanConfigStruct,
         validator: &T::AccountId,
+        session_id: SessionIndex,
     ) {
         let counter = UnderperformedValidatorSessionCount::<T>::mutate(validator, |count| {
             *count += 1;
@@ -410,11 +538,26 @@ impl<T: Config> Pallet<T> {
         });
         if counter >= thresholds.underperformed_session_count_threshold {
             let reason = BanReason::InsufficientProduction(counter);
-            Self::ban_validator(validator, reason);
+            if Self::ban_validator(validator, reason) {
+                let slash = Self::underperformance_slash_config().production_slash;
+                Self::slash_underperformer(validator, slash, session_id);
+            }
             UnderperformedValidatorSessionCount::<T>::remove(validator);
         }
     }
 
+    fn slash_underperformer(validator: &T::AccountId, slash: Perbill, session_id: SessionIndex) {
+        if slash.is_zero() {
+            return;
+        }
+        info!(
+            target: LOG_TARGET,
+            "Slashing {:?} by {:?} for underperformance in session {}", validator, slash, session_id
+        );
+        T::ValidatorSlasher::slash(validator, slash, session_id);
+        Self::deposit_event(Event::UnderperformanceSlash(validator.clone(), slash));
+    }
+
     pub(crate) fn clear_underperformance_session_counter(session: SessionIndex) {
         let clean_session_counter_delay = Self::production_ban_config().clean_session_counter_delay;
         if session % clean_session_counter_delay == 0 {
@@ -431,7 +574,6 @@ impl<T: Config> Pallet<T> {
                 "Clearing UnderperformedFinalizerSessionCount"
             );
             let _result = UnderperformedFinalizerSessionCount::<T>::clear(u32::MAX, None);
-            T::AbftScoresProvider::clear_scores();
         }
     }
 
@@ -446,16 +588,19 @@ impl<T: Config> Pallet<T> {
         unban.for_each(Banned::<T>::remove);
     }
 
-    pub fn ban_validator(validator: &T::AccountId, reason: BanReason) {
+    /// Bans the validator from the next era, returns whether it could be banned.
+    pub fn ban_validator(validator: &T::AccountId, reason: BanReason) -> bool {
         // current era is the latest planned era for which validators are already chosen
         // so we ban from the next era
         let start: EraIndex = T::EraInfoProvider::current_era()
             .unwrap_or(0)
             .saturating_add(1);
-        if T::BanHandler::can_ban(validator) {
-            Banned::<T>::insert(validator, BanInfo { reason, start });
-            T::ValidatorExtractor::remove_validator(validator);
+        if !T::BanHandler::can_ban(validator) {
+            return false;
         }
+        Banned::<T>::insert(validator, BanInfo { reason, start });
+        T::ValidatorExtractor::remove_validator(validator);
+        true
     }
 
     pub fn emit_fresh_bans_event() {
@@ -503,6 +648,94 @@ impl<T: Config> Pallet<T> {
         Self::select_committee(&era_validators, committee_seats, session)
             .ok_or_else(|| SessionValidatorError::Other("Internal error".encode()))
     }
+
+    /// Committee of the `session`, as long as it can still be told. `current_session` is the
+    /// ongoing session.
+    fn session_committee(
+        session: SessionIndex,
+        current_session: SessionIndex,
+    ) -> Option<SessionCommittee<T::AccountId>> {
+        let CurrentAndNextSessionValidators { current, next } =
+            CurrentAndNextSessionValidatorsStorage::<T>::get();
+        let stored = match session {
+            s if s == current_session => current,
+            s if s == current_session.saturating_add(1) => next,
+            _ => return Self::predict_session_committee_for_session(session).ok(),
+        };
+
+        Some(SessionCommittee {
+            producers: stored.producers.to_vec(),
+            finalizers: stored.finalizers.to_vec(),
+        })
+    }
+
+    /// Collects the performance of `validator` in sessions from `first_session` to
+    /// `last_session`, inclusive. Committees are only known from the start of the current era,
+    /// so `first_session` must be within the current era or be the ongoing or the next session.
+    /// Sessions after the next one are skipped, as nothing is known about them yet.
+    pub fn validator_performance(
+        validator: T::AccountId,
+        first_session: SessionIndex,
+        last_session: SessionIndex,
+        current_session: SessionIndex,
+    ) -> Result<ValidatorPerformance<T::AccountId>, SessionValidatorError> {
+        let upper_limit = current_session.saturating_add(1);
+        // In the last session of an era the current era is already the next one.
+        let lower_limit = T::EraInfoProvider::current_era()
+            .and_then(T::EraInfoProvider::era_start_session_index)
+            .map_or(current_session, |start| start.min(current_session));
+        if first_session < lower_limit || first_session > upper_limit.min(last_session) {
+            return Err(SessionValidatorError::SessionNotWithinRange {
+                lower_limit,
+                upper_limit,
+            });
+        }
+        let last_session = last_session.min(upper_limit);
+        let blocks_per_session = Self::blocks_to_produce_per_session();
+
+        let sessions = (first_session..=last_session)
+            .map(|session| {
+                let committee = Self::session_committee(session, current_session);
+                let producer = committee.as_ref().map(|c| c.producers.contains(&validator));
+                let finalizer_index = committee
+                    .as_ref()
+                    .and_then(|c| c.finalizers.iter().position(|v| v == &validator));
+                let finality_score = finalizer_index.and_then(|index| {
+                    T::AbftScoresProvider::scores_for_session(session)
+                        .and_then(|score| score.points.get(index).copied())
+                });
+                let blocks_produced = (session == current_session)
+                    .then(|| SessionValidatorBlockCount::<T>::get(&validator));
+
+                SessionPerformance {
+                    session,
+                    producer,
+                    finalizer: committee.as_ref().map(|_| finalizer_index.is_some()),
+                    blocks_produced,
+                    blocks_expected: producer
+                        .map(|producer| if producer { blocks_per_session } else { 0 }),
+                    finality_score,
+                }
+            })
+            .collect();
+
+        let active_era = T::EraInfoProvider::active_era().unwrap_or(0);
+        let ban_period = Self::production_ban_config().ban_period;
+        let ban = Banned::<T>::get(&validator)
+            .filter(|info| !ban_expired(info.start, ban_period, active_era + 1));
+
+        Ok(ValidatorPerformance {
+            sessions,
+            underperformed_producer_session_count: UnderperformedValidatorSessionCount::<T>::get(
+                &validator,
+            ),
+            underperformed_finalizer_session_count: UnderperformedFinalizerSessionCount::<T>::get(
+                &validator,
+            ),
+            ban,
+            validator,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -512,8 +745,8 @@ mod tests {
     use sp_runtime::Perquintill;
 
     use crate::impls::{
-        calculate_adjusted_session_points, compute_validator_scaled_total_rewards,
-        select_committee_inner, MAX_REWARD,
+        calculate_adjusted_session_points, choose_weighted, compute_validator_scaled_total_rewards,
+        select_committee_inner, select_committee_stake_weighted, session_rng, MAX_REWARD,
     };
 
     const THRESHOLD: Perquintill = Perquintill::from_percent(90);
@@ -658,4 +891,57 @@ mod tests {
             assert_eq!(expected_committee, committee,);
         }
     }
+
+    #[test]
+    fn weighted_choice_is_deterministic_and_without_repetitions() {
+        let candidates: Vec<_> = (0..20u64).map(|v| (v, 1 + v as u128)).collect();
+
+        let chosen = choose_weighted(&candidates, 10, &mut session_rng(b"seed", 7))
+            .expect("Expected non-empty choice!");
+
+        assert_eq!(
+            Some(chosen.clone()),
+            choose_weighted(&candidates, 10, &mut session_rng(b"seed", 7))
+        );
+        assert_eq!(10, BTreeSet::from_iter(chosen).len());
+    }
+
+    #[test]
+    fn weighted_choice_prefers_higher_stake() {
+        let candidates = vec![(0, 1), (1, 1_000_000)];
+
+        let heavy_chosen = (0..100)
+            .filter(|session| {
+                choose_weighted(&candidates, 1, &mut session_rng(b"seed", *session))
+                    == Some(vec![1])
+            })
+            .count();
+
+        assert!(heavy_chosen > 90);
+    }
+
+    #[test]
+    fn stake_weighted_committee_contains_reserved_and_finalizers_among_producers() {
+        let reserved: Vec<_> = (0..5).collect();
+        let non_reserved: Vec<_> = (5..50).map(|v| (v, v as u128)).collect();
+
+        for session_index in 0u32..20u32 {
+            let committee = select_committee_stake_weighted(
+                b"seed",
+                session_index,
+                5,
+                10,
+                4,
+                &reserved,
+                &non_reserved,
+            )
+            .expect("Expected non-empty committee!");
+            let producers = BTreeSet::from_iter(committee.producers.iter());
+
+            assert_eq!(15, producers.len());
+            assert_eq!(9, committee.finalizers.len());
+            assert!(reserved.iter().all(|v| producers.contains(v)));
+            assert!(committee.finalizers.iter().all(|v| producers.contains(v)));
+        }
+    }
 }
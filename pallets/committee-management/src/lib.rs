@@ -18,7 +18,8 @@ pub use pallet::*;
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{
     BanInfo, FinalityBanConfig as FinalityBanConfigStruct,
-    ProductionBanConfig as ProductionBanConfigStruct, SessionValidators, LENIENT_THRESHOLD,
+    ProductionBanConfig as ProductionBanConfigStruct, SessionValidators,
+    UnderperformanceSlashConfig as UnderperformanceSlashConfigStruct, LENIENT_THRESHOLD,
 };
 use scale_info::TypeInfo;
 use sp_runtime::Perquintill;
@@ -61,12 +62,13 @@ pub(crate) const LOG_TARGET: &str = "pallet-committee-management";
 #[pallet_doc("../README.md")]
 pub mod pallet {
     use frame_support::{
-        dispatch::DispatchResult, ensure, pallet_prelude::*, BoundedVec, Twox64Concat,
+        dispatch::DispatchResult, ensure, pallet_prelude::*, traits::Randomness, BoundedVec,
+        Twox64Concat,
     };
-    use frame_system::pallet_prelude::OriginFor;
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
     use primitives::{
-        AbftScoresProvider, BanHandler, BanReason, BlockCount, FinalityCommitteeManager,
-        SessionCount, SessionValidators, ValidatorProvider,
+        AbftScoresProvider, BanHandler, BanReason, BlockCount, CommitteeSelectionMode,
+        FinalityCommitteeManager, SessionCount, SessionValidators, ValidatorProvider,
     };
     use sp_runtime::{Perbill, Perquintill};
     use sp_staking::EraIndex;
@@ -75,7 +77,8 @@ pub mod pallet {
     use crate::{
         traits::{EraInfoProvider, ValidatorRewardsHandler},
         BanInfo, CurrentAndNextSessionValidators, DefaultLenientThreshold, FinalityBanConfigStruct,
-        ProductionBanConfigStruct, ValidatorExtractor, ValidatorTotalRewards, STORAGE_VERSION,
+        ProductionBanConfigStruct, UnderperformanceSlashConfigStruct, ValidatorExtractor,
+        ValidatorSlasher, ValidatorTotalRewards, STORAGE_VERSION,
     };
 
     #[pallet::config]
@@ -94,8 +97,13 @@ pub mod pallet {
         type ValidatorRewardsHandler: ValidatorRewardsHandler<AccountId = Self::AccountId>;
         /// Something that handles removal of the validators
         type ValidatorExtractor: ValidatorExtractor<AccountId = Self::AccountId>;
+        /// Something that slashes validators banned for underperformance
+        type ValidatorSlasher: ValidatorSlasher<AccountId = Self::AccountId>;
         type FinalityCommitteeManager: FinalityCommitteeManager<Self::AccountId>;
         type AbftScoresProvider: AbftScoresProvider;
+        /// Source of the seed for stake weighted committee selection, queried when an era is planned.
+        /// [`RecentBlocksRandomness`] mixes the hashes of the most recent blocks.
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
         /// Nr of blocks in the session.
         #[pallet::constant]
         type SessionPeriod: Get<u32>;
@@ -157,6 +165,22 @@ pub mod pallet {
     #[pallet::getter(fn finality_ban_config)]
     pub type FinalityBanConfig<T> = StorageValue<_, FinalityBanConfigStruct, ValueQuery>;
 
+    /// Slashes applied together with bans for underperformance, off by default.
+    #[pallet::storage]
+    #[pallet::getter(fn underperformance_slash_config)]
+    pub type UnderperformanceSlashConfig<T> =
+        StorageValue<_, UnderperformanceSlashConfigStruct, ValueQuery>;
+
+    /// How non reserved validators are chosen to the committee, takes effect with the next rotation.
+    #[pallet::storage]
+    #[pallet::getter(fn committee_selection_mode)]
+    pub type SelectionMode<T> = StorageValue<_, CommitteeSelectionMode, ValueQuery>;
+
+    /// Seed of the stake weighted committee selection in the current era.
+    #[pallet::storage]
+    #[pallet::getter(fn committee_seed)]
+    pub type CommitteeSeed<T: Config> = StorageValue<_, T::Hash, ValueQuery>;
+
     #[pallet::error]
     pub enum Error<T> {
         /// Raised in any scenario [`ProductionBanConfig`] is invalid
@@ -184,6 +208,15 @@ pub mod pallet {
 
         /// Validators have been banned from the committee
         BanValidators(Vec<(T::AccountId, BanInfo)>),
+
+        /// Underperformance slashes have changed
+        SetUnderperformanceSlashConfig(UnderperformanceSlashConfigStruct),
+
+        /// Validator has been reported for slashing due to underperformance, the slash is deferred
+        UnderperformanceSlash(T::AccountId, Perbill),
+
+        /// Committee selection mode has changed
+        SetCommitteeSelectionMode(CommitteeSelectionMode),
     }
 
     #[pallet::call]
@@ -339,6 +372,57 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Sets fractions of the exposure slashed when banning for underperformance, zero turns
+        /// the slashing off. Slashes are deferred and can be cancelled in `pallet_staking`.
+        #[pallet::call_index(6)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn set_underperformance_slash_config(
+            origin: OriginFor<T>,
+            production_slash: Option<Perbill>,
+            finality_slash: Option<Perbill>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let mut slash_config = Self::underperformance_slash_config();
+
+            if let Some(production_slash) = production_slash {
+                slash_config.production_slash = production_slash;
+            }
+            if let Some(finality_slash) = finality_slash {
+                slash_config.finality_slash = finality_slash;
+            }
+
+            UnderperformanceSlashConfig::<T>::put(slash_config.clone());
+            Self::deposit_event(Event::SetUnderperformanceSlashConfig(slash_config));
+
+            Ok(())
+        }
+
+        /// Sets how non reserved validators are chosen to the committee, starting from the next
+        /// rotation.
+        ///
+        /// The stake weighted selection is only as unpredictable as [`Config::Randomness`]. With
+        /// [`RecentBlocksRandomness`] the author of the block planning an era can still
+        /// withhold or grind that single block to influence the committees of the era, so the
+        /// mode should only be enabled where that is an acceptable risk.
+        #[pallet::call_index(7)]
+        #[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+        pub fn set_committee_selection_mode(
+            origin: OriginFor<T>,
+            mode: CommitteeSelectionMode,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            SelectionMode::<T>::put(mode);
+            // The seed is otherwise only set when the next era is planned.
+            if mode == CommitteeSelectionMode::StakeWeighted && !CommitteeSeed::<T>::exists() {
+                Self::update_committee_seed();
+            }
+            Self::deposit_event(Event::SetCommitteeSelectionMode(mode));
+
+            Ok(())
+        }
     }
 
     #[pallet::genesis_config]
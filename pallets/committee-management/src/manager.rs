@@ -24,7 +24,7 @@ use crate::{
 /// *  if session `S+1` % `clean_session_counter_delay` == 0, we clean up underperformed session counter.
 /// * `clean_session_counter_delay` is read from pallet's storage
 /// 4. `new_session(S + 2)` is called.
-/// *  If session `S+2` starts new era we emit fresh bans events
+/// *  If session `S+2` starts new era we fix the committee selection seed for it and emit fresh bans events
 /// *  We rotate the validators for session `S + 2` using the information about reserved and non reserved validators.
 
 impl<T> pallet_authorship::EventHandler<T::AccountId, BlockNumberFor<T>> for Pallet<T>
@@ -119,6 +119,7 @@ where
         T::new_session(new_index);
         if let Some(era) = Self::session_starts_next_era(new_index) {
             EM::on_new_era(era);
+            Pallet::<C>::update_committee_seed();
             Pallet::<C>::emit_fresh_bans_event();
         }
 
@@ -141,7 +142,7 @@ where
 
     fn end_session(end_index: SessionIndex) {
         T::end_session(end_index);
-        Pallet::<C>::calculate_underperforming_validators();
+        Pallet::<C>::calculate_underperforming_validators(end_index);
         let underperfs = Pallet::<C>::calculate_underperforming_finalizers(end_index);
         Pallet::<C>::adjust_rewards_for_session(underperfs);
         // clear block count after calculating stats for underperforming validators, as they use
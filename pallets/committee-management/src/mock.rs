@@ -12,7 +12,7 @@ use primitives::{
     TotalIssuanceProvider as TotalIssuanceProviderT, DEFAULT_MAX_WINNERS, DEFAULT_SESSIONS_PER_ERA,
     DEFAULT_SESSION_PERIOD,
 };
-use sp_core::{ConstU64, H256};
+use sp_core::{ConstU128, ConstU64, H256};
 use sp_runtime::{
     impl_opaque_keys,
     testing::{TestXt, UintAuthorityId},
@@ -234,6 +234,7 @@ impl TotalIssuanceProviderT for TotalIssuanceProvider {
 
 parameter_types! {
     pub static MaxWinners: u32 = DEFAULT_MAX_WINNERS;
+    pub const MaxCommissionCap: Perbill = Perbill::one();
 }
 
 impl pallet_elections::Config for TestRuntime {
@@ -244,6 +245,13 @@ impl pallet_elections::Config for TestRuntime {
     type BannedValidators = CommitteeManagement;
     type MaxValidators = ConstU32<100>;
     type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type SessionKeysProvider = Session;
+    type StakingInfoProvider = Staking;
+    type Currency = Balances;
+    type ValidatorIntentDeposit = ConstU128<1>;
+    type MaxCommissionCap = MaxCommissionCap;
+    type MinSelfBond = ConstU128<1>;
+    type WeightInfo = ();
 }
 
 impl Config for TestRuntime {
@@ -254,9 +262,11 @@ impl Config for TestRuntime {
     type ValidatorProvider = Elections;
     type ValidatorRewardsHandler = Staking;
     type ValidatorExtractor = Staking;
+    type ValidatorSlasher = Staking;
     type FinalityCommitteeManager = Aleph;
     type SessionPeriod = SessionPeriod;
     type AbftScoresProvider = Aleph;
+    type Randomness = RecentBlocksRandomness<Self>;
     type MaxValidators = ConstU32<100>;
     type MaxValidatorRewards = ConstU32<100>;
 }
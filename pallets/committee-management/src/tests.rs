@@ -1,16 +1,23 @@
 use std::collections::BTreeSet;
 
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Currency, Randomness},
+};
+use frame_system::RawOrigin;
 use pallet_aleph::AbftScores;
-use primitives::{BanInfo, BannedValidators, Score};
+use primitives::{BanInfo, BannedValidators, CommitteeSelectionMode, Score, SessionValidatorError};
+use sp_core::H256;
+use sp_runtime::{DispatchError, Perbill};
 
 use crate::{
     mock::{
-        active_era, advance_era, committee_management_events, start_session, AccountId,
-        CommitteeManagement, Elections, SessionPeriod, TestBuilderConfig, TestExtBuilder,
-        TestRuntime,
+        active_era, advance_era, committee_management_events, run_to_block, start_session,
+        AccountId, Balances, CommitteeManagement, Elections, SessionPeriod, SessionsPerEra, System,
+        TestBuilderConfig, TestExtBuilder, TestRuntime,
     },
-    CurrentAndNextSessionValidatorsStorage, Event, FinalityBanConfig, ProductionBanConfig,
-    SessionValidatorBlockCount,
+    CommitteeSeed, CurrentAndNextSessionValidatorsStorage, Event, FinalityBanConfig,
+    ProductionBanConfig, RecentBlocksRandomness, SessionValidatorBlockCount,
 };
 
 fn gen_config() -> TestBuilderConfig {
@@ -228,3 +235,238 @@ fn ban_underperforming_finalizers() {
         assert_eq!(CommitteeManagement::banned(), Vec::<AccountId>::new());
     })
 }
+
+#[test]
+fn validator_performance_reports_ongoing_session() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        let session_index = 2;
+        start_session(session_index);
+        // Reserved validators are in every committee.
+        let validator = 0;
+        let finalizers = CommitteeManagement::current_session_validators()
+            .current
+            .finalizers;
+        let finalizer_index = finalizers
+            .iter()
+            .position(|v| *v == validator)
+            .expect("Reserved validator should be a finalizer");
+        let mut points = vec![0; finalizers.len()];
+        points[finalizer_index] = 7;
+        let score = Score {
+            session_id: session_index,
+            nonce: 1,
+            points: points.try_into().expect("Should fit in BoundedVec"),
+        };
+        AbftScores::<TestRuntime>::insert(session_index, score);
+        SessionValidatorBlockCount::<TestRuntime>::insert(validator, 3);
+
+        let performance = CommitteeManagement::validator_performance(
+            validator,
+            session_index,
+            session_index + 5,
+            session_index,
+        )
+        .expect("Ongoing session should be within range");
+
+        // Only the ongoing and the next session are reported.
+        assert_eq!(performance.sessions.len(), 2);
+        let ongoing = &performance.sessions[0];
+        assert_eq!(ongoing.producer, Some(true));
+        assert_eq!(ongoing.finalizer, Some(true));
+        assert_eq!(ongoing.blocks_produced, Some(3));
+        assert_eq!(
+            ongoing.blocks_expected,
+            Some(CommitteeManagement::blocks_to_produce_per_session())
+        );
+        assert_eq!(ongoing.finality_score, Some(7));
+        let next = &performance.sessions[1];
+        assert_eq!(next.blocks_produced, None);
+        assert_eq!(next.finality_score, None);
+        assert_eq!(performance.ban, None);
+    })
+}
+
+#[test]
+fn validator_performance_rejects_sessions_outside_current_era() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        // The second session of the era 1, which spans sessions 3 to 5.
+        start_session(SessionsPerEra::get() + 1);
+        let current_session = SessionsPerEra::get() + 1;
+        let out_of_range = Err(SessionValidatorError::SessionNotWithinRange {
+            lower_limit: SessionsPerEra::get(),
+            upper_limit: current_session + 1,
+        });
+
+        // Committees of the previous era are no longer known.
+        assert_eq!(
+            CommitteeManagement::validator_performance(0, 0, current_session, current_session),
+            out_of_range
+        );
+        // Nothing is known about sessions after the next one.
+        assert_eq!(
+            CommitteeManagement::validator_performance(
+                0,
+                current_session + 2,
+                current_session + 5,
+                current_session
+            ),
+            out_of_range
+        );
+
+        // The span is clamped to the next session.
+        let performance = CommitteeManagement::validator_performance(
+            0,
+            SessionsPerEra::get(),
+            u32::MAX,
+            current_session,
+        )
+        .expect("Current era should be within range");
+        assert_eq!(performance.sessions.len(), 3);
+    })
+}
+
+#[test]
+fn only_admin_sets_underperformance_slash_config() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        let slash = Perbill::from_percent(10);
+        assert_noop!(
+            CommitteeManagement::set_underperformance_slash_config(
+                RawOrigin::Signed(10).into(),
+                Some(slash),
+                None
+            ),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(CommitteeManagement::set_underperformance_slash_config(
+            RawOrigin::Root.into(),
+            Some(slash),
+            None
+        ));
+        let slash_config = CommitteeManagement::underperformance_slash_config();
+        assert_eq!(slash_config.production_slash, slash);
+        assert_eq!(slash_config.finality_slash, Perbill::from_percent(0));
+        assert_eq!(
+            *committee_management_events().last().unwrap(),
+            Event::SetUnderperformanceSlashConfig(slash_config)
+        );
+    })
+}
+
+#[test]
+fn slash_banned_underperforming_producers() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        let session_index = 2;
+        start_session(session_index);
+        let slash = Perbill::from_percent(10);
+        assert_ok!(CommitteeManagement::set_underperformance_slash_config(
+            RawOrigin::Root.into(),
+            Some(slash),
+            None
+        ));
+        let ban_config = CommitteeManagement::production_ban_config();
+        // Reserved validators can't be banned, so they are not slashed either.
+        for underperformer in [0, 10] {
+            for _ in 0..ban_config.underperformed_session_count_threshold {
+                CommitteeManagement::mark_validator_underperformance(
+                    &ban_config,
+                    &underperformer,
+                    session_index,
+                );
+            }
+        }
+
+        assert_eq!(CommitteeManagement::banned(), vec![10]);
+        let slashes: Vec<_> = committee_management_events()
+            .into_iter()
+            .filter(|e| matches!(e, Event::UnderperformanceSlash(..)))
+            .collect();
+        assert_eq!(slashes, vec![Event::UnderperformanceSlash(10, slash)]);
+
+        // Slashes are not deferred in the mock, so the bond is reduced right away.
+        let active_bond = |stash| {
+            pallet_staking::Ledger::<TestRuntime>::get(stash)
+                .expect("Validators should be bonded")
+                .active
+        };
+        assert_eq!(active_bond(10), 4_500_000);
+        assert_eq!(active_bond(0), 5_000_000);
+        assert_eq!(Balances::total_balance(&10), 9_500_000);
+    })
+}
+
+#[test]
+fn randomness_mixes_recent_block_hashes() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        let random = || RecentBlocksRandomness::<TestRuntime>::random(b"subject");
+        let mut seen = BTreeSet::new();
+        for block in 2..6 {
+            run_to_block(block);
+            frame_system::BlockHash::<TestRuntime>::insert(
+                block - 1,
+                H256::repeat_byte(block as u8),
+            );
+            let (seed, parent_number) = random();
+            assert_eq!(parent_number, block - 1);
+            assert!(seen.insert(seed));
+        }
+
+        // Changing any of the recent hashes changes the outcome.
+        let (seed, _) = random();
+        frame_system::BlockHash::<TestRuntime>::insert(2, H256::repeat_byte(0xFF));
+        assert_ne!(random().0, seed);
+    })
+}
+
+#[test]
+fn switching_to_stake_weighted_sets_seed() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        assert!(!CommitteeSeed::<TestRuntime>::exists());
+
+        assert_ok!(CommitteeManagement::set_committee_selection_mode(
+            RawOrigin::Root.into(),
+            CommitteeSelectionMode::StakeWeighted
+        ));
+        let seed = CommitteeManagement::committee_seed();
+        assert_ne!(seed, Default::default());
+
+        // Switching again within the era keeps the committees predictable.
+        run_to_block(System::block_number() + 1);
+        assert_ok!(CommitteeManagement::set_committee_selection_mode(
+            RawOrigin::Root.into(),
+            CommitteeSelectionMode::StakeWeighted
+        ));
+        assert_eq!(CommitteeManagement::committee_seed(), seed);
+    })
+}
+
+#[test]
+fn stake_weighted_committee_is_predictable_within_era() {
+    TestExtBuilder::new(gen_config()).build().execute_with(|| {
+        start_session(2);
+        assert_ok!(CommitteeManagement::set_committee_selection_mode(
+            RawOrigin::Root.into(),
+            CommitteeSelectionMode::StakeWeighted
+        ));
+        // Seed for the next era is fixed when it is planned.
+        advance_era();
+
+        let era_start = pallet_staking::ErasStartSessionIndex::<TestRuntime>::get(active_era())
+            .expect("Active era should have a starting session");
+        for session_index in era_start + 1..era_start + SessionsPerEra::get() {
+            let predicted =
+                CommitteeManagement::predict_session_committee_for_session(session_index)
+                    .expect("Session should be within the current era");
+            start_session(session_index);
+            let current = CommitteeManagement::current_session_validators().current;
+
+            assert_eq!(
+                BTreeSet::from_iter(predicted.producers),
+                BTreeSet::from_iter(current.producers)
+            );
+            assert_eq!(predicted.finalizers, current.finalizers.to_vec());
+        }
+    })
+}
@@ -1,6 +1,16 @@
-use frame_support::pallet_prelude::Get;
-use sp_staking::{EraIndex, SessionIndex};
-use sp_std::vec::Vec;
+use frame_support::{pallet_prelude::Get, traits::Randomness, weights::Weight};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_session::historical::IdentificationTuple;
+use parity_scale_codec::Encode;
+use sp_runtime::{
+    traits::{Convert, Hash, One, Saturating},
+    Perbill,
+};
+use sp_staking::{
+    offence::{OffenceDetails, OnOffenceHandler},
+    EraIndex, SessionIndex,
+};
+use sp_std::{marker::PhantomData, vec, vec::Vec};
 
 pub trait EraInfoProvider {
     type AccountId;
@@ -54,6 +64,8 @@ pub trait ValidatorRewardsHandler {
     type AccountId;
     /// Returns total exposure of validators for the `era`
     fn validator_totals(era: EraIndex) -> Vec<(Self::AccountId, u128)>;
+    /// Returns total exposure of `validator` for the `era`, if it was elected in it
+    fn validator_total(era: EraIndex, validator: &Self::AccountId) -> Option<u128>;
     /// Add reward for validators
     fn add_rewards(rewards: impl IntoIterator<Item = (Self::AccountId, u32)>);
 }
@@ -74,6 +86,16 @@ where
             .collect()
     }
 
+    fn validator_total(era: EraIndex, validator: &Self::AccountId) -> Option<u128> {
+        pallet_staking::ErasStakersOverview::<T>::get(era, validator)
+            .map(|overview| overview.total.into())
+            .or_else(|| {
+                pallet_staking::ErasStakers::<T>::try_get(era, validator)
+                    .ok()
+                    .map(|exposure| exposure.total.into())
+            })
+    }
+
     fn add_rewards(rewards: impl IntoIterator<Item = (Self::AccountId, u32)>) {
         pallet_staking::Pallet::<T>::reward_by_ids(rewards);
     }
@@ -96,3 +118,59 @@ where
         pallet_staking::Pallet::<T>::do_remove_validator(who);
     }
 }
+
+pub trait ValidatorSlasher {
+    type AccountId;
+
+    /// Reports `who` to be slashed by `fraction` of its exposure for an offence in `session`.
+    /// The slash is subject to the usual deferral, during which it can be cancelled.
+    fn slash(who: &Self::AccountId, fraction: Perbill, session: SessionIndex);
+}
+
+impl<T> ValidatorSlasher for pallet_staking::Pallet<T>
+where
+    T: pallet_staking::Config
+        + pallet_session::historical::Config
+        + pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+    Self: OnOffenceHandler<<T as frame_system::Config>::AccountId, IdentificationTuple<T>, Weight>,
+{
+    type AccountId = <T as frame_system::Config>::AccountId;
+
+    fn slash(who: &Self::AccountId, fraction: Perbill, session: SessionIndex) {
+        let Some(exposure) = T::FullIdentificationOf::convert(who.clone()) else {
+            return;
+        };
+        let offender = OffenceDetails {
+            offender: (who.clone(), exposure),
+            reporters: vec![],
+        };
+        <Self as OnOffenceHandler<_, _, Weight>>::on_offence(&[offender], &[fraction], session);
+    }
+}
+
+/// Number of the most recent block hashes mixed by [`RecentBlocksRandomness`].
+pub const RANDOMNESS_BLOCKS: u32 = 81;
+
+/// Randomness mixed from the hashes of the [`RANDOMNESS_BLOCKS`] most recent blocks kept by
+/// `frame_system`, up to the parent one. Nothing is stored for it, the hashes are only read when
+/// it is queried.
+///
+/// Every recent author contributes the hash of their block, so no author controls the outcome by
+/// themselves. It is not a VRF though: the author of the parent block knows all the other hashes
+/// and can withhold or grind its block to pick between outcomes.
+pub struct RecentBlocksRandomness<T>(PhantomData<T>);
+
+impl<T: frame_system::Config> Randomness<T::Hash, BlockNumberFor<T>> for RecentBlocksRandomness<T> {
+    fn random(subject: &[u8]) -> (T::Hash, BlockNumberFor<T>) {
+        let parent_number = frame_system::Pallet::<T>::block_number().saturating_sub(One::one());
+        let hashes: Vec<_> = (0..RANDOMNESS_BLOCKS)
+            .map(|age| {
+                frame_system::Pallet::<T>::block_hash(parent_number.saturating_sub(age.into()))
+            })
+            .collect();
+        (
+            (subject, hashes).using_encoded(T::Hashing::hash),
+            parent_number,
+        )
+    }
+}
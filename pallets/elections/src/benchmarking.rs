@@ -0,0 +1,76 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+
+use crate::{
+    traits::{SessionKeysProvider, StakingInfoProvider},
+    Call, Config, Pallet, ValidatorIntents,
+};
+
+/// Funds the stash, bonds `MinSelfBond` from it and sets its session keys, so that it can
+/// register an intent.
+fn setup_validator<T: Config>(stash: &T::AccountId) {
+    let self_bond = T::MinSelfBond::get();
+    T::Currency::make_free_balance_be(
+        stash,
+        T::Currency::minimum_balance() + self_bond + T::ValidatorIntentDeposit::get(),
+    );
+    T::StakingInfoProvider::set_self_bond(stash, self_bond);
+    T::SessionKeysProvider::set_session_keys(stash);
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_validator_intent() {
+        let stash: T::AccountId = whitelisted_caller();
+        setup_validator::<T>(&stash);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(stash.clone()),
+            T::MaxCommissionCap::get(),
+            T::MinSelfBond::get(),
+        );
+
+        assert!(ValidatorIntents::<T>::contains_key(&stash));
+    }
+
+    #[benchmark]
+    fn withdraw_validator_intent() {
+        let stash: T::AccountId = whitelisted_caller();
+        setup_validator::<T>(&stash);
+        Pallet::<T>::register_validator_intent(
+            RawOrigin::Signed(stash.clone()).into(),
+            T::MaxCommissionCap::get(),
+            T::MinSelfBond::get(),
+        )
+        .expect("Validator should be able to register an intent");
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(stash.clone()));
+
+        assert!(!ValidatorIntents::<T>::contains_key(&stash));
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::benchmarking::tests::new_test_ext(),
+        crate::mock::Test,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_io::TestExternalities;
+
+    use crate::mock::TestExtBuilder;
+
+    pub fn new_test_ext() -> TestExternalities {
+        TestExtBuilder::new(vec![], vec![]).build()
+    }
+}
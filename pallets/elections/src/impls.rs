@@ -1,14 +1,118 @@
-use primitives::{CommitteeSeats, EraValidators};
+use frame_election_provider_support::{DataProviderBounds, ElectionDataProvider};
+use frame_support::traits::ReservableCurrency;
+use primitives::{
+    BannedValidators, CandidateIneligibility, CommitteeSeats, ElectionCandidates, ElectionOpenness,
+    EraValidators, IneligibleCandidate,
+};
 use rand::{seq::SliceRandom, SeedableRng};
 use rand_pcg::Pcg32;
 use sp_staking::EraIndex;
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
 use crate::{
-    traits::ValidatorProvider, CommitteeSize, Config, CurrentEraValidators, NextEraCommitteeSize,
-    NextEraNonReservedValidators, NextEraReservedValidators, Openness, Pallet,
+    traits::{SessionKeysProvider, StakingInfoProvider, ValidatorProvider},
+    CommitteeSize, Config, CurrentEraValidators, Event, NextEraCommitteeSize,
+    NextEraNonReservedValidators, NextEraReservedValidators, Openness, Pallet, ValidatorIntents,
 };
 
+/// Everything needed to tell whether a validator can be elected to a non-reserved seat in the
+/// next era.
+pub(crate) struct Candidacy<T: Config> {
+    openness: ElectionOpenness,
+    staking_validators: BTreeSet<T::AccountId>,
+    staking_reserved_validators: BTreeSet<T::AccountId>,
+    permitted_validators: BTreeSet<T::AccountId>,
+    banned_validators: BTreeSet<T::AccountId>,
+}
+
+impl<T: Config> Candidacy<T> {
+    pub(crate) fn new(staking_validators: Vec<T::AccountId>) -> Self {
+        let staking_validators = staking_validators.into_iter().collect::<BTreeSet<_>>();
+        let staking_reserved_validators = NextEraReservedValidators::<T>::get()
+            .into_iter()
+            .filter(|v| staking_validators.contains(v))
+            .collect();
+
+        Self {
+            openness: Openness::<T>::get(),
+            staking_validators,
+            staking_reserved_validators,
+            permitted_validators: NextEraNonReservedValidators::<T>::get()
+                .into_iter()
+                .collect(),
+            banned_validators: T::BannedValidators::banned().into_iter().collect(),
+        }
+    }
+
+    pub(crate) fn staking_reserved_validators(&self) -> BTreeSet<T::AccountId> {
+        self.staking_reserved_validators.clone()
+    }
+
+    /// Validators competing for the non-reserved seats: the current non-reserved ones, in their
+    /// order, followed by the staking validators and the ones with a registered intent. Staking
+    /// reserved validators are not candidates.
+    pub(crate) fn candidates(&self) -> Vec<T::AccountId> {
+        let mut seen = self.staking_reserved_validators.clone();
+
+        NextEraNonReservedValidators::<T>::get()
+            .into_iter()
+            .chain(self.staking_validators.iter().cloned())
+            .chain(ValidatorIntents::<T>::iter_keys())
+            .filter(|v| seen.insert(v.clone()))
+            .collect()
+    }
+
+    /// Removes the intents of stashes that are not staking validators, as they chilled or
+    /// unbonded, and returns their deposits.
+    pub(crate) fn prune_validator_intents(&self) {
+        let stale: Vec<_> = ValidatorIntents::<T>::iter_keys()
+            .filter(|stash| !self.staking_validators.contains(stash))
+            .collect();
+
+        for stash in stale {
+            if let Some(intent) = ValidatorIntents::<T>::take(&stash) {
+                T::Currency::unreserve(&stash, intent.deposit);
+                Pallet::<T>::deposit_event(Event::ValidatorIntentPruned(stash));
+            }
+        }
+    }
+
+    /// Reasons why `candidate` cannot be elected, empty if it can.
+    pub(crate) fn ineligibility(&self, candidate: &T::AccountId) -> Vec<CandidateIneligibility> {
+        let mut reasons = Vec::new();
+
+        if !self.staking_validators.contains(candidate) {
+            reasons.push(CandidateIneligibility::NotValidating);
+        }
+        match self.openness {
+            ElectionOpenness::Permissioned => {
+                if !self.permitted_validators.contains(candidate) {
+                    reasons.push(CandidateIneligibility::NotPermitted);
+                }
+            }
+            ElectionOpenness::Permissionless => match ValidatorIntents::<T>::get(candidate) {
+                Some(intent) => {
+                    if T::StakingInfoProvider::commission(candidate) > intent.commission_cap {
+                        reasons.push(CandidateIneligibility::CommissionAboveCap);
+                    }
+                    if T::StakingInfoProvider::self_bond(candidate) < intent.self_bond {
+                        reasons.push(CandidateIneligibility::SelfBondBelowIntent);
+                    }
+                }
+                None => reasons.push(CandidateIneligibility::NoValidatorIntent),
+            },
+        }
+        if !T::SessionKeysProvider::has_session_keys(candidate) {
+            reasons.push(CandidateIneligibility::MissingSessionKeys);
+        }
+        if self.banned_validators.contains(candidate) {
+            reasons.push(CandidateIneligibility::Banned);
+        }
+
+        reasons
+    }
+}
+
 impl<T> Pallet<T>
 where
     T: Config,
@@ -66,6 +170,32 @@ where
     }
 }
 
+impl<T: Config> Pallet<T> {
+    /// Candidates for the non-reserved seats in the next era, as they would be judged if the
+    /// election took place now.
+    pub fn election_candidates() -> ElectionCandidates<T::AccountId> {
+        let staking_validators =
+            T::DataProvider::electable_targets(DataProviderBounds::default()).unwrap_or_default();
+        let candidacy = Candidacy::<T>::new(staking_validators);
+
+        let mut eligible = Vec::new();
+        let mut ineligible = Vec::new();
+        for candidate in candidacy.candidates() {
+            let reasons = candidacy.ineligibility(&candidate);
+            if reasons.is_empty() {
+                eligible.push(candidate);
+            } else {
+                ineligible.push(IneligibleCandidate { candidate, reasons });
+            }
+        }
+
+        ElectionCandidates {
+            eligible,
+            ineligible,
+        }
+    }
+}
+
 impl<T: Config> primitives::EraManager for Pallet<T> {
     fn on_new_era(era: EraIndex) {
         Self::populate_next_era_validators_on_next_era_start(era);
@@ -1,30 +1,52 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 mod impls;
+pub mod migration;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 mod traits;
+pub mod weights;
 
 use frame_support::traits::StorageVersion;
 pub use pallet::*;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 pub use primitives::EraValidators;
 use scale_info::TypeInfo;
+use sp_runtime::Perbill;
 use sp_std::{
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
     prelude::*,
 };
+pub use weights::WeightInfo;
 
 pub type TotalReward = u32;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
+pub(crate) const LOG_TARGET: &str = "pallet-elections";
 
 #[derive(Decode, Encode, TypeInfo)]
 pub struct ValidatorTotalRewards<T>(pub BTreeMap<T, TotalReward>);
 
+/// Declaration of a validator willing to take a non-reserved seat in permissionless elections.
+#[derive(Decode, Encode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+pub struct ValidatorIntent<Balance> {
+    /// The highest commission the validator may charge and still be elected.
+    pub commission_cap: Perbill,
+    /// The lowest active bond the validator keeps in its own stash.
+    pub self_bond: Balance,
+    /// Deposit reserved from the stash, returned when the intent is withdrawn or pruned.
+    pub deposit: Balance,
+}
+
+pub type BalanceOf<T> =
+    <<T as pallet::Config>::StakingInfoProvider as traits::StakingInfoProvider>::Balance;
+
 #[frame_support::pallet]
 #[pallet_doc("../README.md")]
 pub mod pallet {
@@ -32,14 +54,21 @@ pub mod pallet {
         BoundedSupportsOf, DataProviderBounds, ElectionDataProvider, ElectionProvider,
         ElectionProviderBase, Support, Supports,
     };
-    use frame_support::{pallet_prelude::*, traits::{Get, EnsureOrigin}};
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{EnsureOrigin, Get, ReservableCurrency},
+    };
     use frame_system::{
+        ensure_signed,
         pallet_prelude::{BlockNumberFor, OriginFor},
     };
     use primitives::{BannedValidators, CommitteeSeats, ElectionOpenness};
 
     use super::*;
-    use crate::traits::ValidatorProvider;
+    use crate::{
+        impls::Candidacy,
+        traits::{SessionKeysProvider, StakingInfoProvider, ValidatorProvider},
+    };
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -63,6 +92,23 @@ pub mod pallet {
         /// Maximum number of validators
         #[pallet::constant]
         type MaxValidators: Get<u32>;
+        /// Tells whether candidates have set their session keys.
+        type SessionKeysProvider: SessionKeysProvider<AccountId = Self::AccountId>;
+        /// Commissions and bonds of candidates, checked against their validator intents.
+        type StakingInfoProvider: StakingInfoProvider<AccountId = Self::AccountId>;
+        /// Currency the deposits of validator intents are reserved in.
+        type Currency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+        /// Deposit reserved for every registered validator intent.
+        #[pallet::constant]
+        type ValidatorIntentDeposit: Get<BalanceOf<Self>>;
+        /// The highest commission cap a validator intent may declare.
+        #[pallet::constant]
+        type MaxCommissionCap: Get<Perbill>;
+        /// The lowest self-bond a validator intent may declare.
+        #[pallet::constant]
+        type MinSelfBond: Get<BalanceOf<Self>>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::event]
@@ -70,6 +116,12 @@ pub mod pallet {
     pub enum Event<T: Config> {
         /// Committee for the next era has changed
         ChangeValidators(Vec<T::AccountId>, Vec<T::AccountId>, CommitteeSeats),
+        /// A validator has registered or updated its intent
+        ValidatorIntentRegistered(T::AccountId, ValidatorIntent<BalanceOf<T>>),
+        /// A validator has withdrawn its intent
+        ValidatorIntentWithdrawn(T::AccountId),
+        /// The intent of a validator which stopped validating has been removed
+        ValidatorIntentPruned(T::AccountId),
     }
 
     #[pallet::pallet]
@@ -116,6 +168,12 @@ pub mod pallet {
     #[pallet::storage]
     pub type Openness<T> = StorageValue<_, ElectionOpenness, ValueQuery, DefaultOpenness<T>>;
 
+    /// Intents of validators to take non-reserved seats, required in permissionless elections.
+    /// At most `MaxValidators` intents are registered at a time.
+    #[pallet::storage]
+    pub type ValidatorIntents<T: Config> =
+        CountedStorageMap<_, Twox64Concat, T::AccountId, ValidatorIntent<BalanceOf<T>>>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
@@ -172,6 +230,77 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Register, or replace, the intent of the caller's stash to be elected in
+        /// permissionless elections. `commission_cap` can be at most `MaxCommissionCap` and
+        /// `self_bond` at least `MinSelfBond`. Session keys have to be set and the stash has to
+        /// have at least `self_bond` actively bonded. In every election the commission of the
+        /// validator is checked against `commission_cap` and its active bond against
+        /// `self_bond`, and the intent is removed if the stash is not validating anymore.
+        /// Reserves `ValidatorIntentDeposit` from the stash, returning the deposit of the
+        /// replaced intent.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::register_validator_intent())]
+        pub fn register_validator_intent(
+            origin: OriginFor<T>,
+            commission_cap: Perbill,
+            self_bond: BalanceOf<T>,
+        ) -> DispatchResult {
+            let stash = ensure_signed(origin)?;
+
+            ensure!(
+                commission_cap <= T::MaxCommissionCap::get(),
+                Error::<T>::CommissionCapTooHigh
+            );
+            ensure!(
+                self_bond >= T::MinSelfBond::get(),
+                Error::<T>::SelfBondTooLow
+            );
+            ensure!(
+                T::SessionKeysProvider::has_session_keys(&stash),
+                Error::<T>::MissingSessionKeys
+            );
+            ensure!(
+                T::StakingInfoProvider::self_bond(&stash) >= self_bond,
+                Error::<T>::SelfBondBelowIntent
+            );
+
+            if let Some(replaced) = ValidatorIntents::<T>::get(&stash) {
+                T::Currency::unreserve(&stash, replaced.deposit);
+            } else {
+                ensure!(
+                    ValidatorIntents::<T>::count() < T::MaxValidators::get(),
+                    Error::<T>::TooManyValidatorIntents
+                );
+            }
+            let deposit = T::ValidatorIntentDeposit::get();
+            T::Currency::reserve(&stash, deposit)?;
+
+            let intent = ValidatorIntent {
+                commission_cap,
+                self_bond,
+                deposit,
+            };
+            ValidatorIntents::<T>::insert(&stash, intent.clone());
+            Self::deposit_event(Event::ValidatorIntentRegistered(stash, intent));
+
+            Ok(())
+        }
+
+        /// Withdraw the intent of the caller's stash and return the deposit, it will not be
+        /// elected in permissionless elections anymore.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::withdraw_validator_intent())]
+        pub fn withdraw_validator_intent(origin: OriginFor<T>) -> DispatchResult {
+            let stash = ensure_signed(origin)?;
+
+            let intent =
+                ValidatorIntents::<T>::take(&stash).ok_or(Error::<T>::NoValidatorIntent)?;
+            T::Currency::unreserve(&stash, intent.deposit);
+            Self::deposit_event(Event::ValidatorIntentWithdrawn(stash));
+
+            Ok(())
+        }
     }
 
     #[pallet::hooks]
@@ -289,6 +418,18 @@ pub mod pallet {
         NotEnoughNonReservedValidators,
         NonUniqueListOfValidators,
         NonReservedFinalitySeatsLargerThanNonReservedSeats,
+        /// Session keys of the validator are not set.
+        MissingSessionKeys,
+        /// Active bond of the validator is lower than the declared self-bond.
+        SelfBondBelowIntent,
+        /// The validator has no intent registered.
+        NoValidatorIntent,
+        /// The declared commission cap is higher than `MaxCommissionCap`.
+        CommissionCapTooHigh,
+        /// The declared self-bond is lower than `MinSelfBond`.
+        SelfBondTooLow,
+        /// `MaxValidators` intents are registered already.
+        TooManyValidatorIntents,
     }
 
     impl<T: Config> ElectionProviderBase for Pallet<T> {
@@ -306,35 +447,22 @@ pub mod pallet {
 
         /// We calculate the supports for each validator. The external validators are chosen as:
         /// 1) "`NextEraNonReservedValidators` that are staking and are not banned" in case of Permissioned ElectionOpenness
-        /// 2) "All staking and not banned validators" in case of Permissionless ElectionOpenness
+        /// 2) "All staking and not banned validators with a satisfied `ValidatorIntent`" in case of Permissionless ElectionOpenness
+        /// In both cases validators without session keys are skipped. Intents of stashes that are
+        /// not staking validators anymore, as they chilled or unbonded, are removed.
         fn elect() -> Result<BoundedSupportsOf<Self>, Self::Error> {
             let staking_validators =
                 Self::DataProvider::electable_targets(DataProviderBounds::default())
-                    .map_err(Self::Error::DataProvider)?
-                    .into_iter()
-                    .collect::<BTreeSet<_>>();
-            let staking_reserved_validators = NextEraReservedValidators::<T>::get()
-                .into_iter()
-                .filter(|v| staking_validators.contains(v))
-                .collect::<BTreeSet<_>>();
-            let banned_validators = T::BannedValidators::banned()
-                .into_iter()
-                .collect::<BTreeSet<_>>();
-            let old_non_reserved_validators = NextEraNonReservedValidators::<T>::get().into_iter();
+                    .map_err(Self::Error::DataProvider)?;
+            let candidacy = Candidacy::<T>::new(staking_validators);
+            candidacy.prune_validator_intents();
+            let staking_reserved_validators = candidacy.staking_reserved_validators();
 
-            let eligible_non_reserved = staking_validators
+            let new_non_reserved_validators: Vec<_> = candidacy
+                .candidates()
                 .into_iter()
-                .filter(|v| {
-                    !banned_validators.contains(v) && !staking_reserved_validators.contains(v)
-                })
-                .collect::<BTreeSet<_>>();
-
-            let new_non_reserved_validators: Vec<_> = match Openness::<T>::get() {
-                ElectionOpenness::Permissioned => old_non_reserved_validators
-                    .filter(|v| eligible_non_reserved.contains(v))
-                    .collect(),
-                ElectionOpenness::Permissionless => eligible_non_reserved.into_iter().collect(),
-            };
+                .filter(|v| candidacy.ineligibility(v).is_empty())
+                .collect();
             // We store new list here to ensure that validators that end up in the result of the elect
             // method are a disjoint union of NextEraReservedValidators and NextEraNonReservedValidators.
             // This condition is important since results of elect ends up in pallet staking while the above lists
@@ -0,0 +1,103 @@
+use frame_support::{
+    pallet_prelude::{PalletInfoAccess, StorageVersion, Weight},
+    traits::OnRuntimeUpgrade,
+};
+use log::info;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// Ensure that the current pallet storage version matches `version`.
+pub fn ensure_storage_version<P: PalletInfoAccess>(version: u16) -> Result<(), &'static str> {
+    if StorageVersion::get::<P>() == StorageVersion::new(version) {
+        Ok(())
+    } else {
+        Err("Bad storage version")
+    }
+}
+
+/// Registers intents for the non-reserved validators that have none, so that they are not
+/// dropped by the first permissionless election after the upgrade. The intents take no deposit
+/// and declare the current commission and self-bond of the validator if they are outside the
+/// pallet limits, so that the validators stay eligible until they register intents of their own.
+pub mod v6 {
+    use frame_support::traits::Get;
+    use sp_runtime::traits::Zero;
+
+    use super::*;
+    use crate::{
+        traits::StakingInfoProvider, Config, NextEraNonReservedValidators, Pallet, ValidatorIntent,
+        ValidatorIntents, LOG_TARGET,
+    };
+
+    const OLD_VERSION: u16 = 5;
+    const NEW_VERSION: u16 = 6;
+
+    pub struct Migration<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for Migration<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get::<Pallet<T>>() != StorageVersion::new(OLD_VERSION) {
+                log::info!(
+                    target: LOG_TARGET,
+                    "Skipping migrations from STORAGE_VERSION 5 to 6 for pallet elections."
+                );
+                return T::DbWeight::get().reads(1);
+            };
+
+            info!(target: LOG_TARGET, "Running migration from STORAGE_VERSION 5 to 6 for pallet elections.");
+
+            let validators = NextEraNonReservedValidators::<T>::get();
+            // StorageVersion, NextEraNonReservedValidators
+            let mut reads = 2;
+            // StorageVersion
+            let mut writes = 1;
+
+            for validator in validators {
+                reads += 1;
+                if ValidatorIntents::<T>::contains_key(&validator) {
+                    continue;
+                }
+
+                // Staking Validators, Bonded, Ledger
+                reads += 3;
+                let intent = ValidatorIntent {
+                    commission_cap: T::StakingInfoProvider::commission(&validator)
+                        .max(T::MaxCommissionCap::get()),
+                    self_bond: T::StakingInfoProvider::self_bond(&validator)
+                        .min(T::MinSelfBond::get()),
+                    deposit: Zero::zero(),
+                };
+                // Together with the registered intents these may exceed `MaxValidators`, which
+                // only blocks new registrations until enough intents are withdrawn or pruned.
+                ValidatorIntents::<T>::insert(&validator, intent);
+                // ValidatorIntents, CounterForValidatorIntents
+                reads += 1;
+                writes += 2;
+            }
+
+            StorageVersion::new(NEW_VERSION).put::<Pallet<T>>();
+            info!(target: LOG_TARGET, "Finished migration from STORAGE_VERSION 5 to 6 for pallet elections.");
+
+            T::DbWeight::get().reads(reads) + T::DbWeight::get().writes(writes)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+            ensure_storage_version::<Pallet<T>>(OLD_VERSION)?;
+
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+            ensure_storage_version::<Pallet<T>>(NEW_VERSION)?;
+            frame_support::ensure!(
+                NextEraNonReservedValidators::<T>::get()
+                    .iter()
+                    .all(ValidatorIntents::<T>::contains_key),
+                "Not all the non-reserved validators have intents"
+            );
+            Ok(())
+        }
+    }
+}
@@ -9,13 +9,13 @@ use frame_support::{
 };
 use primitives::{BannedValidators, CommitteeSeats, DEFAULT_MAX_WINNERS};
 use sp_core::H256;
-use sp_runtime::{testing::TestXt, traits::IdentityLookup, BuildStorage};
+use sp_runtime::{testing::TestXt, traits::IdentityLookup, BuildStorage, Perbill};
 use sp_staking::EraIndex;
 use sp_std::cell::RefCell;
 
 use super::*;
 use crate as pallet_elections;
-use crate::traits::ValidatorProvider;
+use crate::traits::{SessionKeysProvider, StakingInfoProvider, ValidatorProvider};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -108,6 +108,15 @@ thread_local! {
     static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(Default::default());
     static ELECTED_VALIDATORS: RefCell<BTreeMap<EraIndex, Vec<AccountId>>> = RefCell::new(Default::default());
     static BANNNED_VALIDATORS: RefCell<Vec<AccountId>> = RefCell::new(Default::default());
+    static WITHOUT_SESSION_KEYS: RefCell<Vec<AccountId>> = RefCell::new(Default::default());
+}
+
+pub fn with_banned_validators(banned: Vec<AccountId>) {
+    BANNNED_VALIDATORS.with(|b| *b.borrow_mut() = banned);
+}
+
+pub fn with_missing_session_keys(validators: Vec<AccountId>) {
+    WITHOUT_SESSION_KEYS.with(|wsk| *wsk.borrow_mut() = validators);
 }
 
 impl ValidatorProvider for MockProvider {
@@ -126,6 +135,29 @@ impl BannedValidators for MockProvider {
     }
 }
 
+impl SessionKeysProvider for MockProvider {
+    type AccountId = AccountId;
+
+    fn has_session_keys(validator: &Self::AccountId) -> bool {
+        WITHOUT_SESSION_KEYS.with(|wsk| !wsk.borrow().contains(validator))
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_session_keys(validator: &Self::AccountId) {
+        WITHOUT_SESSION_KEYS.with(|wsk| wsk.borrow_mut().retain(|v| v != validator));
+    }
+}
+
+pub(crate) const INTENT_DEPOSIT: Balance = 1_000;
+pub(crate) const MIN_SELF_BOND: Balance = 10;
+pub(crate) const INITIAL_BALANCE: Balance = 10_000_000;
+
+parameter_types! {
+    pub const ValidatorIntentDeposit: Balance = INTENT_DEPOSIT;
+    pub const MaxCommissionCap: Perbill = Perbill::from_percent(50);
+    pub const MinSelfBond: Balance = MIN_SELF_BOND;
+}
+
 impl Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type DataProvider = StakingMock;
@@ -134,6 +166,13 @@ impl Config for Test {
     type BannedValidators = MockProvider;
     type MaxValidators = ConstU32<100>;
     type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type SessionKeysProvider = MockProvider;
+    type StakingInfoProvider = StakingMock;
+    type Currency = Balances;
+    type ValidatorIntentDeposit = ValidatorIntentDeposit;
+    type MaxCommissionCap = MaxCommissionCap;
+    type MinSelfBond = MinSelfBond;
+    type WeightInfo = ();
 }
 
 type MaxVotesPerVoter = ConstU32<1>;
@@ -143,6 +182,8 @@ type Vote = (AccountId, VoteWeight, AccountIdBoundedVec);
 thread_local! {
     static ELECTABLE_TARGETS: RefCell<Vec<AccountId>> = RefCell::new(Default::default());
     static ELECTING_VOTERS: RefCell<Vec<Vote>> = RefCell::new(Default::default());
+    static COMMISSIONS: RefCell<BTreeMap<AccountId, Perbill>> = RefCell::new(Default::default());
+    static SELF_BONDS: RefCell<BTreeMap<AccountId, Balance>> = RefCell::new(Default::default());
 }
 
 pub fn with_electable_targets(targets: Vec<AccountId>) {
@@ -153,6 +194,14 @@ pub fn with_electing_voters(voters: Vec<Vote>) {
     ELECTING_VOTERS.with(|ev| *ev.borrow_mut() = voters);
 }
 
+pub fn with_commission(validator: AccountId, commission: Perbill) {
+    COMMISSIONS.with(|c| c.borrow_mut().insert(validator, commission));
+}
+
+pub fn with_self_bond(stash: AccountId, self_bond: Balance) {
+    SELF_BONDS.with(|sb| sb.borrow_mut().insert(stash, self_bond));
+}
+
 pub struct StakingMock;
 impl ElectionDataProvider for StakingMock {
     type AccountId = AccountId;
@@ -178,6 +227,24 @@ impl ElectionDataProvider for StakingMock {
     }
 }
 
+impl StakingInfoProvider for StakingMock {
+    type AccountId = AccountId;
+    type Balance = Balance;
+
+    fn commission(validator: &Self::AccountId) -> Perbill {
+        COMMISSIONS.with(|c| c.borrow().get(validator).copied().unwrap_or_default())
+    }
+
+    fn self_bond(stash: &Self::AccountId) -> Self::Balance {
+        SELF_BONDS.with(|sb| sb.borrow().get(stash).copied().unwrap_or_default())
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_self_bond(stash: &Self::AccountId, value: Self::Balance) {
+        with_self_bond(*stash, value);
+    }
+}
+
 pub struct TestExtBuilder {
     reserved_validators: Vec<AccountId>,
     non_reserved_validators: Vec<AccountId>,
@@ -202,6 +269,11 @@ impl TestExtBuilder {
         }
     }
 
+    pub fn with_storage_version(mut self, storage_version: StorageVersion) -> Self {
+        self.storage_version = storage_version;
+        self
+    }
+
     pub fn with_committee_seats(mut self, committee_seats: CommitteeSeats) -> Self {
         self.committee_seats = committee_seats;
         self
@@ -213,13 +285,16 @@ impl TestExtBuilder {
         )
         .expect("Storage should be build.");
 
-        let validators: Vec<_> = self
+        // Accounts that are not validators at genesis may still register intents.
+        let accounts: BTreeSet<_> = self
             .non_reserved_validators
             .iter()
             .chain(self.reserved_validators.iter())
+            .copied()
+            .chain(1..=10)
             .collect();
 
-        let balances: Vec<_> = validators.iter().map(|i| (**i, 10_000_000)).collect();
+        let balances: Vec<_> = accounts.into_iter().map(|i| (i, INITIAL_BALANCE)).collect();
 
         pallet_balances::GenesisConfig::<Test> { balances }
             .assimilate_storage(&mut t)
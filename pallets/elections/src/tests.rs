@@ -1,14 +1,24 @@
 use frame_election_provider_support::{ElectionProvider, Support};
-use primitives::CommitteeSeats;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use primitives::{
+    CandidateIneligibility, CommitteeSeats, ElectionCandidates, ElectionOpenness,
+    IneligibleCandidate,
+};
 use sp_core::bounded_vec;
+use sp_runtime::Perbill;
 
 use crate::{
+    migration::v6,
     mock::{
-        with_electable_targets, with_electing_voters, AccountId, Balance, Elections, Test,
-        TestExtBuilder,
+        with_banned_validators, with_commission, with_electable_targets, with_electing_voters,
+        with_missing_session_keys, with_self_bond, AccountId, Balance, Balances, Elections,
+        RuntimeOrigin, Test, TestExtBuilder, INTENT_DEPOSIT, MIN_SELF_BOND,
     },
-    CommitteeSize, CurrentEraValidators, NextEraCommitteeSize, NextEraNonReservedValidators,
-    NextEraReservedValidators,
+    CommitteeSize, CurrentEraValidators, Error, NextEraCommitteeSize, NextEraNonReservedValidators,
+    NextEraReservedValidators, Openness, Pallet, ValidatorIntent, ValidatorIntents,
 };
 
 fn no_support() -> Support<AccountId> {
@@ -80,3 +90,435 @@ fn validators_are_elected_only_when_staking() {
             );
         });
 }
+
+#[test]
+fn validator_intent_requires_session_keys_and_self_bond() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            let commission_cap = Perbill::from_percent(10);
+
+            with_missing_session_keys(vec![2]);
+            assert_noop!(
+                Elections::register_validator_intent(RuntimeOrigin::signed(2), commission_cap, 100),
+                Error::<Test>::MissingSessionKeys
+            );
+
+            with_missing_session_keys(vec![]);
+            with_self_bond(2, 99);
+            assert_noop!(
+                Elections::register_validator_intent(RuntimeOrigin::signed(2), commission_cap, 100),
+                Error::<Test>::SelfBondBelowIntent
+            );
+
+            with_self_bond(2, 100);
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(2),
+                commission_cap,
+                100
+            ));
+            assert_eq!(
+                ValidatorIntents::<Test>::get(2),
+                Some(ValidatorIntent {
+                    commission_cap,
+                    self_bond: 100,
+                    deposit: INTENT_DEPOSIT,
+                })
+            );
+            assert_eq!(Balances::reserved_balance(2), INTENT_DEPOSIT);
+
+            assert_ok!(Elections::withdraw_validator_intent(RuntimeOrigin::signed(
+                2
+            )));
+            assert_eq!(ValidatorIntents::<Test>::get(2), None);
+            assert_eq!(Balances::reserved_balance(2), 0);
+            assert_noop!(
+                Elections::withdraw_validator_intent(RuntimeOrigin::signed(2)),
+                Error::<Test>::NoValidatorIntent
+            );
+        });
+}
+
+#[test]
+fn permissionless_elections_require_satisfied_intents() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            let commission_cap = Perbill::from_percent(10);
+            for validator in [2, 4, 5, 6, 7, 8] {
+                with_self_bond(validator, 100);
+                assert_ok!(Elections::register_validator_intent(
+                    RuntimeOrigin::signed(validator),
+                    commission_cap,
+                    100
+                ));
+            }
+
+            with_electable_targets(vec![1, 2, 3, 4, 5, 6, 7]);
+            with_commission(4, Perbill::from_percent(20));
+            with_self_bond(5, 50);
+            with_missing_session_keys(vec![6]);
+            with_banned_validators(vec![7]);
+
+            let ineligible = |candidate, reasons| IneligibleCandidate { candidate, reasons };
+            assert_eq!(
+                Elections::election_candidates(),
+                ElectionCandidates {
+                    eligible: vec![2],
+                    ineligible: vec![
+                        ineligible(3, vec![CandidateIneligibility::NoValidatorIntent]),
+                        ineligible(4, vec![CandidateIneligibility::CommissionAboveCap]),
+                        ineligible(5, vec![CandidateIneligibility::SelfBondBelowIntent]),
+                        ineligible(6, vec![CandidateIneligibility::MissingSessionKeys]),
+                        ineligible(7, vec![CandidateIneligibility::Banned]),
+                        ineligible(8, vec![CandidateIneligibility::NotValidating]),
+                    ],
+                }
+            );
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[(1, no_support()), (2, no_support())]
+            );
+            assert_eq!(NextEraNonReservedValidators::<Test>::get().as_slice(), [2]);
+        });
+}
+
+#[test]
+fn validator_intent_has_to_respect_pallet_limits() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            with_self_bond(2, 100);
+
+            assert_noop!(
+                Elections::register_validator_intent(
+                    RuntimeOrigin::signed(2),
+                    Perbill::from_percent(51),
+                    100
+                ),
+                Error::<Test>::CommissionCapTooHigh
+            );
+            assert_noop!(
+                Elections::register_validator_intent(
+                    RuntimeOrigin::signed(2),
+                    Perbill::from_percent(50),
+                    MIN_SELF_BOND - 1
+                ),
+                Error::<Test>::SelfBondTooLow
+            );
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(2),
+                Perbill::from_percent(50),
+                MIN_SELF_BOND
+            ));
+        });
+}
+
+#[test]
+fn replacing_validator_intent_keeps_single_deposit() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            with_self_bond(2, 100);
+
+            for self_bond in [50, 100] {
+                assert_ok!(Elections::register_validator_intent(
+                    RuntimeOrigin::signed(2),
+                    Perbill::from_percent(10),
+                    self_bond
+                ));
+            }
+
+            assert_eq!(ValidatorIntents::<Test>::count(), 1);
+            assert_eq!(
+                ValidatorIntents::<Test>::get(2).map(|intent| intent.self_bond),
+                Some(100)
+            );
+            assert_eq!(Balances::reserved_balance(2), INTENT_DEPOSIT);
+        });
+}
+
+#[test]
+fn validator_intents_are_bounded() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            for validator in [2, 3] {
+                with_self_bond(validator, 100);
+            }
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(2),
+                Perbill::from_percent(10),
+                100
+            ));
+            let intent = ValidatorIntent {
+                commission_cap: Perbill::from_percent(10),
+                self_bond: 100,
+                deposit: 0,
+            };
+            // Together with 2, `MaxValidators` intents are registered.
+            for stash in 101..200 {
+                ValidatorIntents::<Test>::insert(stash, intent.clone());
+            }
+
+            assert_noop!(
+                Elections::register_validator_intent(
+                    RuntimeOrigin::signed(3),
+                    Perbill::from_percent(10),
+                    100
+                ),
+                Error::<Test>::TooManyValidatorIntents
+            );
+
+            // Replacing a registered intent does not take another slot.
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(2),
+                Perbill::from_percent(20),
+                100
+            ));
+        });
+}
+
+#[test]
+fn intents_of_validators_that_stopped_validating_are_pruned_in_elections() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            for validator in [2, 3] {
+                with_self_bond(validator, 100);
+                assert_ok!(Elections::register_validator_intent(
+                    RuntimeOrigin::signed(validator),
+                    Perbill::from_percent(10),
+                    100
+                ));
+            }
+
+            // 3 chilled.
+            with_electable_targets(vec![1, 2]);
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[(1, no_support()), (2, no_support())]
+            );
+            assert!(ValidatorIntents::<Test>::contains_key(2));
+            assert_eq!(Balances::reserved_balance(2), INTENT_DEPOSIT);
+            assert!(!ValidatorIntents::<Test>::contains_key(3));
+            assert_eq!(Balances::reserved_balance(3), 0);
+            assert_eq!(ValidatorIntents::<Test>::count(), 1);
+        });
+}
+
+#[test]
+fn banned_validators_are_not_elected_in_permissionless_elections() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            for validator in [2, 3] {
+                with_self_bond(validator, 100);
+                assert_ok!(Elections::register_validator_intent(
+                    RuntimeOrigin::signed(validator),
+                    Perbill::from_percent(10),
+                    100
+                ));
+            }
+            with_electable_targets(vec![1, 2, 3]);
+            with_banned_validators(vec![3]);
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[(1, no_support()), (2, no_support())]
+            );
+            assert_eq!(NextEraNonReservedValidators::<Test>::get().as_slice(), [2]);
+            // Banned validators keep their intents, they may be elected once the ban is over.
+            assert!(ValidatorIntents::<Test>::contains_key(3));
+        });
+}
+
+#[test]
+fn validators_charging_commission_above_cap_are_not_elected() {
+    TestExtBuilder::new(vec![1], vec![])
+        .build()
+        .execute_with(|| {
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            for validator in [2, 3] {
+                with_self_bond(validator, 100);
+                assert_ok!(Elections::register_validator_intent(
+                    RuntimeOrigin::signed(validator),
+                    Perbill::from_percent(10),
+                    100
+                ));
+            }
+            with_electable_targets(vec![1, 2, 3]);
+            with_commission(2, Perbill::from_percent(10));
+            with_commission(3, Perbill::from_parts(100_000_001));
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[(1, no_support()), (2, no_support())]
+            );
+            assert_eq!(NextEraNonReservedValidators::<Test>::get().as_slice(), [2]);
+        });
+}
+
+#[test]
+fn election_candidates_report_all_reasons_of_ineligibility() {
+    TestExtBuilder::new(vec![1], vec![2])
+        .build()
+        .execute_with(|| {
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            with_self_bond(3, 100);
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(3),
+                Perbill::from_percent(10),
+                100
+            ));
+
+            with_electable_targets(vec![1, 3]);
+            with_commission(3, Perbill::from_percent(20));
+            with_self_bond(3, 50);
+            with_missing_session_keys(vec![2, 3]);
+            with_banned_validators(vec![2, 3]);
+
+            let ineligible = |candidate, reasons| IneligibleCandidate { candidate, reasons };
+            assert_eq!(
+                Elections::election_candidates(),
+                ElectionCandidates {
+                    eligible: vec![],
+                    ineligible: vec![
+                        ineligible(
+                            2,
+                            vec![
+                                CandidateIneligibility::NotValidating,
+                                CandidateIneligibility::NoValidatorIntent,
+                                CandidateIneligibility::MissingSessionKeys,
+                                CandidateIneligibility::Banned,
+                            ]
+                        ),
+                        ineligible(
+                            3,
+                            vec![
+                                CandidateIneligibility::CommissionAboveCap,
+                                CandidateIneligibility::SelfBondBelowIntent,
+                                CandidateIneligibility::MissingSessionKeys,
+                                CandidateIneligibility::Banned,
+                            ]
+                        ),
+                    ],
+                }
+            );
+        });
+}
+
+#[test]
+fn permissioned_elections_skip_validators_without_session_keys() {
+    TestExtBuilder::new(vec![1], vec![2, 3, 4])
+        .build()
+        .execute_with(|| {
+            with_electable_targets(vec![1, 2, 3, 5]);
+            with_missing_session_keys(vec![3]);
+
+            let ineligible = |candidate, reasons| IneligibleCandidate { candidate, reasons };
+            assert_eq!(
+                Elections::election_candidates(),
+                ElectionCandidates {
+                    eligible: vec![2],
+                    ineligible: vec![
+                        ineligible(3, vec![CandidateIneligibility::MissingSessionKeys]),
+                        ineligible(4, vec![CandidateIneligibility::NotValidating]),
+                        ineligible(5, vec![CandidateIneligibility::NotPermitted]),
+                    ],
+                }
+            );
+
+            let elected =
+                <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                elected.into_inner(),
+                &[(1, no_support()), (2, no_support())]
+            );
+        });
+}
+
+#[test]
+fn migration_grandfathers_non_reserved_validators() {
+    TestExtBuilder::new(vec![1], vec![2, 3, 4, 5])
+        .with_storage_version(StorageVersion::new(5))
+        .build()
+        .execute_with(|| {
+            for validator in [2, 3, 4, 5] {
+                with_self_bond(validator, MIN_SELF_BOND);
+            }
+            let registered = ValidatorIntent {
+                commission_cap: Perbill::from_percent(10),
+                self_bond: MIN_SELF_BOND,
+                deposit: INTENT_DEPOSIT,
+            };
+            assert_ok!(Elections::register_validator_intent(
+                RuntimeOrigin::signed(2),
+                registered.commission_cap,
+                registered.self_bond
+            ));
+            // 3 charges more than the cap and 4 keeps less than the lowest self-bond.
+            with_commission(3, Perbill::from_percent(60));
+            with_self_bond(4, MIN_SELF_BOND / 2);
+
+            v6::Migration::<Test>::on_runtime_upgrade();
+
+            assert_eq!(
+                StorageVersion::get::<Pallet<Test>>(),
+                StorageVersion::new(6)
+            );
+            let grandfathered = |commission_cap, self_bond| ValidatorIntent {
+                commission_cap,
+                self_bond,
+                deposit: 0,
+            };
+            assert_eq!(ValidatorIntents::<Test>::get(2), Some(registered));
+            assert_eq!(
+                ValidatorIntents::<Test>::get(3),
+                Some(grandfathered(Perbill::from_percent(60), MIN_SELF_BOND))
+            );
+            assert_eq!(
+                ValidatorIntents::<Test>::get(4),
+                Some(grandfathered(Perbill::from_percent(50), MIN_SELF_BOND / 2))
+            );
+            assert_eq!(
+                ValidatorIntents::<Test>::get(5),
+                Some(grandfathered(Perbill::from_percent(50), MIN_SELF_BOND))
+            );
+            assert_eq!(ValidatorIntents::<Test>::count(), 4);
+
+            Openness::<Test>::put(ElectionOpenness::Permissionless);
+            with_electable_targets(vec![1, 2, 3, 4, 5]);
+            assert_eq!(
+                Elections::election_candidates(),
+                ElectionCandidates {
+                    eligible: vec![2, 3, 4, 5],
+                    ineligible: vec![],
+                }
+            );
+
+            <Elections as ElectionProvider>::elect().expect("`elect()` should succeed");
+
+            assert_eq!(
+                NextEraNonReservedValidators::<Test>::get().as_slice(),
+                [2, 3, 4, 5]
+            );
+        });
+}
@@ -1,4 +1,10 @@
-use sp_staking::EraIndex;
+use frame_support::Parameter;
+use parity_scale_codec::MaxEncodedLen;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, Convert},
+    Perbill,
+};
+use sp_staking::{EraIndex, StakingAccount};
 use sp_std::vec::Vec;
 
 pub trait ValidatorProvider {
@@ -17,3 +23,71 @@ impl<T: pallet_staking::Config> ValidatorProvider for pallet_staking::Pallet<T>
             .collect()
     }
 }
+
+pub trait SessionKeysProvider {
+    type AccountId;
+    /// Whether the validator has set session keys to be used from the next session.
+    fn has_session_keys(validator: &Self::AccountId) -> bool;
+    /// Sets any session keys for the validator, used to set up benchmarks.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_session_keys(validator: &Self::AccountId);
+}
+
+impl<T: pallet_session::Config> SessionKeysProvider for pallet_session::Pallet<T> {
+    type AccountId = T::AccountId;
+
+    fn has_session_keys(validator: &Self::AccountId) -> bool {
+        T::ValidatorIdOf::convert(validator.clone())
+            .map(pallet_session::NextKeys::<T>::contains_key)
+            .unwrap_or(false)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_session_keys(validator: &Self::AccountId) {
+        use parity_scale_codec::Decode;
+        use sp_runtime::traits::TrailingZeroInput;
+
+        if let Some(validator_id) = T::ValidatorIdOf::convert(validator.clone()) {
+            let keys =
+                T::Keys::decode(&mut TrailingZeroInput::zeroes()).expect("Keys decode from zeroes");
+            pallet_session::NextKeys::<T>::insert(validator_id, keys);
+        }
+    }
+}
+
+pub trait StakingInfoProvider {
+    type AccountId;
+    type Balance: Parameter + MaxEncodedLen + AtLeast32BitUnsigned + Copy;
+    /// Commission of the validator, zero if it does not validate.
+    fn commission(validator: &Self::AccountId) -> Perbill;
+    /// Active bond of the stash, zero if it is not bonded.
+    fn self_bond(stash: &Self::AccountId) -> Self::Balance;
+    /// Bonds `value` from the free balance of the stash, used to set up benchmarks.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_self_bond(stash: &Self::AccountId, value: Self::Balance);
+}
+
+impl<T: pallet_staking::Config> StakingInfoProvider for pallet_staking::Pallet<T> {
+    type AccountId = T::AccountId;
+    type Balance = pallet_staking::BalanceOf<T>;
+
+    fn commission(validator: &Self::AccountId) -> Perbill {
+        pallet_staking::Validators::<T>::get(validator).commission
+    }
+
+    fn self_bond(stash: &Self::AccountId) -> Self::Balance {
+        Self::ledger(StakingAccount::Stash(stash.clone()))
+            .map(|ledger| ledger.active)
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_self_bond(stash: &Self::AccountId, value: Self::Balance) {
+        pallet_staking::Pallet::<T>::bond(
+            frame_system::RawOrigin::Signed(stash.clone()).into(),
+            value,
+            pallet_staking::RewardDestination::Staked,
+        )
+        .expect("Stash should be able to bond");
+    }
+}
@@ -0,0 +1,97 @@
+//! Weights for pallet_elections
+//!
+//! THESE WEIGHTS ARE NOT MEASURED YET. They follow the benchmark CLI template, with the storage
+//! accesses of the benchmarks in `benchmarking.rs` and an estimated execution time of 25µs. Run
+//! the command below on reference hardware to replace them.
+
+// Executed Command:
+// ./target/release/selendra-node
+// benchmark
+// pallet
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_elections
+// --extrinsic=*
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/elections/src/weights.rs
+// --template=./scripts/templates/weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_elections.
+pub trait WeightInfo {
+	fn register_validator_intent() -> Weight;
+	fn withdraw_validator_intent() -> Weight;
+}
+
+/// Weights for pallet_elections using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Staking Bonded (r:2 w:0)
+	/// Proof: Staking Bonded (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: Staking Ledger (r:2 w:0)
+	/// Proof: Staking Ledger (max_values: None, max_size: Some(1091), added: 3566, mode: MaxEncodedLen)
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Elections ValidatorIntents (r:1 w:1)
+	/// Proof: Elections ValidatorIntents (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: Elections CounterForValidatorIntents (r:1 w:1)
+	/// Proof: Elections CounterForValidatorIntents (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn register_validator_intent() -> Weight {
+		Weight::from_parts(25_000_000, 4764)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Elections ValidatorIntents (r:1 w:1)
+	/// Proof: Elections ValidatorIntents (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: Elections CounterForValidatorIntents (r:1 w:1)
+	/// Proof: Elections CounterForValidatorIntents (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_validator_intent() -> Weight {
+		Weight::from_parts(25_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Staking Bonded (r:2 w:0)
+	/// Proof: Staking Bonded (max_values: None, max_size: Some(72), added: 2547, mode: MaxEncodedLen)
+	/// Storage: Staking Ledger (r:2 w:0)
+	/// Proof: Staking Ledger (max_values: None, max_size: Some(1091), added: 3566, mode: MaxEncodedLen)
+	/// Storage: Session NextKeys (r:1 w:0)
+	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Elections ValidatorIntents (r:1 w:1)
+	/// Proof: Elections ValidatorIntents (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: Elections CounterForValidatorIntents (r:1 w:1)
+	/// Proof: Elections CounterForValidatorIntents (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn register_validator_intent() -> Weight {
+		Weight::from_parts(25_000_000, 4764)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Elections ValidatorIntents (r:1 w:1)
+	/// Proof: Elections ValidatorIntents (max_values: None, max_size: Some(76), added: 2551, mode: MaxEncodedLen)
+	/// Storage: Elections CounterForValidatorIntents (r:1 w:1)
+	/// Proof: Elections CounterForValidatorIntents (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_validator_intent() -> Weight {
+		Weight::from_parts(25_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+}
@@ -21,8 +21,8 @@
 //! ## Overview
 //!
 //! A `pallet-ethereum` like pallet that executes transactions from checked sources,
-//! like cross-VM calls, system-level calls, etc. Only `Call` transactions are supported
-//! (no `Create`).
+//! like cross-VM calls, system-level calls, etc. Cross-VM calls only support `Call`
+//! transactions, signed origins can also deploy contracts with `Create` ones.
 //!
 //! The checked source guarantees that transactions are valid with prior checks, so these
 //! transactions are not required to include valid signatures. Instead, `pallet-ethereum-checked`
@@ -34,6 +34,7 @@
 //! ### Dispatch-able calls
 //!
 //! - `transact`: transact an Ethereum transaction from a signed origin.
+//! - `create`: deploy a contract with an Ethereum transaction from a signed origin.
 //!
 //! ### Implementation
 //!
@@ -46,10 +47,10 @@ use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 
 use ethereum_types::{H160, U256};
-use fp_ethereum::{TransactionData, ValidatedTransaction};
+use fp_ethereum::{Transaction, TransactionData, ValidatedTransaction};
 use fp_evm::{
-    CallInfo, CallOrCreateInfo, CheckEvmTransaction, CheckEvmTransactionConfig, ExitReason,
-    ExitSucceed, TransactionValidationError,
+    CallInfo, CallOrCreateInfo, CheckEvmTransaction, CheckEvmTransactionConfig, CreateInfo,
+    ExitReason, ExitSucceed, TransactionValidationError,
 };
 use pallet_evm::GasWeightMapping;
 
@@ -62,7 +63,7 @@ use sp_runtime::traits::UniqueSaturatedInto;
 use sp_std::result::Result;
 
 use primitives::{
-    ethereum_checked::{CheckedEthereumTransact, CheckedEthereumTx},
+    ethereum_checked::{CheckedEthereumCreateTx, CheckedEthereumTransact, CheckedEthereumTx},
     evm::UnifiedAddressMapper,
 };
 
@@ -163,6 +164,29 @@ pub mod pallet {
                 post_info
             })
         }
+
+        /// Deploy a contract with an Ethereum transaction from a signed origin.
+        #[pallet::call_index(1)]
+        #[pallet::weight({
+            let weight_limit = T::GasWeightMapping::gas_to_weight(tx.gas_limit.unique_saturated_into(), false);
+            weight_limit.saturating_add(WeightInfoOf::<T>::transact_without_apply())
+        })]
+        pub fn create(
+            origin: OriginFor<T>,
+            tx: CheckedEthereumCreateTx,
+        ) -> DispatchResultWithPostInfo {
+            let source = ensure_signed(origin)?;
+            let from = T::AddressMapper::to_h160_or_default(&source).into_address();
+            Self::do_create(from, tx).map(|(post_info, create_info)| {
+                Self::deposit_event(Event::Executed {
+                    from,
+                    to: create_info.value,
+                    transaction_hash: ethereum_types::H256::zero(), // Will be set properly by Ethereum pallet
+                    exit_reason: create_info.exit_reason,
+                });
+                post_info
+            })
+        }
     }
 }
 
@@ -174,9 +198,69 @@ impl<T: Config> Pallet<T> {
         tx_kind: CheckedEthereumTxKind,
         skip_apply: bool,
     ) -> Result<(PostDispatchInfo, CallInfo), DispatchErrorWithPostInfo> {
+        let tx = Self::validate(
+            |nonce, chain_id| checked_tx.into_ethereum_tx(nonce, chain_id),
+            &tx_kind,
+        )?;
+
+        if skip_apply {
+            return Ok((
+                PostDispatchInfo {
+                    actual_weight: Some(WeightInfoOf::<T>::transact_without_apply()),
+                    pays_fee: Pays::Yes,
+                },
+                CallInfo {
+                    exit_reason: ExitReason::Succeed(ExitSucceed::Returned),
+                    value: Default::default(),
+                    used_gas: fp_evm::UsedGas {
+                        standard: checked_tx.gas_limit,
+                        effective: checked_tx.gas_limit,
+                    },
+                    weight_info: None,
+                    logs: Default::default(),
+                },
+            ));
+        }
+
+        // Execute the tx.
+        let (post_info, apply_info) = T::ValidatedTransaction::apply(source, tx)?;
+        match apply_info {
+            CallOrCreateInfo::Call(info) => Ok((post_info, info)),
+            // It is not possible to have a `Create` transaction via `CheckedEthereumTx`.
+            CallOrCreateInfo::Create(_) => {
+                unreachable!("Cannot create a 'Create' transaction; qed")
+            }
+        }
+    }
+
+    /// Validate and execute the checked tx deploying a contract.
+    fn do_create(
+        source: H160,
+        checked_tx: CheckedEthereumCreateTx,
+    ) -> Result<(PostDispatchInfo, CreateInfo), DispatchErrorWithPostInfo> {
+        let tx = Self::validate(
+            |nonce, chain_id| checked_tx.into_ethereum_tx(nonce, chain_id),
+            &CheckedEthereumTxKind::Call,
+        )?;
+
+        let (post_info, apply_info) = T::ValidatedTransaction::apply(source, tx)?;
+        match apply_info {
+            CallOrCreateInfo::Create(info) => Ok((post_info, info)),
+            // It is not possible to have a `Call` transaction via `CheckedEthereumCreateTx`.
+            CallOrCreateInfo::Call(_) => {
+                unreachable!("Cannot create a 'Call' transaction; qed")
+            }
+        }
+    }
+
+    /// Build the tx with the global nonce, validate it and bump the nonce.
+    fn validate(
+        into_ethereum_tx: impl FnOnce(U256, u64) -> Transaction,
+        tx_kind: &CheckedEthereumTxKind,
+    ) -> Result<Transaction, DispatchErrorWithPostInfo> {
         let chain_id = T::ChainId::get();
         let nonce = Nonce::<T>::get();
-        let tx = checked_tx.into_ethereum_tx(Nonce::<T>::get(), chain_id);
+        let tx = into_ethereum_tx(nonce, chain_id);
         let tx_data: TransactionData = (&tx).into();
 
         let (weight_limit, proof_size_base_cost) =
@@ -196,7 +280,7 @@ impl<T: Config> Pallet<T> {
         let _ = CheckEvmTransaction::<T::InvalidEvmTransactionError>::new(
             CheckEvmTransactionConfig {
                 evm_config: T::config(),
-                block_gas_limit: U256::from(Self::block_gas_limit(&tx_kind)),
+                block_gas_limit: U256::from(Self::block_gas_limit(tx_kind)),
                 base_fee: U256::zero(),
                 chain_id,
                 is_transactional: true,
@@ -221,34 +305,7 @@ impl<T: Config> Pallet<T> {
 
         Nonce::<T>::put(nonce.saturating_add(U256::one()));
 
-        if skip_apply {
-            return Ok((
-                PostDispatchInfo {
-                    actual_weight: Some(WeightInfoOf::<T>::transact_without_apply()),
-                    pays_fee: Pays::Yes,
-                },
-                CallInfo {
-                    exit_reason: ExitReason::Succeed(ExitSucceed::Returned),
-                    value: Default::default(),
-                    used_gas: fp_evm::UsedGas {
-                        standard: checked_tx.gas_limit,
-                        effective: checked_tx.gas_limit,
-                    },
-                    weight_info: None,
-                    logs: Default::default(),
-                },
-            ));
-        }
-
-        // Execute the tx.
-        let (post_info, apply_info) = T::ValidatedTransaction::apply(source, tx)?;
-        match apply_info {
-            CallOrCreateInfo::Call(info) => Ok((post_info, info)),
-            // It is not possible to have a `Create` transaction via `CheckedEthereumTx`.
-            CallOrCreateInfo::Create(_) => {
-                unreachable!("Cannot create a 'Create' transaction; qed")
-            }
-        }
+        Ok(tx)
     }
 
     /// Block gas limit calculation based on the tx kind.
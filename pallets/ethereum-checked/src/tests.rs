@@ -162,3 +162,53 @@ fn no_hash_collision() {
         assert_eq!(tx_hashes.len(), 15);
     });
 }
+
+#[test]
+fn create_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        let create_tx = CheckedEthereumCreateTx {
+            gas_limit: U256::from(1_000_000),
+            value: U256::zero(),
+            init: EthereumTxInput::try_from(hex::decode(STORAGE_CONTRACT).unwrap()).unwrap(),
+            maybe_access_list: None,
+        };
+        assert_noop!(
+            EthereumChecked::create(RuntimeOrigin::root(), create_tx.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(EthereumChecked::create(
+            RuntimeOrigin::signed(ALICE),
+            create_tx
+        ));
+
+        let created = match System::events().last().map(|record| &record.event) {
+            Some(RuntimeEvent::EthereumChecked(Event::Executed {
+                from,
+                to,
+                exit_reason: ExitReason::Succeed(_),
+                ..
+            })) if *from == ALICE_H160 => *to,
+            event => panic!("unexpected event {:?}", event),
+        };
+        assert_ne!(created, contract_address());
+        assert!(pallet_evm::AccountCodes::<TestRuntime>::contains_key(
+            created
+        ));
+        assert_eq!(Nonce::<TestRuntime>::get(), U256::one());
+
+        let store_tx = CheckedEthereumTx {
+            gas_limit: U256::from(1_000_000),
+            target: created,
+            value: U256::zero(),
+            // Calling `store(3)`
+            input: bounded_input(
+                "6057361d0000000000000000000000000000000000000000000000000000000000000003",
+            ),
+            maybe_access_list: None,
+        };
+        assert_ok!(EthereumChecked::transact(
+            RuntimeOrigin::signed(ALICE),
+            store_tx
+        ));
+    });
+}
@@ -31,6 +31,7 @@
 //! connect their evm address to have a unified experience across the different VMs.
 //! - Connect evm address you control
 //! - Connect default evm address
+//! - Disconnect or replace the connected evm address
 //!
 //! ## Interface
 //!
@@ -38,16 +39,31 @@
 //!    account id given that no prior mapping exists for both and signature provided is valid.
 //! * `claim_default_evm_address`: Creates the double mapping with default evm address given that
 //!    no prior mapping exists.
+//! * `unclaim_evm_address`: Removes the double mappings of the caller given a valid signature from
+//!    the mapped evm address, refunding the storage fee.
+//! * `rotate_evm_address`: Replaces the evm address of the caller with a new one given a valid
+//!    signature from the new address, for when the old key is compromised or lost.
+//!
+//! ## Mapping Changes
+//! Each change of an account's mapping (unclaim or rotation) bumps its [`MappingNonce`], which is
+//! part of the signed payloads so that signatures cannot be replayed once the mapping they were
+//! made for is gone. Changes are rate limited per account by
+//! [`MappingChangeCooldown`](`crate::Config::MappingChangeCooldown`) blocks, counting from the
+//! last claim, unclaim or rotation. Reaping an account removes all of its mapping storage.
 //!
 //! ## Storage Fee
 //! User is also charged a storage fee [`AccountMappingStorageFee`](`crate::Config::AccountMappingStorageFee`)
-//! before mappings are created to prevent storage abuse.
+//! before mappings are created to prevent storage abuse. The fee is held on the account and the
+//! amount paid is recorded in [`MappingDeposit`], so unclaiming releases exactly what was paid
+//! even if the configured fee changed in the meantime. Mappings created before the fee was held
+//! had it burned and have nothing to release.
 //!
 //! WARNINGS:
 //! * This pallet only handles transfer of native balance only, for the rest of native assets
 //!   hold by evm address like XC20, DAppStaking unclaimed rewards, etc should be transferred
 //!   manually beforehand by user himself otherwise FUNDS WILL BE LOST FOREVER.
-//! * Once mapping is created it cannot be changed.
+//! * Unclaiming or rotating does not move any funds, the balance of the native account stays with
+//!   it while the old evm address acts as its default account again.
 //!
 //! ## Traits
 //!
@@ -70,7 +86,7 @@ use primitives::{
 use frame_support::{
     pallet_prelude::*,
     traits::{
-        fungible::{Inspect as FungibleInspect, Mutate as FungibleMutate},
+        fungible::{Inspect as FungibleInspect, Mutate as FungibleMutate, MutateHold},
         tokens::{Fortitude::*, Precision::*, Preservation::*},
         IsType, OnKilledAccount,
     },
@@ -81,7 +97,7 @@ use precompile_utils::keccak256;
 use sp_core::{H160, H256, U256};
 use sp_io::hashing::keccak_256;
 use sp_runtime::{
-    traits::{LookupError, StaticLookup, Zero},
+    traits::{LookupError, Saturating, StaticLookup, Zero},
     MultiAddress,
 };
 use sp_std::marker::PhantomData;
@@ -110,8 +126,11 @@ pub mod pallet {
     pub trait Config: frame_system::Config {
         /// The overarching event type
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// The overarching hold reason
+        type RuntimeHoldReason: From<HoldReason>;
         /// The Currency for managing evm address assets
-        type Currency: FungibleMutate<Self::AccountId, Balance = Balance>;
+        type Currency: FungibleMutate<Self::AccountId, Balance = Balance>
+            + MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
         /// Default address conversion
         type DefaultMappings: UnifiedAddressMapper<Self::AccountId>;
         /// EVM chain id
@@ -122,10 +141,21 @@ pub mod pallet {
         /// respectively
         #[pallet::constant]
         type AccountMappingStorageFee: Get<Balance>;
+        /// Number of blocks an account must wait after its mapping changed before changing it
+        /// again.
+        #[pallet::constant]
+        type MappingChangeCooldown: Get<BlockNumberFor<Self>>;
         /// Weight information for the extrinsics in this module
         type WeightInfo: WeightInfo;
     }
 
+    /// Reasons for the pallet placing a hold on funds
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        /// The storage fee of an account mapping
+        AccountMapping,
+    }
+
     #[pallet::error]
     pub enum Error<T> {
         /// AccountId or EvmAddress already mapped
@@ -136,6 +166,10 @@ pub mod pallet {
         InvalidSignature,
         /// Funds unavailable to claim account
         FundsUnavailable,
+        /// AccountId has no mapping
+        NotMapped,
+        /// The mapping of the account changed too recently
+        CooldownNotElapsed,
     }
 
     #[pallet::event]
@@ -147,6 +181,18 @@ pub mod pallet {
             account_id: T::AccountId,
             evm_address: EvmAddress,
         },
+        /// Evm Address unclaimed.
+        /// Double Mapping b/w native and evm address removed and storage fee refunded
+        AccountUnclaimed {
+            account_id: T::AccountId,
+            evm_address: EvmAddress,
+        },
+        /// Evm Address of an account replaced by a new one
+        EvmAddressRotated {
+            account_id: T::AccountId,
+            old_evm_address: EvmAddress,
+            new_evm_address: EvmAddress,
+        },
     }
 
     /// Native accounts for evm address
@@ -161,6 +207,24 @@ pub mod pallet {
     pub type NativeToEvm<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, EvmAddress, OptionQuery>;
 
+    /// Number of times the mapping of native accounts was removed or replaced
+    /// MappingNonce: AccountId => u32
+    #[pallet::storage]
+    pub type MappingNonce<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Block in which the mapping of native accounts last changed
+    /// LastMappingChange: AccountId => Option<BlockNumber>
+    #[pallet::storage]
+    pub type LastMappingChange<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Storage fee held for the mapping of native accounts
+    /// MappingDeposit: AccountId => Option<Balance>
+    #[pallet::storage]
+    pub type MappingDeposit<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Balance, OptionQuery>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Claim account mapping between Substrate account and Evm address.
@@ -173,7 +237,8 @@ pub mod pallet {
         /// - This extrinsic only handles transfer of native balance, if your EVM
         /// address contains any other native assets like XC20, DAppStaking unclaimed rewards,
         /// etc you need to transfer them before hand, otherwise FUNDS WILL BE LOST FOREVER.
-        /// - Once connected user cannot change their mapping EVER.
+        /// - The mapping can only be removed or replaced with `unclaim_evm_address` and
+        /// `rotate_evm_address`, which are rate limited by `MappingChangeCooldown`.
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::claim_evm_address())]
         pub fn claim_evm_address(
@@ -191,6 +256,7 @@ pub mod pallet {
                 !EvmToNative::<T>::contains_key(evm_address),
                 Error::<T>::AlreadyMapped
             );
+            Self::ensure_cooldown_elapsed(&who)?;
 
             // recover evm address from signature
             let address = Self::verify_signature(&who, &signature)
@@ -199,26 +265,14 @@ pub mod pallet {
             ensure!(evm_address == address, Error::<T>::InvalidSignature);
 
             // charge the storage fee
-            Self::charge_storage_fee(&who)?;
-
-            // Check if the default account id already exists for this evm address
-            let default_account_id = T::DefaultMappings::to_default_account_id(&evm_address);
-            if frame_system::Pallet::<T>::account_exists(&default_account_id) {
-                // Transfer all the free native balance from old account id to the newly
-                // since this `default_account_id` will no longer be connected to evm address
-                // and users cannot access it.
-                // For the reset of the assets types (like XC20, etc) that should be handled by UI.
-                T::Currency::transfer(
-                    &default_account_id,
-                    &who,
-                    T::Currency::reducible_balance(&default_account_id, Expendable, Polite),
-                    Expendable,
-                )?;
-            }
+            Self::hold_storage_fee(&who)?;
+
+            Self::transfer_default_account_funds(&evm_address, &who)?;
 
             // create double mappings for the pair
             EvmToNative::<T>::insert(&evm_address, &who);
             NativeToEvm::<T>::insert(&who, &evm_address);
+            LastMappingChange::<T>::insert(&who, frame_system::Pallet::<T>::block_number());
 
             Self::deposit_event(Event::AccountClaimed {
                 account_id: who,
@@ -230,7 +284,8 @@ pub mod pallet {
         /// Claim default evm address for given account id
         /// Ensure no prior mapping exists for the account
         ///
-        /// WARNINGS: Once connected user cannot change their mapping EVER.
+        /// WARNINGS: Nobody controls the default evm address, so the mapping cannot be unclaimed,
+        /// only rotated to an evm address the user controls.
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::claim_default_evm_address())]
         pub fn claim_default_evm_address(origin: OriginFor<T>) -> DispatchResult {
@@ -239,6 +294,91 @@ pub mod pallet {
             let _ = Self::do_claim_default_evm_address(who)?;
             Ok(())
         }
+
+        /// Remove the mapping of the caller's account and release the storage fee held for it.
+        ///
+        /// - `signature`: A signature of the unclaim payload generated by the mapped evm address,
+        ///   see [`Pallet::build_unclaim_signing_payload`]
+        ///
+        /// No funds are moved, the mapped evm address acts as its default account afterwards.
+        /// Claiming again is only possible once the cooldown since the unclaim has passed.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::unclaim_evm_address())]
+        pub fn unclaim_evm_address(
+            origin: OriginFor<T>,
+            signature: EvmSignature,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let evm_address = NativeToEvm::<T>::get(&who).ok_or(Error::<T>::NotMapped)?;
+            Self::ensure_cooldown_elapsed(&who)?;
+
+            // recover evm address from signature
+            let address = Self::verify_unclaim_signature(&who, &signature)
+                .ok_or(Error::<T>::UnexpectedSignatureFormat)?;
+
+            ensure!(evm_address == address, Error::<T>::InvalidSignature);
+
+            // remove double mappings for the pair
+            EvmToNative::<T>::remove(evm_address);
+            NativeToEvm::<T>::remove(&who);
+            Self::note_mapping_change(&who);
+
+            // refund the storage fee
+            Self::release_storage_fee(&who)?;
+
+            Self::deposit_event(Event::AccountUnclaimed {
+                account_id: who,
+                evm_address,
+            });
+            Ok(())
+        }
+
+        /// Replace the evm address mapped to the caller's account.
+        /// Ensure no prior mapping exists for the new evm address.
+        ///
+        /// - `new_evm_address`: The evm address to bind to the caller's account
+        /// - `signature`: A signature generated by the new address to prove ownership, see
+        ///   [`Pallet::build_signing_payload`]
+        ///
+        /// The old evm address does not need to sign, so a compromised or lost key can be
+        /// replaced. Native balance held by the default account of the new address is moved to
+        /// the caller like in `claim_evm_address`.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::rotate_evm_address())]
+        pub fn rotate_evm_address(
+            origin: OriginFor<T>,
+            new_evm_address: EvmAddress,
+            signature: EvmSignature,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let old_evm_address = NativeToEvm::<T>::get(&who).ok_or(Error::<T>::NotMapped)?;
+            ensure!(
+                !EvmToNative::<T>::contains_key(new_evm_address),
+                Error::<T>::AlreadyMapped
+            );
+            Self::ensure_cooldown_elapsed(&who)?;
+
+            // recover evm address from signature
+            let address = Self::verify_signature(&who, &signature)
+                .ok_or(Error::<T>::UnexpectedSignatureFormat)?;
+
+            ensure!(new_evm_address == address, Error::<T>::InvalidSignature);
+
+            Self::transfer_default_account_funds(&new_evm_address, &who)?;
+
+            // replace the double mappings
+            EvmToNative::<T>::remove(old_evm_address);
+            EvmToNative::<T>::insert(&new_evm_address, &who);
+            NativeToEvm::<T>::insert(&who, &new_evm_address);
+            Self::note_mapping_change(&who);
+
+            Self::deposit_event(Event::EvmAddressRotated {
+                account_id: who,
+                old_evm_address,
+                new_evm_address,
+            });
+            Ok(())
+        }
     }
 }
 
@@ -249,6 +389,7 @@ impl<T: Config> Pallet<T> {
             !NativeToEvm::<T>::contains_key(&account_id),
             Error::<T>::AlreadyMapped
         );
+        Self::ensure_cooldown_elapsed(&account_id)?;
         // get the default evm address
         let evm_address = T::DefaultMappings::to_default_h160(&account_id);
         // make sure default address is not already mapped, this should not
@@ -258,11 +399,12 @@ impl<T: Config> Pallet<T> {
             Error::<T>::AlreadyMapped
         );
 
-        Self::charge_storage_fee(&account_id)?;
+        Self::hold_storage_fee(&account_id)?;
 
         // create double mappings for the pair with default evm address
         EvmToNative::<T>::insert(&evm_address, &account_id);
         NativeToEvm::<T>::insert(&account_id, &evm_address);
+        LastMappingChange::<T>::insert(&account_id, frame_system::Pallet::<T>::block_number());
 
         Self::deposit_event(Event::AccountClaimed {
             account_id,
@@ -271,45 +413,107 @@ impl<T: Config> Pallet<T> {
         Ok(evm_address)
     }
 
-    /// Charge the (exact) storage fee (politely) from the user and burn it
-    /// while preserving the account from being reaped.
-    fn charge_storage_fee(who: &T::AccountId) -> Result<Balance, DispatchError> {
+    /// Transfer all the free native balance of the default account of `evm_address` to `who`,
+    /// since it will no longer be connected to the evm address and users cannot access it.
+    /// For the rest of the assets types (like XC20, etc) that should be handled by UI.
+    fn transfer_default_account_funds(
+        evm_address: &EvmAddress,
+        who: &T::AccountId,
+    ) -> DispatchResult {
+        let default_account_id = T::DefaultMappings::to_default_account_id(evm_address);
+        // nothing to move when claiming for the default account itself
+        if &default_account_id != who
+            && frame_system::Pallet::<T>::account_exists(&default_account_id)
+        {
+            T::Currency::transfer(
+                &default_account_id,
+                who,
+                T::Currency::reducible_balance(&default_account_id, Expendable, Polite),
+                Expendable,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Ensure the cooldown since the last mapping change of the account has passed.
+    fn ensure_cooldown_elapsed(who: &T::AccountId) -> DispatchResult {
+        if let Some(changed_at) = LastMappingChange::<T>::get(who) {
+            ensure!(
+                frame_system::Pallet::<T>::block_number()
+                    >= changed_at.saturating_add(T::MappingChangeCooldown::get()),
+                Error::<T>::CooldownNotElapsed
+            );
+        }
+        Ok(())
+    }
+
+    /// Record that the mapping of the account was replaced, invalidating the signatures made
+    /// for it.
+    fn note_mapping_change(who: &T::AccountId) {
+        MappingNonce::<T>::mutate(who, |nonce| *nonce = nonce.saturating_add(1));
+        LastMappingChange::<T>::insert(who, frame_system::Pallet::<T>::block_number());
+    }
+
+    /// Hold the (exact) storage fee (politely) on the user's account while preserving the
+    /// account from being reaped, and record the amount to release on unclaim.
+    fn hold_storage_fee(who: &T::AccountId) -> DispatchResult {
         let balance = T::Currency::reducible_balance(who, Preserve, Polite);
         let fee = T::AccountMappingStorageFee::get();
         ensure!(balance >= fee, Error::<T>::FundsUnavailable);
-        T::Currency::burn_from(
-            who,
-            T::AccountMappingStorageFee::get(),
-            Preserve,
-            Exact,
-            Polite,
-        )
+        T::Currency::hold(&HoldReason::AccountMapping.into(), who, fee)?;
+        MappingDeposit::<T>::insert(who, fee);
+        Ok(())
+    }
+
+    /// Release the storage fee held for the mapping of the account, if any.
+    fn release_storage_fee(who: &T::AccountId) -> DispatchResult {
+        if let Some(deposit) = MappingDeposit::<T>::take(who) {
+            T::Currency::release(&HoldReason::AccountMapping.into(), who, deposit, BestEffort)?;
+        }
+        Ok(())
     }
 }
 
 /// EIP-712 compatible signature scheme for verifying ownership of EVM Address
 /// https://eips.ethereum.org/EIPS/eip-712
 ///
-/// Raw Data = Domain Separator + Type Hash + keccak256(AccountId)
+/// Raw Data = Domain Separator + Type Hash + keccak256(AccountId) [+ Nonce]
+///
+/// The first claim of an account signs `Claim(bytes substrateAddress)`, claims and rotations
+/// after its mapping changed sign `Reclaim(bytes substrateAddress,uint256 nonce)` and unclaims
+/// sign `Unclaim(bytes substrateAddress,uint256 nonce)`, with the account's [`MappingNonce`].
 impl<T: Config> Pallet<T> {
     pub fn build_signing_payload(who: &T::AccountId) -> [u8; 32] {
-        let domain_separator = Self::build_domain_separator();
-        let args_hash = Self::build_args_hash(who);
+        Self::build_payload(&Self::build_args_hash(who))
+    }
 
-        let mut payload = b"\x19\x01".to_vec();
-        payload.extend_from_slice(&domain_separator);
-        payload.extend_from_slice(&args_hash);
-        keccak_256(&payload)
+    pub fn build_unclaim_signing_payload(who: &T::AccountId) -> [u8; 32] {
+        Self::build_payload(&Self::build_unclaim_args_hash(who))
     }
 
     pub fn verify_signature(who: &T::AccountId, sig: &EvmSignature) -> Option<EvmAddress> {
-        let payload_hash = Self::build_signing_payload(who);
+        Self::recover_signer(&Self::build_signing_payload(who), sig)
+    }
+
+    pub fn verify_unclaim_signature(who: &T::AccountId, sig: &EvmSignature) -> Option<EvmAddress> {
+        Self::recover_signer(&Self::build_unclaim_signing_payload(who), sig)
+    }
 
-        sp_io::crypto::secp256k1_ecdsa_recover(sig, &payload_hash)
+    fn recover_signer(payload_hash: &[u8; 32], sig: &EvmSignature) -> Option<EvmAddress> {
+        sp_io::crypto::secp256k1_ecdsa_recover(sig, payload_hash)
             .map(|pubkey| H160::from(H256::from_slice(&keccak_256(&pubkey))))
             .ok()
     }
 
+    fn build_payload(args_hash: &[u8; 32]) -> [u8; 32] {
+        let domain_separator = Self::build_domain_separator();
+
+        let mut payload = b"\x19\x01".to_vec();
+        payload.extend_from_slice(&domain_separator);
+        payload.extend_from_slice(args_hash);
+        keccak_256(&payload)
+    }
+
     fn build_domain_separator() -> [u8; 32] {
         let mut domain =
             keccak256!("EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)")
@@ -324,8 +528,23 @@ impl<T: Config> Pallet<T> {
     }
 
     fn build_args_hash(account: &T::AccountId) -> [u8; 32] {
-        let mut args_hash = keccak256!("Claim(bytes substrateAddress)").to_vec();
+        let nonce = MappingNonce::<T>::get(account);
+        if nonce.is_zero() {
+            let mut args_hash = keccak256!("Claim(bytes substrateAddress)").to_vec();
+            args_hash.extend_from_slice(&keccak_256(&account.encode()));
+            return keccak_256(args_hash.as_slice());
+        }
+
+        let mut args_hash = keccak256!("Reclaim(bytes substrateAddress,uint256 nonce)").to_vec();
+        args_hash.extend_from_slice(&keccak_256(&account.encode()));
+        args_hash.extend_from_slice(&U256::from(nonce).to_big_endian());
+        keccak_256(args_hash.as_slice())
+    }
+
+    fn build_unclaim_args_hash(account: &T::AccountId) -> [u8; 32] {
+        let mut args_hash = keccak256!("Unclaim(bytes substrateAddress,uint256 nonce)").to_vec();
         args_hash.extend_from_slice(&keccak_256(&account.encode()));
+        args_hash.extend_from_slice(&U256::from(MappingNonce::<T>::get(account)).to_big_endian());
         keccak_256(args_hash.as_slice())
     }
 }
@@ -389,6 +608,9 @@ impl<T: Config> OnKilledAccount<T::AccountId> for KillAccountMapping<T> {
             EvmToNative::<T>::remove(evm_addr);
             NativeToEvm::<T>::remove(who);
         }
+        MappingNonce::<T>::remove(who);
+        LastMappingChange::<T>::remove(who);
+        MappingDeposit::<T>::remove(who);
     }
 }
 
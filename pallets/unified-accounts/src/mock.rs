@@ -155,15 +155,18 @@ impl pallet_ethereum::Config for TestRuntime {
 }
 
 parameter_types! {
-    pub const AccountMappingStorageFee: u128 = 100_000_000;
+    pub static AccountMappingStorageFee: u128 = 100_000_000;
+    pub const MappingChangeCooldown: u64 = 10;
 }
 
 impl pallet_unified_accounts::Config for TestRuntime {
     type RuntimeEvent = RuntimeEvent;
+    type RuntimeHoldReason = RuntimeHoldReason;
     type Currency = Balances;
     type DefaultMappings = HashedDefaultMappings<BlakeTwo256>;
     type ChainId = ChainId;
     type AccountMappingStorageFee = AccountMappingStorageFee;
+    type MappingChangeCooldown = MappingChangeCooldown;
     type WeightInfo = ();
 }
 
@@ -19,12 +19,18 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{
+        fungible::{InspectHold, MutateHold},
+        tokens,
+    },
+};
 use mock::*;
 
 use ethers::{
     contract::{Eip712, EthAbiType},
-    core::types::{transaction::eip712::Eip712, Bytes},
+    core::types::{transaction::eip712::Eip712, Bytes, U256 as EthU256},
 };
 use parity_scale_codec::Encode;
 use sp_runtime::{traits::StaticLookup, AccountId32, MultiAddress};
@@ -32,7 +38,7 @@ use sp_runtime::{traits::StaticLookup, AccountId32, MultiAddress};
 /// EIP712 Payload struct
 #[derive(Eip712, EthAbiType, Clone)]
 #[eip712(
-        name = "SelendraSelendra EVM Claim",
+        name = "Selendra EVM Claim",
         version = "1",
         chain_id = 1953,
         // mock genisis hash
@@ -42,6 +48,34 @@ struct Claim {
     substrate_address: Bytes,
 }
 
+/// EIP712 Payload struct for claims after the mapping changed
+#[derive(Eip712, EthAbiType, Clone)]
+#[eip712(
+        name = "Selendra EVM Claim",
+        version = "1",
+        chain_id = 1953,
+        // mock genisis hash
+        raw_salt = "0x4545454545454545454545454545454545454545454545454545454545454545"
+    )]
+struct Reclaim {
+    substrate_address: Bytes,
+    nonce: EthU256,
+}
+
+/// EIP712 Payload struct for unclaims
+#[derive(Eip712, EthAbiType, Clone)]
+#[eip712(
+        name = "Selendra EVM Claim",
+        version = "1",
+        chain_id = 1953,
+        // mock genisis hash
+        raw_salt = "0x4545454545454545454545454545454545454545454545454545454545454545"
+    )]
+struct Unclaim {
+    substrate_address: Bytes,
+    nonce: EthU256,
+}
+
 /// Build the signature payload for given native account and eth private key
 fn get_evm_signature(who: &AccountId32, secret: &libsecp256k1::SecretKey) -> [u8; 65] {
     // sign the payload
@@ -55,6 +89,26 @@ fn get_evm_signature(who: &AccountId32, secret: &libsecp256k1::SecretKey) -> [u8
     )
 }
 
+/// Sign the current claim payload of given native account, which depends on its mapping nonce
+fn get_claim_signature(who: &AccountId32, secret: &libsecp256k1::SecretKey) -> [u8; 65] {
+    UnifiedAccounts::eth_sign_prehash(&UnifiedAccounts::build_signing_payload(who), secret)
+}
+
+/// Sign the current unclaim payload of given native account
+fn get_unclaim_signature(who: &AccountId32, secret: &libsecp256k1::SecretKey) -> [u8; 65] {
+    UnifiedAccounts::eth_sign_prehash(&UnifiedAccounts::build_unclaim_signing_payload(who), secret)
+}
+
+/// Move past the mapping change cooldown of accounts changed in the current block
+fn pass_cooldown() {
+    System::set_block_number(System::block_number() + MappingChangeCooldown::get());
+}
+
+/// Storage fee currently held for the mapping of the account
+fn held_storage_fee(who: &AccountId32) -> u128 {
+    Balances::balance_on_hold(&HoldReason::AccountMapping.into(), who)
+}
+
 /// Create the mappings for the accounts
 fn connect_accounts(who: &AccountId32, secret: &libsecp256k1::SecretKey) {
     assert_ok!(UnifiedAccounts::claim_evm_address(
@@ -124,7 +178,13 @@ fn on_killed_account_hook() {
         // create the mappings
         connect_accounts(&ALICE, &alice_secret());
 
-        // kill alice by transfering everything to bob
+        // kill alice by slashing the held fee and transfering everything to bob
+        assert_ok!(Balances::burn_all_held(
+            &HoldReason::AccountMapping.into(),
+            &ALICE,
+            tokens::Precision::BestEffort,
+            tokens::Fortitude::Force
+        ));
         Balances::set_balance(&ALICE, 0);
 
         // check killed account events
@@ -136,6 +196,9 @@ fn on_killed_account_hook() {
         // make sure mapping is removed
         assert_eq!(NativeToEvm::<TestRuntime>::get(ALICE), None);
         assert_eq!(EvmToNative::<TestRuntime>::get(alice_eth), None);
+        assert_eq!(LastMappingChange::<TestRuntime>::get(ALICE), None);
+        assert_eq!(MappingDeposit::<TestRuntime>::get(ALICE), None);
+        assert!(!MappingNonce::<TestRuntime>::contains_key(ALICE));
     });
 }
 
@@ -170,12 +233,12 @@ fn account_claim_should_work() {
                 if account == &alice_eth_old_account
         )));
 
-        // check if storage fee is charged and burned
-        assert!(System::events().iter().any(|r| matches!(
-            &r.event,
-            RuntimeEvent::Balances(pallet_balances::Event::Burned { who, amount })
-                if who == &ALICE && amount == &AccountMappingStorageFee::get()
-        )));
+        // check if storage fee is charged and held
+        assert_eq!(held_storage_fee(&ALICE), AccountMappingStorageFee::get());
+        assert_eq!(
+            MappingDeposit::<TestRuntime>::get(&ALICE),
+            Some(AccountMappingStorageFee::get())
+        );
 
         // check for claim account event
         System::assert_last_event(RuntimeEvent::UnifiedAccounts(
@@ -191,6 +254,45 @@ fn account_claim_should_work() {
     });
 }
 
+#[test]
+fn account_claim_for_default_account_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+        let alice_eth_default_account =
+            <TestRuntime as Config>::DefaultMappings::to_default_account_id(&alice_eth);
+
+        // fund the default account so it can pay the storage fee
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(BOB),
+            alice_eth_default_account.clone().into(),
+            1_000_000_000
+        ));
+
+        // the default account claims the eth address it is derived from
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(alice_eth_default_account.clone()),
+            alice_eth,
+            get_evm_signature(&alice_eth_default_account, &alice_secret())
+        ));
+
+        // the account keeps its funds, minus the storage fee
+        assert_eq!(
+            Balances::free_balance(&alice_eth_default_account),
+            1_000_000_000 - AccountMappingStorageFee::get()
+        );
+
+        // the address resolves to the same account as before, now through the mappings
+        assert_eq!(
+            EvmToNative::<TestRuntime>::get(alice_eth).unwrap(),
+            alice_eth_default_account
+        );
+        assert_eq!(
+            NativeToEvm::<TestRuntime>::get(&alice_eth_default_account).unwrap(),
+            alice_eth
+        );
+    });
+}
+
 #[test]
 fn account_default_claim_works() {
     ExtBuilder::default().build().execute_with(|| {
@@ -207,12 +309,12 @@ fn account_default_claim_works() {
             },
         ));
 
-        // check if storage fee is charged and burned
-        assert!(System::events().iter().any(|r| matches!(
-            &r.event,
-            RuntimeEvent::Balances(pallet_balances::Event::Burned { who, amount })
-                if who == &ALICE && amount == &AccountMappingStorageFee::get()
-        )));
+        // check if storage fee is charged and held
+        assert_eq!(held_storage_fee(&ALICE), AccountMappingStorageFee::get());
+        assert_eq!(
+            MappingDeposit::<TestRuntime>::get(&ALICE),
+            Some(AccountMappingStorageFee::get())
+        );
 
         // check UnifiedAddressMapper's mapping works
         assert_eq!(
@@ -377,8 +479,12 @@ fn charging_storage_fee_should_not_reap_account() {
             },
         ));
 
-        // confirm the balance, should be equal to ED
-        assert_eq!(Balances::total_balance(&ALICE), ExistentialDeposit::get());
+        // confirm the balance, should be equal to ED with the fee on hold
+        assert_eq!(Balances::free_balance(&ALICE), ExistentialDeposit::get());
+        assert_eq!(
+            Balances::total_balance(&ALICE),
+            ExistentialDeposit::get() + AccountMappingStorageFee::get()
+        );
 
         // clear mappings
         EvmToNative::<TestRuntime>::remove(alice_eth);
@@ -397,3 +503,394 @@ fn charging_storage_fee_should_not_reap_account() {
         );
     });
 }
+
+#[test]
+fn eip712_mapping_change_payloads_are_correct() {
+    ExtBuilder::default().build().execute_with(|| {
+        let unclaim = Unclaim {
+            substrate_address: ALICE.encode().into(),
+            nonce: 0.into(),
+        };
+        assert_eq!(
+            unclaim.encode_eip712().unwrap(),
+            UnifiedAccounts::build_unclaim_signing_payload(&ALICE),
+            "unclaim payload should match"
+        );
+
+        // claims sign the nonce once the mapping changed
+        MappingNonce::<TestRuntime>::insert(&ALICE, 2);
+        let reclaim = Reclaim {
+            substrate_address: ALICE.encode().into(),
+            nonce: 2.into(),
+        };
+        assert_eq!(
+            reclaim.encode_eip712().unwrap(),
+            UnifiedAccounts::build_signing_payload(&ALICE),
+            "reclaim payload should match"
+        );
+        let unclaim = Unclaim {
+            substrate_address: ALICE.encode().into(),
+            nonce: 2.into(),
+        };
+        assert_eq!(
+            unclaim.encode_eip712().unwrap(),
+            UnifiedAccounts::build_unclaim_signing_payload(&ALICE),
+            "unclaim payload should match"
+        );
+    });
+}
+
+#[test]
+fn account_unclaim_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+        let alice_balance = Balances::free_balance(&ALICE);
+        let total_issuance = Balances::total_issuance();
+
+        connect_accounts(&ALICE, &alice_secret());
+        pass_cooldown();
+
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            get_unclaim_signature(&ALICE, &alice_secret())
+        ));
+
+        // check for unclaim account event
+        System::assert_last_event(RuntimeEvent::UnifiedAccounts(
+            crate::Event::AccountUnclaimed {
+                account_id: ALICE.clone(),
+                evm_address: alice_eth,
+            },
+        ));
+
+        // storage fee is released, without minting anything
+        assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+        assert_eq!(held_storage_fee(&ALICE), 0);
+        assert_eq!(MappingDeposit::<TestRuntime>::get(&ALICE), None);
+        assert_eq!(Balances::total_issuance(), total_issuance);
+
+        // mappings are removed and the eth address acts as its default account again
+        assert_eq!(NativeToEvm::<TestRuntime>::get(ALICE), None);
+        assert_eq!(EvmToNative::<TestRuntime>::get(alice_eth), None);
+        assert_eq!(
+            <UnifiedAccounts as UnifiedAddressMapper<_>>::to_account_id_or_default(&alice_eth)
+                .into_address(),
+            <TestRuntime as Config>::DefaultMappings::to_default_account_id(&alice_eth)
+        );
+        assert_eq!(MappingNonce::<TestRuntime>::get(ALICE), 1);
+        assert_eq!(
+            LastMappingChange::<TestRuntime>::get(ALICE),
+            Some(System::block_number())
+        );
+    });
+}
+
+#[test]
+fn account_unclaim_releases_the_fee_paid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_balance = Balances::free_balance(&ALICE);
+        let paid_fee = AccountMappingStorageFee::get();
+
+        connect_accounts(&ALICE, &alice_secret());
+        pass_cooldown();
+
+        // the fee changes while the mapping exists
+        AccountMappingStorageFee::set(paid_fee * 3);
+
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            get_unclaim_signature(&ALICE, &alice_secret())
+        ));
+
+        // exactly the paid fee comes back
+        assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+        assert_eq!(held_storage_fee(&ALICE), 0);
+    });
+}
+
+#[test]
+fn account_unclaim_without_deposit_releases_nothing() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+
+        // a mapping created when the storage fee was burned
+        EvmToNative::<TestRuntime>::insert(alice_eth, ALICE);
+        NativeToEvm::<TestRuntime>::insert(ALICE, alice_eth);
+        let alice_balance = Balances::free_balance(&ALICE);
+        let total_issuance = Balances::total_issuance();
+
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            get_unclaim_signature(&ALICE, &alice_secret())
+        ));
+
+        assert_eq!(NativeToEvm::<TestRuntime>::get(ALICE), None);
+        assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+        assert_eq!(Balances::total_issuance(), total_issuance);
+    });
+}
+
+#[test]
+fn account_unclaim_requires_both_sides() {
+    ExtBuilder::default().build().execute_with(|| {
+        // unmapped accounts have nothing to unclaim
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                get_unclaim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::NotMapped
+        );
+
+        connect_accounts(&ALICE, &alice_secret());
+        pass_cooldown();
+
+        // the mapped eth address has to sign
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                get_unclaim_signature(&ALICE, &bob_secret())
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+
+        // the claim signature is not valid for unclaiming
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                get_evm_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+
+        // the signature is only valid for the account it was made for
+        connect_accounts(&BOB, &bob_secret());
+        pass_cooldown();
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(BOB),
+                get_unclaim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn mapping_signatures_cannot_be_replayed_after_change() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+        let claim_signature = get_evm_signature(&ALICE, &alice_secret());
+        let unclaim_signature = get_unclaim_signature(&ALICE, &alice_secret());
+
+        connect_accounts(&ALICE, &alice_secret());
+        pass_cooldown();
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            unclaim_signature
+        ));
+        pass_cooldown();
+
+        // the first claim signature cannot be used to connect again
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                alice_eth,
+                claim_signature
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+
+        // a fresh signature over the new nonce is needed
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            alice_eth,
+            get_claim_signature(&ALICE, &alice_secret())
+        ));
+        pass_cooldown();
+
+        // the first unclaim signature cannot be used to disconnect again
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(RuntimeOrigin::signed(ALICE), unclaim_signature),
+            Error::<TestRuntime>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn mapping_changes_should_respect_cooldown() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+        connect_accounts(&ALICE, &alice_secret());
+
+        // too early to unclaim
+        System::set_block_number(MappingChangeCooldown::get());
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                get_unclaim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::CooldownNotElapsed
+        );
+        assert_noop!(
+            UnifiedAccounts::rotate_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                UnifiedAccounts::eth_address(&bob_secret()),
+                get_claim_signature(&ALICE, &bob_secret())
+            ),
+            Error::<TestRuntime>::CooldownNotElapsed
+        );
+
+        System::set_block_number(1 + MappingChangeCooldown::get());
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            get_unclaim_signature(&ALICE, &alice_secret())
+        ));
+        // the unclaim is a mapping change, so claiming again is rate limited as well
+        assert_noop!(
+            UnifiedAccounts::claim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                alice_eth,
+                get_claim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::CooldownNotElapsed
+        );
+        assert_noop!(
+            UnifiedAccounts::claim_default_evm_address(RuntimeOrigin::signed(ALICE)),
+            Error::<TestRuntime>::CooldownNotElapsed
+        );
+
+        // claiming again starts a new mapping, which is rate limited in turn
+        pass_cooldown();
+        assert_ok!(UnifiedAccounts::claim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            alice_eth,
+            get_claim_signature(&ALICE, &alice_secret())
+        ));
+        assert_noop!(
+            UnifiedAccounts::unclaim_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                get_unclaim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::CooldownNotElapsed
+        );
+
+        pass_cooldown();
+        assert_ok!(UnifiedAccounts::unclaim_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            get_unclaim_signature(&ALICE, &alice_secret())
+        ));
+    });
+}
+
+#[test]
+fn account_rotate_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+        let bob_eth = UnifiedAccounts::eth_address(&bob_secret());
+        let bob_eth_default_account =
+            <TestRuntime as Config>::DefaultMappings::to_default_account_id(&bob_eth);
+
+        connect_accounts(&ALICE, &alice_secret());
+        let alice_balance = Balances::free_balance(&ALICE);
+        pass_cooldown();
+
+        // transfer some funds to the default account of the new eth address
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(BOB),
+            bob_eth_default_account.clone().into(),
+            1001
+        ));
+
+        // the old eth address does not need to sign
+        assert_ok!(UnifiedAccounts::rotate_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            bob_eth,
+            get_claim_signature(&ALICE, &bob_secret())
+        ));
+
+        System::assert_last_event(RuntimeEvent::UnifiedAccounts(
+            crate::Event::EvmAddressRotated {
+                account_id: ALICE.clone(),
+                old_evm_address: alice_eth,
+                new_evm_address: bob_eth,
+            },
+        ));
+
+        // funds of the default account are moved, no storage fee is charged
+        assert_eq!(Balances::free_balance(&ALICE), alice_balance + 1001);
+        assert_eq!(Balances::free_balance(&bob_eth_default_account), 0);
+
+        // mappings are replaced
+        assert_eq!(NativeToEvm::<TestRuntime>::get(ALICE), Some(bob_eth));
+        assert_eq!(EvmToNative::<TestRuntime>::get(bob_eth), Some(ALICE));
+        assert_eq!(EvmToNative::<TestRuntime>::get(alice_eth), None);
+        assert_eq!(MappingNonce::<TestRuntime>::get(ALICE), 1);
+    });
+}
+
+#[test]
+fn account_rotate_from_default_evm_address_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let alice_default_h160 = <TestRuntime as Config>::DefaultMappings::to_default_h160(&ALICE);
+        let alice_eth = UnifiedAccounts::eth_address(&alice_secret());
+
+        assert_ok!(UnifiedAccounts::claim_default_evm_address(
+            RuntimeOrigin::signed(ALICE)
+        ));
+        pass_cooldown();
+
+        assert_ok!(UnifiedAccounts::rotate_evm_address(
+            RuntimeOrigin::signed(ALICE),
+            alice_eth,
+            get_claim_signature(&ALICE, &alice_secret())
+        ));
+
+        assert_eq!(NativeToEvm::<TestRuntime>::get(ALICE), Some(alice_eth));
+        assert_eq!(EvmToNative::<TestRuntime>::get(alice_default_h160), None);
+    });
+}
+
+#[test]
+fn account_rotate_should_not_work_for_mapped_or_unsigned_address() {
+    ExtBuilder::default().build().execute_with(|| {
+        let bob_eth = UnifiedAccounts::eth_address(&bob_secret());
+
+        // unmapped accounts have nothing to rotate
+        assert_noop!(
+            UnifiedAccounts::rotate_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                bob_eth,
+                get_claim_signature(&ALICE, &bob_secret())
+            ),
+            Error::<TestRuntime>::NotMapped
+        );
+
+        connect_accounts(&ALICE, &alice_secret());
+        connect_accounts(&BOB, &bob_secret());
+        pass_cooldown();
+
+        // new eth address is already mapped
+        assert_noop!(
+            UnifiedAccounts::rotate_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                bob_eth,
+                get_claim_signature(&ALICE, &bob_secret())
+            ),
+            Error::<TestRuntime>::AlreadyMapped
+        );
+
+        // new eth address has to sign
+        let charlie_eth = UnifiedAccounts::eth_address(
+            &libsecp256k1::SecretKey::parse(&keccak_256(b"Charlie")).unwrap(),
+        );
+        assert_noop!(
+            UnifiedAccounts::rotate_evm_address(
+                RuntimeOrigin::signed(ALICE),
+                charlie_eth,
+                get_claim_signature(&ALICE, &alice_secret())
+            ),
+            Error::<TestRuntime>::InvalidSignature
+        );
+    });
+}
@@ -39,6 +39,11 @@
 // --output=./benchmark-results/shibuya-dev/unified_accounts_weights.rs
 // --template=./scripts/templates/weight-template.hbs
 
+// NOTE: `unclaim_evm_address`, `rotate_evm_address` and the storage fee and mapping change
+// accesses of the claims were added after the run above and are not measured yet. Their execution
+// time is the one of `claim_evm_address`, which performs the same signature recovery. Re-run the
+// command above on reference hardware to replace them.
+
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -50,6 +55,8 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn claim_evm_address() -> Weight;
 	fn claim_default_evm_address() -> Weight;
+	fn unclaim_evm_address() -> Weight;
+	fn rotate_evm_address() -> Weight;
 	fn to_account_id() -> Weight;
 	fn to_account_id_or_default() -> Weight;
 	fn to_h160() -> Weight;
@@ -67,29 +74,79 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	/// Proof: EVMChainId ChainId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: System BlockHash (r:1 w:0)
 	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
-	/// Storage: System Account (r:1 w:0)
+	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:0)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:0 w:1)
 	fn claim_evm_address() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `256`
 		//  Estimated: `3593`
 		// Minimum execution time: 91_231_000 picoseconds.
 		Weight::from_parts(91_688_000, 3593)
-			.saturating_add(T::DbWeight::get().reads(5_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
 	}
 	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
 	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
 	/// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
 	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:0)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:0 w:1)
 	fn claim_default_evm_address() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `42`
-		//  Estimated: `3533`
+		//  Estimated: `3593`
 		// Minimum execution time: 40_749_000 picoseconds.
-		Weight::from_parts(41_411_000, 3533)
-			.saturating_add(T::DbWeight::get().reads(2_u64))
-			.saturating_add(T::DbWeight::get().writes(2_u64))
+		Weight::from_parts(41_411_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts EvmToNative (r:0 w:1)
+	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	fn unclaim_evm_address() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `256`
+		//  Estimated: `3593`
+		// Minimum execution time: 91_231_000 picoseconds.
+		Weight::from_parts(91_688_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts EvmToNative (r:1 w:2)
+	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:1)
+	fn rotate_evm_address() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `256`
+		//  Estimated: `6196`
+		// Minimum execution time: 91_231_000 picoseconds.
+		Weight::from_parts(91_688_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
 	}
 	/// Storage: UnifiedAccounts EvmToNative (r:1 w:0)
 	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
@@ -143,29 +200,79 @@ impl WeightInfo for () {
 	/// Proof: EVMChainId ChainId (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: System BlockHash (r:1 w:0)
 	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
-	/// Storage: System Account (r:1 w:0)
+	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:0)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:0 w:1)
 	fn claim_evm_address() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `256`
 		//  Estimated: `3593`
 		// Minimum execution time: 91_231_000 picoseconds.
 		Weight::from_parts(91_688_000, 3593)
-			.saturating_add(RocksDbWeight::get().reads(5_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
 	}
 	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
 	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
 	/// Storage: UnifiedAccounts EvmToNative (r:1 w:1)
 	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:0)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:0 w:1)
 	fn claim_default_evm_address() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `42`
-		//  Estimated: `3533`
+		//  Estimated: `3593`
 		// Minimum execution time: 40_749_000 picoseconds.
-		Weight::from_parts(41_411_000, 3533)
-			.saturating_add(RocksDbWeight::get().reads(2_u64))
-			.saturating_add(RocksDbWeight::get().writes(2_u64))
+		Weight::from_parts(41_411_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts EvmToNative (r:0 w:1)
+	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingDeposit (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	fn unclaim_evm_address() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `256`
+		//  Estimated: `3593`
+		// Minimum execution time: 91_231_000 picoseconds.
+		Weight::from_parts(91_688_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: UnifiedAccounts NativeToEvm (r:1 w:1)
+	/// Proof: UnifiedAccounts NativeToEvm (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts EvmToNative (r:1 w:2)
+	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: UnifiedAccounts LastMappingChange (r:1 w:1)
+	/// Storage: UnifiedAccounts MappingNonce (r:1 w:1)
+	fn rotate_evm_address() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `256`
+		//  Estimated: `6196`
+		// Minimum execution time: 91_231_000 picoseconds.
+		Weight::from_parts(91_688_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
 	}
 	/// Storage: UnifiedAccounts EvmToNative (r:1 w:0)
 	/// Proof: UnifiedAccounts EvmToNative (max_values: None, max_size: Some(68), added: 2543, mode: MaxEncodedLen)
@@ -0,0 +1,119 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use frame_benchmarking::v2::*;
+use frame_support::{
+    sp_runtime::RuntimeAppPublic,
+    traits::{Currency, Get},
+    BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use primitives::{validator_address_message, SessionIndex};
+use sp_runtime::traits::Zero;
+use sp_std::vec;
+
+use crate::{Addresses, Call, Config, Pallet, Signature};
+
+fn funded_account<T: Config>(name: &'static str) -> T::AccountId {
+    let account: T::AccountId = account(name, 0, 0);
+    T::Currency::make_free_balance_be(
+        &account,
+        T::Currency::minimum_balance() + T::AddressDeposit::get(),
+    );
+    account
+}
+
+fn sign<T: Config>(
+    authority: &T::AuthorityId,
+    owner: &T::AccountId,
+    address: &[u8],
+    session: SessionIndex,
+) -> Signature<T> {
+    let genesis_hash = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+    authority
+        .sign(&validator_address_message(
+            &genesis_hash,
+            owner,
+            address,
+            session,
+        ))
+        .expect("Benchmarks have a keystore with the authority key")
+}
+
+/// Registers an address of length `length` for a new authority in session 0, to be replaced
+/// in the benchmarks.
+fn registered_authority<T: Config>(
+    owner: &T::AccountId,
+    length: u32,
+) -> (T::AuthorityId, BoundedVec<u8, T::MaxAddressLength>) {
+    let authority = T::AuthorityId::generate_pair(None);
+    let address: BoundedVec<_, _> = vec![0; length as usize]
+        .try_into()
+        .expect("Length is at most MaxAddressLength");
+    Pallet::<T>::register_address(
+        RawOrigin::Signed(owner.clone()).into(),
+        authority.clone(),
+        address.clone(),
+        0,
+        sign::<T>(&authority, owner, &address, 0),
+    )
+    .expect("Authority should be able to register an address");
+
+    (authority, address)
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    // Replacing a registration of a different owner, which returns the deposit to it, is the
+    // most expensive case.
+    #[benchmark]
+    fn register_address(l: Linear<1, { T::MaxAddressLength::get() }>) {
+        let previous_owner = funded_account::<T>("previous_owner");
+        let owner = funded_account::<T>("owner");
+        let (authority, address) = registered_authority::<T>(&previous_owner, l);
+        let signature = sign::<T>(&authority, &owner, &address, 1);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(owner.clone()),
+            authority.clone(),
+            address,
+            1,
+            signature,
+        );
+
+        assert_eq!(
+            Addresses::<T>::get(&authority).map(|registered| registered.owner),
+            Some(owner)
+        );
+    }
+
+    #[benchmark]
+    fn unregister_address() {
+        let owner = funded_account::<T>("owner");
+        let (authority, _) = registered_authority::<T>(&owner, T::MaxAddressLength::get());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(owner), authority.clone());
+
+        assert!(!Addresses::<T>::contains_key(&authority));
+    }
+
+    impl_benchmark_test_suite!(
+        Pallet,
+        crate::benchmarking::tests::new_test_ext(),
+        crate::mock::Test,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_io::TestExternalities;
+
+    use crate::mock;
+
+    pub fn new_test_ext() -> TestExternalities {
+        mock::new_test_ext(&[])
+    }
+}
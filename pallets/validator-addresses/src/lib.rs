@@ -0,0 +1,186 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![doc = include_str!("../README.md")]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use frame_support::{
+    pallet_prelude::{BoundedVec, MaxEncodedLen},
+    traits::{Currency, StorageVersion},
+};
+pub use pallet::*;
+use parity_scale_codec::{Decode, Encode};
+use primitives::SessionIndex;
+use scale_info::TypeInfo;
+pub use weights::WeightInfo;
+
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+pub type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Validator network address of an authority, together with the account that registered it.
+#[derive(Decode, Encode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, Debug)]
+pub struct RegisteredAddress<AccountId, Balance, Address> {
+    /// Account that registered the address and holds the deposit for it.
+    pub owner: AccountId,
+    /// Encoded addressing information of the validator network endpoint.
+    pub address: Address,
+    /// Session the registration was signed for.
+    pub session: SessionIndex,
+    /// Deposit reserved from the owner, returned when the address is removed or replaced.
+    pub deposit: Balance,
+}
+
+pub type RegisteredAddressOf<T> = RegisteredAddress<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    BoundedVec<u8, <T as Config>::MaxAddressLength>,
+>;
+
+#[frame_support::pallet]
+#[pallet_doc("../README.md")]
+pub mod pallet {
+    use frame_support::{
+        pallet_prelude::*,
+        sp_runtime::RuntimeAppPublic,
+        traits::{Currency, ReservableCurrency},
+    };
+    use frame_system::{ensure_signed, pallet_prelude::*};
+    use primitives::{validator_address_message, SessionInfoProvider};
+    use sp_runtime::traits::{MaybeSerializeDeserialize, Zero};
+
+    use super::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Key the addresses are registered for, has to sign the registrations.
+        type AuthorityId: Member
+            + Parameter
+            + RuntimeAppPublic
+            + MaybeSerializeDeserialize
+            + MaxEncodedLen;
+        /// Currency the deposits are reserved in.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for every registered address.
+        #[pallet::constant]
+        type AddressDeposit: Get<BalanceOf<Self>>;
+        /// Maximal length of encoded addressing information.
+        #[pallet::constant]
+        type MaxAddressLength: Get<u32>;
+        type SessionInfoProvider: SessionInfoProvider<BlockNumberFor<Self>>;
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    pub type Signature<T> = <<T as Config>::AuthorityId as RuntimeAppPublic>::Signature;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Address of the authority has been registered by the account
+        AddressRegistered(T::AuthorityId, T::AccountId),
+        /// Address of the authority has been removed
+        AddressUnregistered(T::AuthorityId),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The registration is not signed by the authority.
+        InvalidSignature,
+        /// The registration was signed for a session after the next one.
+        SessionInFuture,
+        /// An address signed for the same or a later session is already registered.
+        StaleRegistration,
+        /// No address is registered for the authority.
+        NoAddress,
+        /// The address was registered by a different account.
+        NotOwner,
+    }
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    /// Validator network addresses of the authorities.
+    #[pallet::storage]
+    pub type Addresses<T: Config> =
+        StorageMap<_, Twox64Concat, T::AuthorityId, RegisteredAddressOf<T>>;
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register `address`, the encoded addressing information of a validator network
+        /// endpoint, for `authority`. The signature of the authority over
+        /// `validator_address_message(genesis_hash, caller, address, session)` ties the two
+        /// together and to the caller, `session` has to be later than that of the currently
+        /// registered address and not later than the next session. Reserves `AddressDeposit`
+        /// from the caller, returning the deposit of the replaced registration to its owner.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::register_address(address.len() as u32))]
+        pub fn register_address(
+            origin: OriginFor<T>,
+            authority: T::AuthorityId,
+            address: BoundedVec<u8, T::MaxAddressLength>,
+            session: SessionIndex,
+            signature: Signature<T>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+
+            if session > T::SessionInfoProvider::current_session().saturating_add(1) {
+                return Err(Error::<T>::SessionInFuture.into());
+            }
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero());
+            let message = validator_address_message(&genesis_hash, &owner, &address, session);
+            if !authority.verify(&message, &signature) {
+                return Err(Error::<T>::InvalidSignature.into());
+            }
+            if let Some(registered) = Addresses::<T>::get(&authority) {
+                if registered.session >= session {
+                    return Err(Error::<T>::StaleRegistration.into());
+                }
+                T::Currency::unreserve(&registered.owner, registered.deposit);
+            }
+
+            let deposit = T::AddressDeposit::get();
+            T::Currency::reserve(&owner, deposit)?;
+            Addresses::<T>::insert(
+                &authority,
+                RegisteredAddress {
+                    owner: owner.clone(),
+                    address,
+                    session,
+                    deposit,
+                },
+            );
+            Self::deposit_event(Event::AddressRegistered(authority, owner));
+
+            Ok(())
+        }
+
+        /// Remove the address registered for `authority` by the caller and return the deposit.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::unregister_address())]
+        pub fn unregister_address(
+            origin: OriginFor<T>,
+            authority: T::AuthorityId,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+
+            let registered = Addresses::<T>::get(&authority).ok_or(Error::<T>::NoAddress)?;
+            if registered.owner != owner {
+                return Err(Error::<T>::NotOwner.into());
+            }
+            T::Currency::unreserve(&owner, registered.deposit);
+            Addresses::<T>::remove(&authority);
+            Self::deposit_event(Event::AddressUnregistered(authority));
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::ConstU32,
+    weights::{RuntimeDbWeight, Weight},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use primitives::{SessionIndex, SessionInfoProvider};
+use sp_core::H256;
+use sp_runtime::{testing::UintAuthorityId, traits::IdentityLookup, BuildStorage};
+
+use super::*;
+use crate as pallet_validator_addresses;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+    pub struct Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        ValidatorAddresses: pallet_validator_addresses,
+    }
+);
+
+pub(crate) type AccountId = u64;
+pub(crate) type Balance = u128;
+
+pub(crate) const DEPOSIT: Balance = 100;
+pub(crate) const INITIAL_BALANCE: Balance = 1_000;
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub BlockWeights: frame_system::limits::BlockWeights =
+        frame_system::limits::BlockWeights::simple_max(Weight::from_parts(1024, 0));
+    pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight {
+        read: 25,
+        write: 100
+    };
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type RuntimeTask = RuntimeTask;
+    type Nonce = u64;
+    type Hash = H256;
+    type Block = Block;
+    type Hashing = sp_runtime::traits::BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = TestDbWeight;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type FreezeIdentifier = ();
+    type MaxHolds = ConstU32<0>;
+    type MaxFreezes = ConstU32<0>;
+    type RuntimeHoldReason = ();
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+}
+
+parameter_types! {
+    pub const AddressDeposit: Balance = DEPOSIT;
+    pub static CurrentSession: SessionIndex = 0;
+}
+
+pub struct SessionInfoImpl;
+impl SessionInfoProvider<BlockNumberFor<Test>> for SessionInfoImpl {
+    fn current_session() -> SessionIndex {
+        CurrentSession::get()
+    }
+
+    fn next_session_block_number(
+        _current_block: BlockNumberFor<Test>,
+    ) -> Option<BlockNumberFor<Test>> {
+        None
+    }
+}
+
+impl Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type AuthorityId = UintAuthorityId;
+    type Currency = Balances;
+    type AddressDeposit = AddressDeposit;
+    type MaxAddressLength = ConstU32<16>;
+    type SessionInfoProvider = SessionInfoImpl;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext(accounts: &[AccountId]) -> sp_io::TestExternalities {
+    let mut t = <frame_system::GenesisConfig<Test> as BuildStorage>::build_storage(
+        &frame_system::GenesisConfig::default(),
+    )
+    .expect("Storage should be build.");
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: accounts
+            .iter()
+            .map(|account| (*account, INITIAL_BALANCE))
+            .collect(),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    t.into()
+}
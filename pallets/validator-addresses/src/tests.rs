@@ -0,0 +1,198 @@
+use frame_support::{
+    assert_noop, assert_ok, sp_runtime::RuntimeAppPublic, traits::ReservableCurrency, BoundedVec,
+};
+use primitives::{validator_address_message, SessionIndex};
+use sp_core::H256;
+use sp_runtime::testing::{TestSignature, UintAuthorityId};
+
+use crate::{mock::*, Addresses, Error, RegisteredAddress};
+
+const AUTHORITY: UintAuthorityId = UintAuthorityId(7);
+
+fn address(bytes: &[u8]) -> BoundedVec<u8, <Test as crate::Config>::MaxAddressLength> {
+    bytes
+        .to_vec()
+        .try_into()
+        .expect("the address is short enough")
+}
+
+fn genesis_hash() -> H256 {
+    frame_system::Pallet::<Test>::block_hash(0)
+}
+
+fn sign(
+    authority: &UintAuthorityId,
+    owner: AccountId,
+    address: &[u8],
+    session: SessionIndex,
+) -> TestSignature {
+    authority
+        .sign(&validator_address_message(
+            &genesis_hash(),
+            &owner,
+            address,
+            session,
+        ))
+        .expect("test keys always sign")
+}
+
+fn register(
+    owner: AccountId,
+    bytes: &[u8],
+    session: SessionIndex,
+) -> frame_support::dispatch::DispatchResult {
+    ValidatorAddresses::register_address(
+        RuntimeOrigin::signed(owner),
+        AUTHORITY,
+        address(bytes),
+        session,
+        sign(&AUTHORITY, owner, bytes, session),
+    )
+}
+
+#[test]
+fn registration_reserves_deposit() {
+    new_test_ext(&[1]).execute_with(|| {
+        assert_ok!(register(1, b"address", 0));
+
+        assert_eq!(
+            Addresses::<Test>::get(AUTHORITY),
+            Some(RegisteredAddress {
+                owner: 1,
+                address: address(b"address"),
+                session: 0,
+                deposit: DEPOSIT,
+            })
+        );
+        assert_eq!(Balances::reserved_balance(1), DEPOSIT);
+    });
+}
+
+#[test]
+fn registration_has_to_be_signed_by_authority() {
+    new_test_ext(&[1]).execute_with(|| {
+        assert_noop!(
+            ValidatorAddresses::register_address(
+                RuntimeOrigin::signed(1),
+                AUTHORITY,
+                address(b"address"),
+                0,
+                sign(&UintAuthorityId(8), 1, b"address", 0),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+        assert_noop!(
+            ValidatorAddresses::register_address(
+                RuntimeOrigin::signed(1),
+                AUTHORITY,
+                address(b"address"),
+                0,
+                sign(&AUTHORITY, 1, b"other address", 0),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn registration_cannot_be_submitted_by_another_account() {
+    new_test_ext(&[1, 2]).execute_with(|| {
+        assert_noop!(
+            ValidatorAddresses::register_address(
+                RuntimeOrigin::signed(2),
+                AUTHORITY,
+                address(b"address"),
+                0,
+                sign(&AUTHORITY, 1, b"address", 0),
+            ),
+            Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn registration_is_bound_to_chain() {
+    new_test_ext(&[1]).execute_with(|| {
+        let signature = AUTHORITY
+            .sign(&validator_address_message(
+                &H256::zero(),
+                &1u64,
+                b"address",
+                0,
+            ))
+            .expect("test keys always sign");
+        assert_ne!(genesis_hash(), H256::zero());
+
+        assert_noop!(
+            ValidatorAddresses::register_address(
+                RuntimeOrigin::signed(1),
+                AUTHORITY,
+                address(b"address"),
+                0,
+                signature,
+            ),
+            Error::<Test>::InvalidSignature
+        );
+    });
+}
+
+#[test]
+fn registrations_are_ordered_by_session() {
+    new_test_ext(&[1]).execute_with(|| {
+        CurrentSession::set(5);
+        assert_noop!(register(1, b"address", 7), Error::<Test>::SessionInFuture);
+
+        assert_ok!(register(1, b"address", 6));
+        assert_noop!(
+            register(1, b"old address", 5),
+            Error::<Test>::StaleRegistration
+        );
+        assert_noop!(register(1, b"address", 6), Error::<Test>::StaleRegistration);
+
+        CurrentSession::set(6);
+        assert_ok!(register(1, b"new address", 7));
+        assert_eq!(
+            Addresses::<Test>::get(AUTHORITY).map(|registered| registered.address),
+            Some(address(b"new address"))
+        );
+        assert_eq!(Balances::reserved_balance(1), DEPOSIT);
+    });
+}
+
+#[test]
+fn replaced_registration_returns_deposit() {
+    new_test_ext(&[1, 2]).execute_with(|| {
+        assert_ok!(register(1, b"address", 0));
+        assert_ok!(register(2, b"new address", 1));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::reserved_balance(2), DEPOSIT);
+        assert_eq!(
+            Addresses::<Test>::get(AUTHORITY).map(|registered| registered.owner),
+            Some(2)
+        );
+    });
+}
+
+#[test]
+fn only_owner_unregisters() {
+    new_test_ext(&[1, 2]).execute_with(|| {
+        assert_noop!(
+            ValidatorAddresses::unregister_address(RuntimeOrigin::signed(1), AUTHORITY),
+            Error::<Test>::NoAddress
+        );
+
+        assert_ok!(register(1, b"address", 0));
+        assert_noop!(
+            ValidatorAddresses::unregister_address(RuntimeOrigin::signed(2), AUTHORITY),
+            Error::<Test>::NotOwner
+        );
+
+        assert_ok!(ValidatorAddresses::unregister_address(
+            RuntimeOrigin::signed(1),
+            AUTHORITY
+        ));
+        assert_eq!(Addresses::<Test>::get(AUTHORITY), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+    });
+}
@@ -0,0 +1,90 @@
+//! Weights for pallet_validator_addresses
+//!
+//! THESE WEIGHTS ARE NOT MEASURED YET. They follow the benchmark CLI template, with the storage
+//! accesses of the benchmarks in `benchmarking.rs`, an estimated execution time of 60µs for
+//! the signature verification of a registration and 2ns for every byte of its address. Run the
+//! command below on reference hardware to replace them.
+
+// Executed Command:
+// ./target/release/selendra-node
+// benchmark
+// pallet
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_validator_addresses
+// --extrinsic=*
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/validator-addresses/src/weights.rs
+// --template=./scripts/templates/weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_validator_addresses.
+pub trait WeightInfo {
+	fn register_address(l: u32, ) -> Weight;
+	fn unregister_address() -> Weight;
+}
+
+/// Weights for pallet_validator_addresses using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: ValidatorAddresses Addresses (r:1 w:1)
+	/// Proof: ValidatorAddresses Addresses (max_values: None, max_size: Some(1118), added: 3593, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 1024]`.
+	fn register_address(l: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 6196)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: ValidatorAddresses Addresses (r:1 w:1)
+	/// Proof: ValidatorAddresses Addresses (max_values: None, max_size: Some(1118), added: 3593, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn unregister_address() -> Weight {
+		Weight::from_parts(25_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Session CurrentIndex (r:1 w:0)
+	/// Proof Skipped: Session CurrentIndex (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System BlockHash (r:1 w:0)
+	/// Proof: System BlockHash (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Storage: ValidatorAddresses Addresses (r:1 w:1)
+	/// Proof: ValidatorAddresses Addresses (max_values: None, max_size: Some(1118), added: 3593, mode: MaxEncodedLen)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `l` is `[1, 1024]`.
+	fn register_address(l: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 6196)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: ValidatorAddresses Addresses (r:1 w:1)
+	/// Proof: ValidatorAddresses Addresses (max_values: None, max_size: Some(1118), added: 3593, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn unregister_address() -> Weight {
+		Weight::from_parts(25_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity >=0.8.0;
+
+/// Interface to native staking and nomination pools.
+/// Address: 0x0000000000000000000000000000000000005001
+///
+/// The caller is resolved to its native account through unified accounts: a claimed mapping
+/// acts as the mapped account, otherwise the default account derived from the H160 is used.
+/// Native accounts are passed as `bytes32`, amounts in the smallest unit of SEL.
+interface Staking {
+    /// Bond `value` from the caller's stash.
+    /// `payee`: 0 - rewards are re-staked, 1 - paid to the stash, 2 - not paid out.
+    function bond(uint256 value, uint8 payee) external returns (bool);
+
+    /// Bond additional funds on top of an existing bond.
+    function bondExtra(uint256 value) external returns (bool);
+
+    /// Schedule `value` of the active bond to be unlocked.
+    function unbond(uint256 value) external returns (bool);
+
+    /// Withdraw all unlocked chunks of the caller's bond.
+    function withdrawUnbonded(uint32 numSlashingSpans) external returns (bool);
+
+    /// Nominate the given validator stashes (at most 16).
+    function nominate(bytes32[] calldata targets) external returns (bool);
+
+    /// Stop nominating or validating.
+    function chill() external returns (bool);
+
+    /// Join nomination pool `poolId` with `amount`.
+    function joinPool(uint256 amount, uint32 poolId) external returns (bool);
+
+    /// Bond additional free balance into the caller's pool.
+    function poolBondExtra(uint256 amount) external returns (bool);
+
+    /// Unbond `points` from the caller's pool, to be withdrawn with `poolWithdrawUnbonded`.
+    function unbondFromPool(uint256 points) external returns (bool);
+
+    /// Withdraw the caller's unlocked pool funds.
+    function poolWithdrawUnbonded(uint32 numSlashingSpans) external returns (bool);
+
+    /// Claim pending pool rewards to the caller's free balance.
+    function claimPoolRewards() external returns (bool);
+
+    /// Native account the given address acts as.
+    function nativeAccount(address account) external view returns (bytes32);
+
+    /// Current and active era index.
+    function era() external view returns (uint32 current, uint32 active);
+
+    /// Minimum bond to nominate and to join a pool.
+    function minBonds() external view returns (uint256 nominator, uint256 poolJoin);
+
+    /// Staking ledger of `staker`.
+    function ledger(address staker)
+        external
+        view
+        returns (uint256 total, uint256 active, uint256 unlocking);
+
+    /// Validators nominated by `nominator`.
+    function nominations(address nominator) external view returns (bytes32[] memory);
+
+    /// Pool membership of `member`, `poolId` is zero for non-members.
+    function poolMembership(address member)
+        external
+        view
+        returns (uint32 poolId, uint256 points, uint256 pendingRewards);
+
+    /// State of pool `poolId`: 0 - open, 1 - blocked, 2 - destroying.
+    function poolInfo(uint32 poolId)
+        external
+        view
+        returns (uint8 state, uint32 memberCount, uint256 points, uint256 balance);
+}
@@ -0,0 +1,405 @@
+//! EVM precompile giving Solidity contracts access to `pallet_staking` and
+//! `pallet_nomination_pools`.
+//!
+//! The caller's H160 is resolved to a native account through `pallet_unified_accounts`, so an
+//! EVM address that claimed a mapping acts as its native account, while an unmapped one acts as
+//! its default account. All dispatchables are executed with a signed origin of that account and
+//! charged according to the weight of the underlying call. The precompile can't be reached with
+//! DELEGATECALL or CALLCODE, as the caller would then be the account of whoever called the
+//! delegating contract.
+//!
+//! The Solidity interface is described in `Staking.sol`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    pallet_prelude::MaxEncodedLen,
+};
+use pallet_nomination_pools::{BondExtra, PoolId, PoolState};
+use pallet_staking::RewardDestination;
+use precompile_utils::prelude::*;
+use primitives::{
+    evm::{EvmAddress, UnifiedAddressMapper},
+    Balance,
+};
+use sp_core::{H256, U256};
+use sp_runtime::traits::{Dispatchable, StaticLookup};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of validators that can be nominated in a single call.
+pub const MAX_NOMINATION_TARGETS: u32 = 16;
+
+type GetMaxTargets = frame_support::traits::ConstU32<MAX_NOMINATION_TARGETS>;
+
+/// Reward destinations accepted by `bond`, encoded as `uint8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayeeKind {
+    /// Rewards are added to the bonded amount.
+    Staked = 0,
+    /// Rewards are paid to the stash as free balance.
+    Stash = 1,
+    /// Rewards are not paid out.
+    None = 2,
+}
+
+impl TryFrom<u8> for PayeeKind {
+    type Error = RevertReason;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PayeeKind::Staked),
+            1 => Ok(PayeeKind::Stash),
+            2 => Ok(PayeeKind::None),
+            _ => Err(RevertReason::custom("Unknown reward destination")),
+        }
+    }
+}
+
+impl PayeeKind {
+    fn into_destination<AccountId>(self) -> RewardDestination<AccountId> {
+        match self {
+            PayeeKind::Staked => RewardDestination::Staked,
+            PayeeKind::Stash => RewardDestination::Stash,
+            PayeeKind::None => RewardDestination::None,
+        }
+    }
+}
+
+/// Encodes `PoolState` as the `uint8` returned by `poolInfo`.
+pub fn pool_state_to_u8(state: PoolState) -> u8 {
+    match state {
+        PoolState::Open => 0,
+        PoolState::Blocked => 1,
+        PoolState::Destroying => 2,
+    }
+}
+
+/// Converts an EVM `uint256` amount into native balance.
+pub fn u256_to_balance(value: U256) -> EvmResult<Balance> {
+    value
+        .try_into()
+        .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+}
+
+pub struct StakingPrecompile<R>(PhantomData<R>);
+
+#[precompile_utils::precompile]
+impl<R> StakingPrecompile<R>
+where
+    R: pallet_evm::Config
+        + pallet_staking::Config<CurrencyBalance = Balance>
+        + pallet_nomination_pools::Config
+        + pallet_unified_accounts::Config,
+    R::AccountId: From<[u8; 32]> + Into<[u8; 32]>,
+    R::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+        + GetDispatchInfo
+        + From<pallet_staking::Call<R>>
+        + From<pallet_nomination_pools::Call<R>>,
+    <R::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<R::AccountId>>,
+    pallet_nomination_pools::BalanceOf<R>: From<Balance> + Into<Balance>,
+{
+    /// Rejects DELEGATECALL and CALLCODE, which would let a contract act as its caller's account.
+    #[precompile::pre_check]
+    fn no_delegate_call(handle: &mut impl PrecompileHandle) -> EvmResult {
+        if handle.code_address() != handle.context().address {
+            return Err(revert("Cannot be called with DELEGATECALL or CALLCODE"));
+        }
+        Ok(())
+    }
+
+    /// Bond `value` from the caller's stash with the given reward destination.
+    #[precompile::public("bond(uint256,uint8)")]
+    fn bond(handle: &mut impl PrecompileHandle, value: U256, payee: u8) -> EvmResult<bool> {
+        let value = u256_to_balance(value)?;
+        let payee = PayeeKind::try_from(payee).map_err(|e| e.in_field("payee"))?;
+
+        let call = pallet_staking::Call::<R>::bond {
+            value,
+            payee: payee.into_destination(),
+        };
+        Self::dispatch(handle, call)
+    }
+
+    /// Bond additional funds on top of an existing bond.
+    #[precompile::public("bondExtra(uint256)")]
+    fn bond_extra(handle: &mut impl PrecompileHandle, value: U256) -> EvmResult<bool> {
+        let max_additional = u256_to_balance(value)?;
+
+        let call = pallet_staking::Call::<R>::bond_extra { max_additional };
+        Self::dispatch(handle, call)
+    }
+
+    /// Schedule `value` of the active bond to be unlocked.
+    #[precompile::public("unbond(uint256)")]
+    fn unbond(handle: &mut impl PrecompileHandle, value: U256) -> EvmResult<bool> {
+        let value = u256_to_balance(value)?;
+
+        let call = pallet_staking::Call::<R>::unbond { value };
+        Self::dispatch(handle, call)
+    }
+
+    /// Withdraw all unlocked chunks of the caller's bond.
+    #[precompile::public("withdrawUnbonded(uint32)")]
+    fn withdraw_unbonded(
+        handle: &mut impl PrecompileHandle,
+        num_slashing_spans: u32,
+    ) -> EvmResult<bool> {
+        let call = pallet_staking::Call::<R>::withdraw_unbonded { num_slashing_spans };
+        Self::dispatch(handle, call)
+    }
+
+    /// Nominate the given validator stashes.
+    #[precompile::public("nominate(bytes32[])")]
+    fn nominate(
+        handle: &mut impl PrecompileHandle,
+        targets: BoundedVec<H256, GetMaxTargets>,
+    ) -> EvmResult<bool> {
+        let targets: Vec<H256> = targets.into();
+        let targets = targets
+            .into_iter()
+            .map(|target| R::Lookup::unlookup(R::AccountId::from(target.0)))
+            .collect();
+
+        let call = pallet_staking::Call::<R>::nominate { targets };
+        Self::dispatch(handle, call)
+    }
+
+    /// Stop nominating or validating.
+    #[precompile::public("chill()")]
+    fn chill(handle: &mut impl PrecompileHandle) -> EvmResult<bool> {
+        Self::dispatch(handle, pallet_staking::Call::<R>::chill {})
+    }
+
+    /// Join nomination pool `pool_id` with `amount`.
+    #[precompile::public("joinPool(uint256,uint32)")]
+    fn join_pool(
+        handle: &mut impl PrecompileHandle,
+        amount: U256,
+        pool_id: PoolId,
+    ) -> EvmResult<bool> {
+        let amount = u256_to_balance(amount)?.into();
+
+        let call = pallet_nomination_pools::Call::<R>::join { amount, pool_id };
+        Self::dispatch(handle, call)
+    }
+
+    /// Bond additional free balance into the pool the caller is a member of.
+    #[precompile::public("poolBondExtra(uint256)")]
+    fn pool_bond_extra(handle: &mut impl PrecompileHandle, amount: U256) -> EvmResult<bool> {
+        let amount = u256_to_balance(amount)?.into();
+
+        let call = pallet_nomination_pools::Call::<R>::bond_extra {
+            extra: BondExtra::FreeBalance(amount),
+        };
+        Self::dispatch(handle, call)
+    }
+
+    /// Unbond `points` from the pool the caller is a member of. The funds are withdrawn with
+    /// `poolWithdrawUnbonded` once unlocked, which also leaves the pool when all points were
+    /// unbonded.
+    #[precompile::public("unbondFromPool(uint256)")]
+    fn unbond_from_pool(handle: &mut impl PrecompileHandle, points: U256) -> EvmResult<bool> {
+        let unbonding_points = u256_to_balance(points)?.into();
+        let member_account = R::Lookup::unlookup(Self::caller_account(handle));
+
+        let call = pallet_nomination_pools::Call::<R>::unbond {
+            member_account,
+            unbonding_points,
+        };
+        Self::dispatch(handle, call)
+    }
+
+    /// Withdraw the caller's unlocked pool funds.
+    #[precompile::public("poolWithdrawUnbonded(uint32)")]
+    fn pool_withdraw_unbonded(
+        handle: &mut impl PrecompileHandle,
+        num_slashing_spans: u32,
+    ) -> EvmResult<bool> {
+        let member_account = R::Lookup::unlookup(Self::caller_account(handle));
+
+        let call = pallet_nomination_pools::Call::<R>::withdraw_unbonded {
+            member_account,
+            num_slashing_spans,
+        };
+        Self::dispatch(handle, call)
+    }
+
+    /// Claim pending pool rewards to the caller's free balance.
+    #[precompile::public("claimPoolRewards()")]
+    fn claim_pool_rewards(handle: &mut impl PrecompileHandle) -> EvmResult<bool> {
+        Self::dispatch(handle, pallet_nomination_pools::Call::<R>::claim_payout {})
+    }
+
+    /// Native account the given EVM address acts as.
+    #[precompile::public("nativeAccount(address)")]
+    #[precompile::view]
+    fn native_account(handle: &mut impl PrecompileHandle, account: Address) -> EvmResult<H256> {
+        // EvmToNative: Blake2_128(16) + H160(20) + AccountId(32)
+        handle.record_db_read::<R>(68)?;
+
+        Ok(H256(Self::to_native(account.into()).into()))
+    }
+
+    /// Index of the current and active era.
+    #[precompile::public("era()")]
+    #[precompile::view]
+    fn era(handle: &mut impl PrecompileHandle) -> EvmResult<(u32, u32)> {
+        // CurrentEra: EraIndex(4), ActiveEra: ActiveEraInfo(4 + 1 + 8)
+        handle.record_db_read::<R>(4)?;
+        handle.record_db_read::<R>(13)?;
+
+        let current = pallet_staking::CurrentEra::<R>::get().unwrap_or_default();
+        let active = pallet_staking::ActiveEra::<R>::get()
+            .map(|info| info.index)
+            .unwrap_or_default();
+        Ok((current, active))
+    }
+
+    /// Minimum bond required to nominate and to join a pool.
+    #[precompile::public("minBonds()")]
+    #[precompile::view]
+    fn min_bonds(handle: &mut impl PrecompileHandle) -> EvmResult<(U256, U256)> {
+        handle.record_db_read::<R>(16)?;
+        handle.record_db_read::<R>(16)?;
+
+        let nominator = pallet_staking::MinNominatorBond::<R>::get();
+        let join: Balance = pallet_nomination_pools::MinJoinBond::<R>::get().into();
+        Ok((nominator.into(), join.into()))
+    }
+
+    /// Staking ledger of `staker`: total bonded, active and still unlocking amounts.
+    #[precompile::public("ledger(address)")]
+    #[precompile::view]
+    fn ledger(
+        handle: &mut impl PrecompileHandle,
+        staker: Address,
+    ) -> EvmResult<(U256, U256, U256)> {
+        // EvmToNative + Bonded: Twox64(8) + AccountId(32) * 2
+        handle.record_db_read::<R>(68)?;
+        handle.record_db_read::<R>(72)?;
+        handle.record_db_read::<R>(pallet_staking::StakingLedger::<R>::max_encoded_len())?;
+
+        let stash = Self::to_native(staker.into());
+        let Some(ledger) = pallet_staking::Bonded::<R>::get(&stash)
+            .and_then(|controller| pallet_staking::Ledger::<R>::get(controller))
+        else {
+            return Ok(Default::default());
+        };
+
+        let unlocking = ledger
+            .unlocking
+            .iter()
+            .fold(0 as Balance, |acc, chunk| acc.saturating_add(chunk.value));
+        Ok((ledger.total.into(), ledger.active.into(), unlocking.into()))
+    }
+
+    /// Validators nominated by `nominator`.
+    #[precompile::public("nominations(address)")]
+    #[precompile::view]
+    fn nominations(handle: &mut impl PrecompileHandle, nominator: Address) -> EvmResult<Vec<H256>> {
+        handle.record_db_read::<R>(68)?;
+        handle.record_db_read::<R>(pallet_staking::Nominations::<R>::max_encoded_len())?;
+
+        let stash = Self::to_native(nominator.into());
+        Ok(pallet_staking::Nominators::<R>::get(&stash)
+            .map(|nominations| {
+                nominations
+                    .targets
+                    .into_iter()
+                    .map(|target| H256(target.into()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Pool membership of `member`: pool id, points and pending rewards. Pool id is zero
+    /// when `member` does not belong to any pool.
+    #[precompile::public("poolMembership(address)")]
+    #[precompile::view]
+    fn pool_membership(
+        handle: &mut impl PrecompileHandle,
+        member: Address,
+    ) -> EvmResult<(u32, U256, U256)> {
+        handle.record_db_read::<R>(68)?;
+        handle.record_db_read::<R>(pallet_nomination_pools::PoolMember::<R>::max_encoded_len())?;
+        // pending rewards read the bonded and reward pools as well
+        handle.record_db_read::<R>(
+            pallet_nomination_pools::BondedPoolInner::<R>::max_encoded_len(),
+        )?;
+        handle.record_db_read::<R>(pallet_nomination_pools::RewardPool::<R>::max_encoded_len())?;
+
+        let who = Self::to_native(member.into());
+        let Some(membership) = pallet_nomination_pools::PoolMembers::<R>::get(&who) else {
+            return Ok(Default::default());
+        };
+
+        let points: Balance = membership.points.into();
+        let pending: Balance = pallet_nomination_pools::Pallet::<R>::api_pending_rewards(who)
+            .unwrap_or_default()
+            .into();
+        Ok((membership.pool_id, points.into(), pending.into()))
+    }
+
+    /// State of pool `pool_id`: state (0 open, 1 blocked, 2 destroying), member count, points
+    /// and total balance. Reverts when the pool does not exist.
+    #[precompile::public("poolInfo(uint32)")]
+    #[precompile::view]
+    fn pool_info(
+        handle: &mut impl PrecompileHandle,
+        pool_id: PoolId,
+    ) -> EvmResult<(u8, u32, U256, U256)> {
+        handle.record_db_read::<R>(
+            pallet_nomination_pools::BondedPoolInner::<R>::max_encoded_len(),
+        )?;
+        handle.record_db_read::<R>(pallet_staking::StakingLedger::<R>::max_encoded_len())?;
+
+        let pool = pallet_nomination_pools::BondedPools::<R>::get(pool_id)
+            .ok_or_else(|| revert("Pool does not exist"))?;
+
+        let points: Balance = pool.points.into();
+        let balance: Balance =
+            pallet_nomination_pools::Pallet::<R>::api_pool_balance(pool_id).into();
+        Ok((
+            pool_state_to_u8(pool.state),
+            pool.member_counter,
+            points.into(),
+            balance.into(),
+        ))
+    }
+}
+
+impl<R> StakingPrecompile<R>
+where
+    R: pallet_evm::Config + pallet_unified_accounts::Config,
+    R::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    <R::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<R::AccountId>>,
+{
+    /// Native account of an EVM address, either the claimed mapping or the default one.
+    fn to_native(address: EvmAddress) -> R::AccountId {
+        pallet_unified_accounts::Pallet::<R>::to_account_id_or_default(&address).into_address()
+    }
+
+    fn caller_account(handle: &mut impl PrecompileHandle) -> R::AccountId {
+        Self::to_native(handle.context().caller)
+    }
+
+    fn dispatch<Call>(handle: &mut impl PrecompileHandle, call: Call) -> EvmResult<bool>
+    where
+        R::RuntimeCall: From<Call>,
+    {
+        // EvmToNative lookup for the caller
+        handle.record_db_read::<R>(68)?;
+        let origin = Self::caller_account(handle);
+
+        RuntimeHelper::<R>::try_dispatch(handle, Some(origin).into(), call, 0)?;
+        Ok(true)
+    }
+}
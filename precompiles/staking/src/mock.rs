@@ -0,0 +1,283 @@
+use frame_election_provider_support::NoElection;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, ConstU8, Everything},
+    weights::Weight,
+    PalletId,
+};
+use frame_system::EnsureRoot;
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot};
+use precompile_utils::precompile_set::*;
+use primitives::evm::{HashedDefaultMappings, UnifiedAddressMapper};
+use sp_core::{H160, H256, U256};
+use sp_io::TestExternalities;
+use sp_runtime::{
+    traits::{AccountIdLookup, BlakeTwo256, Convert},
+    AccountId32, BuildStorage, FixedU128,
+};
+use sp_staking::{EraIndex, SessionIndex};
+
+use super::*;
+
+pub type AccountId = AccountId32;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub struct Runtime {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        Balances: pallet_balances,
+        Evm: pallet_evm,
+        UnifiedAccounts: pallet_unified_accounts,
+        Staking: pallet_staking,
+        NominationPools: pallet_nomination_pools,
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Nonce = u64;
+    type RuntimeCall = RuntimeCall;
+    type Block = Block;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = AccountIdLookup<AccountId, ()>;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+    type RuntimeTask = RuntimeTask;
+    type SingleBlockMigrations = ();
+    type MultiBlockMigrator = ();
+    type PreInherents = ();
+    type PostInherents = ();
+    type PostTransactions = ();
+    type ExtensionsWeightInfo = ();
+}
+
+impl pallet_balances::Config for Runtime {
+    type MaxLocks = ConstU32<4>;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type FreezeIdentifier = RuntimeFreezeReason;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type MaxFreezes = ConstU32<4>;
+    type DoneSlashHandler = ();
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<3>;
+    type WeightInfo = ();
+}
+
+/// Address the precompile is registered at, same as in the runtime.
+pub const PRECOMPILE_ADDRESS: u64 = 20481;
+
+pub type Precompiles<R> =
+    PrecompileSetBuilder<R, (PrecompileAt<AddressU64<PRECOMPILE_ADDRESS>, StakingPrecompile<R>>,)>;
+
+pub type PCall = StakingPrecompileCall<Runtime>;
+
+parameter_types! {
+    pub PrecompilesValue: Precompiles<Runtime> = Precompiles::new();
+    pub WeightPerGas: Weight = Weight::from_parts(1, 0);
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub const ChainId: u64 = 1953;
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = UnifiedAccounts;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = Precompiles<Self>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ChainId;
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = ConstU64<4>;
+    type GasLimitStorageGrowthRatio = ConstU64<0>;
+    type Timestamp = Timestamp;
+    type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
+    type AccountProvider = pallet_evm::FrameSystemAccountProvider<Self>;
+}
+
+impl pallet_unified_accounts::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type Currency = Balances;
+    type DefaultMappings = HashedDefaultMappings<BlakeTwo256>;
+    type ChainId = ChainId;
+    type AccountMappingStorageFee = ConstU128<100>;
+    type MappingChangeCooldown = ConstU64<10>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const SessionsPerEra: SessionIndex = 3;
+    pub const BondingDuration: EraIndex = 3;
+}
+
+impl pallet_staking::Config for Runtime {
+    type Currency = Balances;
+    type CurrencyBalance = Balance;
+    type UnixTime = Timestamp;
+    type CurrencyToVote = ();
+    type ElectionProvider = NoElection<(AccountId, u64, Staking, ConstU32<10>)>;
+    type GenesisElectionProvider = Self::ElectionProvider;
+    type NominationsQuota = pallet_staking::FixedNominationsQuota<MAX_NOMINATION_TARGETS>;
+    type HistoryDepth = ConstU32<84>;
+    type RewardRemainder = ();
+    type RuntimeEvent = RuntimeEvent;
+    type Slash = ();
+    type Reward = ();
+    type SessionsPerEra = SessionsPerEra;
+    type BondingDuration = BondingDuration;
+    type SlashDeferDuration = ();
+    type AdminOrigin = EnsureRoot<AccountId>;
+    type SessionInterface = ();
+    type EraPayout = ();
+    type NextNewSession = ();
+    type MaxExposurePageSize = ConstU32<64>;
+    type VoterList = pallet_staking::UseNominatorsAndValidatorsMap<Self>;
+    type TargetList = pallet_staking::UseValidatorsMap<Self>;
+    type MaxUnlockingChunks = ConstU32<32>;
+    type MaxControllersInDeprecationBatch = ConstU32<100>;
+    type EventListeners = NominationPools;
+    type DisablingStrategy = pallet_staking::UpToLimitDisablingStrategy;
+    type BenchmarkingConfig = pallet_staking::TestBenchmarkingConfig;
+    type WeightInfo = ();
+}
+
+pub struct BalanceToU256;
+impl Convert<Balance, U256> for BalanceToU256 {
+    fn convert(balance: Balance) -> U256 {
+        U256::from(balance)
+    }
+}
+
+pub struct U256ToBalance;
+impl Convert<U256, Balance> for U256ToBalance {
+    fn convert(n: U256) -> Balance {
+        n.try_into().unwrap_or(Balance::MAX)
+    }
+}
+
+parameter_types! {
+    pub const PoolsPalletId: PalletId = PalletId(*b"py/nopls");
+}
+
+impl pallet_nomination_pools::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type RewardCounter = FixedU128;
+    type BalanceToU256 = BalanceToU256;
+    type U256ToBalance = U256ToBalance;
+    type StakeAdapter = pallet_nomination_pools::adapter::TransferStake<Self, Staking>;
+    type PostUnbondingPoolsWindow = ConstU32<4>;
+    type MaxMetadataLen = ConstU32<256>;
+    type MaxUnbonding = ConstU32<8>;
+    type PalletId = PoolsPalletId;
+    type MaxPointsToBalance = ConstU8<10>;
+    type AdminOrigin = EnsureRoot<AccountId>;
+}
+
+pub const ALICE: H160 = H160::repeat_byte(0xAA);
+pub const BOB: H160 = H160::repeat_byte(0xBB);
+/// Native account creating the pool used in tests.
+pub const POOL_DEPOSITOR: AccountId = AccountId32::new([0xDD; 32]);
+
+pub const INITIAL_BALANCE: Balance = 1_000_000;
+/// Bond of the pool depositor.
+pub const POOL_BOND: Balance = 1_000;
+
+/// Native account an EVM address without a claimed mapping acts as.
+pub fn native(address: H160) -> AccountId {
+    <Runtime as pallet_unified_accounts::Config>::DefaultMappings::to_default_account_id(&address)
+}
+
+pub fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+pub fn precompile_address() -> H160 {
+    H160::from_low_u64_be(PRECOMPILE_ADDRESS)
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+    pub fn build(self) -> TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap();
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![
+                (native(ALICE), INITIAL_BALANCE),
+                (native(BOB), INITIAL_BALANCE),
+                (POOL_DEPOSITOR, INITIAL_BALANCE),
+            ],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        pallet_staking::GenesisConfig::<Runtime>::default()
+            .assimilate_storage(&mut t)
+            .unwrap();
+        pallet_nomination_pools::GenesisConfig::<Runtime>::default()
+            .assimilate_storage(&mut t)
+            .unwrap();
+
+        let mut ext = TestExternalities::from(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    /// Builds the externalities with pool 1 created by [`POOL_DEPOSITOR`].
+    pub fn build_with_pool(self) -> TestExternalities {
+        let mut ext = self.build();
+        ext.execute_with(|| {
+            frame_support::assert_ok!(NominationPools::create(
+                RuntimeOrigin::signed(POOL_DEPOSITOR),
+                POOL_BOND,
+                POOL_DEPOSITOR.into(),
+                POOL_DEPOSITOR.into(),
+                POOL_DEPOSITOR.into(),
+            ));
+        });
+        ext
+    }
+}
@@ -0,0 +1,346 @@
+use super::*;
+
+#[test]
+fn payee_kind_decodes_known_destinations() {
+    assert_eq!(PayeeKind::try_from(0).ok(), Some(PayeeKind::Staked));
+    assert_eq!(PayeeKind::try_from(1).ok(), Some(PayeeKind::Stash));
+    assert_eq!(PayeeKind::try_from(2).ok(), Some(PayeeKind::None));
+    assert!(PayeeKind::try_from(3).is_err());
+}
+
+#[test]
+fn payee_kind_maps_to_reward_destination() {
+    assert_eq!(
+        PayeeKind::Staked.into_destination::<u64>(),
+        RewardDestination::Staked
+    );
+    assert_eq!(
+        PayeeKind::Stash.into_destination::<u64>(),
+        RewardDestination::Stash
+    );
+    assert_eq!(
+        PayeeKind::None.into_destination::<u64>(),
+        RewardDestination::None
+    );
+}
+
+#[test]
+fn pool_state_encoding_is_stable() {
+    assert_eq!(pool_state_to_u8(PoolState::Open), 0);
+    assert_eq!(pool_state_to_u8(PoolState::Blocked), 1);
+    assert_eq!(pool_state_to_u8(PoolState::Destroying), 2);
+}
+
+#[test]
+fn u256_to_balance_rejects_overflow() {
+    assert_eq!(u256_to_balance(U256::from(42u128)).ok(), Some(42));
+    assert_eq!(
+        u256_to_balance(U256::from(Balance::MAX)).ok(),
+        Some(Balance::MAX)
+    );
+    assert!(u256_to_balance(U256::from(Balance::MAX) + 1).is_err());
+}
+
+mod dispatch {
+    use fp_evm::{Context, Precompile, PrecompileFailure};
+    use frame_support::assert_ok;
+    use pallet_nomination_pools::{BondedPools, PoolMembers};
+    use pallet_staking::{Bonded, Ledger, Nominators, Payee};
+    use precompile_utils::testing::*;
+    use sp_core::{H160, H256, U256};
+
+    use super::*;
+    use crate::mock::*;
+
+    fn bond(who: H160, value: Balance) {
+        precompiles()
+            .prepare_test(
+                who,
+                precompile_address(),
+                PCall::bond {
+                    value: value.into(),
+                    payee: PayeeKind::Stash as u8,
+                },
+            )
+            .execute_returns(true);
+    }
+
+    #[test]
+    fn bond_bond_extra_and_unbond_update_the_ledger() {
+        ExtBuilder::default().build().execute_with(|| {
+            let stash = native(ALICE);
+            bond(ALICE, 1_000);
+
+            assert_eq!(Bonded::<Runtime>::get(&stash), Some(stash.clone()));
+            assert_eq!(
+                Payee::<Runtime>::get(&stash),
+                Some(RewardDestination::Stash)
+            );
+            let ledger = Ledger::<Runtime>::get(&stash).expect("bonded");
+            assert_eq!((ledger.total, ledger.active), (1_000, 1_000));
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::bond_extra { value: 500.into() },
+                )
+                .execute_returns(true);
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::unbond { value: 300.into() },
+                )
+                .execute_returns(true);
+
+            let ledger = Ledger::<Runtime>::get(&stash).expect("bonded");
+            assert_eq!((ledger.total, ledger.active), (1_500, 1_200));
+            assert_eq!(ledger.unlocking.len(), 1);
+
+            precompiles()
+                .prepare_test(
+                    BOB,
+                    precompile_address(),
+                    PCall::ledger {
+                        staker: Address(ALICE),
+                    },
+                )
+                .execute_returns((U256::from(1_500), U256::from(1_200), U256::from(300)));
+        });
+    }
+
+    #[test]
+    fn bond_rejects_unknown_payee() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::bond {
+                        value: 1_000.into(),
+                        payee: 3,
+                    },
+                )
+                .execute_reverts(|output| output == b"payee: Unknown reward destination");
+
+            assert!(Bonded::<Runtime>::get(native(ALICE)).is_none());
+        });
+    }
+
+    #[test]
+    fn nominate_and_chill() {
+        ExtBuilder::default().build().execute_with(|| {
+            let stash = native(ALICE);
+            let target = H256::repeat_byte(0x11);
+            bond(ALICE, 1_000);
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::nominate {
+                        targets: vec![target].into(),
+                    },
+                )
+                .execute_returns(true);
+
+            let nominations = Nominators::<Runtime>::get(&stash).expect("nominating");
+            assert_eq!(
+                nominations.targets.into_inner(),
+                vec![AccountId::new(target.0)]
+            );
+            precompiles()
+                .prepare_test(
+                    BOB,
+                    precompile_address(),
+                    PCall::nominations {
+                        nominator: Address(ALICE),
+                    },
+                )
+                .execute_returns(vec![target]);
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), PCall::chill {})
+                .execute_returns(true);
+            assert!(!Nominators::<Runtime>::contains_key(&stash));
+        });
+    }
+
+    #[test]
+    fn nominate_rejects_too_many_targets() {
+        ExtBuilder::default().build().execute_with(|| {
+            bond(ALICE, 1_000);
+
+            let targets: Vec<H256> = (0..=MAX_NOMINATION_TARGETS as u8)
+                .map(H256::repeat_byte)
+                .collect();
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::nominate {
+                        targets: targets.into(),
+                    },
+                )
+                .execute_reverts(|output| output.ends_with(b"Value is too large for length"));
+
+            assert!(!Nominators::<Runtime>::contains_key(native(ALICE)));
+        });
+    }
+
+    #[test]
+    fn dispatch_is_rejected_in_static_context() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::bond {
+                        value: 1_000.into(),
+                        payee: PayeeKind::Staked as u8,
+                    },
+                )
+                .with_static_call(true)
+                .execute_reverts(|output| {
+                    output == b"Can't call non-static function in static context"
+                });
+
+            assert!(Bonded::<Runtime>::get(native(ALICE)).is_none());
+        });
+    }
+
+    #[test]
+    fn join_bond_extra_and_unbond_from_pool() {
+        ExtBuilder::default().build_with_pool().execute_with(|| {
+            let member = native(ALICE);
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::join_pool {
+                        amount: 200.into(),
+                        pool_id: 1,
+                    },
+                )
+                .execute_returns(true);
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::pool_bond_extra { amount: 100.into() },
+                )
+                .execute_returns(true);
+
+            let membership = PoolMembers::<Runtime>::get(&member).expect("joined");
+            assert_eq!((membership.pool_id, membership.points), (1, 300));
+            assert_eq!(
+                BondedPools::<Runtime>::get(1).expect("pool exists").points,
+                POOL_BOND + 300
+            );
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::unbond_from_pool { points: 100.into() },
+                )
+                .execute_returns(true);
+
+            let membership = PoolMembers::<Runtime>::get(&member).expect("still a member");
+            assert_eq!(membership.points, 200);
+            assert_eq!(
+                membership.unbonding_eras.values().copied().sum::<Balance>(),
+                100
+            );
+
+            precompiles()
+                .prepare_test(
+                    BOB,
+                    precompile_address(),
+                    PCall::pool_membership {
+                        member: Address(ALICE),
+                    },
+                )
+                .execute_returns((1u32, U256::from(200), U256::zero()));
+            precompiles()
+                .prepare_test(BOB, precompile_address(), PCall::pool_info { pool_id: 1 })
+                .execute_returns((
+                    pool_state_to_u8(PoolState::Open),
+                    2u32,
+                    U256::from(POOL_BOND + 200),
+                    // unbonding funds stay on the pool account until withdrawn
+                    U256::from(POOL_BOND + 300),
+                ));
+        });
+    }
+
+    #[test]
+    fn unbond_from_pool_acts_on_the_caller() {
+        ExtBuilder::default().build_with_pool().execute_with(|| {
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::unbond_from_pool { points: 100.into() },
+                )
+                .execute_reverts(|output| output.starts_with(b"Dispatched call failed"));
+
+            assert_ok!(NominationPools::join(
+                RuntimeOrigin::signed(native(BOB)),
+                100,
+                1
+            ));
+            precompiles()
+                .prepare_test(
+                    BOB,
+                    precompile_address(),
+                    PCall::unbond_from_pool { points: 100.into() },
+                )
+                .execute_returns(true);
+            assert_eq!(
+                PoolMembers::<Runtime>::get(native(BOB))
+                    .expect("still a member")
+                    .points,
+                0
+            );
+        });
+    }
+
+    #[test]
+    fn pool_info_reverts_for_missing_pool() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), PCall::pool_info { pool_id: 7 })
+                .execute_reverts(|output| output == b"Pool does not exist");
+        });
+    }
+
+    #[test]
+    fn delegatecall_reverts() {
+        ExtBuilder::default().build().execute_with(|| {
+            // A contract delegating to the precompile runs it in its own context, with the
+            // account that called the contract as the caller.
+            let mut handle = MockHandle::new(
+                precompile_address(),
+                Context {
+                    address: H160::repeat_byte(0xDD),
+                    caller: ALICE,
+                    apparent_value: U256::zero(),
+                },
+            );
+            handle.input = PCall::bond {
+                value: 1_000.into(),
+                payee: PayeeKind::Stash as u8,
+            }
+            .into();
+
+            assert!(matches!(
+                <StakingPrecompile<Runtime> as Precompile>::execute(&mut handle),
+                Err(PrecompileFailure::Revert { .. })
+            ));
+            assert!(Bonded::<Runtime>::get(native(ALICE)).is_none());
+        });
+    }
+}
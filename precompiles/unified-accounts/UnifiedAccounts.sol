@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity >=0.8.0;
+
+/// Interface to the unified accounts mappings between EVM addresses and native accounts.
+/// Address: 0x0000000000000000000000000000000000005006
+interface UnifiedAccounts {
+    /// Native account `evmAddress` acts as: the claimed one if mapped, its default one otherwise.
+    function getNative(address evmAddress) external view returns (bytes32 account);
+
+    /// EVM address `account` acts as: the claimed one if mapped, its default one otherwise.
+    function getEvm(bytes32 account) external view returns (address evmAddress);
+
+    /// Whether `evmAddress` has claimed a mapping.
+    function isMapped(address evmAddress) external view returns (bool mapped);
+
+    /// Whether `account` has claimed a mapping.
+    function isNativeMapped(bytes32 account) external view returns (bool mapped);
+
+    /// EIP-712 digest that must be signed to claim a mapping for `account`.
+    function signingPayload(bytes32 account) external view returns (bytes32 payload);
+
+    /// Map `evmAddress` to its default native account.
+    ///
+    /// The native account can later drop the mapping with `unclaim_evm_address` or move it to
+    /// another EVM address with `rotate_evm_address`, both rate limited by a cooldown.
+    /// `signature` is the 65 bytes `r | s | v` signature of `signingPayload` for that default
+    /// account, made by `evmAddress`. Anyone can submit it, so wallets may relay claims. The
+    /// storage fee is paid by the default account.
+    function claimEvmAddress(address evmAddress, bytes calldata signature)
+        external
+        returns (bool);
+}
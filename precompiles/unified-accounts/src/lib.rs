@@ -0,0 +1,153 @@
+//! EVM precompile exposing the `pallet_unified_accounts` mappings.
+//!
+//! Solidity contracts can resolve which native account an EVM address acts as (and the other way
+//! round), and EVM wallets can claim a mapping without a Substrate signer. A claim binds an EVM
+//! address to its default native account; the EIP-712 signature checked by the pallet proves the
+//! EVM address owner consents, so the claim can be relayed by any caller.
+//!
+//! The Solidity interface is described in `UnifiedAccounts.sol`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::dispatch::{GetDispatchInfo, PostDispatchInfo};
+use pallet_unified_accounts::{EvmToNative, NativeToEvm};
+use precompile_utils::prelude::*;
+use primitives::evm::{EvmAddress, UnifiedAddressMapper};
+use sp_core::{ConstU32, H256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Length of an `r | s | v` ECDSA signature.
+pub const SIGNATURE_LENGTH: usize = 65;
+
+/// Gas charged for recovering the signer of a claim, same as the `ecrecover` builtin.
+const ECRECOVER_COST: u64 = 3_000;
+
+/// Converts the signature passed by the caller to the format expected by the pallet.
+pub fn to_signature(bytes: &[u8]) -> Option<[u8; SIGNATURE_LENGTH]> {
+    bytes.try_into().ok()
+}
+
+pub struct UnifiedAccountsPrecompile<R>(PhantomData<R>);
+
+#[precompile_utils::precompile]
+impl<R> UnifiedAccountsPrecompile<R>
+where
+    R: pallet_evm::Config + pallet_unified_accounts::Config,
+    R::AccountId: From<[u8; 32]> + Into<[u8; 32]>,
+    R::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+        + GetDispatchInfo
+        + From<pallet_unified_accounts::Call<R>>,
+    <R::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<R::AccountId>>,
+{
+    /// Native account the given EVM address acts as.
+    #[precompile::public("getNative(address)")]
+    #[precompile::view]
+    fn get_native(handle: &mut impl PrecompileHandle, evm_address: Address) -> EvmResult<H256> {
+        // EvmToNative: Blake2_128(16) + H160(20) + AccountId(32)
+        handle.record_db_read::<R>(68)?;
+
+        let account =
+            pallet_unified_accounts::Pallet::<R>::to_account_id_or_default(&evm_address.into())
+                .into_address();
+        Ok(H256(account.into()))
+    }
+
+    /// EVM address the given native account acts as.
+    #[precompile::public("getEvm(bytes32)")]
+    #[precompile::view]
+    fn get_evm(handle: &mut impl PrecompileHandle, account: H256) -> EvmResult<Address> {
+        // NativeToEvm: Blake2_128(16) + AccountId(32) + H160(20)
+        handle.record_db_read::<R>(68)?;
+
+        let evm_address =
+            pallet_unified_accounts::Pallet::<R>::to_h160_or_default(&account.0.into())
+                .into_address();
+        Ok(Address(evm_address))
+    }
+
+    /// Whether the given EVM address claimed a mapping.
+    #[precompile::public("isMapped(address)")]
+    #[precompile::view]
+    fn is_mapped(handle: &mut impl PrecompileHandle, evm_address: Address) -> EvmResult<bool> {
+        // EvmToNative: Blake2_128(16) + H160(20) + AccountId(32)
+        handle.record_db_read::<R>(68)?;
+
+        Ok(EvmToNative::<R>::contains_key(EvmAddress::from(
+            evm_address,
+        )))
+    }
+
+    /// Whether the given native account claimed a mapping.
+    #[precompile::public("isNativeMapped(bytes32)")]
+    #[precompile::view]
+    fn is_native_mapped(handle: &mut impl PrecompileHandle, account: H256) -> EvmResult<bool> {
+        // NativeToEvm: Blake2_128(16) + AccountId(32) + H160(20)
+        handle.record_db_read::<R>(68)?;
+
+        Ok(NativeToEvm::<R>::contains_key(R::AccountId::from(
+            account.0,
+        )))
+    }
+
+    /// EIP-712 digest an EVM address signs to claim a mapping with the given native account.
+    #[precompile::public("signingPayload(bytes32)")]
+    #[precompile::view]
+    fn signing_payload(handle: &mut impl PrecompileHandle, account: H256) -> EvmResult<H256> {
+        // BlockHash of the genesis block: Twox64Concat(8) + BlockNumber(4) + Hash(32)
+        handle.record_db_read::<R>(44)?;
+
+        Ok(H256(
+            pallet_unified_accounts::Pallet::<R>::build_signing_payload(&account.0.into()),
+        ))
+    }
+
+    /// Map `evm_address` to its default native account.
+    ///
+    /// The claim is dispatched on behalf of the default account once `signature` is known to
+    /// come from `evm_address`, which controls that account already. The storage fee is paid
+    /// by the default account.
+    #[precompile::public("claimEvmAddress(address,bytes)")]
+    fn claim_evm_address(
+        handle: &mut impl PrecompileHandle,
+        evm_address: Address,
+        signature: BoundedBytes<ConstU32<{ SIGNATURE_LENGTH as u32 }>>,
+    ) -> EvmResult<bool> {
+        let evm_address: EvmAddress = evm_address.into();
+        let signature = to_signature(signature.as_bytes()).ok_or_else(|| {
+            RevertReason::custom("Invalid signature length").in_field("signature")
+        })?;
+
+        // EvmToNative: Blake2_128(16) + H160(20) + AccountId(32)
+        handle.record_db_read::<R>(68)?;
+        if EvmToNative::<R>::contains_key(evm_address) {
+            return Err(revert("EVM address already mapped"));
+        }
+
+        let who = pallet_unified_accounts::Pallet::<R>::to_default_account_id(&evm_address);
+
+        // BlockHash of the genesis block: Twox64Concat(8) + BlockNumber(4) + Hash(32)
+        handle.record_db_read::<R>(44)?;
+        handle.record_cost(ECRECOVER_COST)?;
+        if pallet_unified_accounts::Pallet::<R>::verify_signature(&who, &signature)
+            != Some(evm_address)
+        {
+            return Err(RevertReason::custom("Invalid signature")
+                .in_field("signature")
+                .into());
+        }
+
+        let call = pallet_unified_accounts::Call::<R>::claim_evm_address {
+            evm_address,
+            signature,
+        };
+        RuntimeHelper::<R>::try_dispatch(handle, Some(who).into(), call, 0)?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+#[test]
+fn signature_of_expected_length_is_accepted() {
+    let bytes = [7u8; SIGNATURE_LENGTH];
+    assert_eq!(to_signature(&bytes), Some(bytes));
+}
+
+#[test]
+fn signature_of_wrong_length_is_rejected() {
+    assert_eq!(to_signature(&[]), None);
+    assert_eq!(to_signature(&[7u8; SIGNATURE_LENGTH - 1]), None);
+    assert_eq!(to_signature(&[7u8; SIGNATURE_LENGTH + 1]), None);
+}
+
+mod dispatch {
+    use frame_support::traits::fungible::InspectHold;
+    use pallet_unified_accounts::HoldReason;
+    use precompile_utils::testing::*;
+    use sp_core::H160;
+
+    use super::*;
+    use crate::mock::*;
+
+    fn claim_signature(who: &AccountId, secret: &libsecp256k1::SecretKey) -> Vec<u8> {
+        UnifiedAccounts::eth_sign_prehash(&UnifiedAccounts::build_signing_payload(who), secret)
+            .to_vec()
+    }
+
+    fn claim(evm_address: H160, signature: Vec<u8>) -> PCall {
+        PCall::claim_evm_address {
+            evm_address: Address(evm_address),
+            signature: signature.into(),
+        }
+    }
+
+    #[test]
+    fn claim_through_the_precompile_maps_the_default_account() {
+        ExtBuilder::default().build().execute_with(|| {
+            let evm_address = UnifiedAccounts::eth_address(&alice_secret());
+            let who = native(evm_address);
+
+            // anyone can relay the claim
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    claim(evm_address, claim_signature(&who, &alice_secret())),
+                )
+                .execute_returns(true);
+
+            assert_eq!(EvmToNative::<Runtime>::get(evm_address), Some(who.clone()));
+            assert_eq!(NativeToEvm::<Runtime>::get(&who), Some(evm_address));
+            assert_eq!(
+                Balances::balance_on_hold(&HoldReason::AccountMapping.into(), &who),
+                STORAGE_FEE
+            );
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::is_mapped {
+                        evm_address: Address(evm_address),
+                    },
+                )
+                .execute_returns(true);
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::get_native {
+                        evm_address: Address(evm_address),
+                    },
+                )
+                .execute_returns(H256(who.clone().into()));
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::is_native_mapped {
+                        account: H256(who.into()),
+                    },
+                )
+                .execute_returns(true);
+        });
+    }
+
+    #[test]
+    fn signing_payload_matches_the_pallet() {
+        ExtBuilder::default().build().execute_with(|| {
+            let who = native(UnifiedAccounts::eth_address(&alice_secret()));
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    PCall::signing_payload {
+                        account: H256(who.clone().into()),
+                    },
+                )
+                .execute_returns(H256(UnifiedAccounts::build_signing_payload(&who)));
+        });
+    }
+
+    #[test]
+    fn claim_signed_by_another_key_is_rejected() {
+        ExtBuilder::default().build().execute_with(|| {
+            let evm_address = UnifiedAccounts::eth_address(&alice_secret());
+            let who = native(evm_address);
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    claim(evm_address, claim_signature(&who, &bob_secret())),
+                )
+                .execute_reverts(|output| output == b"signature: Invalid signature");
+
+            assert!(!EvmToNative::<Runtime>::contains_key(evm_address));
+            assert!(!NativeToEvm::<Runtime>::contains_key(&who));
+        });
+    }
+
+    #[test]
+    fn claim_with_short_signature_is_rejected() {
+        ExtBuilder::default().build().execute_with(|| {
+            let evm_address = UnifiedAccounts::eth_address(&alice_secret());
+            let mut signature = claim_signature(&native(evm_address), &alice_secret());
+            signature.pop();
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), claim(evm_address, signature))
+                .execute_reverts(|output| output == b"signature: Invalid signature length");
+
+            assert!(!EvmToNative::<Runtime>::contains_key(evm_address));
+        });
+    }
+
+    #[test]
+    fn claim_of_mapped_address_is_rejected() {
+        ExtBuilder::default().build().execute_with(|| {
+            let evm_address = UnifiedAccounts::eth_address(&alice_secret());
+            let signature = claim_signature(&native(evm_address), &alice_secret());
+
+            precompiles()
+                .prepare_test(
+                    ALICE,
+                    precompile_address(),
+                    claim(evm_address, signature.clone()),
+                )
+                .execute_returns(true);
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), claim(evm_address, signature))
+                .execute_reverts(|output| output == b"EVM address already mapped");
+        });
+    }
+}
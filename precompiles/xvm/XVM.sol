@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: Apache-2.0
+
+pragma solidity >=0.8.0;
+
+/// Interface to cross-VM calls.
+/// Address: 0x0000000000000000000000000000000000005005
+///
+/// The caller is resolved to its native account through unified accounts and acts as the
+/// caller of the target contract.
+interface XVM {
+    /// Call contract `to` living in VM `vmId` (0x1F for Wasm) with `input`, transferring `value`.
+    /// `storageDepositLimit` applies to Wasm calls only, zero means no limit.
+    ///
+    /// Reverts of the target contract are bubbled up with their original revert data.
+    function xvmCall(
+        uint8 vmId,
+        bytes calldata to,
+        bytes calldata input,
+        uint256 value,
+        uint256 storageDepositLimit
+    ) external returns (bool success, bytes memory data);
+}
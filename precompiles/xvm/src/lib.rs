@@ -0,0 +1,137 @@
+//! EVM precompile for cross-VM calls.
+//!
+//! Lets Solidity contracts call contracts living in another VM (currently ink! contracts on
+//! `pallet_contracts`) through `pallet_xvm`. The caller's H160 is resolved to a native account
+//! through `pallet_unified_accounts`, and the weight used by the XVM call is charged back as gas.
+//!
+//! The Solidity interface is described in `XVM.sol`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+
+use fp_evm::{ExitError, ExitRevert, PrecompileFailure};
+use pallet_evm::GasWeightMapping;
+use precompile_utils::prelude::*;
+use primitives::{
+    evm::UnifiedAddressMapper,
+    xvm::{Context, FailureError, FailureReason, FailureRevert, VmId, XvmCall},
+    Balance,
+};
+use sp_core::U256;
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub struct XvmPrecompile<R, XC>(PhantomData<(R, XC)>);
+
+#[precompile_utils::precompile]
+impl<R, XC> XvmPrecompile<R, XC>
+where
+    R: pallet_evm::Config + pallet_unified_accounts::Config,
+    XC: XvmCall<R::AccountId>,
+{
+    /// Rejects DELEGATECALL and CALLCODE, which would let a contract move its caller's value.
+    #[precompile::pre_check]
+    fn no_delegate_call(handle: &mut impl PrecompileHandle) -> EvmResult {
+        if handle.code_address() != handle.context().address {
+            return Err(revert("Cannot be called with DELEGATECALL or CALLCODE"));
+        }
+        Ok(())
+    }
+
+    /// Call `to` in VM `vm_id` with `input`, transferring `value` from the caller.
+    /// `storage_deposit_limit` only applies to Wasm calls, zero means no limit.
+    #[precompile::public("xvmCall(uint8,bytes,bytes,uint256,uint256)")]
+    fn xvm_call(
+        handle: &mut impl PrecompileHandle,
+        vm_id: u8,
+        to: UnboundedBytes,
+        input: UnboundedBytes,
+        value: U256,
+        storage_deposit_limit: U256,
+    ) -> EvmResult<(bool, UnboundedBytes)> {
+        let vm_id = VmId::try_from(vm_id).map_err(|_| revert("Invalid VM id"))?;
+        let value: Balance = value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").in_field("value"))?;
+        let storage_deposit_limit: Balance = storage_deposit_limit.try_into().map_err(|_| {
+            RevertReason::value_is_too_large("balance type").in_field("storageDepositLimit")
+        })?;
+        let storage_deposit_limit = (storage_deposit_limit > 0).then_some(storage_deposit_limit);
+
+        let mut gas_limit = handle.remaining_gas();
+        if let Some(limit) = handle.gas_limit() {
+            gas_limit = gas_limit.min(limit);
+        }
+        let context = Context {
+            source_vm_id: VmId::Evm,
+            weight_limit: R::GasWeightMapping::gas_to_weight(gas_limit, true),
+        };
+
+        let source = pallet_unified_accounts::Pallet::<R>::to_account_id_or_default(
+            &handle.context().caller,
+        )
+        .into_address();
+
+        log::trace!(
+            target: "xvm-precompile::xvm_call",
+            "vm_id: {:?}, source: {:?}, value: {:?}",
+            vm_id, source, value,
+        );
+
+        let call_result = XC::call(
+            context,
+            vm_id,
+            source,
+            to.into(),
+            input.into(),
+            value,
+            storage_deposit_limit,
+        );
+
+        let used_weight = match &call_result {
+            Ok(success) => success.used_weight,
+            Err(failure) => failure.used_weight,
+        };
+        handle.record_cost(R::GasWeightMapping::weight_to_gas(used_weight))?;
+        handle.record_external_cost(
+            Some(used_weight.ref_time()),
+            Some(used_weight.proof_size()),
+            None,
+        )?;
+
+        match call_result {
+            Ok(success) => Ok((true, success.output.into())),
+            Err(failure) => Err(failure_into_precompile_failure(failure.reason)),
+        }
+    }
+}
+
+/// Maps an XVM failure to the EVM failure returned to the caller.
+///
+/// Reverts of the callee are passed through unchanged so Solidity can bubble them up, other
+/// failures revert with a message describing the reason. Running out of weight stays an
+/// out of gas error.
+pub fn failure_into_precompile_failure(reason: FailureReason) -> PrecompileFailure {
+    match reason {
+        FailureReason::Revert(FailureRevert::VmRevert(output)) => PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output,
+        },
+        FailureReason::Revert(details) => revert(format!("XVM call reverted: {:?}", details)),
+        FailureReason::Error(FailureError::OutOfGas) => PrecompileFailure::Error {
+            exit_status: ExitError::OutOfGas,
+        },
+        FailureReason::Error(FailureError::VmError(message)) => revert(format!(
+            "XVM call failed: {}",
+            sp_std::str::from_utf8(&message).unwrap_or("unknown VM error")
+        )),
+        FailureReason::Error(details) => revert(format!("XVM call failed: {:?}", details)),
+    }
+}
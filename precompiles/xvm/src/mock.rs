@@ -0,0 +1,242 @@
+use std::cell::RefCell;
+
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU128, ConstU32, ConstU64, Everything},
+    weights::Weight,
+};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot};
+use precompile_utils::precompile_set::*;
+use primitives::{
+    evm::HashedDefaultMappings,
+    xvm::{CallFailure, CallOutput, CallResult},
+};
+use sp_core::{H160, H256};
+use sp_io::TestExternalities;
+use sp_runtime::{
+    traits::{AccountIdLookup, BlakeTwo256},
+    AccountId32, BuildStorage,
+};
+
+use super::*;
+
+pub type AccountId = AccountId32;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub struct Runtime {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        Balances: pallet_balances,
+        Evm: pallet_evm,
+        UnifiedAccounts: pallet_unified_accounts,
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type Nonce = u64;
+    type RuntimeCall = RuntimeCall;
+    type Block = Block;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = AccountIdLookup<AccountId, ()>;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+    type RuntimeTask = RuntimeTask;
+    type SingleBlockMigrations = ();
+    type MultiBlockMigrator = ();
+    type PreInherents = ();
+    type PostInherents = ();
+    type PostTransactions = ();
+    type ExtensionsWeightInfo = ();
+}
+
+impl pallet_balances::Config for Runtime {
+    type MaxLocks = ConstU32<4>;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type FreezeIdentifier = ();
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type MaxFreezes = ConstU32<0>;
+    type DoneSlashHandler = ();
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<3>;
+    type WeightInfo = ();
+}
+
+/// Address the precompile is registered at, same as in the runtime.
+pub const PRECOMPILE_ADDRESS: u64 = 20485;
+
+pub type Precompiles<R> = PrecompileSetBuilder<
+    R,
+    (PrecompileAt<AddressU64<PRECOMPILE_ADDRESS>, XvmPrecompile<R, MockXvm>>,),
+>;
+
+pub type PCall = XvmPrecompileCall<Runtime, MockXvm>;
+
+parameter_types! {
+    pub PrecompilesValue: Precompiles<Runtime> = Precompiles::new();
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub const ChainId: u64 = 1953;
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = UnifiedAccounts;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = Precompiles<Self>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ChainId;
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = ConstU64<4>;
+    type GasLimitStorageGrowthRatio = ConstU64<0>;
+    type Timestamp = Timestamp;
+    type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
+    type AccountProvider = pallet_evm::FrameSystemAccountProvider<Self>;
+}
+
+impl pallet_unified_accounts::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type Currency = Balances;
+    type DefaultMappings = HashedDefaultMappings<BlakeTwo256>;
+    type ChainId = ChainId;
+    type AccountMappingStorageFee = ConstU128<100>;
+    type MappingChangeCooldown = ConstU64<10>;
+    type WeightInfo = ();
+}
+
+/// Arguments `MockXvm` was called with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCall {
+    pub context: Context,
+    pub vm_id: VmId,
+    pub source: AccountId,
+    pub target: Vec<u8>,
+    pub input: Vec<u8>,
+    pub value: Balance,
+    pub storage_deposit_limit: Option<Balance>,
+}
+
+thread_local! {
+    static CALL_RESULT: RefCell<CallResult> =
+        RefCell::new(Ok(CallOutput::new(vec![], Weight::zero())));
+    static RECORDED_CALL: RefCell<Option<RecordedCall>> = RefCell::new(None);
+}
+
+/// `XvmCall` returning a preset result and recording its arguments.
+pub struct MockXvm;
+
+impl MockXvm {
+    pub fn set_result(result: CallResult) {
+        CALL_RESULT.with(|r| *r.borrow_mut() = result);
+    }
+
+    pub fn recorded_call() -> Option<RecordedCall> {
+        RECORDED_CALL.with(|c| c.borrow().clone())
+    }
+}
+
+impl XvmCall<AccountId> for MockXvm {
+    fn call(
+        context: Context,
+        vm_id: VmId,
+        source: AccountId,
+        target: Vec<u8>,
+        input: Vec<u8>,
+        value: Balance,
+        storage_deposit_limit: Option<Balance>,
+    ) -> CallResult {
+        RECORDED_CALL.with(|c| {
+            *c.borrow_mut() = Some(RecordedCall {
+                context,
+                vm_id,
+                source,
+                target,
+                input,
+                value,
+                storage_deposit_limit,
+            })
+        });
+        CALL_RESULT.with(|r| r.borrow().clone())
+    }
+}
+
+/// Failure returned by `MockXvm` with the given reason and used weight.
+pub fn failure(reason: FailureReason, used_weight: Weight) -> CallFailure {
+    CallFailure {
+        reason,
+        used_weight,
+    }
+}
+
+pub const ALICE: H160 = H160::repeat_byte(0xAA);
+
+/// Native account an EVM address without a claimed mapping acts as.
+pub fn native(address: H160) -> AccountId {
+    <Runtime as pallet_unified_accounts::Config>::DefaultMappings::to_default_account_id(&address)
+}
+
+pub fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+pub fn precompile_address() -> H160 {
+    H160::from_low_u64_be(PRECOMPILE_ADDRESS)
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+    pub fn build(self) -> TestExternalities {
+        MockXvm::set_result(Ok(CallOutput::new(vec![], Weight::zero())));
+        RECORDED_CALL.with(|c| *c.borrow_mut() = None);
+
+        let t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap();
+
+        let mut ext = TestExternalities::from(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
@@ -0,0 +1,236 @@
+use super::*;
+use precompile_utils::solidity::revert::revert_as_bytes;
+
+fn revert_output(failure: PrecompileFailure) -> Vec<u8> {
+    match failure {
+        PrecompileFailure::Revert { output, .. } => output,
+        other => panic!("expected revert, got {:?}", other),
+    }
+}
+
+#[test]
+fn callee_revert_data_is_passed_through() {
+    let data = vec![0x08, 0xc3, 0x79, 0xa0, 1, 2, 3];
+    let failure = failure_into_precompile_failure(FailureReason::Revert(FailureRevert::VmRevert(
+        data.clone(),
+    )));
+
+    assert_eq!(revert_output(failure), data);
+}
+
+#[test]
+fn invalid_input_reverts_with_reason() {
+    let failure =
+        failure_into_precompile_failure(FailureReason::Revert(FailureRevert::InvalidTarget));
+
+    assert_eq!(
+        revert_output(failure),
+        revert_as_bytes("XVM call reverted: InvalidTarget")
+    );
+}
+
+#[test]
+fn vm_error_reverts_with_message() {
+    let failure = failure_into_precompile_failure(FailureReason::Error(FailureError::VmError(
+        b"WASM call error: ContractTrapped".to_vec(),
+    )));
+
+    assert_eq!(
+        revert_output(failure),
+        revert_as_bytes("XVM call failed: WASM call error: ContractTrapped")
+    );
+}
+
+#[test]
+fn xvm_errors_revert_with_reason() {
+    let failure =
+        failure_into_precompile_failure(FailureReason::Error(FailureError::ReentranceDenied));
+
+    assert_eq!(
+        revert_output(failure),
+        revert_as_bytes("XVM call failed: ReentranceDenied")
+    );
+}
+
+#[test]
+fn out_of_gas_is_not_a_revert() {
+    let failure = failure_into_precompile_failure(FailureReason::Error(FailureError::OutOfGas));
+
+    assert_eq!(
+        failure,
+        PrecompileFailure::Error {
+            exit_status: ExitError::OutOfGas
+        }
+    );
+}
+
+mod dispatch {
+    use fp_evm::{Context as EvmContext, Precompile};
+    use frame_support::weights::Weight;
+    use precompile_utils::testing::*;
+    use primitives::xvm::CallOutput;
+    use sp_core::H160;
+
+    use super::*;
+    use crate::mock::*;
+
+    fn xvm_call(vm_id: u8, storage_deposit_limit: u128) -> PCall {
+        PCall::xvm_call {
+            vm_id,
+            to: vec![0xBB; 32].into(),
+            input: vec![0x12, 0x34].into(),
+            value: U256::from(7),
+            storage_deposit_limit: U256::from(storage_deposit_limit),
+        }
+    }
+
+    fn gas_for(weight: Weight) -> u64 {
+        <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(weight)
+    }
+
+    #[test]
+    fn call_is_forwarded_with_the_caller_as_source() {
+        ExtBuilder::default().build().execute_with(|| {
+            MockXvm::set_result(Ok(CallOutput::new(vec![1, 2, 3], Weight::zero())));
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .with_target_gas(Some(1_000_000))
+                .execute_returns((true, UnboundedBytes::from(vec![1, 2, 3])));
+
+            let call = MockXvm::recorded_call().expect("XVM was called");
+            assert_eq!(
+                call.context,
+                Context {
+                    source_vm_id: VmId::Evm,
+                    weight_limit: <Runtime as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+                        1_000_000, true
+                    ),
+                }
+            );
+            assert_eq!(call.vm_id, VmId::Wasm);
+            assert_eq!(call.source, native(ALICE));
+            assert_eq!(call.target, vec![0xBB; 32]);
+            assert_eq!(call.input, vec![0x12, 0x34]);
+            assert_eq!(call.value, 7);
+            assert_eq!(call.storage_deposit_limit, None);
+        });
+    }
+
+    #[test]
+    fn non_zero_storage_deposit_limit_is_passed() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 500))
+                .execute_returns((true, UnboundedBytes::from(vec![])));
+
+            let call = MockXvm::recorded_call().expect("XVM was called");
+            assert_eq!(call.storage_deposit_limit, Some(500));
+        });
+    }
+
+    #[test]
+    fn used_weight_is_charged_as_gas() {
+        ExtBuilder::default().build().execute_with(|| {
+            let used_weight = Weight::from_parts(2_000_000_000, 0);
+            MockXvm::set_result(Ok(CallOutput::new(vec![], used_weight)));
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .expect_cost(gas_for(used_weight))
+                .execute_returns((true, UnboundedBytes::from(vec![])));
+        });
+    }
+
+    #[test]
+    fn static_calls_are_rejected() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .with_static_call(true)
+                .execute_reverts(|output| {
+                    output == b"Can't call non-static function in static context"
+                });
+
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+
+    #[test]
+    fn invalid_vm_id_reverts() {
+        ExtBuilder::default().build().execute_with(|| {
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(0x42, 0))
+                .execute_reverts(|output| output == b"Invalid VM id");
+
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+
+    #[test]
+    fn callee_revert_is_propagated_and_charged() {
+        ExtBuilder::default().build().execute_with(|| {
+            let used_weight = Weight::from_parts(1_000_000_000, 0);
+            MockXvm::set_result(Err(failure(
+                FailureReason::Revert(FailureRevert::VmRevert(revert_as_bytes("callee reverted"))),
+                used_weight,
+            )));
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .expect_cost(gas_for(used_weight))
+                .execute_reverts(|output| output == b"callee reverted");
+        });
+    }
+
+    #[test]
+    fn callee_error_reverts_with_message() {
+        ExtBuilder::default().build().execute_with(|| {
+            MockXvm::set_result(Err(failure(
+                FailureReason::Error(FailureError::VmError(b"ContractTrapped".to_vec())),
+                Weight::zero(),
+            )));
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .execute_reverts(|output| output == b"XVM call failed: ContractTrapped");
+        });
+    }
+
+    #[test]
+    fn callee_out_of_gas_is_an_error() {
+        ExtBuilder::default().build().execute_with(|| {
+            MockXvm::set_result(Err(failure(
+                FailureReason::Error(FailureError::OutOfGas),
+                Weight::zero(),
+            )));
+
+            precompiles()
+                .prepare_test(ALICE, precompile_address(), xvm_call(VmId::Wasm as u8, 0))
+                .execute_error(ExitError::OutOfGas);
+        });
+    }
+
+    #[test]
+    fn delegatecall_reverts() {
+        ExtBuilder::default().build().execute_with(|| {
+            // A contract delegating to the precompile runs it in its own context, with the
+            // account that called the contract as the caller.
+            let mut handle = MockHandle::new(
+                precompile_address(),
+                EvmContext {
+                    address: H160::repeat_byte(0xDD),
+                    caller: ALICE,
+                    apparent_value: U256::zero(),
+                },
+            );
+            handle.input = xvm_call(VmId::Wasm as u8, 0).into();
+
+            assert!(matches!(
+                <XvmPrecompile<Runtime, MockXvm> as Precompile>::execute(&mut handle),
+                Err(PrecompileFailure::Revert { .. })
+            ));
+            assert!(MockXvm::recorded_call().is_none());
+        });
+    }
+}
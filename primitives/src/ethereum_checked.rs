@@ -37,7 +37,7 @@ pub const MAX_ETHEREUM_TX_INPUT_SIZE: u32 = 2u32.pow(16);
 
 pub type EthereumTxInput = BoundedVec<u8, ConstU32<MAX_ETHEREUM_TX_INPUT_SIZE>>;
 
-/// The checked Ethereum transaction. Only contracts `call` is support(no `create`).
+/// The checked Ethereum transaction calling a contract.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct CheckedEthereumTx {
     /// Gas limit.
@@ -54,34 +54,81 @@ pub struct CheckedEthereumTx {
 
 impl CheckedEthereumTx {
     pub fn into_ethereum_tx(&self, nonce: U256, chain_id: u64) -> Transaction {
-        let access_list = if let Some(ref list) = self.maybe_access_list {
-            list.iter()
-                .map(|(address, storage_keys)| AccessListItem {
-                    address: *address,
-                    storage_keys: storage_keys.clone(),
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        Transaction::EIP1559(EIP1559Transaction {
+        dummy_signed_tx(
+            nonce,
             chain_id,
+            self.gas_limit,
+            self.value,
+            TransactionAction::Call(self.target),
+            self.input.to_vec(),
+            &self.maybe_access_list,
+        )
+    }
+}
+
+/// The checked Ethereum transaction deploying a contract.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CheckedEthereumCreateTx {
+    /// Gas limit.
+    pub gas_limit: U256,
+    /// Amount to transfer to the contract.
+    pub value: U256,
+    /// Contract bytecode together with encoded constructor arguments.
+    pub init: EthereumTxInput,
+    /// Optional access list, specified in EIP-2930.
+    pub maybe_access_list: Option<Vec<(H160, Vec<H256>)>>,
+}
+
+impl CheckedEthereumCreateTx {
+    pub fn into_ethereum_tx(&self, nonce: U256, chain_id: u64) -> Transaction {
+        dummy_signed_tx(
             nonce,
-            max_fee_per_gas: U256::zero(),
-            max_priority_fee_per_gas: U256::zero(),
-            gas_limit: self.gas_limit,
-            value: self.value,
-            action: TransactionAction::Call(self.target),
-            input: self.input.to_vec(),
-            access_list,
-            odd_y_parity: true,
-            r: dummy_rs(),
-            s: dummy_rs(),
-        })
+            chain_id,
+            self.gas_limit,
+            self.value,
+            TransactionAction::Create,
+            self.init.to_vec(),
+            &self.maybe_access_list,
+        )
     }
 }
 
+fn dummy_signed_tx(
+    nonce: U256,
+    chain_id: u64,
+    gas_limit: U256,
+    value: U256,
+    action: TransactionAction,
+    input: Vec<u8>,
+    maybe_access_list: &Option<Vec<(H160, Vec<H256>)>>,
+) -> Transaction {
+    let access_list = if let Some(list) = maybe_access_list {
+        list.iter()
+            .map(|(address, storage_keys)| AccessListItem {
+                address: *address,
+                storage_keys: storage_keys.clone(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Transaction::EIP1559(EIP1559Transaction {
+        chain_id,
+        nonce,
+        max_fee_per_gas: U256::zero(),
+        max_priority_fee_per_gas: U256::zero(),
+        gas_limit,
+        value,
+        action,
+        input,
+        access_list,
+        odd_y_parity: true,
+        r: dummy_rs(),
+        s: dummy_rs(),
+    })
+}
+
 /// Dummy signature for all transactions.
 fn dummy_rs() -> H256 {
     H256::from_low_u64_be(1u64)
@@ -31,6 +31,9 @@ pub mod ethereum_checked;
 
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"alp0");
 
+/// Key type of the BLS keys used for aggregated finality signatures.
+pub const BLS_KEY_TYPE: KeyTypeId = KeyTypeId(*b"alpb");
+
 // Same as GRANDPA_ENGINE_ID because as of right now substrate sends only
 // grandpa justifications over the network.
 // TODO: change this once https://github.com/paritytech/substrate/issues/8172 will be resolved.
@@ -47,6 +50,17 @@ sp_application_crypto::with_pair! {
 pub type AuthoritySignature = app::Signature;
 pub type AuthorityId = app::Public;
 
+mod app_bls {
+    use sp_application_crypto::{app_crypto, bls381};
+    app_crypto!(bls381, crate::BLS_KEY_TYPE);
+}
+
+sp_application_crypto::with_pair! {
+    pub type AuthorityBlsPair = app_bls::Pair;
+}
+pub type AuthorityBlsSignature = app_bls::Signature;
+pub type AuthorityBlsId = app_bls::Public;
+
 impl_opaque_keys! {
     pub struct SelendraNodeSessionKeys {
         pub aura: AuraId,
@@ -170,6 +184,9 @@ pub const DEFAULT_COMMITTEE_SIZE: u32 = 4;
 pub const DEFAULT_CLEAN_SESSION_COUNTER_DELAY: SessionCount = 960;
 pub const DEFAULT_BAN_PERIOD: EraIndex = 10;
 
+/// Number of past sessions whose AlephBFT scores are kept in storage
+pub const DEFAULT_ABFT_SCORES_RETENTION: SessionCount = 960;
+
 /// Version returned when no version has been set.
 pub const DEFAULT_FINALITY_VERSION: Version = 0;
 
@@ -188,6 +205,32 @@ pub const DEFAULT_MAX_NON_FINALIZED_BLOCKS: u32 = 20;
 /// A relative folder where to store ABFT backups
 pub const DEFAULT_BACKUP_FOLDER: &str = "backup-stash";
 
+/// Maximal length of encoded validator network addressing information kept on chain
+pub const MAX_VALIDATOR_ADDRESS_LENGTH: u32 = 1024;
+
+/// Context of the signatures tying validator network addresses to authorities
+pub const VALIDATOR_ADDRESS_SIGNING_CONTEXT: &[u8] = b"selendra-validator-address";
+
+/// Message an authority signs to register the encoded addressing information of its validator
+/// network endpoint, `session` orders the registrations of the same authority. The message
+/// covers the chain, through its genesis hash, and the account submitting the registration, so
+/// the signature cannot be replayed on another chain or by anyone else.
+pub fn validator_address_message<H: Encode, A: Encode>(
+    genesis_hash: &H,
+    owner: &A,
+    address: &[u8],
+    session: SessionIndex,
+) -> Vec<u8> {
+    (
+        VALIDATOR_ADDRESS_SIGNING_CONTEXT,
+        genesis_hash,
+        owner,
+        address,
+        session,
+    )
+        .encode()
+}
+
 /// Hold set of validators that produce blocks and set of validators that participate in finality
 /// during session.
 #[derive(Decode, Encode, TypeInfo, Debug, Clone, PartialEq, Eq)]
@@ -231,6 +274,17 @@ impl Default for CommitteeSeats {
     }
 }
 
+/// How non reserved validators are chosen to the committee in a session
+#[derive(Decode, Encode, TypeInfo, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, MaxEncodedLen)]
+pub enum CommitteeSelectionMode {
+    /// Consecutive validators in a fixed order, shifted every session
+    #[default]
+    RoundRobin,
+    /// Validators sampled with probability proportional to their stake, seeded with randomness
+    /// fixed when the era is planned
+    StakeWeighted,
+}
+
 pub trait FinalityCommitteeManager<T> {
     /// `committee` is the set elected for finality committee for the next session
     fn on_next_session_finality_committee(committee: Vec<T>);
@@ -238,7 +292,6 @@ pub trait FinalityCommitteeManager<T> {
 
 pub trait AbftScoresProvider {
     fn scores_for_session(session_id: SessionIndex) -> Option<Score>;
-    fn clear_scores();
     fn clear_nonce();
 }
 
@@ -300,8 +353,19 @@ impl Default for ProductionBanConfig {
     }
 }
 
+/// Configurable slashes for validators banned due to underperformance.
+/// Zero fraction turns the slashing off.
+#[derive(Decode, Encode, TypeInfo, Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, MaxEncodedLen)]
+pub struct UnderperformanceSlashConfig {
+    /// fraction of the exposure slashed when banned for insufficient block production
+    pub production_slash: Perbill,
+    /// fraction of the exposure slashed when banned for insufficient finalization
+    pub finality_slash: Perbill,
+}
+
 /// Represent any possible reason a validator can be removed from the committee due to
 #[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum BanReason {
     /// Validator has been removed from the committee due to insufficient production in a given number of sessions
     InsufficientProduction(u32),
@@ -315,6 +379,7 @@ pub enum BanReason {
 
 /// Details of why and for how long a validator is removed from the committee
 #[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct BanInfo {
     /// reason for banning a validator
     pub reason: BanReason,
@@ -322,6 +387,90 @@ pub struct BanInfo {
     pub start: EraIndex,
 }
 
+/// How a validator did in a single session
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SessionPerformance {
+    pub session: SessionIndex,
+    /// whether the validator was a block producer, `None` if the committee is no longer known
+    pub producer: Option<bool>,
+    /// whether the validator was in the finality committee, `None` if the committee is no longer known
+    pub finalizer: Option<bool>,
+    /// number of blocks authored, only tracked for the ongoing session
+    pub blocks_produced: Option<BlockCount>,
+    /// number of blocks the validator was supposed to author
+    pub blocks_expected: Option<BlockCount>,
+    /// AlephBFT score of the validator, the lower the better, `None` if no score was submitted
+    pub finality_score: Option<u16>,
+}
+
+/// Performance of a validator over a range of sessions, together with its ban status
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ValidatorPerformance<AccountId> {
+    pub validator: AccountId,
+    pub sessions: Vec<SessionPerformance>,
+    /// sessions with insufficient block production since the counter was last cleared
+    pub underperformed_producer_session_count: SessionCount,
+    /// sessions with insufficient finalization since the counter was last cleared
+    pub underperformed_finalizer_session_count: SessionCount,
+    /// the ban of the validator, if it has not expired yet
+    pub ban: Option<BanInfo>,
+}
+
+/// AlephBFT score of a finality committee member in a single session
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SessionScore {
+    pub session: SessionIndex,
+    /// the lower the better
+    pub score: u16,
+}
+
+/// AlephBFT scores of a validator in the sessions it was a member of the finality committee
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ValidatorScores<AccountId> {
+    pub validator: AccountId,
+    pub scores: Vec<SessionScore>,
+}
+
+/// Reason why a validator cannot take a non-reserved seat in the next era
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CandidateIneligibility {
+    /// not a validator in staking
+    NotValidating,
+    /// not a non-reserved validator while the elections are permissioned
+    NotPermitted,
+    /// no validator intent registered while the elections are permissionless
+    NoValidatorIntent,
+    /// commission set in staking is above the cap declared in the validator intent
+    CommissionAboveCap,
+    /// active bond is below the self-bond declared in the validator intent
+    SelfBondBelowIntent,
+    /// session keys are not set
+    MissingSessionKeys,
+    /// the validator is banned
+    Banned,
+}
+
+/// Validator that cannot take a non-reserved seat in the next era, with all the reasons why
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct IneligibleCandidate<AccountId> {
+    pub candidate: AccountId,
+    pub reasons: Vec<CandidateIneligibility>,
+}
+
+/// Candidates for the non-reserved seats in the next era, split by whether they can be elected
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ElectionCandidates<AccountId> {
+    pub eligible: Vec<AccountId>,
+    pub ineligible: Vec<IneligibleCandidate<AccountId>>,
+}
+
 /// Represent committee, ie set of nodes that produce and finalize blocks in the session
 #[derive(Eq, Clone, PartialEq, Decode, Encode, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(S))]
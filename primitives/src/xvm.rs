@@ -18,7 +18,7 @@
 
 use crate::Balance;
 
-use frame_support::weights::Weight;
+pub use frame_support::weights::Weight;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::RuntimeDebug;